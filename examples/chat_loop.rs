@@ -2,11 +2,6 @@ use std::io::{self, Write};
 
 use zai_rs::model::{chat_base_response::ChatCompletionResponse, *};
 
-fn extract_text_from_content(v: &serde_json::Value) -> Option<String> {
-    // 简化版：假设服务端总是返回纯字符串内容
-    v.as_str().map(|s| s.to_string())
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
@@ -38,12 +33,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let body: ChatCompletionResponse = client.send().await?;
 
         // 获取第一条 choice 的文本内容
-        let ai_text = body
-            .choices()
-            .and_then(|cs| cs.first())
-            .and_then(|c| c.message().content())
-            .and_then(extract_text_from_content)
-            .unwrap_or_else(|| "<empty>".to_string());
+        let ai_text = body.final_text().unwrap_or_else(|| "<empty>".to_string());
 
         println!("AI> {}\n", ai_text);
 
@@ -2,6 +2,7 @@ use anyhow::{anyhow, Result};
 use reqwest::header::{ACCEPT, AUTHORIZATION};
 use serde_json::Value;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use zai_rs::client::sse::parse_sse_events;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -42,7 +43,10 @@ async fn main() -> Result<()> {
     tracing::info!("Tools response: {}", tools_text);
 
     // Parse tools from SSE response
-    let json_obj = parse_sse_response(&tools_text)?;
+    let json_obj = parse_sse_events(&tools_text)
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No valid data found in SSE response"))?;
 
     if let Some(result) = json_obj.get("result").and_then(|r| r.get("tools")) {
         let tools: Vec<Value> = serde_json::from_value(result.clone())?;
@@ -66,14 +70,3 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
-
-fn parse_sse_response(text: &str) -> Result<Value> {
-    for line in text.lines() {
-        if let Some(data) = line.strip_prefix("data:") {
-            if let Ok(json) = serde_json::from_str::<Value>(data.trim()) {
-                return Ok(json);
-            }
-        }
-    }
-    anyhow::bail!("No valid data found in SSE response")
-}
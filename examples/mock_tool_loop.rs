@@ -0,0 +1,92 @@
+//! Two-round tool-call loop against an in-memory mock transport
+//!
+//! Runs `ToolExecutor::drive_chat` without network access: the first mocked
+//! reply asks for the `get_weather` tool, the second (sent only once a tool
+//! result is present) answers in text.
+//!
+//! Run with: `cargo run --example mock_tool_loop --features testing`
+
+use std::sync::Arc;
+
+use serde_json::json;
+use zai_rs::{
+    client::transport::MockTransport,
+    model::{chat_base_response::ChatCompletionResponse, *},
+    toolkits::prelude::*,
+};
+
+fn make_weather_tool() -> FunctionTool {
+    FunctionTool::builder("get_weather", "Get weather for a city")
+        .property(
+            "city",
+            json!({"type": "string", "description": "City name"}),
+        )
+        .required("city")
+        .handler(|args| async move {
+            let city = args.get("city").and_then(|v| v.as_str()).unwrap_or("");
+            Ok(json!({"city": city, "weather": "sunny", "temperature": 26}))
+        })
+        .build()
+        .expect("weather tool")
+}
+
+fn response(value: serde_json::Value) -> ChatCompletionResponse {
+    serde_json::from_value(value).expect("valid response")
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let executor = ToolExecutor::new();
+    executor.add_dyn_tool(Box::new(make_weather_tool()))?;
+
+    let mock = Arc::new(MockTransport::new());
+    // Round 2: answer once the request carries a tool result.
+    mock.push_response_when(
+        |req| {
+            req.json()
+                .and_then(|body| body["messages"].as_array().cloned())
+                .is_some_and(|messages| messages.iter().any(|m| m["role"] == "tool"))
+        },
+        response(json!({
+            "id": "resp-2",
+            "choices": [{
+                "index": 0,
+                "finish_reason": "stop",
+                "message": {"role": "assistant", "content": "It is sunny and 26°C in Shenzhen."}
+            }]
+        })),
+    );
+    // Round 1: ask for the weather tool.
+    mock.push_response(response(json!({
+        "id": "resp-1",
+        "choices": [{
+            "index": 0,
+            "finish_reason": "tool_calls",
+            "message": {
+                "role": "assistant",
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"city\": \"Shenzhen\"}"}
+                }]
+            }
+        }]
+    })));
+
+    let chat = ChatCompletion::new(
+        GLM4_5_flash {},
+        TextMessage::user("What's the weather in Shenzhen?"),
+        "mock-key".to_string(),
+    )
+    .add_tools(executor.export_all_tools_as_functions())
+    .with_transport(mock.clone());
+
+    let reply = executor.drive_chat(chat, 3).await?;
+    println!("Answer: {}", reply.final_text().unwrap_or_default());
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let messages = requests[1].json().expect("json body")["messages"].clone();
+    println!("Second request messages: {:#}", messages);
+    Ok(())
+}
@@ -49,11 +49,6 @@ struct StreamChunk {
     done: bool,
 }
 
-/// Extract text content from AI response
-fn extract_text_from_content(v: &serde_json::Value) -> Option<String> {
-    v.as_str().map(|s| s.to_string())
-}
-
 /// Initialize a new chat session
 fn create_new_session() -> ChatSession {
     ChatSession {
@@ -125,10 +120,7 @@ async fn chat_handler(
     match client.send().await {
         Ok(body) => {
             let ai_text = body
-                .choices()
-                .and_then(|cs| cs.first())
-                .and_then(|c| c.message().content())
-                .and_then(extract_text_from_content)
+                .final_text()
                 .unwrap_or_else(|| "抱歉，我现在无法回复。".to_string());
 
             // Add AI response to session
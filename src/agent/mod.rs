@@ -25,11 +25,62 @@
 //!
 //! // Retrieve conversation history
 //! let history = client.get_history(&agent.id, Some(10)).await?;
+//!
+//! // Clean up test agents
+//! let deleted = client
+//!     .delete_all_matching(|agent| agent.name.starts_with("test-"))
+//!     .await?;
 //! ```
+//!
+//! # Listing
+//!
+//! [`AgentClient::list_agents`] returns one page; pass
+//! [`AgentList::next_cursor`] as `after` to get the next one:
+//!
+//! ```rust,ignore
+//! let mut after = None;
+//! loop {
+//!     let page = client.list_agents(Some(50), after.as_deref()).await?;
+//!     for agent in &page.data {
+//!         println!("{} {}", agent.id, agent.name);
+//!     }
+//!     match page.next_cursor() {
+//!         Some(cursor) => after = Some(cursor.to_string()),
+//!         None => break,
+//!     }
+//! }
+//! ```
+//!
+//! # Resilience
+//!
+//! Calls are retried on transient errors (5xx, rate limits, network errors)
+//! as set by the client's [`HttpClientConfig`]. With
+//! [`AgentClient::with_circuit_breaker`] an endpoint that keeps failing is
+//! not called again until a cooldown has passed; calls fail fast with
+//! [`ZaiError::CircuitOpen`](crate::client::error::ZaiError::CircuitOpen)
+//! meanwhile.
+//!
+//! # Default system prompt
+//!
+//! A chat request carries only the user message; its instructions are the
+//! agent's `system_prompt`, which the server applies to every chat with the
+//! agent. [`AgentClient::with_default_system_prompt`] fills
+//! [`AgentCreateRequest::system_prompt`] for agents created without one, so
+//! the prompt need not be repeated for each new agent. A prompt set on the
+//! request is never replaced, and existing agents keep theirs; change one
+//! with [`AgentClient::update_agent`].
+
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::client::http::{HttpClientConfig, http_client_with_config, parse_api_error_response};
+use crate::client::{
+    circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState},
+    http::{
+        HttpClientConfig, add_jitter, calculate_retry_delay, http_client_with_config,
+        parse_api_error_response, should_retry,
+    },
+};
 
 pub mod request;
 pub mod response;
@@ -60,6 +111,8 @@ pub struct AgentClient {
     base_url: String,
     http_config: HttpClientConfig,
     client: reqwest::Client,
+    breaker: Option<Arc<CircuitBreaker>>,
+    default_system_prompt: Option<String>,
 }
 
 impl AgentClient {
@@ -72,6 +125,8 @@ impl AgentClient {
             base_url: AGENT_API_URL.to_string(),
             http_config: config,
             client,
+            breaker: None,
+            default_system_prompt: None,
         }
     }
 
@@ -88,11 +143,31 @@ impl AgentClient {
         self
     }
 
+    /// Fail fast once the endpoint keeps failing; see
+    /// [`circuit_breaker`](crate::client::circuit_breaker)
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = Some(Arc::new(CircuitBreaker::new(config)));
+        self
+    }
+
+    /// System prompt for agents created without one; see
+    /// [Default system prompt](self#default-system-prompt)
+    pub fn with_default_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.default_system_prompt = Some(prompt.into());
+        self
+    }
+
+    /// State of the circuit breaker, if one is configured
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.breaker.as_ref().map(|b| b.state())
+    }
+
     /// Create a new AI agent
     pub async fn create_agent(
         &self,
         request: AgentCreateRequest,
     ) -> crate::ZaiResult<AgentCreateResponse> {
+        let request = self.with_default_system_prompt_for(request);
         self.send_request(&self.base_url, &request).await
     }
 
@@ -115,20 +190,64 @@ impl AgentClient {
     /// Delete an agent
     pub async fn delete_agent(&self, agent_id: &str) -> crate::ZaiResult<AgentDeleteResponse> {
         let url = format!("{}/{}", self.base_url, agent_id);
-        let response = self
-            .client
-            .delete(&url)
-            .bearer_auth(&self.api_key)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
+        self.execute(|| self.client.delete(&url).bearer_auth(&self.api_key))
+            .await
+    }
+
+    /// List agents, `limit` per page, starting after the agent ID `after`
+    pub async fn list_agents(
+        &self,
+        limit: Option<u32>,
+        after: Option<&str>,
+    ) -> crate::ZaiResult<AgentList> {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(l) = limit {
+            query.append_pair("limit", &l.to_string());
+        }
+        if let Some(a) = after {
+            query.append_pair("after", a);
+        }
+        let query = query.finish();
+        let url = if query.is_empty() {
+            self.base_url.clone()
         } else {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            Err(parse_api_error_response(status, body))
+            format!("{}?{}", self.base_url, query)
+        };
+        self.send_get_request(&url).await
+    }
+
+    /// Delete every agent for which `predicate` returns true and return
+    /// their IDs.
+    ///
+    /// All pages are listed before anything is deleted, so deletions do not
+    /// shift the pagination. Stops at the first failed deletion; agents
+    /// deleted before it stay deleted.
+    pub async fn delete_all_matching(
+        &self,
+        predicate: impl Fn(&AgentSummary) -> bool,
+    ) -> crate::ZaiResult<Vec<String>> {
+        let mut matching = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let page = self.list_agents(None, after.as_deref()).await?;
+            matching.extend(
+                page.data
+                    .iter()
+                    .filter(|agent| predicate(agent))
+                    .map(|agent| agent.id.clone()),
+            );
+            match page.next_cursor() {
+                // A cursor that does not move would list the same page forever.
+                Some(cursor) if after.as_deref() != Some(cursor) => {
+                    after = Some(cursor.to_string())
+                },
+                _ => break,
+            }
+        }
+        for id in &matching {
+            self.delete_agent(id).await?;
         }
+        Ok(matching)
     }
 
     /// Send a chat message to an agent
@@ -154,28 +273,31 @@ impl AgentClient {
         self.send_get_request(&url).await
     }
 
+    /// Fill in the default system prompt unless the request has one
+    fn with_default_system_prompt_for(
+        &self,
+        mut request: AgentCreateRequest,
+    ) -> AgentCreateRequest {
+        if request.system_prompt.is_none() {
+            request.system_prompt = self.default_system_prompt.clone();
+        }
+        request
+    }
+
     /// Internal method to send POST requests (reuses connection pool)
     async fn send_request<T: Serialize, R: for<'de> Deserialize<'de>>(
         &self,
         url: &str,
         body: &T,
     ) -> crate::ZaiResult<R> {
-        let response = self
-            .client
-            .post(url)
-            .bearer_auth(&self.api_key)
-            .header("Content-Type", "application/json")
-            .json(body)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            Err(parse_api_error_response(status, body))
-        }
+        self.execute(|| {
+            self.client
+                .post(url)
+                .bearer_auth(&self.api_key)
+                .header("Content-Type", "application/json")
+                .json(body)
+        })
+        .await
     }
 
     /// Internal method to send GET requests (reuses connection pool)
@@ -183,19 +305,223 @@ impl AgentClient {
         &self,
         url: &str,
     ) -> crate::ZaiResult<R> {
-        let response = self
-            .client
-            .get(url)
-            .bearer_auth(&self.api_key)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
+        self.execute(|| self.client.get(url).bearer_auth(&self.api_key))
+            .await
+    }
+
+    /// Send the request built by `build`, retrying transient errors and
+    /// going through the circuit breaker on every attempt.
+    async fn execute<R: for<'de> Deserialize<'de>>(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> crate::ZaiResult<R> {
+        let max_retries = self.http_config.max_retries;
+        let mut attempt = 0;
+        loop {
+            if let Some(breaker) = &self.breaker {
+                breaker.acquire()?;
+            }
+            let result = Self::send_once(build()).await;
+            if let Some(breaker) = &self.breaker {
+                breaker.record(&result);
+            }
+            match result {
+                Err(error) if should_retry(&error, attempt, max_retries) => {
+                    let delay = add_jitter(calculate_retry_delay(
+                        attempt,
+                        &self.http_config.retry_delay,
+                    ));
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        max_attempts = max_retries + 1,
+                        retry_delay = ?delay,
+                        error = %error.compact(),
+                        "Agent request failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_once<R: for<'de> Deserialize<'de>>(
+        request: reqwest::RequestBuilder,
+    ) -> crate::ZaiResult<R> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if status.is_success() {
+            Ok(serde_json::from_str(&body)?)
         } else {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            Err(parse_api_error_response(status, body))
+            Err(parse_api_error_response(status.as_u16(), body))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::client::{
+        error::ZaiError,
+        http::RetryDelay,
+        test_server::{Reply, received, serve},
+    };
+
+    /// Serve agent details, failing the first `failures` requests with 500.
+    async fn flaky_server(failures: usize) -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let (base, _) = serve(move |_| {
+            if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                Reply::json(500, "down")
+            } else {
+                Reply::json(200, r#"{"id":"a1","name":"helper"}"#)
+            }
+        })
+        .await;
+        (format!("{}/agents", base), hits)
+    }
+
+    fn client(base: &str, max_retries: u32) -> AgentClient {
+        AgentClient::new("key")
+            .with_base_url(base)
+            .with_http_config(
+                HttpClientConfig::builder()
+                    .max_retries(max_retries)
+                    .retry_delay(RetryDelay::none())
+                    .build(),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let (base, hits) = flaky_server(2).await;
+        let agent = client(&base, 2).get_agent("a1").await.unwrap();
+        assert_eq!(agent.name, "helper");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_default_system_prompt_only_when_absent() {
+        let request = |system_prompt: Option<&str>| {
+            let builder = AgentCreateRequest::builder().name("helper");
+            match system_prompt {
+                Some(prompt) => builder.system_prompt(prompt),
+                None => builder,
+            }
+            .build()
+            .unwrap()
+        };
+
+        let plain = AgentClient::new("key");
+        assert_eq!(
+            plain
+                .with_default_system_prompt_for(request(None))
+                .system_prompt,
+            None
+        );
+
+        let client = AgentClient::new("key").with_default_system_prompt("Answer in French.");
+        let injected = client.with_default_system_prompt_for(request(None));
+        assert_eq!(injected.system_prompt.as_deref(), Some("Answer in French."));
+        let body = serde_json::to_value(&injected).unwrap();
+        assert_eq!(body["system_prompt"], "Answer in French.");
+
+        let own = client.with_default_system_prompt_for(request(Some("Be terse.")));
+        assert_eq!(own.system_prompt.as_deref(), Some("Be terse."));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete_matching_agents() {
+        let (base, mut requests) = serve(|req| match req.path() {
+            "/agents" => Reply::json(
+                200,
+                r#"{"data":[{"id":"a1","name":"test-1"},{"id":"a2","name":"prod"}],"has_more":true,"last_id":"a2"}"#,
+            ),
+            "/agents?after=a2" => Reply::json(
+                200,
+                r#"{"data":[{"id":"a3","name":"test-2"}],"has_more":true}"#,
+            ),
+            "/agents?after=a3" => Reply::json(200, r#"{"data":[],"has_more":false}"#),
+            path => Reply::json(
+                200,
+                format!(
+                    r#"{{"id":"{}","success":true}}"#,
+                    path.trim_start_matches("/agents/")
+                ),
+            ),
+        })
+        .await;
+        let base = format!("{}/agents", base);
+        let client = client(&base, 0);
+
+        let page = client.list_agents(None, Some("a2")).await.unwrap();
+        assert_eq!(page.data[0].name, "test-2");
+        assert_eq!(page.next_cursor(), Some("a3"));
+        assert_eq!(
+            requests.recv().await.unwrap().line(),
+            "GET /agents?after=a2 HTTP/1.1"
+        );
+
+        let deleted = client
+            .delete_all_matching(|agent| agent.name.starts_with("test-"))
+            .await
+            .unwrap();
+        assert_eq!(deleted, ["a1", "a3"]);
+        let seen: Vec<_> = received(&mut requests)
+            .iter()
+            .map(|r| r.line().to_string())
+            .collect();
+        assert_eq!(
+            seen,
+            [
+                "GET /agents HTTP/1.1",
+                "GET /agents?after=a2 HTTP/1.1",
+                "GET /agents?after=a3 HTTP/1.1",
+                "DELETE /agents/a1 HTTP/1.1",
+                "DELETE /agents/a3 HTTP/1.1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_trips_and_recovers() {
+        let (base, hits) = flaky_server(3).await;
+        let client = client(&base, 0)
+            .with_circuit_breaker(CircuitBreakerConfig::new(3, Duration::from_millis(50)));
+        assert_eq!(
+            client.circuit_state(),
+            Some(CircuitState::Closed { failures: 0 })
+        );
+
+        for _ in 0..3 {
+            let err = client.get_agent("a1").await.unwrap_err();
+            assert!(matches!(err, ZaiError::HttpError { status: 500, .. }));
         }
+        assert!(matches!(
+            client.circuit_state(),
+            Some(CircuitState::Open { .. })
+        ));
+
+        // Open: fail fast without reaching the server.
+        let err = client.get_agent("a1").await.unwrap_err();
+        assert!(matches!(err, ZaiError::CircuitOpen { .. }));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(client.circuit_state(), Some(CircuitState::HalfOpen));
+        assert_eq!(client.get_agent("a1").await.unwrap().id, "a1");
+        assert_eq!(
+            client.circuit_state(),
+            Some(CircuitState::Closed { failures: 0 })
+        );
     }
 }
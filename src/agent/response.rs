@@ -80,6 +80,51 @@ pub struct AgentDeleteResponse {
     pub deleted_at: Option<u64>,
 }
 
+/// Agent entry in a list page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSummary {
+    /// Agent ID
+    pub id: String,
+
+    /// Agent name
+    pub name: String,
+
+    /// Agent description
+    pub description: Option<String>,
+
+    /// Model used
+    pub model: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: Option<u64>,
+}
+
+/// One page of agents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentList {
+    /// Agents in this page
+    #[serde(default)]
+    pub data: Vec<AgentSummary>,
+
+    /// Last ID in this page
+    pub last_id: Option<String>,
+
+    /// Whether more agents are available
+    pub has_more: Option<bool>,
+}
+
+impl AgentList {
+    /// Cursor for the next page, `None` on the last page. An empty page is
+    /// always the last one; without `has_more` any other page is not.
+    pub fn next_cursor(&self) -> Option<&str> {
+        if self.has_more == Some(false) {
+            return None;
+        }
+        let last = self.data.last()?;
+        Some(self.last_id.as_deref().unwrap_or(&last.id))
+    }
+}
+
 /// Response from agent chat
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentChatResponse {
@@ -247,6 +292,19 @@ mod tests {
         assert!(parsed.success);
     }
 
+    #[test]
+    fn test_agent_list_next_cursor() {
+        let page = |json: &str| serde_json::from_str::<AgentList>(json).unwrap();
+        let more =
+            page(r#"{"data":[{"id":"a1","name":"x"},{"id":"a2","name":"y"}],"has_more":true}"#);
+        assert_eq!(more.next_cursor(), Some("a2"));
+        let last = page(r#"{"data":[{"id":"a3","name":"z"}],"has_more":false}"#);
+        assert_eq!(last.next_cursor(), None);
+        let empty = page(r#"{"data":[],"has_more":true,"last_id":"a9"}"#);
+        assert_eq!(empty.next_cursor(), None);
+        assert_eq!(page("{}").next_cursor(), None);
+    }
+
     #[test]
     fn test_conversation_history_serde_roundtrip() {
         let history = ConversationHistory {
@@ -0,0 +1,375 @@
+//! Typed construction of batch input files.
+//!
+//! [`BatchBuilder`] collects request lines for a single [`BatchEndpoint`],
+//! validates every line against that endpoint when [`BatchBuilder::build`] is
+//! called, and produces a [`BatchInput`] that serializes to the `.jsonl`
+//! format expected by the batch API. Catching mismatched lines locally avoids
+//! uploading a file that the server would reject as a whole.
+//!
+//! ```rust,ignore
+//! use zai_rs::batches::*;
+//! use zai_rs::model::{chat_base_request::ChatBody, *};
+//!
+//! let input = BatchBuilder::new(BatchEndpoint::ChatCompletions)
+//!     .add_chat_request("request-1", ChatBody::new(GLM4_5_flash {}, TextMessage::user("hi")))
+//!     .build()?;
+//! std::fs::write("batch.jsonl", input.to_jsonl())?;
+//! ```
+
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::create::{BatchEndpoint, CreateBatchRequest};
+use crate::{
+    ZaiError, ZaiResult,
+    model::{
+        chat_base_request::ChatBody,
+        traits::{Bounded, ModelName},
+    },
+};
+
+/// The only completion window accepted by the batch API.
+pub const COMPLETION_WINDOW_24H: &str = "24h";
+
+/// Check a `completion_window` value against the documented `24h` format.
+pub fn validate_completion_window(window: &str) -> ZaiResult<()> {
+    if window == COMPLETION_WINDOW_24H {
+        Ok(())
+    } else {
+        Err(ZaiError::ApiError {
+            code: 1200,
+            message: format!(
+                "invalid completion_window '{}': expected '{}'",
+                window, COMPLETION_WINDOW_24H
+            ),
+        })
+    }
+}
+
+/// Builder for batch input files targeting a single endpoint.
+#[derive(Debug, Clone)]
+pub struct BatchBuilder {
+    endpoint: BatchEndpoint,
+    completion_window: String,
+    lines: Vec<Result<Value, String>>,
+}
+
+impl BatchBuilder {
+    /// Start a batch for the given endpoint
+    pub fn new(endpoint: BatchEndpoint) -> Self {
+        Self {
+            endpoint,
+            completion_window: COMPLETION_WINDOW_24H.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Set the completion window (validated at build time)
+    pub fn with_completion_window(mut self, window: impl Into<String>) -> Self {
+        self.completion_window = window.into();
+        self
+    }
+
+    /// Add a chat completion request
+    pub fn add_chat_request<N, M>(self, custom_id: impl Into<String>, body: ChatBody<N, M>) -> Self
+    where
+        N: ModelName + Serialize,
+        M: Serialize,
+        (N, M): Bounded,
+    {
+        let custom_id = custom_id.into();
+        let line = serde_json::to_value(&body)
+            .map(|body| {
+                json!({
+                    "custom_id": custom_id,
+                    "method": "POST",
+                    "url": BatchEndpoint::ChatCompletions.as_str(),
+                    "body": body,
+                })
+            })
+            .map_err(|e| format!("failed to serialize request: {}", e));
+        self.push(line)
+    }
+
+    /// Add a raw request line (`{"custom_id", "method", "url", "body"}`).
+    ///
+    /// `method` and `url` are filled in from the builder's endpoint when
+    /// omitted.
+    pub fn add_request_raw(self, line: Value) -> Self {
+        self.push(Ok(line))
+    }
+
+    fn push(mut self, line: Result<Value, String>) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    /// Number of request lines added so far
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Whether no request lines were added
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Validate all lines and produce the batch input.
+    ///
+    /// Lines are validated in parallel. On failure the error message lists
+    /// every offending index with its reason.
+    pub fn build(self) -> ZaiResult<BatchInput> {
+        validate_completion_window(&self.completion_window)?;
+        if self.lines.is_empty() {
+            return Err(ZaiError::ApiError {
+                code: 1200,
+                message: "batch must contain at least one request".to_string(),
+            });
+        }
+
+        let endpoint = self.endpoint.clone();
+        let checked: Vec<Result<Value, String>> = self
+            .lines
+            .into_par_iter()
+            .map(|line| line.and_then(|line| normalize_line(&endpoint, line)))
+            .collect();
+
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+        let mut lines = Vec::with_capacity(checked.len());
+        for (index, line) in checked.into_iter().enumerate() {
+            match line {
+                Ok(line) => {
+                    let id = line["custom_id"].as_str().unwrap_or_default().to_string();
+                    if !seen.insert(id.clone()) {
+                        errors.push(format!("[{}] duplicate custom_id '{}'", index, id));
+                    }
+                    lines.push(line);
+                },
+                Err(reason) => errors.push(format!("[{}] {}", index, reason)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ZaiError::ApiError {
+                code: 1200,
+                message: format!(
+                    "batch requests do not match endpoint {}: {}",
+                    self.endpoint.as_str(),
+                    errors.join("; ")
+                ),
+            });
+        }
+
+        Ok(BatchInput {
+            endpoint: self.endpoint,
+            completion_window: self.completion_window,
+            lines,
+        })
+    }
+}
+
+/// Validate a single line against `endpoint`, filling in defaults.
+fn normalize_line(endpoint: &BatchEndpoint, mut line: Value) -> Result<Value, String> {
+    let obj = line
+        .as_object_mut()
+        .ok_or_else(|| "request line must be a JSON object".to_string())?;
+
+    match obj.get("custom_id").and_then(Value::as_str) {
+        Some(id) if !id.is_empty() => {},
+        _ => return Err("missing custom_id".to_string()),
+    }
+
+    let method = obj.entry("method").or_insert_with(|| json!("POST"));
+    if method != "POST" {
+        return Err(format!("unsupported method {}", method));
+    }
+
+    let url = obj.entry("url").or_insert_with(|| json!(endpoint.as_str()));
+    if url != endpoint.as_str() {
+        return Err(format!("url {} does not match endpoint", url));
+    }
+
+    let body = obj
+        .get("body")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "missing body object".to_string())?;
+    if !body.get("model").is_some_and(Value::is_string) {
+        return Err("body.model must be a string".to_string());
+    }
+
+    match endpoint {
+        BatchEndpoint::ChatCompletions => {
+            if body
+                .get("messages")
+                .and_then(Value::as_array)
+                .is_none_or(|m| m.is_empty())
+            {
+                return Err("chat request requires non-empty body.messages".to_string());
+            }
+            if body.contains_key("input") {
+                return Err("chat request must not contain body.input".to_string());
+            }
+        },
+        BatchEndpoint::Embeddings => {
+            if !body.contains_key("input") {
+                return Err("embedding request requires body.input".to_string());
+            }
+            if body.contains_key("messages") {
+                return Err("embedding request must not contain body.messages".to_string());
+            }
+        },
+    }
+
+    Ok(line)
+}
+
+/// Validated batch input produced by [`BatchBuilder::build`].
+#[derive(Debug, Clone)]
+pub struct BatchInput {
+    endpoint: BatchEndpoint,
+    completion_window: String,
+    lines: Vec<Value>,
+}
+
+impl BatchInput {
+    /// Endpoint shared by every line
+    pub fn endpoint(&self) -> &BatchEndpoint {
+        &self.endpoint
+    }
+
+    /// Completion window for the batch
+    pub fn completion_window(&self) -> &str {
+        &self.completion_window
+    }
+
+    /// Validated request lines
+    pub fn lines(&self) -> &[Value] {
+        &self.lines
+    }
+
+    /// Serialize as `.jsonl` (one request per line)
+    pub fn to_jsonl(&self) -> String {
+        let mut out = String::new();
+        for line in &self.lines {
+            out.push_str(&line.to_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Build the create-batch request for an uploaded copy of this input
+    pub fn create_request(
+        &self,
+        key: String,
+        input_file_id: impl Into<String>,
+    ) -> CreateBatchRequest {
+        CreateBatchRequest::new(key, input_file_id, self.endpoint.clone())
+            .with_completion_window(self.completion_window.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{chat_message_types::TextMessage, chat_models::GLM4_5_flash};
+
+    fn chat_body() -> ChatBody<GLM4_5_flash, TextMessage> {
+        ChatBody::new(GLM4_5_flash {}, TextMessage::user("hello"))
+    }
+
+    #[test]
+    fn test_build_chat_batch() {
+        let input = BatchBuilder::new(BatchEndpoint::ChatCompletions)
+            .add_chat_request("r1", chat_body())
+            .add_request_raw(json!({
+                "custom_id": "r2",
+                "body": {"model": "glm-4", "messages": [{"role": "user", "content": "hi"}]}
+            }))
+            .build()
+            .unwrap();
+
+        assert_eq!(input.lines().len(), 2);
+        assert_eq!(input.lines()[1]["url"], "/v4/chat/completions");
+        assert_eq!(input.lines()[1]["method"], "POST");
+        let jsonl = input.to_jsonl();
+        assert_eq!(jsonl.lines().count(), 2);
+        assert!(
+            jsonl
+                .lines()
+                .next()
+                .unwrap()
+                .contains("\"model\":\"glm-4.5-flash\"")
+        );
+    }
+
+    #[test]
+    fn test_build_reports_mismatched_indices() {
+        let err = BatchBuilder::new(BatchEndpoint::ChatCompletions)
+            .add_chat_request("r0", chat_body())
+            .add_request_raw(json!({
+                "custom_id": "r1",
+                "url": "/v4/embeddings",
+                "body": {"model": "embedding-3", "input": "hi"}
+            }))
+            .add_chat_request("r2", chat_body())
+            .add_request_raw(
+                json!({"custom_id": "r3", "body": {"model": "embedding-3", "input": "x"}}),
+            )
+            .build()
+            .unwrap_err();
+
+        let msg = err.message();
+        assert!(msg.contains("[1]"));
+        assert!(msg.contains("[3]"));
+        assert!(!msg.contains("[0]"));
+        assert!(!msg.contains("[2]"));
+    }
+
+    #[test]
+    fn test_build_embedding_batch_rejects_chat() {
+        let err = BatchBuilder::new(BatchEndpoint::Embeddings)
+            .add_request_raw(
+                json!({"custom_id": "e0", "body": {"model": "embedding-3", "input": "a"}}),
+            )
+            .add_chat_request("c1", chat_body())
+            .build()
+            .unwrap_err();
+        assert!(err.message().contains("[1]"));
+    }
+
+    #[test]
+    fn test_build_rejects_duplicate_custom_id() {
+        let err = BatchBuilder::new(BatchEndpoint::ChatCompletions)
+            .add_chat_request("same", chat_body())
+            .add_chat_request("same", chat_body())
+            .build()
+            .unwrap_err();
+        assert!(err.message().contains("duplicate custom_id"));
+    }
+
+    #[test]
+    fn test_completion_window() {
+        assert!(validate_completion_window("24h").is_ok());
+        assert!(validate_completion_window("48h").is_err());
+        assert!(
+            BatchBuilder::new(BatchEndpoint::ChatCompletions)
+                .with_completion_window("1d")
+                .add_chat_request("r", chat_body())
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_empty_fails() {
+        assert!(
+            BatchBuilder::new(BatchEndpoint::ChatCompletions)
+                .build()
+                .is_err()
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use super::types::BatchItem;
-use crate::{ZaiResult, client::http::HttpClient};
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
 
 /// Empty body for cancel API (serializes to `{}`)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -11,6 +14,8 @@ pub struct CancelBatchBody {}
 pub struct CancelBatchRequest {
     /// Bearer API key
     pub key: String,
+    /// Base URL override
+    endpoint: Option<Endpoint>,
     /// Full URL including path parameter
     url: String,
     /// Empty JSON body
@@ -26,11 +31,18 @@ impl CancelBatchRequest {
         );
         Self {
             key,
+            endpoint: None,
             url,
             body: CancelBatchBody::default(),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     /// Send the request and parse typed response
     pub async fn send(&self) -> ZaiResult<CancelBatchResponse> {
         let resp: reqwest::Response = self.post().await?;
@@ -53,6 +65,9 @@ impl HttpClient for CancelBatchRequest {
     fn body(&self) -> &Self::Body {
         &self.body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
 
 /// Response type: a single Batch object
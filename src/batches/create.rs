@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::Validate;
 
-use crate::{ZaiResult, client::http::HttpClient};
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
 
 /// Endpoint for batch requests
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +13,19 @@ pub enum BatchEndpoint {
     /// Chat completions endpoint
     #[serde(rename = "/v4/chat/completions")]
     ChatCompletions,
+    /// Embeddings endpoint
+    #[serde(rename = "/v4/embeddings")]
+    Embeddings,
+}
+
+impl BatchEndpoint {
+    /// Get the endpoint path as used in batch request lines
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChatCompletions => "/v4/chat/completions",
+            Self::Embeddings => "/v4/embeddings",
+        }
+    }
 }
 
 /// Request body for creating a batch task
@@ -26,6 +42,10 @@ pub struct CreateBatchBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auto_delete_input_file: Option<bool>,
 
+    /// Time window for completing the batch; only "24h" is accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_window: Option<String>,
+
     /// Arbitrary metadata for task management and tracking (up to 16 kv pairs)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Value>,
@@ -37,6 +57,7 @@ impl CreateBatchBody {
             input_file_id: input_file_id.into(),
             endpoint,
             auto_delete_input_file: Some(true),
+            completion_window: None,
             metadata: None,
         }
     }
@@ -47,6 +68,12 @@ impl CreateBatchBody {
         self
     }
 
+    /// Set completion window (must be "24h")
+    pub fn with_completion_window(mut self, v: impl Into<String>) -> Self {
+        self.completion_window = Some(v.into());
+        self
+    }
+
     /// Set metadata object
     pub fn with_metadata(mut self, v: Value) -> Self {
         self.metadata = Some(v);
@@ -57,6 +84,7 @@ impl CreateBatchBody {
 /// Create batch request (POST /paas/v4/batches)
 pub struct CreateBatchRequest {
     pub key: String,
+    endpoint: Option<Endpoint>,
     pub body: CreateBatchBody,
 }
 
@@ -64,7 +92,17 @@ impl CreateBatchRequest {
     /// Build a new create-batch request with required fields
     pub fn new(key: String, input_file_id: impl Into<String>, endpoint: BatchEndpoint) -> Self {
         let body = CreateBatchBody::new(input_file_id, endpoint);
-        Self { key, body }
+        Self {
+            key,
+            endpoint: None,
+            body,
+        }
+    }
+
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
     }
 
     /// Set auto-delete flag (default true)
@@ -73,6 +111,12 @@ impl CreateBatchRequest {
         self
     }
 
+    /// Set completion window (must be "24h")
+    pub fn with_completion_window(mut self, v: impl Into<String>) -> Self {
+        self.body = self.body.with_completion_window(v);
+        self
+    }
+
     /// Set metadata object
     pub fn with_metadata(mut self, v: serde_json::Value) -> Self {
         self.body = self.body.with_metadata(v);
@@ -81,7 +125,11 @@ impl CreateBatchRequest {
 
     /// Validate body using `validator`
     pub fn validate(&self) -> ZaiResult<()> {
-        self.body.validate().map_err(|e| e.into())
+        self.body.validate()?;
+        if let Some(window) = &self.body.completion_window {
+            super::builder::validate_completion_window(window)?;
+        }
+        Ok(())
     }
 
     /// Send request and parse typed response
@@ -109,6 +157,9 @@ impl HttpClient for CreateBatchRequest {
     fn body(&self) -> &Self::Body {
         &self.body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
 
 /// Response type for creating a batch task (same as a single item)
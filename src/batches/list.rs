@@ -3,7 +3,10 @@ use url::Url;
 use validator::Validate;
 
 use super::types::BatchItem;
-use crate::{ZaiResult, client::http::HttpClient};
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
 
 /// Query parameters for listing batch processing tasks
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -50,6 +53,8 @@ impl BatchesListQuery {
 pub struct BatchesListRequest {
     /// Bearer API key
     pub key: String,
+    /// Base URL override
+    endpoint: Option<Endpoint>,
     /// Fully built request URL (with query string)
     url: String,
     /// No body for GET
@@ -62,11 +67,18 @@ impl BatchesListRequest {
         let url = "https://open.bigmodel.cn/api/paas/v4/batches".to_string();
         Self {
             key,
+            endpoint: None,
             url,
             _body: (),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     /// Rebuild URL with query parameters
     fn rebuild_url(&mut self, q: &BatchesListQuery) {
         let mut url = Url::parse("https://open.bigmodel.cn/api/paas/v4/batches").unwrap();
@@ -112,6 +124,9 @@ impl HttpClient for BatchesListRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
 
 /// Response for listing batch processing tasks
@@ -6,9 +6,11 @@
 //!
 //! # Operations
 //!
+//! - [`builder`] — Build and validate batch input files per endpoint
 //! - [`create`] — Create a new batch job
 //! - [`list`] — List batch jobs with filtering
 //! - [`retrieve`] — Retrieve a batch job's status and results
+//! - [`results`] — Download and parse a finished job's output and error files
 //! - [`cancel`] — Cancel a running or queued batch job
 //!
 //! # Batch Lifecycle
@@ -32,19 +34,26 @@
 //! // Retrieve
 //! let result = client.retrieve_batch(&BatchesRetrieveRequest::new(&job.id)).await?;
 //!
+//! // Results, once completed
+//! let lines = result.download_results(&api_key).await?;
+//!
 //! // Cancel
 //! client.cancel_batch(&CancelBatchRequest::new(&job.id)).await?;
 //! ```
 
+pub mod builder;
 pub mod cancel;
 pub mod create;
 pub mod list;
+pub mod results;
 pub mod retrieve;
 mod types;
 
 // Re-export selected API types for convenient access via `zai_rs::batches::*`
+pub use builder::{BatchBuilder, BatchInput, COMPLETION_WINDOW_24H, validate_completion_window};
 pub use cancel::{CancelBatchRequest, CancelBatchResponse};
 pub use create::{BatchEndpoint, CreateBatchBody, CreateBatchRequest, CreateBatchResponse};
 pub use list::{BatchesListQuery, BatchesListRequest, BatchesListResponse, ListObject};
+pub use results::{BatchLineError, BatchResultLine, parse_batch_results};
 pub use retrieve::{BatchesRetrieveRequest, BatchesRetrieveResponse};
 pub use types::BatchItem;
@@ -0,0 +1,240 @@
+//! Downloading and parsing batch results.
+//!
+//! A finished batch points at an output file (successful requests) and an
+//! error file (failed requests), both JSONL with one line per request:
+//!
+//! ```text
+//! {"custom_id":"r1","id":"...","response":{"status_code":200,"body":{...chat completion...}}}
+//! {"custom_id":"r2","response":{"status_code":400,"body":{"error":{"code":"1214","message":"..."}}}}
+//! ```
+//!
+//! ```rust,ignore
+//! let batch = BatchesRetrieveRequest::new(key.clone(), batch_id).send().await?;
+//! for line in batch.download_results(&key).await? {
+//!     match line.result {
+//!         Ok(reply) => println!("{}: {:?}", line.custom_id, reply.final_text()),
+//!         Err(e) => eprintln!("{}: {}", line.custom_id, e.message),
+//!     }
+//! }
+//! ```
+
+use serde_json::Value;
+
+use super::types::BatchItem;
+use crate::{
+    ZaiError, ZaiResult, client::endpoint::Endpoint, file::FileContentRequest,
+    model::chat_base_response::ChatCompletionResponse,
+};
+
+/// Outcome of one request in a batch.
+#[derive(Debug, Clone)]
+pub struct BatchResultLine {
+    /// The `custom_id` given in the input file
+    pub custom_id: String,
+    /// Server-assigned id of the line, if present
+    pub id: Option<String>,
+    /// The completion, or why the request failed
+    pub result: Result<ChatCompletionResponse, BatchLineError>,
+}
+
+/// Error reported for one request in a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchLineError {
+    /// HTTP status of the request, if reported
+    pub status_code: Option<u16>,
+    /// API error code, if reported
+    pub code: Option<String>,
+    /// Error message; the raw response body when the line has a non-2xx
+    /// status but no error object
+    pub message: String,
+}
+
+impl std::fmt::Display for BatchLineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.code, self.status_code) {
+            (Some(code), _) => write!(f, "[{}] {}", code, self.message),
+            (None, Some(status)) => write!(f, "HTTP {}: {}", status, self.message),
+            (None, None) => f.write_str(&self.message),
+        }
+    }
+}
+
+impl BatchItem {
+    /// Download the output file and parse one result per request.
+    ///
+    /// Fails if the batch has no `output_file_id` yet (it is set once the
+    /// batch completes).
+    pub async fn download_results(
+        &self,
+        api_key: impl Into<String>,
+    ) -> ZaiResult<Vec<BatchResultLine>> {
+        download(
+            self.output_file_id.as_deref(),
+            "output",
+            api_key.into(),
+            None,
+        )
+        .await
+    }
+
+    /// Like [`download_results`](Self::download_results), against another
+    /// API base URL.
+    pub async fn download_results_with_endpoint(
+        &self,
+        api_key: impl Into<String>,
+        endpoint: Endpoint,
+    ) -> ZaiResult<Vec<BatchResultLine>> {
+        let file_id = self.output_file_id.as_deref();
+        download(file_id, "output", api_key.into(), Some(endpoint)).await
+    }
+
+    /// Download the error file and parse one result per failed request.
+    ///
+    /// Fails if the batch has no `error_file_id`, which is the case when no
+    /// request failed.
+    pub async fn download_errors(
+        &self,
+        api_key: impl Into<String>,
+    ) -> ZaiResult<Vec<BatchResultLine>> {
+        download(self.error_file_id.as_deref(), "error", api_key.into(), None).await
+    }
+
+    /// Like [`download_errors`](Self::download_errors), against another API
+    /// base URL.
+    pub async fn download_errors_with_endpoint(
+        &self,
+        api_key: impl Into<String>,
+        endpoint: Endpoint,
+    ) -> ZaiResult<Vec<BatchResultLine>> {
+        let file_id = self.error_file_id.as_deref();
+        download(file_id, "error", api_key.into(), Some(endpoint)).await
+    }
+}
+
+async fn download(
+    file_id: Option<&str>,
+    kind: &str,
+    api_key: String,
+    endpoint: Option<Endpoint>,
+) -> ZaiResult<Vec<BatchResultLine>> {
+    let file_id = file_id.ok_or_else(|| ZaiError::ApiError {
+        code: 1200,
+        message: format!("batch has no {} file", kind),
+    })?;
+    let mut request = FileContentRequest::new(api_key, file_id);
+    if let Some(endpoint) = endpoint {
+        request = request.with_endpoint(endpoint);
+    }
+    let bytes = request.send().await?;
+    parse_batch_results(&String::from_utf8_lossy(&bytes))
+}
+
+/// Parse a batch output or error file. Blank lines are skipped; a line that
+/// is not valid JSON fails the whole parse.
+pub fn parse_batch_results(jsonl: &str) -> ZaiResult<Vec<BatchResultLine>> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(parse_line(serde_json::from_str(line)?)))
+        .collect()
+}
+
+fn parse_line(line: Value) -> BatchResultLine {
+    let custom_id = line["custom_id"].as_str().unwrap_or_default().to_string();
+    let id = line["id"].as_str().map(str::to_string);
+    let response = &line["response"];
+    let status_code = response["status_code"].as_u64().map(|s| s as u16);
+    let body = &response["body"];
+
+    let error = [&line["error"], &body["error"]]
+        .into_iter()
+        .find(|e| !e.is_null())
+        .cloned();
+    let result = match error {
+        Some(error) => Err(line_error(status_code, &error)),
+        None if status_code.is_some_and(|s| !(200..300).contains(&s)) => Err(BatchLineError {
+            status_code,
+            code: None,
+            message: body.to_string(),
+        }),
+        None => serde_json::from_value(body.clone()).map_err(|e| BatchLineError {
+            status_code,
+            code: None,
+            message: format!("unparseable response body: {}", e),
+        }),
+    };
+    BatchResultLine {
+        custom_id,
+        id,
+        result,
+    }
+}
+
+fn line_error(status_code: Option<u16>, error: &Value) -> BatchLineError {
+    let code = match &error["code"] {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    let message = match &error["message"] {
+        Value::String(s) => s.clone(),
+        _ => error.to_string(),
+    };
+    BatchLineError {
+        status_code,
+        code,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::test_server::{Reply, serve};
+
+    #[test]
+    fn test_parse_batch_results() {
+        let jsonl = r#"{"custom_id":"r1","id":"line-1","response":{"status_code":200,"body":{"id":"c1","choices":[{"index":0,"finish_reason":"stop","message":{"role":"assistant","content":"hello"}}]}}}
+
+{"custom_id":"r2","response":{"status_code":400,"body":{"error":{"code":"1214","message":"bad messages"}}}}
+{"custom_id":"r3","error":{"code":1301,"message":"unsafe content"}}
+"#;
+        let lines = parse_batch_results(jsonl).unwrap();
+        assert_eq!(lines.len(), 3);
+
+        assert_eq!(lines[0].custom_id, "r1");
+        assert_eq!(lines[0].id.as_deref(), Some("line-1"));
+        let reply = lines[0].result.as_ref().unwrap();
+        assert_eq!(reply.final_text().as_deref(), Some("hello"));
+
+        let err = lines[1].result.as_ref().unwrap_err();
+        assert_eq!(err.status_code, Some(400));
+        assert_eq!(err.code.as_deref(), Some("1214"));
+        assert_eq!(err.to_string(), "[1214] bad messages");
+
+        let err = lines[2].result.as_ref().unwrap_err();
+        assert_eq!(err.code.as_deref(), Some("1301"));
+        assert_eq!(err.message, "unsafe content");
+
+        assert!(parse_batch_results("{not json}\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_results_with_endpoint() {
+        let jsonl = r#"{"custom_id":"r1","error":{"code":1301,"message":"unsafe content"}}"#;
+        let (base, mut requests) =
+            serve(move |_| Reply::status(200).body("application/octet-stream", jsonl)).await;
+        let batch: BatchItem =
+            serde_json::from_value(serde_json::json!({"id": "b1", "output_file_id": "f1"}))
+                .unwrap();
+
+        let lines = batch
+            .download_results_with_endpoint("key", Endpoint::new(format!("{}/api", base)))
+            .await
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].custom_id, "r1");
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request.path(), "/api/paas/v4/files/f1/content");
+    }
+}
@@ -1,10 +1,15 @@
 use super::types::BatchItem;
-use crate::{ZaiResult, client::http::HttpClient};
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
 
 /// Retrieve a batch task by ID (GET /paas/v4/batches/{batch_id})
 pub struct BatchesRetrieveRequest {
     /// Bearer API key
     pub key: String,
+    /// Base URL override
+    endpoint: Option<Endpoint>,
     /// Full URL with path parameter bound
     url: String,
     /// No body for GET
@@ -22,11 +27,18 @@ impl BatchesRetrieveRequest {
         );
         Self {
             key,
+            endpoint: None,
             url,
             _body: (),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     /// Send request and parse typed response as a single BatchItem
     pub async fn send(&self) -> ZaiResult<BatchesRetrieveResponse> {
         let resp: reqwest::Response = self.get().await?;
@@ -49,6 +61,9 @@ impl HttpClient for BatchesRetrieveRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
 
 /// Response type: a single Batch object
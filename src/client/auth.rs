@@ -0,0 +1,237 @@
+//! # Authentication
+//!
+//! Requests authenticate with either the raw API key or a short-lived JWT
+//! signed with it. Zhipu API keys have the form `{id}.{secret}`; the JWT
+//! carries the id and is signed with the secret (HS256), with millisecond
+//! `exp` and `timestamp` claims:
+//!
+//! ```text
+//! header:  {"alg":"HS256","sign_type":"SIGN"}
+//! payload: {"api_key":"<id>","exp":<ms>,"timestamp":<ms>}
+//! ```
+//!
+//! Tokens are cached per credential and regenerated shortly before they
+//! expire, so [`Auth::bearer_token`] can be called on every request. The
+//! cache holds at most [`MAX_CACHED_TOKENS`] credentials; expired tokens are
+//! dropped once it fills up.
+//!
+//! Only [`ChatCompletion`](crate::model::chat::data::ChatCompletion) and
+//! [`RealTimeClient`](crate::realTime::client::RealTimeClient) accept an
+//! [`Auth`] through `with_auth`; every other request type sends its API key
+//! as is.
+//!
+//! ```rust,ignore
+//! use zai_rs::client::Auth;
+//!
+//! let auth = Auth::jwt_from_api_key(api_key, Duration::from_secs(3600))?;
+//! let request = ChatCompletion::new(model, messages, api_key).with_auth(auth);
+//! ```
+
+use std::{
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use dashmap::DashMap;
+use ring::hmac;
+use serde_json::json;
+
+use crate::client::error::{ZaiError, ZaiResult};
+
+/// Upper bound on how long before expiry a cached token is replaced.
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// Most credentials whose tokens are cached at once.
+pub const MAX_CACHED_TOKENS: usize = 64;
+
+/// Signed tokens with their expiry (ms), keyed by `(id, secret, ttl)`.
+type TokenCache = DashMap<(String, String, Duration), (String, u128)>;
+
+/// How requests authenticate.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// Send the API key itself as the bearer token
+    ApiKey(String),
+
+    /// Send an HS256 JWT built from the key id and secret, valid for `ttl`
+    Jwt {
+        id: String,
+        secret: String,
+        ttl: Duration,
+    },
+}
+
+impl std::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::ApiKey(_) => f.debug_tuple("ApiKey").field(&"***").finish(),
+            Auth::Jwt { id, ttl, .. } => f
+                .debug_struct("Jwt")
+                .field("id", id)
+                .field("secret", &"***")
+                .field("ttl", ttl)
+                .finish(),
+        }
+    }
+}
+
+impl Auth {
+    /// JWT auth from an `{id}.{secret}` API key
+    pub fn jwt_from_api_key(api_key: &str, ttl: Duration) -> ZaiResult<Self> {
+        match api_key.split_once('.') {
+            Some((id, secret)) if !id.is_empty() && !secret.is_empty() => Ok(Auth::Jwt {
+                id: id.to_string(),
+                secret: secret.to_string(),
+                ttl,
+            }),
+            _ => Err(ZaiError::AuthError {
+                code: 1000,
+                message: "API key must have the form '{id}.{secret}' for JWT auth".to_string(),
+            }),
+        }
+    }
+
+    /// Token for the `Authorization: Bearer` header.
+    ///
+    /// For [`Auth::Jwt`] a cached token is returned while it has more than a
+    /// short margin (a tenth of `ttl`, at most 30s) left; otherwise a new one
+    /// is signed.
+    pub fn bearer_token(&self) -> ZaiResult<String> {
+        match self {
+            Auth::ApiKey(key) => Ok(key.clone()),
+            Auth::Jwt { id, secret, ttl } => {
+                let cache = token_cache();
+                let key = (id.clone(), secret.clone(), *ttl);
+                let now = now_millis();
+                let margin = (*ttl / 10).min(REFRESH_MARGIN).as_millis();
+
+                if let Some(entry) = cache.get(&key)
+                    && entry.1 > now + margin
+                {
+                    return Ok(entry.0.clone());
+                }
+                let exp = now + ttl.as_millis();
+                let token = sign_jwt(id, secret, exp, now);
+                if cache.len() >= MAX_CACHED_TOKENS && !cache.contains_key(&key) {
+                    cache.retain(|_, (_, expires)| *expires > now);
+                    if cache.len() >= MAX_CACHED_TOKENS {
+                        cache.clear();
+                    }
+                }
+                cache.insert(key, (token.clone(), exp));
+                Ok(token)
+            },
+        }
+    }
+}
+
+fn token_cache() -> &'static TokenCache {
+    static CACHE: OnceLock<TokenCache> = OnceLock::new();
+    CACHE.get_or_init(DashMap::new)
+}
+
+/// Sign a fresh JWT for `id`/`secret`, valid for `ttl` from now.
+pub fn generate_jwt(id: &str, secret: &str, ttl: Duration) -> String {
+    let now = now_millis();
+    sign_jwt(id, secret, now + ttl.as_millis(), now)
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn sign_jwt(id: &str, secret: &str, exp_ms: u128, now_ms: u128) -> String {
+    let header = json!({"alg": "HS256", "sign_type": "SIGN"});
+    let payload = json!({"api_key": id, "exp": exp_ms as u64, "timestamp": now_ms as u64});
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(payload.to_string())
+    );
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, signing_input.as_bytes());
+    format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.as_ref())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn decode(part: &str) -> Value {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jwt_structure() {
+        let token = sign_jwt("my-id", "my-secret", 1_700_000_060_000, 1_700_000_000_000);
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        assert_eq!(
+            decode(parts[0]),
+            json!({"alg": "HS256", "sign_type": "SIGN"})
+        );
+        assert_eq!(
+            decode(parts[1]),
+            json!({"api_key": "my-id", "exp": 1_700_000_060_000u64, "timestamp": 1_700_000_000_000u64})
+        );
+
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"my-secret");
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        let signature = URL_SAFE_NO_PAD.decode(parts[2]).unwrap();
+        assert!(hmac::verify(&key, signing_input.as_bytes(), &signature).is_ok());
+    }
+
+    #[test]
+    fn test_bearer_token_caches_until_near_expiry() {
+        let auth = Auth::jwt_from_api_key("cache-id.secret", Duration::from_secs(600)).unwrap();
+        let first = auth.bearer_token().unwrap();
+        assert_eq!(auth.bearer_token().unwrap(), first);
+        let claims = decode(first.split('.').nth(1).unwrap());
+        let ttl = claims["exp"].as_u64().unwrap() - claims["timestamp"].as_u64().unwrap();
+        assert_eq!(ttl, 600_000);
+
+        // A zero TTL is always within the refresh margin.
+        let short = Auth::Jwt {
+            id: "cache-id".to_string(),
+            secret: "secret".to_string(),
+            ttl: Duration::ZERO,
+        };
+        let a = short.bearer_token().unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        assert_ne!(short.bearer_token().unwrap(), a);
+    }
+
+    #[test]
+    fn test_token_cache_is_bounded() {
+        for i in 0..MAX_CACHED_TOKENS + 10 {
+            Auth::Jwt {
+                id: format!("bound-{}", i),
+                secret: "secret".to_string(),
+                ttl: Duration::from_secs(600),
+            }
+            .bearer_token()
+            .unwrap();
+        }
+        // Other tests may sign a token concurrently.
+        assert!(token_cache().len() <= MAX_CACHED_TOKENS + 2);
+    }
+
+    #[test]
+    fn test_api_key_auth() {
+        let auth = Auth::ApiKey("raw-key".to_string());
+        assert_eq!(auth.bearer_token().unwrap(), "raw-key");
+        assert!(!format!("{:?}", auth).contains("raw-key"));
+        assert!(Auth::jwt_from_api_key("no-secret", Duration::from_secs(60)).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! # Circuit Breaker
+//!
+//! Stops calling an endpoint that keeps failing. After
+//! `failure_threshold` consecutive failures the breaker *opens* and calls
+//! fail fast with [`ZaiError::CircuitOpen`] for `cooldown`. Once the
+//! cooldown has passed it is *half-open*: one probe call is let through,
+//! closing the breaker on success and re-opening it on failure.
+//!
+//! Only failures that suggest the endpoint is unhealthy (the ones requests
+//! are retried on: 5xx, rate limits, network errors) count; a rejected
+//! request such as a 400 leaves the breaker alone.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use zai_rs::client::circuit_breaker::CircuitBreakerConfig;
+//!
+//! let client = AgentClient::new(api_key)
+//!     .with_circuit_breaker(CircuitBreakerConfig::new(5, Duration::from_secs(30)));
+//! ```
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::client::{error::ZaiError, http::should_retry};
+
+/// When a [`CircuitBreaker`] opens and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+
+    /// How long the breaker stays open before letting a probe through
+    pub cooldown: Duration,
+}
+
+impl CircuitBreakerConfig {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+        }
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(30))
+    }
+}
+
+/// Observable state of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through; `failures` consecutive failures so far
+    Closed { failures: u32 },
+
+    /// Calls fail fast for `retry_after`
+    Open { retry_after: Duration },
+
+    /// The cooldown has passed; the next call probes the endpoint
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { probing: bool },
+}
+
+/// Thread-safe circuit breaker shared by the calls of one client.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn config(&self) -> CircuitBreakerConfig {
+        self.config
+    }
+
+    /// Current state, for metrics and health checks
+    pub fn state(&self) -> CircuitState {
+        match *self.inner.lock() {
+            Inner::Closed { failures } => CircuitState::Closed { failures },
+            Inner::Open { until } => match until.checked_duration_since(Instant::now()) {
+                Some(retry_after) if !retry_after.is_zero() => CircuitState::Open { retry_after },
+                _ => CircuitState::HalfOpen,
+            },
+            Inner::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Ask to make a call. Fails with [`ZaiError::CircuitOpen`] while the
+    /// breaker is open, or half-open with a probe already in flight.
+    pub fn acquire(&self) -> Result<(), ZaiError> {
+        let mut inner = self.inner.lock();
+        match *inner {
+            Inner::Closed { .. } => Ok(()),
+            Inner::Open { until } => {
+                let now = Instant::now();
+                if now < until {
+                    return Err(ZaiError::CircuitOpen {
+                        retry_after: until - now,
+                    });
+                }
+                *inner = Inner::HalfOpen { probing: true };
+                Ok(())
+            },
+            Inner::HalfOpen { probing: true } => Err(ZaiError::CircuitOpen {
+                retry_after: Duration::ZERO,
+            }),
+            Inner::HalfOpen { probing: false } => {
+                *inner = Inner::HalfOpen { probing: true };
+                Ok(())
+            },
+        }
+    }
+
+    /// Record the outcome of a call allowed by [`acquire`](Self::acquire).
+    pub fn record<T>(&self, result: &Result<T, ZaiError>) {
+        match result {
+            Ok(_) => self.record_success(),
+            Err(error) if counts_as_failure(error) => self.record_failure(),
+            // The endpoint answered; a rejected request says nothing about
+            // its health, but a half-open probe is over.
+            Err(_) => {
+                let mut inner = self.inner.lock();
+                if let Inner::HalfOpen { .. } = *inner {
+                    *inner = Inner::HalfOpen { probing: false };
+                }
+            },
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.inner.lock() = Inner::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock();
+        let open = Inner::Open {
+            until: Instant::now() + self.config.cooldown,
+        };
+        *inner = match *inner {
+            Inner::Closed { failures } if failures + 1 < self.config.failure_threshold => {
+                Inner::Closed {
+                    failures: failures + 1,
+                }
+            },
+            _ => open,
+        };
+    }
+}
+
+/// Whether an error suggests the endpoint itself is failing.
+fn counts_as_failure(error: &ZaiError) -> bool {
+    should_retry(error, 0, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::error::ZaiResult;
+
+    fn server_error() -> ZaiResult<()> {
+        Err(ZaiError::HttpError {
+            status: 503,
+            message: "unavailable".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_opens_after_threshold_and_half_opens_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(2, Duration::from_millis(20)));
+        breaker.acquire().unwrap();
+        breaker.record(&server_error());
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 1 });
+
+        // A client error does not count.
+        breaker.record::<()>(&Err(ZaiError::ApiError {
+            code: 1200,
+            message: "bad".to_string(),
+        }));
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 1 });
+
+        breaker.record(&server_error());
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+        assert!(matches!(
+            breaker.acquire(),
+            Err(ZaiError::CircuitOpen { .. })
+        ));
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.acquire().unwrap();
+        // Only one probe at a time.
+        assert!(breaker.acquire().is_err());
+        breaker.record(&server_error());
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+
+        std::thread::sleep(Duration::from_millis(25));
+        breaker.acquire().unwrap();
+        breaker.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+    }
+}
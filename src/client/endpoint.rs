@@ -0,0 +1,106 @@
+//! API base URL configuration.
+//!
+//! Every request type targets the production host by default. An
+//! [`Endpoint`] replaces the production base (`https://open.bigmodel.cn/api`)
+//! with another one, e.g. a regional deployment or a local mock server, while
+//! keeping each request's path:
+//!
+//! ```rust,ignore
+//! use zai_rs::client::Endpoint;
+//!
+//! let endpoint = Endpoint::new("http://127.0.0.1:8080/api");
+//! let chat = ChatCompletion::new(model, messages, key).with_endpoint(endpoint);
+//! // -> POST http://127.0.0.1:8080/api/paas/v4/chat/completions
+//! ```
+
+/// Production API base URL.
+pub const DEFAULT_BASE_URL: &str = "https://open.bigmodel.cn/api";
+
+/// Base URL that requests are sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    base_url: String,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::production()
+    }
+}
+
+impl Endpoint {
+    /// Use `base_url` in place of `https://open.bigmodel.cn/api`.
+    ///
+    /// A trailing slash is ignored.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self { base_url }
+    }
+
+    /// The production endpoint
+    pub fn production() -> Self {
+        Self::new(DEFAULT_BASE_URL)
+    }
+
+    /// Configured base URL, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Join `path` (relative to the base, e.g. `paas/v4/files`) onto the base
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// Move a production URL onto this endpoint.
+    ///
+    /// URLs that do not start with the production base (for example one set
+    /// explicitly with a request's `with_url`) are returned unchanged.
+    pub fn rebase(&self, url: &str) -> String {
+        match url.strip_prefix(DEFAULT_BASE_URL) {
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '?']) => {
+                format!("{}{}", self.base_url, rest)
+            },
+            _ => url.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_url() {
+        let endpoint = Endpoint::new("http://localhost:8080/api/");
+        assert_eq!(endpoint.base_url(), "http://localhost:8080/api");
+        assert_eq!(
+            endpoint.url("/paas/v4/files"),
+            "http://localhost:8080/api/paas/v4/files"
+        );
+        assert_eq!(
+            Endpoint::default().url("paas/v4/files"),
+            "https://open.bigmodel.cn/api/paas/v4/files"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_rebase() {
+        let endpoint = Endpoint::new("http://localhost:8080/api");
+        assert_eq!(
+            endpoint.rebase("https://open.bigmodel.cn/api/paas/v4/files?limit=1"),
+            "http://localhost:8080/api/paas/v4/files?limit=1"
+        );
+        assert_eq!(
+            endpoint.rebase("https://example.com/v1/chat"),
+            "https://example.com/v1/chat"
+        );
+        assert_eq!(
+            endpoint.rebase("https://open.bigmodel.cn/apix"),
+            "https://open.bigmodel.cn/apix"
+        );
+    }
+}
@@ -9,10 +9,16 @@
 //! | Variant | Code range | Description |
 //! |---------|------------|-------------|
 //! | [`ZaiError::AuthError`] | 1001–1099 | Authentication / authorization (invalid API key, etc.) |
+//! | [`ZaiError::Unauthorized`] | — | API key rejected by [`ping`](crate::client::ping) |
 //! | [`ZaiError::AccountError`] | 1100–1199 | Account-related (insufficient balance, etc.) |
 //! | [`ZaiError::RateLimitError`] | 1200–1299 | Rate-limit / quota errors |
 //! | [`ZaiError::ContentPolicyError`] | 1300–1399 | Content-policy violations |
+//! | [`ZaiError::ModerationBlocked`] | — | Input rejected by the local moderation pre-check |
 //! | [`ZaiError::FileError`] | 1400–1499 | File-processing errors |
+//! | [`ZaiError::DocumentEmbeddingFailed`] | — | Knowledge document failed to embed |
+//! | [`ZaiError::CircuitOpen`] | — | Call refused by an open circuit breaker |
+//! | [`ZaiError::ResponseTooLarge`] | — | Response body over the configured size limit |
+//! | [`ZaiError::WithRequestId`] | — | Another error, tagged with the request's `X-Request-Id` |
 //! | [`ZaiError::ApiError`] | other | General API errors |
 //! | [`ZaiError::NetworkError`] | — | Network / timeout errors |
 //! | [`ZaiError::JsonError`] | — | JSON serialization / deserialization errors |
@@ -48,6 +54,7 @@
 use std::sync::{Arc, LazyLock};
 
 use regex::Regex;
+use serde_json::Value;
 use thiserror::Error;
 
 /// Pre-compiled regex patterns for sensitive data masking (avoids recompilation
@@ -250,6 +257,11 @@ pub enum ZaiError {
     #[error("Authentication error [{code}]: {message}")]
     AuthError { code: u16, message: String },
 
+    /// The API key was rejected outright, as reported by
+    /// [`ping`](crate::client::ping)
+    #[error("Unauthorized: {message}")]
+    Unauthorized { message: String },
+
     /// Account-related errors
     #[error("Account error [{code}]: {message}")]
     AccountError { code: u16, message: String },
@@ -278,11 +290,69 @@ pub enum ZaiError {
     #[error("JSON error: {0}")]
     JsonError(Arc<serde_json::Error>),
 
+    /// Input rejected by the moderation pre-check before sending
+    #[error("Content blocked by moderation: {}", summarize_moderation(details))]
+    ModerationBlocked {
+        details: Vec<crate::model::moderation::ModerationResult>,
+    },
+
+    /// The model still requested tool calls after the allowed number of
+    /// tool-call rounds; `response` is the last response received
+    #[error("Tool-call rounds exhausted after {rounds} rounds with tool calls still pending")]
+    ToolRoundsExhausted {
+        rounds: usize,
+        response: Box<crate::model::chat_base_response::ChatCompletionResponse>,
+    },
+
+    /// A tool-call loop ran past its deadline during `round` (1-based);
+    /// `response` is the last response received, if any
+    #[error("Deadline exceeded during round {round}")]
+    DeadlineExceeded {
+        round: usize,
+        response: Option<Box<crate::model::chat_base_response::ChatCompletionResponse>>,
+    },
+
+    /// A knowledge-base document failed to parse or embed; `code` and
+    /// `reason` are the server's `failInfo`
+    #[error("Document {document_id} embedding failed: {reason}")]
+    DocumentEmbeddingFailed {
+        document_id: String,
+        code: Option<i64>,
+        reason: String,
+    },
+
+    /// The call was refused without being sent because the client's
+    /// [circuit breaker](crate::client::circuit_breaker) is open
+    #[error("Circuit open: endpoint failing, retry after {retry_after:?}")]
+    CircuitOpen { retry_after: std::time::Duration },
+
+    /// The response body exceeded
+    /// [`max_response_bytes`](crate::client::http::HttpClientConfig::max_response_bytes)
+    #[error("Response body exceeds the limit of {limit} bytes")]
+    ResponseTooLarge { limit: u64 },
+
+    /// `source` failed on a request sent with the `X-Request-Id`
+    /// `request_id`; the `is_*`, [`code`](Self::code) and
+    /// [`message`](Self::message) accessors look through to `source`
+    #[error("{source} [request_id: {request_id}]")]
+    WithRequestId {
+        request_id: String,
+        source: Box<ZaiError>,
+    },
+
     /// Other errors
     #[error("Unknown error [{code}]: {message}")]
     Unknown { code: u16, message: String },
 }
 
+fn summarize_moderation(details: &[crate::model::moderation::ModerationResult]) -> String {
+    details
+        .iter()
+        .map(|d| format!("{} {:?} {:?}", d.content_type, d.risk_level, d.risk_types))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl ZaiError {
     /// Convert an HTTP status code and API error response to a ZaiError
     pub fn from_api_response(status: u16, api_code: u16, api_message: String) -> Self {
@@ -363,23 +433,28 @@ impl ZaiError {
 
     /// Check if the error is a rate limit error
     pub fn is_rate_limit(&self) -> bool {
-        matches!(self, ZaiError::RateLimitError { .. })
+        matches!(self.inner(), ZaiError::RateLimitError { .. })
     }
 
     /// Check if the error is an authentication error
     pub fn is_auth_error(&self) -> bool {
-        matches!(self, ZaiError::AuthError { .. })
+        matches!(
+            self.inner(),
+            ZaiError::AuthError { .. } | ZaiError::Unauthorized { .. }
+        )
     }
 
     /// Check if the error is a client error (4xx)
     pub fn is_client_error(&self) -> bool {
-        match self {
+        match self.inner() {
             ZaiError::HttpError { status, .. } => *status >= 400 && *status < 500,
             ZaiError::AuthError { .. }
+            | ZaiError::Unauthorized { .. }
             | ZaiError::AccountError { .. }
             | ZaiError::ApiError { .. }
             | ZaiError::RateLimitError { .. }
             | ZaiError::ContentPolicyError { .. }
+            | ZaiError::ModerationBlocked { .. }
             | ZaiError::FileError { .. } => true,
             _ => false,
         }
@@ -387,7 +462,7 @@ impl ZaiError {
 
     /// Check if the error is a server error (5xx)
     pub fn is_server_error(&self) -> bool {
-        match self {
+        match self.inner() {
             ZaiError::HttpError { status, .. } => *status >= 500,
             ZaiError::Unknown { code, .. } => *code >= 500,
             _ => false,
@@ -403,6 +478,9 @@ impl ZaiError {
             ZaiError::AuthError { code, message } => {
                 format!("AUTH[{}]: {}", code, message)
             },
+            ZaiError::Unauthorized { message } => {
+                format!("UNAUTHORIZED: {}", message)
+            },
             ZaiError::AccountError { code, message } => {
                 format!("ACCOUNT[{}]: {}", code, message)
             },
@@ -424,6 +502,31 @@ impl ZaiError {
             ZaiError::JsonError(err) => {
                 format!("JSON: {}", err)
             },
+            ZaiError::ModerationBlocked { details } => {
+                format!("MODERATION: {}", summarize_moderation(details))
+            },
+            ZaiError::ToolRoundsExhausted { rounds, .. } => {
+                format!("TOOL_ROUNDS[{}]: tool calls still pending", rounds)
+            },
+            ZaiError::DeadlineExceeded { round, .. } => {
+                format!("DEADLINE[{}]: deadline exceeded", round)
+            },
+            ZaiError::DocumentEmbeddingFailed {
+                document_id,
+                reason,
+                ..
+            } => {
+                format!("EMBEDDING[{}]: {}", document_id, reason)
+            },
+            ZaiError::CircuitOpen { retry_after } => {
+                format!("CIRCUIT_OPEN: retry after {:?}", retry_after)
+            },
+            ZaiError::ResponseTooLarge { limit } => {
+                format!("TOO_LARGE: response over {} bytes", limit)
+            },
+            ZaiError::WithRequestId { request_id, source } => {
+                format!("{} [request_id: {}]", source.compact(), request_id)
+            },
             ZaiError::Unknown { code, message } => {
                 format!("UNKNOWN[{}]: {}", code, message)
             },
@@ -435,6 +538,7 @@ impl ZaiError {
         match self {
             ZaiError::HttpError { status, .. } => Some(*status),
             ZaiError::AuthError { code, .. } => Some(*code),
+            ZaiError::Unauthorized { .. } => Some(401),
             ZaiError::AccountError { code, .. } => Some(*code),
             ZaiError::ApiError { code, .. } => Some(*code),
             ZaiError::RateLimitError { code, .. } => Some(*code),
@@ -442,6 +546,13 @@ impl ZaiError {
             ZaiError::FileError { code, .. } => Some(*code),
             ZaiError::NetworkError(_) => None,
             ZaiError::JsonError(_) => None,
+            ZaiError::ModerationBlocked { .. } => None,
+            ZaiError::ToolRoundsExhausted { .. } => None,
+            ZaiError::DeadlineExceeded { .. } => None,
+            ZaiError::DocumentEmbeddingFailed { code, .. } => code.and_then(|c| c.try_into().ok()),
+            ZaiError::CircuitOpen { .. } => None,
+            ZaiError::ResponseTooLarge { .. } => None,
+            ZaiError::WithRequestId { source, .. } => source.code(),
             ZaiError::Unknown { code, .. } => Some(*code),
         }
     }
@@ -451,6 +562,7 @@ impl ZaiError {
         match self {
             ZaiError::HttpError { message, .. } => message.clone(),
             ZaiError::AuthError { message, .. } => message.clone(),
+            ZaiError::Unauthorized { message } => message.clone(),
             ZaiError::AccountError { message, .. } => message.clone(),
             ZaiError::ApiError { message, .. } => message.clone(),
             ZaiError::RateLimitError { message, .. } => message.clone(),
@@ -458,11 +570,82 @@ impl ZaiError {
             ZaiError::FileError { message, .. } => message.clone(),
             ZaiError::NetworkError(err) => err.to_string(),
             ZaiError::JsonError(err) => err.to_string(),
+            ZaiError::ModerationBlocked { details } => summarize_moderation(details),
+            ZaiError::ToolRoundsExhausted { .. } => self.to_string(),
+            ZaiError::DeadlineExceeded { .. } => self.to_string(),
+            ZaiError::DocumentEmbeddingFailed { reason, .. } => reason.clone(),
+            ZaiError::CircuitOpen { .. } => self.to_string(),
+            ZaiError::ResponseTooLarge { .. } => self.to_string(),
+            ZaiError::WithRequestId { source, .. } => source.message(),
             ZaiError::Unknown { message, .. } => message.clone(),
         }
     }
 }
 
+impl ZaiError {
+    /// Tag the error with the `X-Request-Id` the failed request was sent
+    /// with, wrapping it in [`ZaiError::WithRequestId`]. An error that
+    /// already carries an id is returned unchanged.
+    pub fn with_request_id(self, request_id: impl Into<String>) -> Self {
+        match self {
+            ZaiError::WithRequestId { .. } => self,
+            source => ZaiError::WithRequestId {
+                request_id: request_id.into(),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// The client-generated request id of the failed request, if it was
+    /// sent with one. Quote it when contacting Zhipu support.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            ZaiError::WithRequestId { request_id, .. } => Some(request_id),
+            _ => None,
+        }
+    }
+
+    /// The error without its request id, for matching on the variant.
+    pub fn inner(&self) -> &ZaiError {
+        match self {
+            ZaiError::WithRequestId { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+}
+
+/// Code and message of an API error object, `{"code": ..., "message": ...}`
+/// or the same wrapped as `{"error": {...}}`, as sent in error responses and
+/// in stream and realtime `error` events. Codes may be numbers or strings;
+/// a missing or invalid code is 0 and a missing message is the object's
+/// JSON.
+pub(crate) fn api_error_parts(value: &Value) -> (u16, String) {
+    let error = value
+        .get("error")
+        .filter(|e| e.is_object())
+        .unwrap_or(value);
+    let code = match &error["code"] {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    let message = error["message"]
+        .as_str()
+        .map_or_else(|| error.to_string(), str::to_string);
+    (
+        code.and_then(|c| u16::try_from(c).ok()).unwrap_or(0),
+        message,
+    )
+}
+
+impl ZaiError {
+    /// `ApiError` from an API error object; see [`api_error_parts`]
+    pub(crate) fn from_error_object(value: &Value) -> Self {
+        let (code, message) = api_error_parts(value);
+        ZaiError::ApiError { code, message }
+    }
+}
+
 impl Clone for ZaiError {
     fn clone(&self) -> Self {
         match self {
@@ -474,6 +657,9 @@ impl Clone for ZaiError {
                 code: *code,
                 message: message.clone(),
             },
+            ZaiError::Unauthorized { message } => ZaiError::Unauthorized {
+                message: message.clone(),
+            },
             ZaiError::AccountError { code, message } => ZaiError::AccountError {
                 code: *code,
                 message: message.clone(),
@@ -497,6 +683,34 @@ impl Clone for ZaiError {
             // Arc-wrapped errors can now be cloned properly
             ZaiError::NetworkError(err) => ZaiError::NetworkError(Arc::clone(err)),
             ZaiError::JsonError(err) => ZaiError::JsonError(Arc::clone(err)),
+            ZaiError::ModerationBlocked { details } => ZaiError::ModerationBlocked {
+                details: details.clone(),
+            },
+            ZaiError::ToolRoundsExhausted { rounds, response } => ZaiError::ToolRoundsExhausted {
+                rounds: *rounds,
+                response: response.clone(),
+            },
+            ZaiError::DeadlineExceeded { round, response } => ZaiError::DeadlineExceeded {
+                round: *round,
+                response: response.clone(),
+            },
+            ZaiError::DocumentEmbeddingFailed {
+                document_id,
+                code,
+                reason,
+            } => ZaiError::DocumentEmbeddingFailed {
+                document_id: document_id.clone(),
+                code: *code,
+                reason: reason.clone(),
+            },
+            ZaiError::CircuitOpen { retry_after } => ZaiError::CircuitOpen {
+                retry_after: *retry_after,
+            },
+            ZaiError::ResponseTooLarge { limit } => ZaiError::ResponseTooLarge { limit: *limit },
+            ZaiError::WithRequestId { request_id, source } => ZaiError::WithRequestId {
+                request_id: request_id.clone(),
+                source: source.clone(),
+            },
             ZaiError::Unknown { code, message } => ZaiError::Unknown {
                 code: *code,
                 message: message.clone(),
@@ -662,6 +876,24 @@ mod tests {
         assert_eq!(err.message(), "Too many requests");
     }
 
+    #[test]
+    fn test_with_request_id() {
+        let err = ZaiError::RateLimitError {
+            code: 1302,
+            message: "slow down".to_string(),
+        }
+        .with_request_id("req-1")
+        .with_request_id("req-2");
+
+        assert_eq!(err.request_id(), Some("req-1"));
+        assert!(err.is_rate_limit());
+        assert_eq!(err.code(), Some(1302));
+        assert_eq!(err.message(), "slow down");
+        assert!(matches!(err.inner(), ZaiError::RateLimitError { .. }));
+        assert!(err.to_string().ends_with("[request_id: req-1]"), "{}", err);
+        assert_eq!(err.clone().request_id(), Some("req-1"));
+    }
+
     #[test]
     fn test_from_reqwest_error_with_status() {
         let io_err = std::io::Error::other("test error");
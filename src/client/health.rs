@@ -0,0 +1,118 @@
+//! # API Key Health Check
+//!
+//! [`ping`] verifies that an API key is accepted before a workload starts, so
+//! a bad key fails fast with [`ZaiError::Unauthorized`] instead of surfacing
+//! mid-run.
+//!
+//! The probe lists at most one uploaded file (`GET /files?limit=1`). It
+//! generates no tokens and is not billed, but it does count against the
+//! key's request rate limit.
+//!
+//! ```rust,ignore
+//! zai_rs::client::ping(&api_key).await?;
+//! ```
+
+use crate::{
+    client::{
+        endpoint::Endpoint,
+        error::{ZaiError, ZaiResult},
+    },
+    file::{FileListQuery, FileListRequest},
+};
+
+/// Check that `api_key` is accepted by the production API.
+///
+/// Returns [`ZaiError::Unauthorized`] when the key is rejected; other
+/// failures (network, server errors) are returned unchanged.
+pub async fn ping(api_key: &str) -> ZaiResult<()> {
+    probe(FileListRequest::new(api_key.to_string())).await
+}
+
+/// Like [`ping`], against another API base URL.
+pub async fn ping_with_endpoint(api_key: &str, endpoint: Endpoint) -> ZaiResult<()> {
+    probe(FileListRequest::new(api_key.to_string()).with_endpoint(endpoint)).await
+}
+
+async fn probe(request: FileListRequest) -> ZaiResult<()> {
+    match request
+        .with_query(FileListQuery::new().with_limit(1))
+        .send()
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => Err(rejected_key(e)),
+    }
+}
+
+/// Report a rejected key as [`ZaiError::Unauthorized`], keeping the request
+/// id of the failed request.
+fn rejected_key(err: ZaiError) -> ZaiError {
+    let message = match err.inner() {
+        ZaiError::HttpError {
+            status: 401,
+            message,
+        }
+        | ZaiError::AuthError { message, .. } => message.clone(),
+        _ => return err,
+    };
+    let unauthorized = ZaiError::Unauthorized { message };
+    match err.request_id() {
+        Some(id) => unauthorized.with_request_id(id),
+        None => unauthorized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::test_server::{Reply, serve};
+
+    #[tokio::test]
+    async fn test_ping_ok() {
+        let (base, mut requests) =
+            serve(|_| Reply::json(200, r#"{"object":"list","data":[]}"#)).await;
+        ping_with_endpoint("key", Endpoint::new(format!("{}/api", base)))
+            .await
+            .unwrap();
+        let request = requests.recv().await.unwrap();
+        assert!(
+            request.path().starts_with("/api/paas/v4/files?limit=1"),
+            "{}",
+            request.line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ping_maps_rejected_key_to_unauthorized() {
+        let (base, _) =
+            serve(|_| Reply::json(401, r#"{"error":{"code":"1000","message":"invalid key"}}"#))
+                .await;
+        let err = ping_with_endpoint("bad", Endpoint::new(format!("{}/api", base)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZaiError::Unauthorized { .. }), "{:?}", err);
+        assert!(err.is_auth_error());
+    }
+
+    #[test]
+    fn test_rejected_key_with_request_id() {
+        let err = ZaiError::HttpError {
+            status: 401,
+            message: "invalid key".to_string(),
+        }
+        .with_request_id("req-1");
+        let err = rejected_key(err);
+        assert!(
+            matches!(err.inner(), ZaiError::Unauthorized { .. }),
+            "{:?}",
+            err
+        );
+        assert_eq!(err.request_id(), Some("req-1"));
+
+        let other = rejected_key(ZaiError::HttpError {
+            status: 500,
+            message: "boom".to_string(),
+        });
+        assert!(matches!(other, ZaiError::HttpError { status: 500, .. }));
+    }
+}
@@ -32,55 +32,31 @@
 //!     .retry_delay(RetryDelay::exponential(Duration::from_millis(100), Duration::from_secs(10)))
 //!     .build();
 //! ```
+//!
+//! # Request Identification
+//!
+//! Every request carries a `User-Agent` (default `zai-rs/<version>`). With
+//! [`HttpClientConfigBuilder::request_id`] enabled, each request also gets a
+//! fresh `X-Request-Id`, which is attached to errors
+//! ([`ZaiError::request_id`]) and responses ([`response_request_id`]) so a
+//! failing call can be quoted to Zhipu support.
 
 use std::{
     sync::{Arc, OnceLock},
     time::Duration,
 };
 
-use serde::Deserialize;
-use tracing::{debug, info, warn};
-
-use crate::client::error::{ZaiError, ZaiResult, mask_sensitive_info};
-
-#[derive(Debug, Deserialize)]
-
-struct ApiErrorEnvelope {
-    error: ApiError,
-}
-
-#[derive(Debug, Deserialize)]
-
-struct ApiError {
-    code: ErrorCode,
-
-    message: String,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-enum ErrorCode {
-    Str(String),
-
-    Num(i64),
-}
-
-impl std::fmt::Display for ErrorCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ErrorCode::Str(s) => write!(f, "{}", s),
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
 
-            ErrorCode::Num(n) => write!(f, "{}", n),
-        }
-    }
-}
+#[cfg(feature = "tracing")]
+use super::logging::LogConfig;
 
-fn to_api_code(code: &ErrorCode) -> u16 {
-    match code {
-        ErrorCode::Num(n) => (*n).try_into().unwrap_or(0),
-        ErrorCode::Str(s) => s.parse::<u16>().unwrap_or(0),
-    }
-}
+use crate::client::{
+    auth::Auth,
+    endpoint::Endpoint,
+    error::{ZaiError, ZaiResult, api_error_parts},
+    transport::{Transport, TransportRequest},
+};
 
 /// Parse an API error response body into a ZaiError.
 ///
@@ -88,11 +64,12 @@ fn to_api_code(code: &ErrorCode) -> u16 {
 /// and maps it to the appropriate ZaiError variant. Falls back to a generic
 /// HttpError if parsing fails.
 pub fn parse_api_error_response(status: u16, body: String) -> crate::client::error::ZaiError {
-    if let Ok(parsed) = serde_json::from_str::<ApiErrorEnvelope>(&body) {
-        let api_code = to_api_code(&parsed.error.code);
-        crate::client::error::ZaiError::from_api_response(status, api_code, parsed.error.message)
-    } else {
-        crate::client::error::ZaiError::from_api_response(status, 0, body)
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(value) if value["error"].is_object() => {
+            let (api_code, message) = api_error_parts(&value);
+            crate::client::error::ZaiError::from_api_response(status, api_code, message)
+        },
+        _ => crate::client::error::ZaiError::from_api_response(status, 0, body),
     }
 }
 
@@ -135,6 +112,15 @@ impl Default for RetryDelay {
     }
 }
 
+/// `User-Agent` sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = concat!("zai-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Header carrying the client-generated request id.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Client-wide request settings; another name for [`HttpClientConfig`].
+pub type ClientConfig = HttpClientConfig;
+
 /// Configuration for HTTP client behavior.
 ///
 /// Use the builder pattern for fluent configuration:
@@ -163,11 +149,30 @@ pub struct HttpClientConfig {
     /// Retry delay strategy
     pub retry_delay: RetryDelay,
 
-    /// Enable detailed logging (default: false)
+    /// Log requests and responses as [`logging`](Self::logging) does, with
+    /// the default [`LogConfig`] at `INFO`, when that is unset (default:
+    /// false)
     pub enable_logging: bool,
 
-    /// Enable sensitive data masking in logs (default: true)
+    /// Kept for compatibility; logged data is always redacted
     pub mask_sensitive_data: bool,
+
+    /// `User-Agent` header (default: [`DEFAULT_USER_AGENT`])
+    pub user_agent: String,
+
+    /// Send a generated `X-Request-Id` with every request (default: false)
+    pub send_request_id: bool,
+
+    /// Largest response body read into memory, in bytes (default: `None`,
+    /// no limit). Bodies over the limit fail with
+    /// [`ZaiError::ResponseTooLarge`]. Event streams are read incrementally
+    /// and not limited.
+    pub max_response_bytes: Option<u64>,
+
+    /// Log requests and responses with secrets redacted (default: off); see
+    /// [`logging`](super::logging)
+    #[cfg(feature = "tracing")]
+    pub logging: Option<LogConfig>,
 }
 
 impl Default for HttpClientConfig {
@@ -179,6 +184,11 @@ impl Default for HttpClientConfig {
             retry_delay: RetryDelay::default(),
             enable_logging: false,
             mask_sensitive_data: true,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            send_request_id: false,
+            max_response_bytes: None,
+            #[cfg(feature = "tracing")]
+            logging: None,
         }
     }
 }
@@ -188,6 +198,260 @@ impl HttpClientConfig {
     pub fn builder() -> HttpClientConfigBuilder {
         HttpClientConfigBuilder::new()
     }
+
+    /// Log request and response bodies as configured by `log`, with the
+    /// API key and binary data redacted
+    #[cfg(feature = "tracing")]
+    pub fn with_logging(mut self, log: LogConfig) -> Self {
+        self.logging = Some(log);
+        self
+    }
+
+    /// Logging in effect: [`logging`](Self::logging), or the defaults at
+    /// `INFO` when only [`enable_logging`](Self::enable_logging) is set
+    #[cfg(feature = "tracing")]
+    fn log_config(&self) -> Option<std::borrow::Cow<'_, LogConfig>> {
+        use std::borrow::Cow;
+
+        match &self.logging {
+            Some(log) => Some(Cow::Borrowed(log)),
+            None if self.enable_logging => {
+                Some(Cow::Owned(LogConfig::default().level(tracing::Level::INFO)))
+            },
+            None => None,
+        }
+    }
+
+    /// Identification headers for one request, and the request id among
+    /// them if one was generated.
+    fn request_headers(&self) -> (reqwest::header::HeaderMap, Option<String>) {
+        use reqwest::header::USER_AGENT;
+
+        let mut headers = HeaderMap::new();
+        if let Ok(value) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, value);
+        }
+        let request_id = self
+            .send_request_id
+            .then(|| uuid::Uuid::new_v4().to_string());
+        if let Some(id) = &request_id
+            && let Ok(value) = HeaderValue::from_str(id)
+        {
+            headers.insert(REQUEST_ID_HEADER, value);
+        }
+        (headers, request_id)
+    }
+}
+
+/// Extra headers for one request, e.g. for a gateway that needs an
+/// `X-Org-Id`.
+///
+/// They are sent after the standard headers and replace any of the same
+/// name. `Authorization` is refused unless set with
+/// [`authorization`](Self::authorization), so a stray header cannot replace
+/// the API key by accident.
+#[derive(Clone, Default)]
+pub struct CustomHeaders {
+    headers: Vec<(String, String)>,
+    allow_authorization: bool,
+}
+
+impl CustomHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header; a name added twice is sent twice
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.headers.push((name.into(), value.into()));
+    }
+
+    /// Send `value` as the `Authorization` header instead of the bearer
+    /// token built from the API key
+    pub fn authorization(&mut self, value: impl Into<String>) {
+        self.headers.retain(|(name, _)| !is_authorization(name));
+        self.headers
+            .push((AUTHORIZATION.as_str().to_string(), value.into()));
+        self.allow_authorization = true;
+    }
+
+    /// These headers without an `Authorization` override
+    pub fn without_authorization(&self) -> Self {
+        Self {
+            headers: self
+                .headers
+                .iter()
+                .filter(|(name, _)| !is_authorization(name))
+                .cloned()
+                .collect(),
+            allow_authorization: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// The headers as a map; fails with a validation error (code 1200) for
+    /// an invalid name or value, or an `Authorization` header added with
+    /// [`insert`](Self::insert).
+    pub fn to_header_map(&self) -> ZaiResult<HeaderMap> {
+        let invalid = |message: String| ZaiError::ApiError {
+            code: 1200,
+            message,
+        };
+        let mut map = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| invalid(format!("invalid header name '{}'", name)))?;
+            if header == AUTHORIZATION && !self.allow_authorization {
+                return Err(invalid(
+                    "the Authorization header can only be replaced with an explicit authorization override"
+                        .to_string(),
+                ));
+            }
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| invalid(format!("invalid value for header '{}'", name)))?;
+            map.append(header, value);
+        }
+        Ok(map)
+    }
+}
+
+/// Values are not shown, as they may hold credentials.
+impl std::fmt::Debug for CustomHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.headers.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+fn is_authorization(name: &str) -> bool {
+    name.eq_ignore_ascii_case(AUTHORIZATION.as_str())
+}
+
+/// Request id stored in a response's extensions by [`HttpClient`] requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// The `X-Request-Id` the request behind `response` was sent with, if any.
+pub fn response_request_id(response: &reqwest::Response) -> Option<&str> {
+    response
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.as_str())
+}
+
+/// Read the body of a successful response into memory, failing once it
+/// grows past `limit`. A declared `Content-Length` over the limit fails
+/// before anything is read. Event streams and unlimited requests are passed
+/// through untouched.
+async fn limit_body(
+    result: ZaiResult<reqwest::Response>,
+    limit: Option<u64>,
+) -> ZaiResult<reqwest::Response> {
+    use reqwest::header::CONTENT_TYPE;
+
+    let (Ok(resp), Some(limit)) = (result.as_ref(), limit) else {
+        return result;
+    };
+    let is_event_stream = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if is_event_stream {
+        return result;
+    }
+    let mut resp = result?;
+    if resp.content_length().is_some_and(|len| len > limit) {
+        return Err(ZaiError::ResponseTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() as u64 + chunk.len() as u64 > limit {
+            return Err(ZaiError::ResponseTooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    rebuild_response(resp, body)
+}
+
+/// `resp` with its body replaced by `body`, which was read from it
+pub(crate) fn rebuild_response(
+    mut resp: reqwest::Response,
+    body: Vec<u8>,
+) -> ZaiResult<reqwest::Response> {
+    use reqwest::ResponseBuilderExt;
+
+    let mut builder = http::Response::builder()
+        .status(resp.status())
+        .version(resp.version())
+        .url(resp.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = std::mem::take(resp.headers_mut());
+    }
+    let rebuilt = builder.body(body).map_err(|e| ZaiError::Unknown {
+        code: 0,
+        message: e.to_string(),
+    })?;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+/// Log an outgoing request when logging is enabled.
+#[cfg(feature = "tracing")]
+fn log_request(
+    config: &HttpClientConfig,
+    method: &str,
+    url: &str,
+    headers: &HeaderMap,
+    body: Option<&str>,
+) {
+    if let Some(log) = config.log_config() {
+        super::logging::log_request(&log, method, url, headers, body);
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+fn log_request(_: &HttpClientConfig, _: &str, _: &str, _: &HeaderMap, _: Option<&str>) {}
+
+/// Log the outcome of a request when logging is enabled.
+#[cfg(feature = "tracing")]
+async fn log_response(
+    result: ZaiResult<reqwest::Response>,
+    config: &HttpClientConfig,
+) -> ZaiResult<reqwest::Response> {
+    match config.log_config() {
+        Some(log) => super::logging::log_response(result, &log).await,
+        None => result,
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+async fn log_response(
+    result: ZaiResult<reqwest::Response>,
+    _: &HttpClientConfig,
+) -> ZaiResult<reqwest::Response> {
+    result
+}
+
+/// Attach the request id to the outcome of a request.
+fn tag_request_id(
+    result: ZaiResult<reqwest::Response>,
+    request_id: Option<String>,
+) -> ZaiResult<reqwest::Response> {
+    let Some(id) = request_id else {
+        return result;
+    };
+    match result {
+        Ok(mut resp) => {
+            resp.extensions_mut().insert(RequestId(id));
+            Ok(resp)
+        },
+        Err(e) => Err(e.with_request_id(id)),
+    }
 }
 
 /// Builder for creating `HttpClientConfig` instances.
@@ -247,12 +511,30 @@ impl HttpClientConfigBuilder {
         self
     }
 
-    /// Enable or disable sensitive data masking in logs
+    /// Kept for compatibility; logged data is always redacted
     pub fn mask_sensitive_data(mut self, enable: bool) -> Self {
         self.config.mask_sensitive_data = enable;
         self
     }
 
+    /// Set the `User-Agent` header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    /// Send a generated `X-Request-Id` with every request
+    pub fn request_id(mut self, enable: bool) -> Self {
+        self.config.send_request_id = enable;
+        self
+    }
+
+    /// Limit response bodies to `limit` bytes (`None` for no limit)
+    pub fn max_response_bytes(mut self, limit: Option<u64>) -> Self {
+        self.config.max_response_bytes = limit;
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> HttpClientConfig {
         self.config
@@ -274,8 +556,8 @@ static HTTP_CLIENTS: OnceLock<dashmap::DashMap<String, reqwest::Client>> = OnceL
 /// Clients are cached by configuration to allow connection reuse.
 pub fn http_client_with_config(config: &HttpClientConfig) -> reqwest::Client {
     let config_key = format!(
-        "timeout:{:?}|compression:{}",
-        config.timeout, config.enable_compression
+        "timeout:{:?}|compression:{}|ua:{}",
+        config.timeout, config.enable_compression, config.user_agent
     );
 
     let clients = HTTP_CLIENTS.get_or_init(dashmap::DashMap::new);
@@ -283,7 +565,9 @@ pub fn http_client_with_config(config: &HttpClientConfig) -> reqwest::Client {
     clients
         .entry(config_key)
         .or_insert_with(|| {
-            let builder = reqwest::Client::builder().timeout(config.timeout);
+            let builder = reqwest::Client::builder()
+                .timeout(config.timeout)
+                .user_agent(config.user_agent.as_str());
 
             // Note: reqwest enables gzip compression by default
             // if config.enable_compression {
@@ -305,6 +589,57 @@ pub trait HttpClient {
     fn api_key(&self) -> &Self::ApiKey;
     fn body(&self) -> &Self::Body;
 
+    /// Base URL override for this request.
+    ///
+    /// Request types that support `with_endpoint` return the configured
+    /// endpoint; the default targets production.
+    fn endpoint(&self) -> Option<&Endpoint> {
+        None
+    }
+
+    /// Authentication override for this request.
+    ///
+    /// Request types that support `with_auth` return the configured
+    /// [`Auth`]; the default sends [`api_key`](Self::api_key) as is.
+    fn auth(&self) -> Option<&Auth> {
+        None
+    }
+
+    /// Transport override for this request.
+    ///
+    /// Request types that support `with_transport` return the configured
+    /// [`Transport`]; the default sends over the pooled `reqwest` client.
+    fn transport(&self) -> Option<Arc<dyn Transport>> {
+        None
+    }
+
+    /// Extra headers for this request.
+    ///
+    /// Request types that support `with_header` return the configured
+    /// [`CustomHeaders`]; the default sends only the standard headers.
+    fn custom_headers(&self) -> Option<&CustomHeaders> {
+        None
+    }
+
+    /// Bearer token for this request, signing a JWT when
+    /// [`auth`](Self::auth) asks for one.
+    fn bearer_token(&self) -> ZaiResult<String> {
+        match self.auth() {
+            Some(auth) => auth.bearer_token(),
+            None => Ok(self.api_key().as_ref().to_owned()),
+        }
+    }
+
+    /// Final request URL: [`api_url`](Self::api_url) moved onto the
+    /// configured [`endpoint`](Self::endpoint), if any.
+    fn request_url(&self) -> String {
+        let url = self.api_url().as_ref();
+        match self.endpoint() {
+            Some(endpoint) => endpoint.rebase(url),
+            None => url.to_owned(),
+        }
+    }
+
     /// Get HTTP client configuration for this request
     ///
     /// Override this method to provide custom configuration.
@@ -316,6 +651,15 @@ pub trait HttpClient {
             .clone()
     }
 
+    /// Check run by [`post`](Self::post) and [`get`](Self::get) before the
+    /// request is sent.
+    ///
+    /// Request types with a pre-send step (e.g. chat input moderation)
+    /// override this; the default does nothing.
+    fn precheck(&self) -> impl std::future::Future<Output = ZaiResult<()>> + Send + 'static {
+        std::future::ready(Ok(()))
+    }
+
     /// Sends a POST request to the API endpoint.
     ///
     /// This method implements retry logic with exponential backoff and jitter.
@@ -325,43 +669,38 @@ pub trait HttpClient {
             serde_json::to_string(self.body()).map_err(|e| ZaiError::JsonError(Arc::new(e)));
 
         let config = self.http_config().clone();
-        let enable_logging = config.enable_logging;
-        let mask_sensitive = config.mask_sensitive_data;
-
-        let body_pretty_opt = if enable_logging {
-            match serde_json::to_string_pretty(self.body()) {
-                Ok(pretty) => Some(pretty),
-                Err(e) => {
-                    warn!("Failed to pretty-print request body: {}", e);
-                    None
-                },
-            }
-        } else {
-            None
-        };
 
-        let url = self.api_url().as_ref().to_owned();
-        let key = self.api_key().as_ref().to_owned();
+        let url = self.request_url();
+        let key = self.bearer_token();
+        let transport = self.transport();
+        let custom = self
+            .custom_headers()
+            .map(CustomHeaders::to_header_map)
+            .transpose();
+        let precheck = self.precheck();
 
         async move {
+            precheck.await?;
             let body = body_compact?;
-
-            if enable_logging {
-                let log_body = if mask_sensitive {
-                    mask_sensitive_info(body.as_str())
-                } else {
-                    body.clone()
+            let key = key?;
+            let (mut headers, request_id) = config.request_headers();
+            if let Some(custom) = custom? {
+                headers.extend(custom);
+            }
+            log_request(&config, "POST", &url, &headers, Some(&body));
+
+            if let Some(transport) = transport {
+                let request = TransportRequest {
+                    method: reqwest::Method::POST,
+                    url,
+                    bearer_token: key,
+                    headers,
+                    body: Some(body),
                 };
-                if let Some(pretty) = body_pretty_opt {
-                    let log_pretty = if mask_sensitive {
-                        mask_sensitive_info(&pretty)
-                    } else {
-                        pretty
-                    };
-                    info!(request_body = %log_pretty, "Sending POST request");
-                } else {
-                    debug!(request_body = %log_body, "Sending POST request");
-                }
+                let result = send_with_transport(transport.as_ref(), request).await;
+                let result = limit_body(result, config.max_response_bytes).await;
+                let result = log_response(result, &config).await;
+                return tag_request_id(result, request_id);
             }
 
             let client = http_client_with_config(&config);
@@ -369,9 +708,13 @@ pub trait HttpClient {
                 .post(&url)
                 .bearer_auth(&key)
                 .header("Content-Type", "application/json")
+                .headers(headers)
                 .body(body);
 
-            send_with_retry(request_builder, &config).await
+            let result = send_with_retry(request_builder, &config).await;
+            let result = limit_body(result, config.max_response_bytes).await;
+            let result = log_response(result, &config).await;
+            tag_request_id(result, request_id)
         }
     }
 
@@ -381,13 +724,42 @@ pub trait HttpClient {
     /// It supports configuration through the `http_config` method.
     fn get(&self) -> impl std::future::Future<Output = ZaiResult<reqwest::Response>> + Send {
         let config = self.http_config().clone();
-        let url = self.api_url().as_ref().to_owned();
-        let key = self.api_key().as_ref().to_owned();
+        let url = self.request_url();
+        let key = self.bearer_token();
+        let transport = self.transport();
+        let custom = self
+            .custom_headers()
+            .map(CustomHeaders::to_header_map)
+            .transpose();
+        let precheck = self.precheck();
 
         async move {
+            precheck.await?;
+            let key = key?;
+            let (mut headers, request_id) = config.request_headers();
+            if let Some(custom) = custom? {
+                headers.extend(custom);
+            }
+            log_request(&config, "GET", &url, &headers, None);
+            if let Some(transport) = transport {
+                let request = TransportRequest {
+                    method: reqwest::Method::GET,
+                    url,
+                    bearer_token: key,
+                    headers,
+                    body: None,
+                };
+                let result = send_with_transport(transport.as_ref(), request).await;
+                let result = limit_body(result, config.max_response_bytes).await;
+                let result = log_response(result, &config).await;
+                return tag_request_id(result, request_id);
+            }
             let client = http_client_with_config(&config);
-            let request_builder = client.get(&url).bearer_auth(&key);
-            send_with_retry(request_builder, &config).await
+            let request_builder = client.get(&url).bearer_auth(&key).headers(headers);
+            let result = send_with_retry(request_builder, &config).await;
+            let result = limit_body(result, config.max_response_bytes).await;
+            let result = log_response(result, &config).await;
+            tag_request_id(result, request_id)
         }
     }
 }
@@ -426,8 +798,9 @@ async fn send_with_retry(
             Ok(resp) => {
                 let status = resp.status();
 
-                if status.is_success() {
-                    debug!(http_status = %status, "Request succeeded");
+                if is_accepted(status) {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(http_status = %status, "Request succeeded");
                     return Ok(resp);
                 }
 
@@ -438,7 +811,8 @@ async fn send_with_retry(
                     last_error = Some(error.clone());
                     let delay = calculate_retry_delay(attempt, &config.retry_delay);
                     let delay_with_jitter = add_jitter(delay);
-                    warn!(
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
                         attempt = attempt + 1,
                         max_attempts = config.max_retries + 1,
                         retry_delay = ?delay_with_jitter,
@@ -457,7 +831,8 @@ async fn send_with_retry(
                     last_error = Some(error.clone());
                     let delay = calculate_retry_delay(attempt, &config.retry_delay);
                     let delay_with_jitter = add_jitter(delay);
-                    warn!(
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
                         attempt = attempt + 1,
                         max_attempts = config.max_retries + 1,
                         retry_delay = ?delay_with_jitter,
@@ -478,8 +853,30 @@ async fn send_with_retry(
     }))
 }
 
+/// Internal helper: sends through a custom [`Transport`], mapping non-2xx
+/// responses to errors like [`send_with_retry`] (without retrying).
+async fn send_with_transport(
+    transport: &dyn Transport,
+    request: TransportRequest,
+) -> ZaiResult<reqwest::Response> {
+    let resp = transport.send(request).await?;
+    let status = resp.status();
+    if is_accepted(status) {
+        return Ok(resp);
+    }
+    let text = resp.text().await.unwrap_or_default();
+    Err(parse_api_error_response(status.as_u16(), text))
+}
+
+/// Whether a response is returned to the caller rather than mapped to an
+/// error: any 2xx, plus `304 Not Modified`, which only answers conditional
+/// requests the caller sent on purpose.
+fn is_accepted(status: reqwest::StatusCode) -> bool {
+    status.is_success() || status == reqwest::StatusCode::NOT_MODIFIED
+}
+
 /// Calculate delay for a retry attempt based on retry delay strategy.
-fn calculate_retry_delay(attempt: u32, strategy: &RetryDelay) -> Duration {
+pub(crate) fn calculate_retry_delay(attempt: u32, strategy: &RetryDelay) -> Duration {
     match strategy {
         RetryDelay::Fixed(delay) => *delay,
         RetryDelay::Exponential { base, max } => {
@@ -491,7 +888,7 @@ fn calculate_retry_delay(attempt: u32, strategy: &RetryDelay) -> Duration {
 }
 
 /// Determines if an error should trigger a retry.
-fn should_retry(error: &ZaiError, attempt: u32, max_retries: u32) -> bool {
+pub(crate) fn should_retry(error: &ZaiError, attempt: u32, max_retries: u32) -> bool {
     if attempt >= max_retries {
         return false;
     }
@@ -509,64 +906,52 @@ fn should_retry(error: &ZaiError, attempt: u32, max_retries: u32) -> bool {
 }
 
 /// Adds jitter to delay to avoid thundering herd.
-fn add_jitter(delay: Duration) -> Duration {
+pub(crate) fn add_jitter(delay: Duration) -> Duration {
     let jitter_ms = fastrand::u64(0..=delay.as_millis() as u64 / 4);
     delay + Duration::from_millis(jitter_ms)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    #[test]
-    fn test_error_code_display_num() {
-        let code = ErrorCode::Num(123);
-        assert_eq!(format!("{}", code), "123");
-    }
-
-    #[test]
-    fn test_error_code_display_str() {
-        let code = ErrorCode::Str("auth_error".to_string());
-        assert_eq!(format!("{}", code), "auth_error");
-    }
-
-    #[test]
-    fn test_to_api_code_num() {
-        let code = ErrorCode::Num(401);
-        assert_eq!(to_api_code(&code), 401);
-    }
+    use tokio::sync::mpsc::UnboundedReceiver;
 
-    #[test]
-    fn test_to_api_code_str_valid() {
-        let code = ErrorCode::Str("429".to_string());
-        assert_eq!(to_api_code(&code), 429);
-    }
+    use super::*;
+    use crate::client::test_server::{Reply, Request, serve};
 
     #[test]
-    fn test_to_api_code_str_invalid() {
-        let code = ErrorCode::Str("invalid".to_string());
-        assert_eq!(to_api_code(&code), 0);
+    fn test_parse_api_error_response_num_code() {
+        let body = r#"{"error":{"code":1113,"message":"Insufficient balance"}}"#;
+        let err = parse_api_error_response(200, body.to_string());
+        assert_eq!(err.code(), Some(1113));
+        assert_eq!(err.message(), "Insufficient balance");
     }
 
     #[test]
-    fn test_to_api_code_num_overflow() {
-        let code = ErrorCode::Num(99999);
-        assert_eq!(to_api_code(&code), 0);
+    fn test_parse_api_error_response_str_code() {
+        let body = r#"{"error":{"code":"1300","message":"Rate limit exceeded"}}"#;
+        let err = parse_api_error_response(200, body.to_string());
+        assert!(err.is_rate_limit());
+        assert_eq!(err.message(), "Rate limit exceeded");
     }
 
     #[test]
-    fn test_api_error_envelope_deserialize() {
-        let json = r#"{"error":{"code":401,"message":"Unauthorized"}}"#;
-        let envelope: ApiErrorEnvelope = serde_json::from_str(json).unwrap();
-        assert_eq!(envelope.error.message, "Unauthorized");
+    fn test_parse_api_error_response_invalid_code() {
+        for code in [r#""invalid""#, "99999"] {
+            let body = format!(r#"{{"error":{{"code":{},"message":"odd"}}}}"#, code);
+            let err = parse_api_error_response(200, body);
+            assert!(
+                matches!(err, ZaiError::Unknown { code: 0, .. }),
+                "{:?}",
+                err
+            );
+        }
     }
 
     #[test]
-    fn test_api_error_envelope_deserialize_str_code() {
-        let json = r#"{"error":{"code":"1300","message":"Rate limit exceeded"}}"#;
-        let envelope: ApiErrorEnvelope = serde_json::from_str(json).unwrap();
-        assert_eq!(envelope.error.message, "Rate limit exceeded");
-        assert_eq!(to_api_code(&envelope.error.code), 1300);
+    fn test_parse_api_error_response_not_an_envelope() {
+        let err = parse_api_error_response(400, "bad gateway input".to_string());
+        assert_eq!(err.code(), Some(400));
+        assert_eq!(err.message(), "bad gateway input");
     }
 
     #[test]
@@ -711,4 +1096,127 @@ mod tests {
         let delay = RetryDelay::default();
         matches!(delay, RetryDelay::Exponential { base, max } if base == Duration::from_millis(500) && max == Duration::from_secs(5));
     }
+
+    struct Probe {
+        url: String,
+        key: String,
+        config: Arc<HttpClientConfig>,
+    }
+
+    impl HttpClient for Probe {
+        type Body = ();
+        type ApiUrl = String;
+        type ApiKey = String;
+
+        fn api_url(&self) -> &String {
+            &self.url
+        }
+        fn api_key(&self) -> &String {
+            &self.key
+        }
+        fn body(&self) -> &() {
+            &()
+        }
+        fn http_config(&self) -> Arc<HttpClientConfig> {
+            self.config.clone()
+        }
+    }
+
+    /// Answer `/ok` with 200 and anything else with 400.
+    async fn serve_heads() -> (String, UnboundedReceiver<Request>) {
+        serve(|req| match req.path() {
+            "/ok" => Reply::json(200, "{}"),
+            _ => Reply::json(400, r#"{"error":{"code":"1214","message":"bad input"}}"#),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_and_request_id_headers() {
+        let (base, mut heads) = serve_heads().await;
+        let config = Arc::new(
+            HttpClientConfig::builder()
+                .user_agent("my-app/1.0")
+                .request_id(true)
+                .build(),
+        );
+        let probe = |path: &str| Probe {
+            url: format!("{}{}", base, path),
+            key: "key".to_string(),
+            config: config.clone(),
+        };
+
+        let resp = probe("/ok").get().await.unwrap();
+        let head = heads.recv().await.unwrap();
+        assert_eq!(head.header("user-agent"), Some("my-app/1.0"));
+        let sent = head.header("x-request-id").unwrap();
+        assert_eq!(response_request_id(&resp), Some(sent));
+
+        let err = probe("/bad").get().await.unwrap_err();
+        let head = heads.recv().await.unwrap();
+        let sent = head.header("x-request-id").unwrap();
+        assert_eq!(err.request_id(), Some(sent));
+        assert_eq!(err.code(), Some(400));
+        assert!(err.to_string().contains("bad input"), "{}", err);
+
+        let plain = Probe {
+            url: format!("{}/ok", base),
+            key: "key".to_string(),
+            config: Arc::new(HttpClientConfig::default()),
+        };
+        let resp = plain.get().await.unwrap();
+        let head = heads.recv().await.unwrap();
+        assert_eq!(head.header("user-agent"), Some(DEFAULT_USER_AGENT));
+        assert!(head.header("x-request-id").is_none());
+        assert_eq!(response_request_id(&resp), None);
+    }
+    /// Serve `/sized` with a content-length, `/unsized` until the connection
+    /// closes, and `/stream` as an event stream; each body is `len` bytes.
+    async fn serve_bodies(len: usize) -> String {
+        let (base, _) = serve(move |req| {
+            let body = vec![b'x'; len];
+            match req.path() {
+                "/sized" => Reply::status(200).body("text/plain", body),
+                "/stream" => Reply::status(200).body("text/event-stream", body),
+                _ => Reply::status(200).body("text/plain", body).until_close(),
+            }
+        })
+        .await;
+        base
+    }
+
+    #[tokio::test]
+    async fn test_max_response_bytes() {
+        let limited = Arc::new(
+            HttpClientConfig::builder()
+                .max_response_bytes(Some(64))
+                .build(),
+        );
+        let probe = |base: &str, path: &str, config: &Arc<HttpClientConfig>| Probe {
+            url: format!("{}{}", base, path),
+            key: "key".to_string(),
+            config: config.clone(),
+        };
+
+        let big = serve_bodies(1000).await;
+        for path in ["/sized", "/unsized"] {
+            let err = probe(&big, path, &limited).get().await.unwrap_err();
+            assert!(
+                matches!(err, ZaiError::ResponseTooLarge { limit: 64 }),
+                "{}: {:?}",
+                path,
+                err
+            );
+        }
+        let stream = probe(&big, "/stream", &limited).get().await.unwrap();
+        assert_eq!(stream.bytes().await.unwrap().len(), 1000);
+        let unlimited = Arc::new(HttpClientConfig::default());
+        let resp = probe(&big, "/unsized", &unlimited).get().await.unwrap();
+        assert_eq!(resp.bytes().await.unwrap().len(), 1000);
+
+        let small = serve_bodies(64).await;
+        let resp = probe(&small, "/unsized", &limited).get().await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().await.unwrap(), "x".repeat(64));
+    }
 }
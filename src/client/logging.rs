@@ -0,0 +1,402 @@
+//! Request/response logging with redaction (feature `tracing`)
+//!
+//! With [`ClientConfig::with_logging`](super::http::HttpClientConfig::with_logging),
+//! every request sent through [`HttpClient`](super::http::HttpClient) logs
+//! its method, URL, headers and JSON body, and every response its status
+//! and body, as `tracing` events with target `zai_rs::http`.
+//! [`enable_logging`](super::http::HttpClientConfig::enable_logging) does
+//! the same with the default [`LogConfig`] at `INFO`. Secrets are removed
+//! before anything is logged:
+//!
+//! - The `Authorization` header is always shown as `[REDACTED]`
+//! - String values of the fields named in [`LogConfig::redact_keys`], at any
+//!   depth, and `data:...;base64,` URLs anywhere become
+//!   `"<redacted:N bytes>"`, which keeps base64 audio and images out of logs
+//! - API keys and `key=value` secrets left in the text are masked with
+//!   [`mask_sensitive_info`]
+//!
+//! Only JSON response bodies are buffered and logged; for event streams and
+//! other content types, such as downloaded files, the status and content
+//! type are logged instead. Logged bodies are cut after
+//! [`LogConfig::max_body_len`] bytes.
+//!
+//! ```rust,ignore
+//! use zai_rs::client::{http::ClientConfig, logging::LogConfig};
+//!
+//! let config = ClientConfig::default()
+//!     .with_logging(LogConfig::default().redact_key("voice").level(tracing::Level::INFO));
+//! ```
+
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap};
+use serde_json::Value;
+use tracing::Level;
+
+use super::{
+    error::{ZaiResult, mask_sensitive_info},
+    http::rebuild_response,
+};
+
+/// Fields redacted by [`LogConfig::default`]
+pub const DEFAULT_REDACT_KEYS: [&str; 5] = ["audio", "b64_json", "file", "image", "mask"];
+
+/// Default for [`LogConfig::max_body_len`]
+pub const DEFAULT_MAX_BODY_LEN: usize = 4096;
+
+/// What to log and at which level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// JSON fields and header names whose values are never logged
+    pub redact_keys: Vec<String>,
+    /// Level of the request and response events (default: `DEBUG`)
+    pub level: Level,
+    /// Longest body logged, in bytes; the rest is replaced by its length
+    /// (default: [`DEFAULT_MAX_BODY_LEN`])
+    pub max_body_len: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            redact_keys: DEFAULT_REDACT_KEYS.iter().map(|k| k.to_string()).collect(),
+            level: Level::DEBUG,
+            max_body_len: DEFAULT_MAX_BODY_LEN,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Also redact the field or header `key`
+    pub fn redact_key(mut self, key: impl Into<String>) -> Self {
+        self.redact_keys.push(key.into());
+        self
+    }
+
+    /// Log at `level`
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Log at most `len` bytes of each body
+    pub fn max_body_len(mut self, len: usize) -> Self {
+        self.max_body_len = len;
+        self
+    }
+
+    fn is_redacted(&self, key: &str) -> bool {
+        self.redact_keys.iter().any(|k| k.eq_ignore_ascii_case(key))
+    }
+}
+
+/// Replace the string values of redacted fields, and base64 data URLs
+/// anywhere, with `"<redacted:N bytes>"`
+pub fn redact_json(value: &mut Value, config: &LogConfig) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if config.is_redacted(key) => *value = summary(s),
+                    _ => redact_json(value, config),
+                }
+            }
+        },
+        Value::Array(items) => items.iter_mut().for_each(|v| redact_json(v, config)),
+        Value::String(s) if s.starts_with("data:") && s.contains(";base64,") => *value = summary(s),
+        _ => {},
+    }
+}
+
+fn summary(s: &str) -> Value {
+    Value::String(format!("<redacted:{} bytes>", s.len()))
+}
+
+/// A request or response body as it may be logged
+fn redact_body(body: &str, config: &LogConfig) -> String {
+    let mut logged = match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value, config);
+            mask_sensitive_info(&value.to_string())
+        },
+        Err(_) => mask_sensitive_info(body),
+    };
+    if logged.len() > config.max_body_len {
+        let total = logged.len();
+        let mut end = config.max_body_len;
+        while !logged.is_char_boundary(end) {
+            end -= 1;
+        }
+        logged.truncate(end);
+        logged.push_str(&format!("...<{} bytes total>", total));
+    }
+    logged
+}
+
+/// `application/json` and `+json` types
+fn is_json(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json")
+        || essence.to_ascii_lowercase().ends_with("+json")
+}
+
+/// `name: value` pairs with `Authorization` (sent even when not in
+/// `headers`) and redacted headers hidden
+fn redact_headers(headers: &HeaderMap, config: &LogConfig) -> String {
+    let mut shown = Vec::with_capacity(headers.len() + 1);
+    if !headers.contains_key(AUTHORIZATION) {
+        shown.push(format!("{}: [REDACTED]", AUTHORIZATION));
+    }
+    for (name, value) in headers {
+        if *name == AUTHORIZATION || config.is_redacted(name.as_str()) {
+            shown.push(format!("{}: [REDACTED]", name));
+        } else {
+            shown.push(format!(
+                "{}: {}",
+                name,
+                mask_sensitive_info(value.to_str().unwrap_or("<binary>"))
+            ));
+        }
+    }
+    shown.join(", ")
+}
+
+/// Emit an event at a level chosen at runtime.
+macro_rules! event_at {
+    ($level:expr, $($rest:tt)+) => {
+        match $level {
+            Level::ERROR => tracing::error!(target: "zai_rs::http", $($rest)+),
+            Level::WARN => tracing::warn!(target: "zai_rs::http", $($rest)+),
+            Level::INFO => tracing::info!(target: "zai_rs::http", $($rest)+),
+            Level::DEBUG => tracing::debug!(target: "zai_rs::http", $($rest)+),
+            Level::TRACE => tracing::trace!(target: "zai_rs::http", $($rest)+),
+        }
+    };
+}
+
+/// Log an outgoing request
+pub(crate) fn log_request(
+    config: &LogConfig,
+    method: &str,
+    url: &str,
+    headers: &HeaderMap,
+    body: Option<&str>,
+) {
+    let headers = redact_headers(headers, config);
+    let body = body.map(|b| redact_body(b, config)).unwrap_or_default();
+    event_at!(
+        config.level,
+        method,
+        url = %mask_sensitive_info(url),
+        headers = %headers,
+        body = %body,
+        "HTTP request"
+    );
+}
+
+/// Log the outcome of a request, buffering the body of a successful JSON
+/// response to do so
+pub(crate) async fn log_response(
+    result: ZaiResult<reqwest::Response>,
+    config: &LogConfig,
+) -> ZaiResult<reqwest::Response> {
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(e) => {
+            event_at!(config.level, error = %e.compact(), "HTTP request failed");
+            return Err(e);
+        },
+    };
+    let status = resp.status().as_u16();
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !is_json(&content_type) {
+        let body = match resp.content_length() {
+            Some(len) => format!("<{} bytes not logged>", len),
+            None => "<not logged>".to_string(),
+        };
+        event_at!(
+            config.level,
+            status,
+            content_type = %content_type,
+            body = %body,
+            "HTTP response"
+        );
+        return Ok(resp);
+    }
+
+    let mut resp = resp;
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        body.extend_from_slice(&chunk);
+    }
+    let logged = redact_body(&String::from_utf8_lossy(&body), config);
+    event_at!(config.level, status, body = %logged, "HTTP response");
+    rebuild_response(resp, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::{
+        http::{HttpClient, HttpClientConfig},
+        test_server::{Reply, serve},
+    };
+
+    /// Collects every event's fields as `name=value` text.
+    #[derive(Default, Clone)]
+    struct EventRecorder {
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut String);
+
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!("{}={:?} ", field.name(), value));
+        }
+    }
+
+    impl tracing::Subscriber for EventRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut line = String::new();
+            event.record(&mut FieldVisitor(&mut line));
+            self.lines.lock().unwrap().push(line);
+        }
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    struct Post {
+        url: String,
+        key: String,
+        body: Value,
+        config: Arc<HttpClientConfig>,
+    }
+
+    impl HttpClient for Post {
+        type Body = Value;
+        type ApiUrl = String;
+        type ApiKey = String;
+
+        fn api_url(&self) -> &String {
+            &self.url
+        }
+        fn api_key(&self) -> &String {
+            &self.key
+        }
+        fn body(&self) -> &Value {
+            &self.body
+        }
+        fn http_config(&self) -> Arc<HttpClientConfig> {
+            self.config.clone()
+        }
+    }
+
+    #[test]
+    fn test_logs_redact_key_and_binary_fields() {
+        let key = "abc123.abcdefghijklmnopqrstuvwxyz";
+        let audio = "A".repeat(1000);
+        let image = "B".repeat(2000);
+        let recorder = EventRecorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                let reply = json!({"data": [{"b64_json": image}], "note": "ok"});
+                let (base, _) = serve(move |_| Reply::json(200, &reply)).await;
+                let url = format!("{}/v4/audio", base);
+
+                let request = Post {
+                    url,
+                    key: key.to_string(),
+                    body: json!({
+                        "model": "glm-asr",
+                        "audio": audio,
+                        "messages": [{"image_url": {"url": "data:image/png;base64,AAAA"}}]
+                    }),
+                    config: Arc::new(
+                        HttpClientConfig::default().with_logging(LogConfig::default()),
+                    ),
+                };
+                let resp = request.post().await.unwrap();
+                // The body is still readable after being logged.
+                let body: Value = resp.json().await.unwrap();
+                assert_eq!(body["note"], "ok");
+            });
+        });
+
+        let lines = recorder.lines.lock().unwrap().join("\n");
+        assert!(!lines.contains(key), "{}", lines);
+        assert!(!lines.contains(&audio) && !lines.contains(&image));
+        assert!(lines.contains("authorization: [REDACTED]"), "{}", lines);
+        assert!(lines.contains("<redacted:1000 bytes>"), "{}", lines);
+        assert!(lines.contains("<redacted:2000 bytes>"), "{}", lines);
+        assert!(lines.contains("<redacted:26 bytes>"), "{}", lines);
+        assert!(lines.contains("glm-asr") && lines.contains("status=200"));
+    }
+
+    #[test]
+    fn test_logs_skip_binary_bodies_and_cap_json() {
+        let key = "abc123.abcdefghijklmnopqrstuvwxyz";
+        let file = vec![0xffu8; 5000];
+        let note = "n".repeat(500);
+        let recorder = EventRecorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                let (binary, json) = (file.clone(), json!({ "note": note }));
+                let (base, _) = serve(move |req| match req.path() {
+                    "/file" => Reply::status(200).body("application/octet-stream", binary.clone()),
+                    _ => Reply::json(200, &json),
+                })
+                .await;
+                let post = |path: &str, config: HttpClientConfig| Post {
+                    url: format!("{}{}", base, path),
+                    key: key.to_string(),
+                    body: json!({"model": "glm-4"}),
+                    config: Arc::new(config),
+                };
+
+                // The legacy flag logs through the same redacting path.
+                let legacy = HttpClientConfig::builder().logging(true).build();
+                let resp = post("/file", legacy).post().await.unwrap();
+                assert_eq!(resp.bytes().await.unwrap().to_vec(), file);
+
+                let capped = HttpClientConfig::default()
+                    .with_logging(LogConfig::default().max_body_len(100));
+                let resp = post("/json", capped).post().await.unwrap();
+                let body: Value = resp.json().await.unwrap();
+                assert_eq!(body["note"], note);
+            });
+        });
+
+        let lines = recorder.lines.lock().unwrap().join("\n");
+        assert!(!lines.contains(key), "{}", lines);
+        assert!(lines.contains("<5000 bytes not logged>"), "{}", lines);
+        assert!(lines.contains("application/octet-stream"), "{}", lines);
+        assert!(!lines.contains(&note), "{}", lines);
+        assert!(lines.contains("bytes total>"), "{}", lines);
+    }
+}
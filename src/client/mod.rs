@@ -6,10 +6,17 @@
 //!
 //! # Core Components
 //!
+//! - [`auth`] — API key or signed JWT authentication ([`Auth`])
 //! - [`http`] — HTTP client implementation supporting POST/GET/DELETE requests,
 //!   connection reuse, exponential-backoff retries, and sensitive-data masking
+//! - [`circuit_breaker`] — Fail fast on an endpoint that keeps failing
+//! - [`endpoint`] — Base URL override ([`Endpoint`]) for all request types
+//! - [`health`] — [`ping`] to check an API key before starting work
+//! - `logging` — Request/response logging with secrets redacted (feature
+//!   `tracing`)
 //! - [`error`] — Unified error type [`ZaiError`] covering API, network,
 //!   serialization, validation, and retry-exhausted errors
+//! - [`sse`] — Incremental Server-Sent Events decoding ([`sse::SseDecoder`])
 //! - [`wss`] — WebSocket Secure connection support (for real-time audio/video)
 //!
 //! # Retry Strategy
@@ -37,11 +44,25 @@
 //! - API keys are automatically masked in logs via
 //!   [`mask_sensitive_info`](error::mask_sensitive_info)
 //! - Structured logging of requests/responses through `tracing`
-//! - Bearer token authentication on every request
+//! - Bearer token authentication on every request, with the raw API key or a
+//!   cached JWT
 
+pub mod auth;
+pub mod circuit_breaker;
+pub mod endpoint;
 pub mod error;
+pub mod health;
 pub mod http;
+#[cfg(feature = "tracing")]
+pub mod logging;
+pub mod sse;
+#[cfg(test)]
+pub(crate) mod test_server;
+pub mod transport;
 pub mod wss;
 
+pub use auth::Auth;
+pub use endpoint::Endpoint;
 pub use error::*;
+pub use health::{ping, ping_with_endpoint};
 pub use http::*;
@@ -0,0 +1,347 @@
+//! Incremental Server-Sent Events decoding.
+//!
+//! [`SseDecoder`] turns an SSE byte stream, fed in arbitrary chunks, into
+//! complete [`SseEvent`]s (or just their `data` payloads), following the
+//! [SSE spec](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation):
+//!
+//! - an event ends at a blank line; its `data:` lines are joined with `\n`;
+//! - lines starting with `:` are comments (keep-alives) and are ignored;
+//! - `event:` names the next event; `id:` sets the last event ID, which
+//!   carries over to later events;
+//! - `retry` and unknown fields are ignored;
+//! - `\n`, `\r\n` and `\r` line endings are accepted;
+//! - an event whose data is `[DONE]` ends the stream; later input is
+//!   discarded.
+//!
+//! ```rust,ignore
+//! use zai_rs::client::sse::SseDecoder;
+//!
+//! let mut decoder = SseDecoder::new();
+//! while let Some(bytes) = stream.next().await {
+//!     for event in decoder.push(&bytes?) {
+//!         println!("{}", event);
+//!     }
+//! }
+//! ```
+
+use serde_json::Value;
+
+/// Data payload that terminates a stream.
+pub const DONE: &str = "[DONE]";
+
+/// A complete server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// `event:` name; `None` for the default `message` event
+    pub name: Option<String>,
+    /// `data:` lines joined with `\n`
+    pub data: String,
+    /// Last event ID seen so far in the stream
+    pub id: Option<String>,
+}
+
+impl SseEvent {
+    /// Whether this is an `event: error` event
+    pub fn is_error(&self) -> bool {
+        self.name.as_deref() == Some("error")
+    }
+}
+
+/// A single parsed SSE line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SseLine<'a> {
+    /// Blank line: dispatches the pending event
+    Blank,
+    /// `:` comment, e.g. a keep-alive
+    Comment(&'a str),
+    /// `name: value` field; one leading space of the value is removed
+    Field { name: &'a str, value: &'a str },
+}
+
+/// Parse one line (without its line terminator).
+pub fn parse_sse_line(line: &str) -> SseLine<'_> {
+    if line.is_empty() {
+        return SseLine::Blank;
+    }
+    if let Some(comment) = line.strip_prefix(':') {
+        return SseLine::Comment(comment);
+    }
+    match line.split_once(':') {
+        Some((name, value)) => SseLine::Field {
+            name,
+            value: value.strip_prefix(' ').unwrap_or(value),
+        },
+        None => SseLine::Field {
+            name: line,
+            value: "",
+        },
+    }
+}
+
+/// Incremental decoder from SSE bytes to event payloads.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: Vec<u8>,
+    data: Option<String>,
+    name: Option<String>,
+    last_id: Option<String>,
+    /// The previous chunk ended with `\r`; a leading `\n` belongs to it.
+    pending_cr: bool,
+    done: bool,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the `[DONE]` terminator has been seen
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Feed bytes, returning the data of every event completed by them.
+    pub fn push_data(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.push_events(bytes)
+            .into_iter()
+            .map(|event| event.data)
+            .collect()
+    }
+
+    /// Feed bytes, returning every event completed by them.
+    pub fn push_events(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        if self.done {
+            return events;
+        }
+        let mut bytes = bytes;
+        if self.pending_cr {
+            self.pending_cr = false;
+            bytes = bytes.strip_prefix(b"\n").unwrap_or(bytes);
+        }
+        self.buf.extend_from_slice(bytes);
+
+        let mut start = 0;
+        while let Some(offset) = self.buf[start..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+        {
+            let end = start + offset;
+            let mut next = end + 1;
+            if self.buf[end] == b'\r' {
+                match self.buf.get(next) {
+                    Some(b'\n') => next += 1,
+                    Some(_) => {},
+                    None => self.pending_cr = true,
+                }
+            }
+            let line = String::from_utf8_lossy(&self.buf[start..end]).into_owned();
+            start = next;
+            if let Some(event) = self.process_line(&line) {
+                if event.data == DONE {
+                    self.done = true;
+                    break;
+                }
+                events.push(event);
+            }
+        }
+        self.buf.drain(..start.min(self.buf.len()));
+        if self.done {
+            self.buf.clear();
+        }
+        events
+    }
+
+    /// Feed bytes, returning every completed event whose data is valid JSON.
+    ///
+    /// Events with non-JSON data are skipped.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Value> {
+        to_json(self.push_data(bytes))
+    }
+
+    /// End of input: dispatch a trailing event that was not followed by a
+    /// blank line. Servers commonly close the stream right after the last
+    /// `data:` line, so it is delivered rather than dropped.
+    pub fn finish_data(&mut self) -> Vec<String> {
+        self.finish_events()
+            .into_iter()
+            .map(|event| event.data)
+            .collect()
+    }
+
+    /// Event variant of [`finish_data`](Self::finish_data)
+    pub fn finish_events(&mut self) -> Vec<SseEvent> {
+        if self.done {
+            return Vec::new();
+        }
+        if !self.buf.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buf)).into_owned();
+            self.process_line(&line);
+        }
+        match self.process_line("") {
+            Some(event) if event.data == DONE => {
+                self.done = true;
+                Vec::new()
+            },
+            Some(event) => vec![event],
+            None => Vec::new(),
+        }
+    }
+
+    /// JSON variant of [`finish_data`](Self::finish_data)
+    pub fn finish(&mut self) -> Vec<Value> {
+        to_json(self.finish_data())
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        match parse_sse_line(line) {
+            SseLine::Blank => {
+                let name = self.name.take();
+                Some(SseEvent {
+                    name,
+                    data: self.data.take()?,
+                    id: self.last_id.clone(),
+                })
+            },
+            SseLine::Comment(_) => None,
+            SseLine::Field {
+                name: "data",
+                value,
+            } => {
+                match &mut self.data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    },
+                    None => self.data = Some(value.to_string()),
+                }
+                None
+            },
+            SseLine::Field {
+                name: "event",
+                value,
+            } => {
+                self.name = Some(value.to_string()).filter(|n| !n.is_empty());
+                None
+            },
+            SseLine::Field { name: "id", value } => {
+                // IDs containing NUL are ignored, per the spec.
+                if !value.contains('\0') {
+                    self.last_id = Some(value.to_string()).filter(|id| !id.is_empty());
+                }
+                None
+            },
+            SseLine::Field { .. } => None,
+        }
+    }
+}
+
+fn to_json(events: Vec<String>) -> Vec<Value> {
+    events
+        .iter()
+        .filter_map(|event| serde_json::from_str(event).ok())
+        .collect()
+}
+
+/// Decode a complete SSE body into its JSON events.
+pub fn parse_sse_events(body: &str) -> Vec<Value> {
+    let mut decoder = SseDecoder::new();
+    let mut events = decoder.push(body.as_bytes());
+    events.extend(decoder.finish());
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_line() {
+        assert_eq!(parse_sse_line(""), SseLine::Blank);
+        assert_eq!(parse_sse_line(": ping"), SseLine::Comment(" ping"));
+        assert_eq!(
+            parse_sse_line("data: {\"a\":1}"),
+            SseLine::Field {
+                name: "data",
+                value: "{\"a\":1}"
+            }
+        );
+        assert_eq!(
+            parse_sse_line("data:x"),
+            SseLine::Field {
+                name: "data",
+                value: "x"
+            }
+        );
+        assert_eq!(
+            parse_sse_line("data"),
+            SseLine::Field {
+                name: "data",
+                value: ""
+            }
+        );
+    }
+
+    #[test]
+    fn test_multi_line_data_with_keep_alives() {
+        let mut decoder = SseDecoder::new();
+        let mut events = decoder.push(b": keep-alive\nevent: message\ndata: {\"a\":\ndata: 1}\n");
+        assert!(events.is_empty());
+        events.extend(decoder.push(b": keep-alive\r\n\r\ndata: {\"b\": 2}\n\n"));
+        assert_eq!(events, vec![json!({"a": 1}), json!({"b": 2})]);
+    }
+
+    #[test]
+    fn test_split_chunks_and_done() {
+        let mut decoder = SseDecoder::new();
+        let body = b"data: {\"n\":1}\r\n\r\ndata: [DONE]\n\ndata: {\"n\":2}\n\n";
+        let mut events = Vec::new();
+        for chunk in body.chunks(3) {
+            events.extend(decoder.push(chunk));
+        }
+        assert_eq!(events, vec![json!({"n": 1})]);
+        assert!(decoder.is_done());
+        assert!(decoder.finish().is_empty());
+    }
+
+    #[test]
+    fn test_event_names_and_ids() {
+        let mut decoder = SseDecoder::new();
+        let mut events =
+            decoder.push_events(b"id: 1\ndata: {\"a\":1}\n\nevent: error\ndata: {\"code\":1");
+        events.extend(decoder.push_events(b"}\n\nevent: ping\n\ndata: x\n\n"));
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    name: None,
+                    data: "{\"a\":1}".to_string(),
+                    id: Some("1".to_string()),
+                },
+                SseEvent {
+                    name: Some("error".to_string()),
+                    data: "{\"code\":1}".to_string(),
+                    id: Some("1".to_string()),
+                },
+                // The data-less `ping` event is dropped along with its name.
+                SseEvent {
+                    name: None,
+                    data: "x".to_string(),
+                    id: Some("1".to_string()),
+                },
+            ]
+        );
+        assert!(events[1].is_error() && !events[0].is_error());
+    }
+
+    #[test]
+    fn test_finish_flushes_unterminated_event() {
+        assert_eq!(
+            parse_sse_events("event: message\ndata: {\"ok\":true}"),
+            vec![json!({"ok": true})]
+        );
+        assert_eq!(parse_sse_events("data: not json\n\n"), Vec::<Value>::new());
+    }
+}
@@ -0,0 +1,220 @@
+//! Local HTTP server for unit tests
+//!
+//! [`serve`] binds `127.0.0.1` on a free port and answers each connection
+//! with the [`Reply`] its handler builds for the [`Request`], one connection
+//! at a time and closing each afterwards. Every request is also sent through
+//! the returned channel so tests can assert on what was sent.
+//!
+//! ```rust,ignore
+//! let (base, mut requests) = serve(|req| match req.path() {
+//!     "/ok" => Reply::json(200, json!({"id": "1"})),
+//!     _ => Reply::status(404),
+//! })
+//! .await;
+//! ```
+
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{UnboundedReceiver, unbounded_channel},
+};
+
+/// One request as received
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    /// Request line and headers, without the blank line
+    pub head: String,
+    /// Body, read up to its `content-length`
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// `GET /path?query HTTP/1.1`
+    pub fn line(&self) -> &str {
+        self.head.lines().next().unwrap_or_default()
+    }
+
+    /// Path and query of the request line
+    pub fn path(&self) -> &str {
+        self.line().split(' ').nth(1).unwrap_or_default()
+    }
+
+    /// Value of header `name`, compared case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    /// Body parsed as JSON
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+enum Framing {
+    /// `content-length` set to this value, which may exceed the body
+    Length(usize),
+    /// No length; the body ends when the connection closes
+    Close,
+    /// Chunked transfer encoding, pausing between chunks
+    Chunked(Vec<Vec<u8>>),
+}
+
+/// Response to send back
+pub(crate) struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    framing: Framing,
+}
+
+impl Reply {
+    /// Empty response with `status`
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            framing: Framing::Length(0),
+        }
+    }
+
+    /// `application/json` response
+    pub fn json(status: u16, body: impl ToString) -> Self {
+        Self::status(status).body("application/json", body.to_string())
+    }
+
+    /// Replace the body, setting its content type
+    pub fn body(mut self, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self.framing = Framing::Length(self.body.len());
+        self.header("content-type", content_type)
+    }
+
+    /// Add a header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Announce `len` bytes whatever the body length, so a shorter body
+    /// reads as a dropped connection
+    pub fn declared_length(mut self, len: usize) -> Self {
+        self.framing = Framing::Length(len);
+        self
+    }
+
+    /// Send no length and end the body by closing the connection
+    pub fn until_close(mut self) -> Self {
+        self.framing = Framing::Close;
+        self
+    }
+
+    /// Send `parts` as separate chunks
+    pub fn chunked(mut self, parts: Vec<Vec<u8>>) -> Self {
+        self.framing = Framing::Chunked(parts);
+        self
+    }
+
+    async fn write_to(self, socket: &mut TcpStream) -> std::io::Result<()> {
+        let reason = http::StatusCode::from_u16(self.status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("Unknown");
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        match &self.framing {
+            Framing::Length(len) => head.push_str(&format!("content-length: {}\r\n", len)),
+            Framing::Close => {},
+            Framing::Chunked(_) => head.push_str("transfer-encoding: chunked\r\n"),
+        }
+        head.push_str("connection: close\r\n\r\n");
+        socket.write_all(head.as_bytes()).await?;
+
+        match self.framing {
+            Framing::Chunked(parts) => {
+                for part in parts {
+                    socket
+                        .write_all(format!("{:x}\r\n", part.len()).as_bytes())
+                        .await?;
+                    socket.write_all(&part).await?;
+                    socket.write_all(b"\r\n").await?;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                socket.write_all(b"0\r\n\r\n").await
+            },
+            _ => socket.write_all(&self.body).await,
+        }
+    }
+}
+
+/// Serve requests with `handler`; returns the base URL (`http://addr`,
+/// without a trailing slash) and the requests received so far.
+pub(crate) async fn serve<F>(mut handler: F) -> (String, UnboundedReceiver<Request>)
+where
+    F: FnMut(&Request) -> Reply + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let Some(request) = read_request(&mut socket).await else {
+                continue;
+            };
+            let reply = handler(&request);
+            let _ = tx.send(request);
+            // Clients may hang up early, e.g. past a size limit.
+            let _ = reply.write_to(&mut socket).await;
+        }
+    });
+    (base, rx)
+}
+
+/// Answer the n-th request with the n-th reply, and any request past the
+/// end with status 599.
+pub(crate) async fn serve_in_order(replies: Vec<Reply>) -> (String, UnboundedReceiver<Request>) {
+    let mut replies = replies.into_iter();
+    serve(move |_| replies.next().unwrap_or_else(|| Reply::status(599))).await
+}
+
+/// Requests received so far. Each is queued before its reply is written, so
+/// once a client has its response the request is here.
+pub(crate) fn received(requests: &mut UnboundedReceiver<Request>) -> Vec<Request> {
+    std::iter::from_fn(|| requests.try_recv().ok()).collect()
+}
+
+/// Read the head and, per its `content-length`, the body of one request.
+async fn read_request(socket: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = socket.read(&mut chunk).await.ok()?;
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..end]).to_string();
+            let mut request = Request {
+                head,
+                body: Vec::new(),
+            };
+            let len = request
+                .header("content-length")
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + len || n == 0 {
+                request.body = buf[end + 4..].to_vec();
+                return Some(request);
+            }
+        }
+        if n == 0 {
+            return None;
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! # Pluggable Transport
+//!
+//! By default [`HttpClient`](super::http::HttpClient) sends requests with a
+//! pooled `reqwest` client. A request type that returns a [`Transport`] from
+//! `transport()` hands the finished request to it instead, which lets tests
+//! answer requests in memory.
+//!
+//! With the `testing` feature, [`MockTransport`] answers requests from a
+//! queue of canned responses:
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use zai_rs::client::transport::MockTransport;
+//!
+//! let mock = Arc::new(MockTransport::new());
+//! mock.push_response(response);
+//! let reply = ChatCompletion::new(model, messages, "key".to_string())
+//!     .with_transport(mock.clone())
+//!     .send()
+//!     .await?;
+//! assert_eq!(mock.requests().len(), 1);
+//! ```
+
+use async_trait::async_trait;
+
+use crate::client::error::ZaiResult;
+
+/// A fully built request handed to a [`Transport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportRequest {
+    pub method: reqwest::Method,
+
+    /// Final URL, after any endpoint override
+    pub url: String,
+
+    /// Bearer token the request is authenticated with
+    pub bearer_token: String,
+
+    /// Headers besides `Authorization` and `Content-Type`, such as
+    /// `User-Agent` and `X-Request-Id`, plus the request's custom headers
+    pub headers: reqwest::header::HeaderMap,
+
+    /// Serialized JSON body (`None` for GET requests)
+    pub body: Option<String>,
+}
+
+impl TransportRequest {
+    /// The body parsed as JSON, if there is one and it parses
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(self.body.as_deref()?).ok()
+    }
+}
+
+/// Sends requests on behalf of an [`HttpClient`](super::http::HttpClient).
+///
+/// Non-2xx responses are turned into errors by the caller, as for the
+/// default transport; retries are not applied.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: TransportRequest) -> ZaiResult<reqwest::Response>;
+}
+
+#[cfg(feature = "testing")]
+pub use mock::*;
+
+#[cfg(feature = "testing")]
+mod mock {
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::{client::error::ZaiError, model::chat_base_response::ChatCompletionResponse};
+
+    /// Decides whether a queued response answers a request.
+    pub type RequestMatcher = Box<dyn Fn(&TransportRequest) -> bool + Send + Sync>;
+
+    struct Queued {
+        matcher: RequestMatcher,
+        status: u16,
+        content_type: &'static str,
+        body: String,
+    }
+
+    /// In-memory [`Transport`] answering from queued responses (feature
+    /// `testing`).
+    ///
+    /// Each request takes the oldest queued response whose matcher accepts it;
+    /// a request nothing matches fails with a 404 `HttpError`. All requests are
+    /// recorded and can be inspected with [`requests`](Self::requests).
+    #[derive(Default)]
+    pub struct MockTransport {
+        queue: Mutex<Vec<Queued>>,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl MockTransport {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queue a chat response for the next request
+        pub fn push_response(&self, response: ChatCompletionResponse) {
+            self.push_response_when(|_| true, response);
+        }
+
+        /// Queue a chat response for the next request accepted by `matcher`
+        pub fn push_response_when(
+            &self,
+            matcher: impl Fn(&TransportRequest) -> bool + Send + Sync + 'static,
+            response: ChatCompletionResponse,
+        ) {
+            let body = serde_json::to_string(&response).unwrap_or_default();
+            self.push_raw_when(matcher, 200, "application/json", body);
+        }
+
+        /// Queue a raw response, e.g. an SSE body or an API error
+        pub fn push_raw_when(
+            &self,
+            matcher: impl Fn(&TransportRequest) -> bool + Send + Sync + 'static,
+            status: u16,
+            content_type: &'static str,
+            body: impl Into<String>,
+        ) {
+            self.queue.lock().push(Queued {
+                matcher: Box::new(matcher),
+                status,
+                content_type,
+                body: body.into(),
+            });
+        }
+
+        /// Requests received so far, oldest first
+        pub fn requests(&self) -> Vec<TransportRequest> {
+            self.requests.lock().clone()
+        }
+
+        /// Number of queued responses not yet used
+        pub fn remaining(&self) -> usize {
+            self.queue.lock().len()
+        }
+    }
+
+    #[async_trait]
+    impl Transport for MockTransport {
+        async fn send(&self, request: TransportRequest) -> ZaiResult<reqwest::Response> {
+            let queued = {
+                let mut queue = self.queue.lock();
+                let position = queue.iter().position(|q| (q.matcher)(&request));
+                position.map(|i| queue.remove(i))
+            };
+            let description = format!("{} {}", request.method, request.url);
+            self.requests.lock().push(request);
+
+            let Some(queued) = queued else {
+                return Err(ZaiError::HttpError {
+                    status: 404,
+                    message: format!("no mock response queued for {}", description),
+                });
+            };
+            let response = http::Response::builder()
+                .status(queued.status)
+                .header("content-type", queued.content_type)
+                .body(queued.body)
+                .map_err(|e| ZaiError::Unknown {
+                    code: 0,
+                    message: e.to_string(),
+                })?;
+            Ok(reqwest::Response::from(response))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::Arc;
+
+        use serde_json::json;
+
+        use super::*;
+        use crate::model::{chat::ChatCompletion, chat_message_types::TextMessage, *};
+
+        fn reply(text: &str) -> ChatCompletionResponse {
+            serde_json::from_value(json!({
+                "id": "resp",
+                "choices": [{
+                    "index": 0,
+                    "finish_reason": "stop",
+                    "message": {"role": "assistant", "content": text}
+                }]
+            }))
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_mock_answers_by_matcher_and_records() {
+            let mock = Arc::new(MockTransport::new());
+            mock.push_response_when(
+                |req| {
+                    req.json()
+                        .is_some_and(|b| b["messages"][0]["content"] == "second")
+                },
+                reply("to second"),
+            );
+            mock.push_response(reply("to first"));
+
+            let send = |text: &'static str| {
+                ChatCompletion::new(GLM4_5_flash {}, TextMessage::user(text), "key".to_string())
+                    .with_transport(mock.clone())
+            };
+            let first = send("first").send().await.unwrap();
+            let second = send("second").send().await.unwrap();
+            assert_eq!(first.final_text().as_deref(), Some("to first"));
+            assert_eq!(second.final_text().as_deref(), Some("to second"));
+
+            let requests = mock.requests();
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[0].method, reqwest::Method::POST);
+            assert_eq!(requests[0].bearer_token, "key");
+            assert!(requests[0].url.ends_with("/chat/completions"));
+
+            let err = send("third").send().await.unwrap_err();
+            assert!(matches!(err, ZaiError::HttpError { status: 404, .. }));
+        }
+
+        #[tokio::test]
+        async fn test_mock_error_status_becomes_api_error() {
+            let mock = Arc::new(MockTransport::new());
+            mock.push_raw_when(
+                |_| true,
+                429,
+                "application/json",
+                json!({"error": {"code": "1302", "message": "slow down"}}).to_string(),
+            );
+            let err = ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".into())
+                .with_transport(mock)
+                .send()
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("slow down"), "{}", err);
+        }
+    }
+}
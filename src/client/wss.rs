@@ -54,4 +54,247 @@
 //! - [`crate::model::chat_stream_response`] - Streaming response handling
 //! - Real-time API capabilities (see realTime module)
 
-// Implementation will be added when WebSocket support is developed
+//!
+//! ## Reconnection
+//!
+//! [`ReconnectConfig`] and [`Reconnector`] implement an opt-in reconnect
+//! strategy that is independent of the socket implementation: the caller
+//! supplies the connect function, and the reconnector retries it with
+//! backoff, then hands back the last `session.update` event to replay and
+//! invokes a hook with any audio that was appended but never committed.
+//! Connections that never use a [`Reconnector`] are unaffected.
+//!
+//! ```rust,ignore
+//! let mut reconnector = Reconnector::new(ReconnectConfig::default())
+//!     .with_on_reconnect(|pending_audio| resend(pending_audio));
+//! reconnector.record_session_update(session_update_json);
+//!
+//! // on handle_close / handle_error:
+//! let (conn, replay) = reconnector.reconnect(|_attempt| connect(url.clone())).await?;
+//! for event in replay {
+//!     conn.send_text(event).await?;
+//! }
+//! ```
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use crate::client::{
+    error::ZaiResult,
+    http::{RetryDelay, add_jitter, calculate_retry_delay},
+};
+
+/// Reconnection settings for a realtime connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectConfig {
+    /// Maximum number of connection attempts before giving up (at least one
+    /// attempt is always made)
+    pub max_attempts: u32,
+
+    /// Delay strategy between attempts
+    pub backoff: RetryDelay,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: RetryDelay::exponential(Duration::from_millis(500), Duration::from_secs(10)),
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Create a reconnect configuration
+    pub fn new(max_attempts: u32, backoff: RetryDelay) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+        }
+    }
+
+    /// Delay before the given 0-based retry, or `None` once attempts are
+    /// exhausted.
+    pub fn delay_for(&self, attempt: u32) -> Option<Duration> {
+        if attempt + 1 >= self.max_attempts {
+            return None;
+        }
+        Some(add_jitter(calculate_retry_delay(attempt, &self.backoff)))
+    }
+}
+
+/// Hook invoked after a successful reconnect with the uncommitted audio
+/// chunks (base64) so they can be re-sent.
+pub type ReconnectHook = Arc<dyn Fn(&[String]) + Send + Sync>;
+
+/// Tracks the state needed to restore a realtime session after a drop.
+pub struct Reconnector {
+    config: ReconnectConfig,
+    last_session_update: Option<String>,
+    pending_audio: Vec<String>,
+    on_reconnect: Option<ReconnectHook>,
+}
+
+impl Reconnector {
+    /// Create a reconnector with the given configuration
+    pub fn new(config: ReconnectConfig) -> Self {
+        Self {
+            config,
+            last_session_update: None,
+            pending_audio: Vec::new(),
+            on_reconnect: None,
+        }
+    }
+
+    /// Set the hook that receives uncommitted audio after reconnecting
+    pub fn with_on_reconnect<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&[String]) + Send + Sync + 'static,
+    {
+        self.on_reconnect = Some(Arc::new(hook));
+        self
+    }
+
+    /// Get the reconnect configuration
+    pub fn config(&self) -> &ReconnectConfig {
+        &self.config
+    }
+
+    /// Remember the latest serialized `session.update` event for replay
+    pub fn record_session_update(&mut self, event: impl Into<String>) {
+        self.last_session_update = Some(event.into());
+    }
+
+    /// Buffer an appended (base64) audio chunk until it is committed
+    pub fn buffer_audio(&mut self, chunk: impl Into<String>) {
+        self.pending_audio.push(chunk.into());
+    }
+
+    /// Drop buffered audio once the server has committed it
+    pub fn commit_audio(&mut self) {
+        self.pending_audio.clear();
+    }
+
+    /// Audio chunks appended since the last commit
+    pub fn pending_audio(&self) -> &[String] {
+        &self.pending_audio
+    }
+
+    /// Re-establish the connection using `connect`.
+    ///
+    /// `connect` receives the 0-based attempt number. On success, returns the
+    /// new connection and the events to replay (the last session update, if
+    /// any), and invokes the reconnect hook with the pending audio.
+    pub async fn reconnect<C, F, Fut>(&self, mut connect: F) -> ZaiResult<(C, Vec<String>)>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = ZaiResult<C>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match connect(attempt).await {
+                Ok(conn) => {
+                    if let Some(hook) = &self.on_reconnect {
+                        hook(&self.pending_audio);
+                    }
+                    let replay = self.last_session_update.iter().cloned().collect();
+                    return Ok((conn, replay));
+                },
+                Err(error) => {
+                    let Some(delay) = self.config.delay_for(attempt) else {
+                        return Err(error);
+                    };
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        max_attempts = self.config.max_attempts,
+                        retry_delay = ?delay,
+                        error = %error.compact(),
+                        "Realtime connection lost, reconnecting"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::client::error::ZaiError;
+
+    fn no_delay(max_attempts: u32) -> ReconnectConfig {
+        ReconnectConfig::new(max_attempts, RetryDelay::None)
+    }
+
+    #[test]
+    fn test_delay_for_exhausts_attempts() {
+        let config = no_delay(3);
+        assert_eq!(config.delay_for(0), Some(Duration::ZERO));
+        assert_eq!(config.delay_for(1), Some(Duration::ZERO));
+        assert_eq!(config.delay_for(2), None);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_replays_session_update_and_pending_audio() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_hook = seen.clone();
+        let mut reconnector = Reconnector::new(no_delay(3))
+            .with_on_reconnect(move |audio| seen_hook.lock().unwrap().extend_from_slice(audio));
+        reconnector.record_session_update(r#"{"type":"session.update"}"#);
+        reconnector.buffer_audio("AAAA");
+
+        let (conn, replay) = reconnector
+            .reconnect(|attempt| async move {
+                if attempt < 2 {
+                    Err(ZaiError::HttpError {
+                        status: 503,
+                        message: "unavailable".into(),
+                    })
+                } else {
+                    Ok(attempt)
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(conn, 2);
+        assert_eq!(replay, vec![r#"{"type":"session.update"}"#.to_string()]);
+        assert_eq!(*seen.lock().unwrap(), vec!["AAAA".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up_after_max_attempts() {
+        let reconnector = Reconnector::new(no_delay(2));
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+
+        let result: ZaiResult<((), Vec<String>)> = reconnector
+            .reconnect(|_| {
+                *counter.lock().unwrap() += 1;
+                async {
+                    Err(ZaiError::HttpError {
+                        status: 503,
+                        message: "unavailable".into(),
+                    })
+                }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(*calls.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_commit_clears_pending_audio() {
+        let mut reconnector = Reconnector::new(ReconnectConfig::default());
+        reconnector.buffer_audio("a");
+        reconnector.buffer_audio("b");
+        assert_eq!(reconnector.pending_audio().len(), 2);
+        reconnector.commit_audio();
+        assert!(reconnector.pending_audio().is_empty());
+    }
+}
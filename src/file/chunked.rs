@@ -0,0 +1,402 @@
+//! Chunked, retrying file uploads with progress reporting.
+//!
+//! The `/files` endpoint accepts a single multipart body and has no range
+//! support, so a dropped connection cannot be resumed server-side. This module
+//! emulates resumable uploads instead: the file is streamed from disk in
+//! fixed-size chunks (so memory stays bounded), the number of bytes handed to
+//! the transport is tracked, and a failed attempt is retried with
+//! backoff using the request's [`HttpClientConfig`](crate::client::http::HttpClientConfig).
+//! The upload only fails once the retry budget is exhausted.
+//!
+//! ```rust,ignore
+//! use futures::StreamExt;
+//! use zai_rs::file::*;
+//!
+//! let req = FileUploadRequest::new(key, FilePurpose::FileExtract, "big.pdf");
+//! let mut progress = req.upload_chunked(DEFAULT_CHUNK_SIZE);
+//! while let Some(event) = progress.next().await {
+//!     match event {
+//!         UploadProgress::ChunkSent { offset, len, total_bytes, .. } => {
+//!             println!("{}/{}", offset + len as u64, total_bytes)
+//!         },
+//!         UploadProgress::Completed(file) => println!("uploaded: {:?}", file.id),
+//!         UploadProgress::Failed(err) => eprintln!("upload failed: {}", err),
+//!         _ => {},
+//!     }
+//! }
+//! ```
+
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::{Stream, channel::mpsc, stream};
+use reqwest::header::HeaderMap;
+use tokio::io::AsyncReadExt;
+
+use super::{request::FilePurpose, response::FileObject, upload::FileUploadRequest};
+use crate::client::{
+    error::{ZaiError, ZaiResult},
+    http::{
+        CustomHeaders, HttpClient, HttpClientConfig, add_jitter, calculate_retry_delay,
+        http_client_with_config, parse_api_error_response, should_retry,
+    },
+};
+
+/// Default chunk size used for chunked uploads (8 MiB).
+pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Progress events emitted by [`FileUploadRequest::upload_chunked`].
+///
+/// The stream always ends with either [`UploadProgress::Completed`] or
+/// [`UploadProgress::Failed`].
+#[derive(Debug, Clone)]
+pub enum UploadProgress {
+    /// An upload attempt has started.
+    Started {
+        /// 1-based attempt number
+        attempt: u32,
+        /// Total file size in bytes
+        total_bytes: u64,
+        /// Number of chunks the file is split into
+        total_chunks: usize,
+    },
+    /// A chunk was handed to the transport.
+    ChunkSent {
+        /// 0-based chunk index
+        index: usize,
+        /// Byte offset of the chunk within the file
+        offset: u64,
+        /// Chunk length in bytes
+        len: usize,
+        /// Total file size in bytes
+        total_bytes: u64,
+    },
+    /// The attempt failed and will be retried after `delay`.
+    Retrying {
+        /// 1-based number of the attempt that failed
+        attempt: u32,
+        /// Maximum number of retries allowed
+        max_retries: u32,
+        /// Bytes handed to the transport before the failure. The server
+        /// acknowledges nothing until the whole upload succeeds, so this only
+        /// says how far the attempt got on the client side.
+        sent_bytes: u64,
+        /// Backoff before the next attempt
+        delay: Duration,
+        /// Error that caused the retry
+        error: ZaiError,
+    },
+    /// Upload finished successfully.
+    Completed(FileObject),
+    /// Upload failed permanently.
+    Failed(ZaiError),
+}
+
+impl UploadProgress {
+    /// Whether this event terminates the stream.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed(_) | Self::Failed(_))
+    }
+}
+
+/// Number of chunks needed to cover `total_bytes` with `chunk_size` chunks.
+///
+/// An empty file still occupies a single (empty) chunk.
+fn chunk_count(total_bytes: u64, chunk_size: usize) -> usize {
+    if total_bytes == 0 {
+        return 1;
+    }
+    total_bytes.div_ceil(chunk_size as u64) as usize
+}
+
+impl FileUploadRequest {
+    /// Upload the file in `chunk_size` pieces, retrying failed attempts.
+    ///
+    /// Returns a stream of [`UploadProgress`] events that can drive a
+    /// progress bar. Retries follow `max_retries` and `retry_delay` from
+    /// [`HttpClient::http_config`]. Because the endpoint has no range support,
+    /// each retry re-streams the file from the start; the `sent_bytes`
+    /// reported in [`UploadProgress::Retrying`] tells how far the failed
+    /// attempt got.
+    ///
+    /// Must be polled from within a Tokio runtime.
+    pub fn upload_chunked(
+        &self,
+        chunk_size: usize,
+    ) -> impl Stream<Item = UploadProgress> + Send + 'static {
+        let (tx, rx) = mpsc::unbounded();
+
+        if chunk_size == 0 {
+            let _ = tx.unbounded_send(UploadProgress::Failed(ZaiError::ApiError {
+                code: 1200,
+                message: "chunk_size must be greater than 0".to_string(),
+            }));
+            return rx;
+        }
+
+        let key = match self.bearer_token() {
+            Ok(key) => key,
+            Err(err) => {
+                let _ = tx.unbounded_send(UploadProgress::Failed(err));
+                return rx;
+            },
+        };
+        let headers = match self
+            .custom_headers()
+            .map(CustomHeaders::to_header_map)
+            .transpose()
+        {
+            Ok(headers) => headers.unwrap_or_default(),
+            Err(err) => {
+                let _ = tx.unbounded_send(UploadProgress::Failed(err));
+                return rx;
+            },
+        };
+
+        let job = ChunkedJob {
+            key,
+            headers,
+            url: self.request_url(),
+            purpose: self.purpose.clone(),
+            path: self.file_path.clone(),
+            file_name: self.upload_file_name(),
+            content_type: self.content_type.clone(),
+            chunk_size,
+            config: self.http_config(),
+        };
+
+        tokio::spawn(async move {
+            let last = match job.run(&tx).await {
+                Ok(file) => UploadProgress::Completed(file),
+                Err(err) => UploadProgress::Failed(err),
+            };
+            let _ = tx.unbounded_send(last);
+        });
+
+        rx
+    }
+}
+
+/// Owned state for a single chunked upload running on a background task.
+struct ChunkedJob {
+    /// Bearer token from [`HttpClient::bearer_token`]
+    key: String,
+    headers: HeaderMap,
+    url: String,
+    purpose: FilePurpose,
+    path: PathBuf,
+    file_name: String,
+    content_type: Option<String>,
+    chunk_size: usize,
+    config: Arc<HttpClientConfig>,
+}
+
+impl ChunkedJob {
+    async fn run(&self, tx: &mpsc::UnboundedSender<UploadProgress>) -> ZaiResult<FileObject> {
+        let total_bytes = tokio::fs::metadata(&self.path).await?.len();
+        let total_chunks = chunk_count(total_bytes, self.chunk_size);
+        let client = http_client_with_config(&self.config);
+
+        let mut attempt = 0;
+        loop {
+            let _ = tx.unbounded_send(UploadProgress::Started {
+                attempt: attempt + 1,
+                total_bytes,
+                total_chunks,
+            });
+
+            let sent = Arc::new(AtomicU64::new(0));
+            let error = match self
+                .attempt(&client, total_bytes, tx.clone(), sent.clone())
+                .await
+            {
+                Ok(file) => return Ok(file),
+                Err(error) => error,
+            };
+
+            if !should_retry(&error, attempt, self.config.max_retries) {
+                return Err(error);
+            }
+
+            let delay = add_jitter(calculate_retry_delay(attempt, &self.config.retry_delay));
+            let _ = tx.unbounded_send(UploadProgress::Retrying {
+                attempt: attempt + 1,
+                max_retries: self.config.max_retries,
+                sent_bytes: sent.load(Ordering::SeqCst),
+                delay,
+                error,
+            });
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        client: &reqwest::Client,
+        total_bytes: u64,
+        tx: mpsc::UnboundedSender<UploadProgress>,
+        sent: Arc<AtomicU64>,
+    ) -> ZaiResult<FileObject> {
+        let file = tokio::fs::File::open(&self.path).await?;
+        let chunk_size = self.chunk_size;
+
+        let chunks = stream::try_unfold((file, 0usize), move |(mut file, index)| {
+            let tx = tx.clone();
+            let sent = sent.clone();
+            async move {
+                let mut buf = vec![0u8; chunk_size];
+                let mut filled = 0;
+                while filled < chunk_size {
+                    let n = file.read(&mut buf[filled..]).await?;
+                    if n == 0 {
+                        break;
+                    }
+                    filled += n;
+                }
+                if filled == 0 {
+                    return Ok::<_, std::io::Error>(None);
+                }
+                buf.truncate(filled);
+
+                let offset = sent.fetch_add(filled as u64, Ordering::SeqCst);
+                let _ = tx.unbounded_send(UploadProgress::ChunkSent {
+                    index,
+                    offset,
+                    len: filled,
+                    total_bytes,
+                });
+                Ok(Some((buf, (file, index + 1))))
+            }
+        });
+
+        let mut part = reqwest::multipart::Part::stream_with_length(
+            reqwest::Body::wrap_stream(chunks),
+            total_bytes,
+        )
+        .file_name(self.file_name.clone());
+        if let Some(ct) = &self.content_type {
+            part = part.mime_str(ct).map_err(|e| ZaiError::ApiError {
+                code: 1200,
+                message: format!("invalid content-type: {}", e),
+            })?;
+        }
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", self.purpose.as_str().to_string())
+            .part("file", part);
+
+        let resp = client
+            .post(&self.url)
+            .bearer_auth(&self.key)
+            .headers(self.headers.clone())
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(parse_api_error_response(status.as_u16(), text));
+        }
+
+        Ok(resp.json::<FileObject>().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::{
+        endpoint::Endpoint,
+        test_server::{Reply, received, serve_in_order},
+    };
+
+    #[test]
+    fn test_chunk_count() {
+        assert_eq!(chunk_count(0, 4), 1);
+        assert_eq!(chunk_count(1, 4), 1);
+        assert_eq!(chunk_count(4, 4), 1);
+        assert_eq!(chunk_count(5, 4), 2);
+        assert_eq!(chunk_count(9, 4), 3);
+    }
+
+    #[tokio::test]
+    async fn test_zero_chunk_size_fails() {
+        let req = FileUploadRequest::new("k".into(), FilePurpose::FileExtract, "a.pdf");
+        let events: Vec<_> = req.upload_chunked(0).collect().await;
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            UploadProgress::Failed(ZaiError::ApiError { code: 1200, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_fails_without_retry() {
+        let req = FileUploadRequest::new(
+            "k".into(),
+            FilePurpose::FileExtract,
+            "/nonexistent/zai-rs-chunked-upload.bin",
+        );
+        let events: Vec<_> = req.upload_chunked(1024).collect().await;
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_terminal());
+        assert!(matches!(events[0], UploadProgress::Failed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_server_error_reports_progress() {
+        let path = std::env::temp_dir().join(format!("zai-chunked-{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"0123456789").unwrap();
+        let (base, mut requests) = serve_in_order(vec![
+            Reply::json(500, json!({"error": {"code": "500", "message": "boom"}})),
+            Reply::json(200, json!({"id": "file-1", "bytes": 10})),
+        ])
+        .await;
+
+        let req = FileUploadRequest::new("k".into(), FilePurpose::FileExtract, &path)
+            .with_endpoint(Endpoint::new(format!("{}/api", base)));
+        let events: Vec<_> = req.upload_chunked(4).collect().await;
+        std::fs::remove_file(&path).unwrap();
+
+        let sent: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                UploadProgress::ChunkSent { offset, len, .. } => Some((*offset, *len)),
+                _ => None,
+            })
+            .collect();
+        let chunks = [(0, 4), (4, 4), (8, 2)];
+        assert_eq!(sent, [chunks, chunks].concat());
+        assert!(matches!(
+            &events[4],
+            UploadProgress::Retrying {
+                attempt: 1,
+                sent_bytes: 10,
+                error: ZaiError::HttpError { status: 500, .. },
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(UploadProgress::Completed(file)) if file.id.as_deref() == Some("file-1")
+        ));
+
+        let requests = received(&mut requests);
+        assert_eq!(requests.len(), 2);
+        for request in &requests {
+            assert_eq!(request.path(), "/api/paas/v4/files");
+            assert_eq!(request.header("authorization"), Some("Bearer k"));
+            assert!(String::from_utf8_lossy(&request.body).contains("0123456789"));
+        }
+    }
+}
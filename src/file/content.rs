@@ -1,9 +1,24 @@
-use crate::client::http::HttpClient;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+use tokio::io::AsyncWriteExt;
+
+use crate::client::{
+    endpoint::Endpoint,
+    error::{ZaiError, ZaiResult},
+    http::{HttpClient, HttpClientConfig},
+};
 
 /// File content request (GET /paas/v4/files/{file_id}/content)
 pub struct FileContentRequest {
     pub key: String,
+    endpoint: Option<Endpoint>,
     url: String,
+    max_response_bytes: Option<u64>,
     _body: (),
 }
 
@@ -15,11 +30,27 @@ impl FileContentRequest {
         );
         Self {
             key,
+            endpoint: None,
             url,
+            max_response_bytes: None,
             _body: (),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Fail with `ResponseTooLarge` if the file is larger than `limit`
+    /// bytes (see [`HttpClientConfig::max_response_bytes`]); no limit by
+    /// default.
+    pub fn with_max_response_bytes(mut self, limit: u64) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
     /// Send the request and return raw bytes of the file content.
     pub async fn send(&self) -> crate::ZaiResult<Vec<u8>> {
         let resp: reqwest::Response = self.get().await?;
@@ -43,22 +74,109 @@ impl FileContentRequest {
     /// It will create parent directories if missing.
     /// Returns the number of bytes written.
     pub async fn send_to<P: AsRef<std::path::Path>>(&self, path: P) -> crate::ZaiResult<usize> {
-        let bytes = self.send().await?;
+        let written = self.download_to(path).await?;
+        Ok(written as usize)
+    }
 
-        let p = path.as_ref();
+    /// Stream the file content chunk by chunk as it arrives, without
+    /// holding the whole file in memory.
+    ///
+    /// The request is sent when the stream is first polled. A limit set with
+    /// [`with_max_response_bytes`](Self::with_max_response_bytes) ends the
+    /// stream with `ResponseTooLarge` once more bytes have arrived.
+    pub fn stream(&self) -> impl Stream<Item = ZaiResult<Bytes>> + Send + 'static {
+        // The limit is enforced per chunk below; the transport must not
+        // buffer the body to check it.
+        let request = FileContentRequest {
+            key: self.key.clone(),
+            endpoint: self.endpoint.clone(),
+            url: self.url.clone(),
+            max_response_bytes: None,
+            _body: (),
+        };
+        let limit = self.max_response_bytes;
+        stream::once(async move { request.open(limit).await })
+            .map(|opened| match opened {
+                Ok(chunks) => chunks.left_stream(),
+                Err(e) => stream::iter([Err(e)]).right_stream(),
+            })
+            .flatten()
+    }
 
-        if let Some(parent) = p.parent()
+    /// Write the file content to `path` as it arrives, creating parent
+    /// directories if missing. Returns the number of bytes written.
+    ///
+    /// The content goes to a temporary file next to `path` that replaces it
+    /// only once complete, so on error `path` is left as it was.
+    pub async fn download_to(&self, path: impl AsRef<Path>) -> ZaiResult<u64> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
             && !parent.as_os_str().is_empty()
         {
-            std::fs::create_dir_all(parent)?;
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let partial = partial_path(path);
+        let mut file = tokio::fs::File::create(&partial).await?;
+        let written = async {
+            let mut chunks = std::pin::pin!(self.stream());
+            let mut written = 0u64;
+            while let Some(chunk) = chunks.try_next().await? {
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+            }
+            file.flush().await?;
+            Ok::<_, ZaiError>(written)
+        }
+        .await;
+        drop(file);
+        let result = match written {
+            Ok(written) => tokio::fs::rename(&partial, path)
+                .await
+                .map(|()| written)
+                .map_err(ZaiError::from),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&partial).await;
+        }
+        result
+    }
+
+    /// Send the request and return the body as a chunk stream capped at
+    /// `limit` bytes.
+    async fn open(
+        self,
+        limit: Option<u64>,
+    ) -> ZaiResult<impl Stream<Item = ZaiResult<Bytes>> + Send + 'static> {
+        // get() already turns a non-2xx response into an error.
+        let resp = self.get().await?;
+        if let Some(limit) = limit
+            && resp.content_length().is_some_and(|len| len > limit)
+        {
+            return Err(ZaiError::ResponseTooLarge { limit });
         }
-        use std::io::Write;
-        let mut f = std::fs::File::create(p)?;
-        f.write_all(&bytes)?;
-        Ok(bytes.len())
+        Ok(stream::try_unfold(
+            (resp, 0u64),
+            move |(mut resp, received)| async move {
+                let Some(chunk) = resp.chunk().await? else {
+                    return Ok(None);
+                };
+                let received = received + chunk.len() as u64;
+                if let Some(limit) = limit.filter(|limit| received > *limit) {
+                    return Err(ZaiError::ResponseTooLarge { limit });
+                }
+                Ok(Some((chunk, (resp, received))))
+            },
+        ))
     }
 }
 
+/// Hidden sibling of `path` that a download is written to first.
+fn partial_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.part", name, uuid::Uuid::new_v4()))
+}
+
 impl HttpClient for FileContentRequest {
     type Body = ();
     type ApiUrl = String;
@@ -73,4 +191,65 @@ impl HttpClient for FileContentRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+    fn http_config(&self) -> Arc<HttpClientConfig> {
+        Arc::new(HttpClientConfig {
+            max_response_bytes: self.max_response_bytes,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::test_server::{Reply, serve};
+
+    /// Serve `parts` as a chunked body, pausing between chunks.
+    async fn serve_chunked(parts: Vec<&'static [u8]>) -> String {
+        let (base, _) = serve(move |_| {
+            Reply::status(200)
+                .header("content-type", "application/octet-stream")
+                .chunked(parts.iter().map(|p| p.to_vec()).collect())
+        })
+        .await;
+        format!("{}/api", base)
+    }
+
+    #[tokio::test]
+    async fn test_stream_and_download_reassemble_chunks() {
+        let parts: Vec<&'static [u8]> = vec![b"{\"id\":1}\n", b"{\"id\":2}\n", b"{\"id\":3}\n"];
+        let expected = parts.concat();
+        let base = serve_chunked(parts).await;
+        let request = || {
+            FileContentRequest::new("key".to_string(), "file-1")
+                .with_endpoint(Endpoint::new(base.clone()))
+        };
+
+        let chunks: Vec<Bytes> = request().stream().try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), expected);
+
+        let dir = std::env::temp_dir().join(format!("zai-content-{}", std::process::id()));
+        let path = dir.join("nested/output.jsonl");
+        let written = request().download_to(&path).await.unwrap();
+        assert_eq!(written, expected.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+
+        // Past the cap the stream fails, the earlier download is kept and
+        // the partial file is removed.
+        let err = request()
+            .with_max_response_bytes(12)
+            .download_to(&path)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZaiError::ResponseTooLarge { limit: 12 }));
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -1,8 +1,9 @@
-use crate::client::http::HttpClient;
+use crate::client::{endpoint::Endpoint, http::HttpClient};
 
 /// File delete request (DELETE /paas/v4/files/{file_id})
 pub struct FileDeleteRequest {
     pub key: String,
+    endpoint: Option<Endpoint>,
     url: String,
     _body: (),
 }
@@ -15,15 +16,22 @@ impl FileDeleteRequest {
         );
         Self {
             key,
+            endpoint: None,
             url,
             _body: (),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     pub fn delete(
         &self,
     ) -> impl std::future::Future<Output = crate::ZaiResult<reqwest::Response>> + Send {
-        let url = self.url.clone();
+        let url = self.request_url();
         let key = self.key.clone();
         async move {
             let resp = reqwest::Client::new()
@@ -85,4 +93,7 @@ impl HttpClient for FileDeleteRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
@@ -1,15 +1,30 @@
+use futures::{Stream, TryStreamExt, stream};
 use url::Url;
 
-use super::request::FileListQuery;
-use crate::{ZaiResult, client::http::HttpClient};
+use super::{
+    request::{FileListQuery, FileOrder, FilePurpose},
+    response::{FileListResponse, FileObject},
+};
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
 
 /// Files list request (GET /paas/v4/files)
 ///
 /// Builds query parameters from `FileListQuery` and performs an authenticated
 /// GET.
+///
+/// The purpose filter is sent to the server and, in case the server ignores
+/// it, also applied to every page received: [`send`](Self::send) and
+/// [`stream_all`](Self::stream_all) only return files of that purpose. The
+/// order is only passed to the server, as reordering one page would not sort
+/// the whole list.
 pub struct FileListRequest {
     pub key: String,
+    endpoint: Option<Endpoint>,
     url: String,
+    query: FileListQuery,
     _body: (),
 }
 
@@ -18,11 +33,19 @@ impl FileListRequest {
         let url = "https://open.bigmodel.cn/api/paas/v4/files".to_string();
         Self {
             key,
+            endpoint: None,
             url,
+            query: FileListQuery::new(),
             _body: (),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     fn rebuild_url(&mut self, q: &FileListQuery) {
         // SAFETY: This URL is a constant and guaranteed to be valid
         let mut url = Url::parse("https://open.bigmodel.cn/api/paas/v4/files")
@@ -47,15 +70,87 @@ impl FileListRequest {
 
     pub fn with_query(mut self, q: FileListQuery) -> Self {
         self.rebuild_url(&q);
+        self.query = q;
         self
     }
+
+    /// Only list files of `purpose`
+    pub fn with_purpose(mut self, purpose: FilePurpose) -> Self {
+        self.query.purpose = Some(purpose);
+        self.rebuild_url(&self.query.clone());
+        self
+    }
+
+    /// Sort order of the listing
+    pub fn with_order(mut self, order: FileOrder) -> Self {
+        self.query.order = Some(order);
+        self.rebuild_url(&self.query.clone());
+        self
+    }
+
     /// Send request and parse typed response.
-    pub async fn send(&self) -> ZaiResult<super::response::FileListResponse> {
+    pub async fn send(&self) -> ZaiResult<FileListResponse> {
+        let mut page = self.fetch().await?;
+        if let Some(data) = page.data.as_mut() {
+            self.keep_matching(data);
+        }
+        Ok(page)
+    }
+
+    /// Every file, fetching the next page once the current one has been
+    /// consumed. Starts after the query's `after` cursor, if any.
+    pub fn stream_all(&self) -> impl Stream<Item = ZaiResult<FileObject>> + Send + 'static {
+        let request = FileListRequest {
+            key: self.key.clone(),
+            endpoint: self.endpoint.clone(),
+            url: self.url.clone(),
+            query: self.query.clone(),
+            _body: (),
+        };
+        let cursor = self.query.after.clone();
+        stream::try_unfold(
+            (request, Some(cursor)),
+            |(mut request, cursor)| async move {
+                // `None` once the last page has been fetched
+                let Some(after) = cursor else {
+                    return ZaiResult::Ok(None);
+                };
+                request.query.after = after.clone();
+                request.rebuild_url(&request.query.clone());
+                let page = request.fetch().await?;
+                let mut files = page.data.unwrap_or_default();
+                let next = match files.last().and_then(|f| f.id.clone()) {
+                    // A cursor that does not move would list the same page
+                    // forever.
+                    Some(id) if page.has_more != Some(false) && after.as_ref() != Some(&id) => {
+                        Some(Some(id))
+                    },
+                    _ => None,
+                };
+                request.keep_matching(&mut files);
+                Ok(Some((
+                    stream::iter(files.into_iter().map(ZaiResult::Ok)),
+                    (request, next),
+                )))
+            },
+        )
+        .try_flatten()
+    }
+
+    /// One page as returned by the server
+    async fn fetch(&self) -> ZaiResult<FileListResponse> {
         let resp = self.get().await?;
-        let parsed = resp.json::<super::response::FileListResponse>().await?;
+        let parsed = resp.json::<FileListResponse>().await?;
         Ok(parsed)
     }
 
+    /// Drop files the server returned despite the purpose filter
+    fn keep_matching(&self, files: &mut Vec<FileObject>) {
+        if let Some(purpose) = &self.query.purpose {
+            files.retain(|f| f.purpose.as_deref() == Some(purpose.as_str()));
+        }
+    }
+
     /// Validate query, rebuild URL and send in one call.
     pub async fn send_with_query(
         mut self,
@@ -64,6 +159,7 @@ impl FileListRequest {
         use validator::Validate;
         q.validate()?;
         self.rebuild_url(q);
+        self.query = q.clone();
         self.send().await
     }
 }
@@ -82,4 +178,68 @@ impl HttpClient for FileListRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::test_server::{Reply, Request, serve};
+
+    /// Serve two pages of files, the first one ignoring the purpose filter.
+    async fn paged_server() -> (String, tokio::sync::mpsc::UnboundedReceiver<Request>) {
+        let (base, requests) = serve(|req| {
+            if req.path().contains("after=f2") {
+                Reply::json(
+                    200,
+                    r#"{"object":"list","data":[{"id":"f3","purpose":"batch"}],"has_more":false}"#,
+                )
+            } else {
+                Reply::json(
+                    200,
+                    r#"{"object":"list","data":[{"id":"f1","purpose":"batch"},{"id":"f2","purpose":"agent"}],"has_more":true}"#,
+                )
+            }
+        })
+        .await;
+        (format!("{}/api", base), requests)
+    }
+
+    #[tokio::test]
+    async fn test_filters_in_query_and_stream_all_pages() {
+        let (base, mut requests) = paged_server().await;
+        let request = FileListRequest::new("key".to_string())
+            .with_endpoint(Endpoint::new(base))
+            .with_query(FileListQuery::new().with_limit(2))
+            .with_purpose(FilePurpose::Batch)
+            .with_order(FileOrder::CreatedAt);
+
+        let page = request.send().await.unwrap();
+        let ids: Vec<_> = page
+            .data
+            .unwrap()
+            .into_iter()
+            .filter_map(|f| f.id)
+            .collect();
+        assert_eq!(ids, ["f1"]);
+        assert_eq!(
+            requests.recv().await.unwrap().line(),
+            "GET /api/paas/v4/files?purpose=batch&order=created_at&limit=2 HTTP/1.1"
+        );
+
+        let files: Vec<FileObject> = request.stream_all().try_collect().await.unwrap();
+        let ids: Vec<_> = files.into_iter().filter_map(|f| f.id).collect();
+        assert_eq!(ids, ["f1", "f3"]);
+        assert_eq!(
+            requests.recv().await.unwrap().line(),
+            "GET /api/paas/v4/files?purpose=batch&order=created_at&limit=2 HTTP/1.1"
+        );
+        assert_eq!(
+            requests.recv().await.unwrap().line(),
+            "GET /api/paas/v4/files?after=f2&purpose=batch&order=created_at&limit=2 HTTP/1.1"
+        );
+        assert!(requests.try_recv().is_err());
+    }
 }
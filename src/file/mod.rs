@@ -8,6 +8,7 @@
 //! | Operation | Module | Description |
 //! |-----------|--------|-------------|
 //! | Upload | [`upload`] | Upload files (PDF, images, etc.) |
+//! | Chunked upload | [`chunked`] | Stream large files with retries and progress |
 //! | List | [`list`] | List files with metadata |
 //! | Content | [`content`] | Retrieve file content |
 //! | Delete | [`delete`] | Delete files |
@@ -34,11 +35,13 @@ pub mod request;
 pub mod response;
 
 // Split operations into clear modules
+pub mod chunked;
 pub mod content;
 pub mod delete;
 pub mod list;
 pub mod upload;
 
+pub use chunked::*;
 pub use content::*;
 pub use delete::*;
 pub use list::*;
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use super::request::FilePurpose;
-use crate::client::http::HttpClient;
+use crate::client::{endpoint::Endpoint, http::HttpClient};
 
 /// File upload request (multipart/form-data)
 ///
@@ -10,16 +10,18 @@ use crate::client::http::HttpClient;
 /// - file: file content
 pub struct FileUploadRequest {
     pub key: String,
-    purpose: FilePurpose,
-    file_path: PathBuf,
+    endpoint: Option<Endpoint>,
+    pub(crate) purpose: FilePurpose,
+    pub(crate) file_path: PathBuf,
     file_name: Option<String>,
-    content_type: Option<String>,
+    pub(crate) content_type: Option<String>,
 }
 
 impl FileUploadRequest {
     pub fn new(key: String, purpose: FilePurpose, file_path: impl Into<PathBuf>) -> Self {
         Self {
             key,
+            endpoint: None,
             purpose,
             file_path: file_path.into(),
             file_name: None,
@@ -27,6 +29,12 @@ impl FileUploadRequest {
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     pub fn with_file_name(mut self, name: impl Into<String>) -> Self {
         self.file_name = Some(name.into());
         self
@@ -38,6 +46,20 @@ impl FileUploadRequest {
         self
     }
 
+    /// File name sent in the multipart part: the explicit name if set,
+    /// otherwise the file name of `file_path`, falling back to `upload.bin`.
+    pub(crate) fn upload_file_name(&self) -> String {
+        self.file_name
+            .clone()
+            .or_else(|| {
+                self.file_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .map(|s| s.to_string())
+            })
+            .unwrap_or_else(|| "upload.bin".to_string())
+    }
+
     /// Send the upload request and parse typed response (`FileObject`)
     pub async fn send(&self) -> crate::ZaiResult<super::response::FileObject> {
         let resp: reqwest::Response = self.post().await?;
@@ -66,26 +88,18 @@ impl HttpClient for FileUploadRequest {
     fn post(
         &self,
     ) -> impl std::future::Future<Output = crate::ZaiResult<reqwest::Response>> + Send {
-        let url: String = "https://open.bigmodel.cn/api/paas/v4/files".to_string();
+        let url = self.request_url();
 
         let key: String = self.key.clone();
 
         let purpose = self.purpose.clone();
         let path = self.file_path.clone();
-        let file_name = self.file_name.clone();
+        let fname = self.upload_file_name();
         let content_type = self.content_type.clone();
         async move {
             let mut form =
                 reqwest::multipart::Form::new().text("purpose", purpose.as_str().to_string());
 
-            let fname = file_name
-                .or_else(|| {
-                    path.file_name()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s.to_string())
-                })
-                .unwrap_or_else(|| "upload.bin".to_string());
-
             let mut part = reqwest::multipart::Part::bytes(std::fs::read(&path)?).file_name(fname);
             if let Some(ct) = content_type {
                 part =
@@ -136,4 +150,7 @@ impl HttpClient for FileUploadRequest {
             }
         }
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
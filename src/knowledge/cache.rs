@@ -0,0 +1,165 @@
+//! Response cache for knowledge retrieve requests.
+//!
+//! A [`KnowledgeCache`] stores the last [`KnowledgeRetrieveResponse`] per
+//! request URL (i.e. per knowledge id and endpoint) together with the
+//! response's `ETag`, if the server sent one. Entries younger than the TTL are
+//! served without a network call; older entries that carry an ETag are
+//! revalidated with `If-None-Match`, and a `304 Not Modified` reply counts as
+//! a hit.
+//!
+//! The cache is per request builder: [`KnowledgeRetrieveRequest::with_cache`]
+//! creates a new, private cache. To share entries between requests, create one
+//! cache and pass a clone to each request with
+//! [`KnowledgeRetrieveRequest::with_shared_cache`]; clones share storage.
+//!
+//! [`KnowledgeRetrieveRequest::with_cache`]: super::KnowledgeRetrieveRequest::with_cache
+//! [`KnowledgeRetrieveRequest::with_shared_cache`]: super::KnowledgeRetrieveRequest::with_shared_cache
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::retrieve::KnowledgeRetrieveResponse;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    response: KnowledgeRetrieveResponse,
+    etag: Option<String>,
+    stored_at: Instant,
+}
+
+/// Cache lookup result.
+#[derive(Debug, Clone)]
+pub(crate) enum Lookup {
+    /// Entry within its TTL
+    Fresh(Box<KnowledgeRetrieveResponse>),
+    /// Expired entry that can be revalidated with this ETag
+    Stale(String),
+    /// Nothing usable cached
+    Miss,
+}
+
+/// TTL-bounded cache of knowledge retrieve responses.
+#[derive(Debug, Clone)]
+pub struct KnowledgeCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl KnowledgeCache {
+    /// Create an empty cache whose entries stay fresh for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Time an entry is served without contacting the server
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Number of cached responses, fresh or stale
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drop all cached responses
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        // A panic while holding the lock cannot leave an entry half-written.
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn lookup(&self, key: &str) -> Lookup {
+        match self.lock().get(key) {
+            Some(entry) if entry.stored_at.elapsed() < self.ttl => {
+                Lookup::Fresh(Box::new(entry.response.clone()))
+            },
+            Some(Entry {
+                etag: Some(etag), ..
+            }) => Lookup::Stale(etag.clone()),
+            _ => Lookup::Miss,
+        }
+    }
+
+    pub(crate) fn insert(
+        &self,
+        key: &str,
+        response: KnowledgeRetrieveResponse,
+        etag: Option<String>,
+    ) {
+        self.lock().insert(
+            key.to_string(),
+            Entry {
+                response,
+                etag,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Mark an entry as fresh again after a `304 Not Modified`.
+    pub(crate) fn revalidate(&self, key: &str) -> Option<KnowledgeRetrieveResponse> {
+        let mut entries = self.lock();
+        let entry = entries.get_mut(key)?;
+        entry.stored_at = Instant::now();
+        Some(entry.response.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(message: &str) -> KnowledgeRetrieveResponse {
+        serde_json::from_value(serde_json::json!({ "code": 200, "message": message })).unwrap()
+    }
+
+    #[test]
+    fn test_fresh_entry_is_served() {
+        let cache = KnowledgeCache::new(Duration::from_secs(60));
+        assert!(matches!(cache.lookup("k"), Lookup::Miss));
+
+        cache.insert("k", response("a"), None);
+        match cache.lookup("k") {
+            Lookup::Fresh(r) => assert_eq!(r.message.as_deref(), Some("a")),
+            other => panic!("expected fresh entry, got {:?}", other),
+        }
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_expired_entry_revalidates_with_etag() {
+        let cache = KnowledgeCache::new(Duration::ZERO);
+        cache.insert("plain", response("a"), None);
+        cache.insert("tagged", response("b"), Some("\"v1\"".to_string()));
+
+        assert!(matches!(cache.lookup("plain"), Lookup::Miss));
+        assert!(matches!(cache.lookup("tagged"), Lookup::Stale(ref t) if t == "\"v1\""));
+        assert_eq!(
+            cache.revalidate("tagged").unwrap().message.as_deref(),
+            Some("b")
+        );
+        assert!(cache.revalidate("missing").is_none());
+    }
+
+    #[test]
+    fn test_clones_share_entries() {
+        let cache = KnowledgeCache::new(Duration::from_secs(60));
+        cache.clone().insert("k", response("a"), None);
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -1,10 +1,17 @@
-use super::types::DocumentDetailResponse;
-use crate::{ZaiResult, client::http::HttpClient};
+use std::{future::Future, time::Duration};
+
+use super::types::{DocumentDetailResponse, EmbeddingStatus};
+use crate::{
+    ZaiError, ZaiResult,
+    client::http::HttpClient,
+    poll::{self, PollConfig, PollError},
+};
 
 /// Retrieve document detail by id
 pub struct DocumentRetrieveRequest {
     /// Bearer API key
     pub key: String,
+    document_id: String,
     url: String,
 }
 
@@ -15,7 +22,11 @@ impl DocumentRetrieveRequest {
             "https://open.bigmodel.cn/api/llm-application/open/document/{}",
             document_id.as_ref()
         );
-        Self { key, url }
+        Self {
+            key,
+            document_id: document_id.as_ref().to_string(),
+            url,
+        }
     }
 
     /// Send GET request and parse typed response
@@ -24,6 +35,88 @@ impl DocumentRetrieveRequest {
         let parsed = resp.json::<DocumentDetailResponse>().await?;
         Ok(parsed)
     }
+
+    /// Poll the document every `interval` until it is embedded, e.g. right
+    /// after a file or URL upload.
+    ///
+    /// Returns the detail response once `embedding_stat` reports
+    /// [`EmbeddingStatus::Completed`].
+    ///
+    /// ## Errors
+    ///
+    /// * [`ZaiError::DocumentEmbeddingFailed`] with the server's `failInfo`
+    ///   if parsing or embedding failed
+    /// * [`ZaiError::HttpError`] with status 408 if `timeout` elapses first
+    /// * any error from the status request itself
+    pub async fn wait_until_embedded(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+    ) -> ZaiResult<DocumentDetailResponse> {
+        self.wait_until_embedded_with(PollConfig::new(interval, timeout))
+            .await
+    }
+
+    /// [`wait_until_embedded`](Self::wait_until_embedded) with backoff,
+    /// transient-error retries or a progress callback from `config`
+    pub async fn wait_until_embedded_with(
+        &self,
+        config: PollConfig,
+    ) -> ZaiResult<DocumentDetailResponse> {
+        poll_until_embedded(&self.document_id, config, || self.send()).await
+    }
+}
+
+/// Drive `fetch` until the document reaches a terminal embedding status,
+/// mapping a failure or timeout to an error.
+async fn poll_until_embedded<F, Fut>(
+    document_id: &str,
+    config: PollConfig,
+    fetch: F,
+) -> ZaiResult<DocumentDetailResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ZaiResult<DocumentDetailResponse>>,
+{
+    let status =
+        |detail: &DocumentDetailResponse| detail.data.as_ref().and_then(|d| d.embedding_status());
+    let detail = match poll::until(
+        fetch,
+        |detail| {
+            matches!(
+                status(detail),
+                Some(EmbeddingStatus::Completed | EmbeddingStatus::Failed)
+            )
+        },
+        config,
+    )
+    .await
+    {
+        Ok(detail) => detail,
+        Err(PollError::TimedOut { last, elapsed }) => {
+            return Err(ZaiError::HttpError {
+                status: 408,
+                message: format!(
+                    "document {} not embedded after {:?} (last status: {:?})",
+                    document_id,
+                    elapsed,
+                    status(&last)
+                ),
+            });
+        },
+        Err(PollError::Failed(e)) => return Err(e),
+    };
+    if status(&detail) == Some(EmbeddingStatus::Failed) {
+        let fail_info = detail.data.as_ref().and_then(|d| d.fail_info.as_ref());
+        return Err(ZaiError::DocumentEmbeddingFailed {
+            document_id: document_id.to_string(),
+            code: fail_info.and_then(|f| f.embedding_code),
+            reason: fail_info
+                .and_then(|f| f.embedding_msg.clone())
+                .unwrap_or_else(|| "no reason given".to_string()),
+        });
+    }
+    Ok(detail)
 }
 
 impl HttpClient for DocumentRetrieveRequest {
@@ -41,3 +134,80 @@ impl HttpClient for DocumentRetrieveRequest {
         &()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn detail(item: serde_json::Value) -> DocumentDetailResponse {
+        serde_json::from_value(json!({"code": 200, "data": item})).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_poll_embedding_then_completed() {
+        let calls = AtomicUsize::new(0);
+        let result = poll_until_embedded(
+            "doc-1",
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5)),
+            || {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                let stat = if n < 2 { 0 } else { 1 };
+                async move { Ok(detail(json!({"id": "doc-1", "embedding_stat": stat}))) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            result.data.unwrap().embedding_status(),
+            Some(EmbeddingStatus::Completed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_failed_reports_fail_info() {
+        let err = poll_until_embedded(
+            "doc-1",
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5)),
+            || async {
+                Ok(detail(json!({
+                    "embedding_stat": 2,
+                    "failInfo": {"embedding_code": 10003, "embedding_msg": "file parse failed"}
+                })))
+            },
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            ZaiError::DocumentEmbeddingFailed {
+                document_id,
+                code,
+                reason,
+            } => {
+                assert_eq!(document_id, "doc-1");
+                assert_eq!(code, Some(10003));
+                assert_eq!(reason, "file parse failed");
+            },
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_timeout() {
+        let err = poll_until_embedded(
+            "doc-1",
+            PollConfig::new(Duration::from_millis(5), Duration::from_millis(20)),
+            || async { Ok(detail(json!({"embedding_stat": 0}))) },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ZaiError::HttpError { status: 408, .. }));
+        assert!(err.to_string().contains("Embedding"), "{}", err);
+    }
+}
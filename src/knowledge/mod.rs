@@ -10,7 +10,9 @@
 //! |-----------|--------|-------------|
 //! | Create | [`create`] | Create a new knowledge base |
 //! | List | [`list`] | List knowledge bases |
-//! | Retrieve | [`retrieve`] | Get knowledge-base details / search |
+//! | Retrieve | [`retrieve`] | Get knowledge-base details |
+//! | Search | [`search`] | Retrieve passages relevant to a query |
+//! | Cache | [`cache`] | Optional TTL/ETag cache for retrieve responses |
 //! | Update | [`update`] | Update metadata |
 //! | Delete | [`delete`] | Delete a knowledge base |
 //! | Capacity | [`capacity`] | Check usage / quota |
@@ -22,7 +24,7 @@
 //! | Upload (file) | [`document_upload_file`] | Upload a local file |
 //! | Upload (URL) | [`document_upload_url`] | Upload from a URL |
 //! | List | [`document_list`] | List documents in a KB |
-//! | Retrieve | [`document_retrieve`] | Get document details |
+//! | Retrieve | [`document_retrieve`] | Get document details / wait for embedding |
 //! | Delete | [`document_delete`] | Delete documents |
 //! | Re-embed | [`document_reembedding`] | Re-run vectorisation |
 //! | Images | [`document_image_list`] | List extracted images |
@@ -43,9 +45,10 @@
 //! let doc = client.upload_document(&DocumentUploadFileRequest::new(kb_id, file, opts)).await?;
 //!
 //! // Semantic search
-//! let results = client.retrieve_knowledge(&KnowledgeRetrieveRequest::new(kb_id, query)).await?;
+//! let results = KnowledgeSearchRequest::new(key, kb_id, query).with_top_k(5).send().await?;
 //! ```
 
+pub mod cache;
 pub mod capacity;
 pub mod create;
 pub mod delete;
@@ -58,9 +61,11 @@ pub mod document_upload_file;
 pub mod document_upload_url;
 pub mod list;
 pub mod retrieve;
+pub mod search;
 pub mod types;
 pub mod update;
 
+pub use cache::KnowledgeCache;
 pub use capacity::KnowledgeCapacityRequest;
 pub use create::{
     BackgroundColor, CreateKnowledgeBody, CreateKnowledgeRequest, CreateKnowledgeResponse,
@@ -78,11 +83,14 @@ pub use document_upload_file::{DocumentSliceType, DocumentUploadFileRequest, Upl
 pub use document_upload_url::{DocumentUploadUrlRequest, UploadUrlBody, UploadUrlDetail};
 pub use list::{KnowledgeListQuery, KnowledgeListRequest};
 pub use retrieve::{KnowledgeRetrieveRequest, KnowledgeRetrieveResponse};
+pub use search::{
+    KnowledgePassage, KnowledgeSearchBody, KnowledgeSearchRequest, KnowledgeSearchResponse,
+};
 pub use types::{
     DocumentDetailResponse, DocumentFailInfo, DocumentImageItem, DocumentImageListData,
     DocumentImageListResponse, DocumentItem, DocumentListData, DocumentListResponse,
-    KnowledgeCapacityData, KnowledgeCapacityResponse, KnowledgeDetailResponse, KnowledgeItem,
-    KnowledgeListData, KnowledgeListResponse, KnowledgeUsageCounts, UploadFileData,
+    EmbeddingStatus, KnowledgeCapacityData, KnowledgeCapacityResponse, KnowledgeDetailResponse,
+    KnowledgeItem, KnowledgeListData, KnowledgeListResponse, KnowledgeUsageCounts, UploadFileData,
     UploadFileFailedInfo, UploadFileResponse, UploadFileSuccessInfo, UploadUrlData,
     UploadUrlFailedInfo, UploadUrlResponse, UploadUrlSuccessInfo,
 };
@@ -1,11 +1,32 @@
-use super::types::KnowledgeDetailResponse;
-use crate::{ZaiResult, client::http::HttpClient};
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{
+    StatusCode,
+    header::{ETAG, IF_NONE_MATCH},
+};
+
+use super::{
+    cache::{KnowledgeCache, Lookup},
+    types::KnowledgeDetailResponse,
+};
+use crate::{
+    ZaiResult,
+    client::{
+        auth::Auth,
+        endpoint::Endpoint,
+        http::{CustomHeaders, HttpClient, HttpClientConfig},
+        transport::Transport,
+    },
+};
 
 /// Knowledge detail request (GET /llm-application/open/knowledge/{id})
 pub struct KnowledgeRetrieveRequest {
     /// Bearer API key
     pub key: String,
     url: String,
+    endpoint: Option<Endpoint>,
+    /// Optional response cache, see [`cache`](super::cache)
+    cache: Option<KnowledgeCache>,
     _body: (),
 }
 
@@ -19,14 +40,67 @@ impl KnowledgeRetrieveRequest {
         Self {
             key,
             url,
+            endpoint: None,
+            cache: None,
             _body: (),
         }
     }
 
-    /// Send and parse typed response
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Cache responses of this request for `ttl`.
+    ///
+    /// The cache belongs to this builder only; use
+    /// [`with_shared_cache`](Self::with_shared_cache) to share one between
+    /// requests.
+    pub fn with_cache(self, ttl: Duration) -> Self {
+        self.with_shared_cache(KnowledgeCache::new(ttl))
+    }
+
+    /// Use an existing (possibly shared) response cache
+    pub fn with_shared_cache(mut self, cache: KnowledgeCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Send and parse typed response.
+    ///
+    /// With a cache configured, a fresh cached response is returned without a
+    /// network call, and an expired one carrying an ETag is revalidated with
+    /// `If-None-Match`.
     pub async fn send(&self) -> ZaiResult<KnowledgeDetailResponse> {
-        let resp = self.get().await?;
+        let Some(cache) = &self.cache else {
+            return self.get().await?.json().await.map_err(Into::into);
+        };
+
+        let key = self.request_url();
+        let resp = match cache.lookup(&key) {
+            Lookup::Fresh(parsed) => return Ok(*parsed),
+            Lookup::Stale(etag) => {
+                let resp = Revalidate::new(self, etag).get().await?;
+                if resp.status() != StatusCode::NOT_MODIFIED {
+                    resp
+                } else if let Some(parsed) = cache.revalidate(&key) {
+                    return Ok(parsed);
+                } else {
+                    // The entry was evicted meanwhile; fetch it again.
+                    self.get().await?
+                }
+            },
+            Lookup::Miss => self.get().await?,
+        };
+
+        let etag = resp
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
         let parsed = resp.json::<KnowledgeDetailResponse>().await?;
+        cache.insert(&key, parsed.clone(), etag);
         Ok(parsed)
     }
 }
@@ -45,7 +119,109 @@ impl HttpClient for KnowledgeRetrieveRequest {
     fn body(&self) -> &Self::Body {
         &self._body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+}
+
+/// A [`KnowledgeRetrieveRequest`] sent with `If-None-Match`, so it goes
+/// through the same [`HttpClient`] path (endpoint, auth, headers, retry) as an
+/// unconditional one.
+struct Revalidate<'a> {
+    request: &'a KnowledgeRetrieveRequest,
+    headers: CustomHeaders,
+}
+
+impl<'a> Revalidate<'a> {
+    fn new(request: &'a KnowledgeRetrieveRequest, etag: String) -> Self {
+        let mut headers = request.custom_headers().cloned().unwrap_or_default();
+        headers.insert(IF_NONE_MATCH.as_str(), etag);
+        Self { request, headers }
+    }
+}
+
+impl HttpClient for Revalidate<'_> {
+    type Body = ();
+    type ApiUrl = String;
+    type ApiKey = String;
+
+    fn api_url(&self) -> &Self::ApiUrl {
+        self.request.api_url()
+    }
+    fn api_key(&self) -> &Self::ApiKey {
+        self.request.api_key()
+    }
+    fn body(&self) -> &Self::Body {
+        self.request.body()
+    }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.request.endpoint()
+    }
+    fn auth(&self) -> Option<&Auth> {
+        self.request.auth()
+    }
+    fn transport(&self) -> Option<Arc<dyn Transport>> {
+        self.request.transport()
+    }
+    fn custom_headers(&self) -> Option<&CustomHeaders> {
+        Some(&self.headers)
+    }
+    fn http_config(&self) -> Arc<HttpClientConfig> {
+        self.request.http_config()
+    }
 }
 
 /// Alias for symmetry with other modules
 pub type KnowledgeRetrieveResponse = KnowledgeDetailResponse;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::test_server::{Reply, received, serve_in_order};
+
+    #[tokio::test]
+    async fn test_send_returns_cached_response() {
+        let cache = KnowledgeCache::new(Duration::from_secs(60));
+        let request = KnowledgeRetrieveRequest::new("key".to_string(), "kb-1")
+            .with_shared_cache(cache.clone());
+        let cached: KnowledgeDetailResponse =
+            serde_json::from_value(serde_json::json!({ "code": 200, "message": "cached" }))
+                .unwrap();
+        cache.insert(&request.request_url(), cached, None);
+
+        let resp = request.send().await.unwrap();
+        assert_eq!(resp.message.as_deref(), Some("cached"));
+    }
+
+    #[tokio::test]
+    async fn test_stale_entry_is_revalidated_through_endpoint() {
+        let (base, mut requests) = serve_in_order(vec![
+            Reply::json(200, json!({ "code": 200, "message": "first" })).header("ETag", "\"v1\""),
+            Reply::status(304),
+        ])
+        .await;
+        let request = KnowledgeRetrieveRequest::new("key".to_string(), "kb-1")
+            .with_endpoint(Endpoint::new(format!("{}/api", base)))
+            .with_cache(Duration::ZERO);
+
+        assert_eq!(
+            request.send().await.unwrap().message.as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            request.send().await.unwrap().message.as_deref(),
+            Some("first")
+        );
+
+        let requests = received(&mut requests);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1].path(),
+            "/api/llm-application/open/knowledge/kb-1"
+        );
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+        assert_eq!(requests[1].header("authorization"), Some("Bearer key"));
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    ZaiResult,
+    client::{endpoint::Endpoint, http::HttpClient},
+};
+
+/// Request body for semantic search over knowledge bases
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct KnowledgeSearchBody {
+    /// Question or search text
+    #[validate(length(min = 1))]
+    pub query: String,
+    /// Knowledge bases to search
+    #[validate(length(min = 1))]
+    pub knowledge_ids: Vec<String>,
+    /// Number of passages to return (server default when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub top_k: Option<u32>,
+}
+
+/// Semantic search request (POST /llm-application/open/knowledge/retrieve)
+///
+/// Returns the passages most relevant to `query`. Unlike
+/// [`KnowledgeRetrieveRequest`](super::KnowledgeRetrieveRequest), which
+/// fetches a knowledge base's details, this searches its documents.
+pub struct KnowledgeSearchRequest {
+    /// Bearer API key
+    pub key: String,
+    /// Base URL override
+    endpoint: Option<Endpoint>,
+    url: String,
+    body: KnowledgeSearchBody,
+}
+
+impl KnowledgeSearchRequest {
+    /// Search `knowledge_id` for `query`
+    pub fn new(key: String, knowledge_id: impl Into<String>, query: impl Into<String>) -> Self {
+        Self {
+            key,
+            endpoint: None,
+            url: "https://open.bigmodel.cn/api/llm-application/open/knowledge/retrieve".to_string(),
+            body: KnowledgeSearchBody {
+                query: query.into(),
+                knowledge_ids: vec![knowledge_id.into()],
+                top_k: None,
+            },
+        }
+    }
+
+    /// Also search another knowledge base
+    pub fn add_knowledge_id(mut self, knowledge_id: impl Into<String>) -> Self {
+        self.body.knowledge_ids.push(knowledge_id.into());
+        self
+    }
+
+    /// Number of passages to return
+    pub fn with_top_k(mut self, top_k: u32) -> Self {
+        self.body.top_k = Some(top_k);
+        self
+    }
+
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Send POST request with JSON body and parse typed response
+    pub async fn send(&self) -> ZaiResult<KnowledgeSearchResponse> {
+        self.body.validate()?;
+        let resp = self.post().await?;
+        let parsed = resp.json::<KnowledgeSearchResponse>().await?;
+        Ok(parsed)
+    }
+}
+
+impl HttpClient for KnowledgeSearchRequest {
+    type Body = KnowledgeSearchBody;
+    type ApiUrl = String;
+    type ApiKey = String;
+
+    fn api_url(&self) -> &Self::ApiUrl {
+        &self.url
+    }
+    fn api_key(&self) -> &Self::ApiKey {
+        &self.key
+    }
+    fn body(&self) -> &Self::Body {
+        &self.body
+    }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+}
+
+/// One retrieved passage
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KnowledgePassage {
+    /// Passage text
+    #[serde(default)]
+    pub text: String,
+    /// Relevance score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// Source information (document id, name, URL, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Search response envelope: { data: [passage], code, message, timestamp }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeSearchResponse {
+    /// Retrieved passages, most relevant first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<KnowledgePassage>>,
+    /// Response code (200 means success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<i64>,
+    /// Response message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Response timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+}
+
+impl KnowledgeSearchResponse {
+    /// Retrieved passages; empty when nothing matched
+    pub fn passages(&self) -> &[KnowledgePassage] {
+        self.data.as_deref().unwrap_or_default()
+    }
+}
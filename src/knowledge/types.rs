@@ -117,6 +117,71 @@ pub struct KnowledgeCapacityResponse {
     pub timestamp: Option<u64>,
 }
 
+impl KnowledgeCapacityResponse {
+    /// Bytes used; 0 when not reported
+    pub fn used_bytes(&self) -> u64 {
+        self.bytes(|d| d.used.as_ref())
+    }
+
+    /// Byte quota; 0 when not reported
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes(|d| d.total.as_ref())
+    }
+
+    /// Bytes used, e.g. `"1.5 GB"` (binary units)
+    pub fn used_human(&self) -> String {
+        human_bytes(self.used_bytes())
+    }
+
+    /// Byte quota, e.g. `"10.0 GB"` (binary units)
+    pub fn total_human(&self) -> String {
+        human_bytes(self.total_bytes())
+    }
+
+    /// Share of the quota in use, from 0.0 upwards; 0.0 when no quota is
+    /// reported
+    pub fn usage_ratio(&self) -> f64 {
+        match self.total_bytes() {
+            0 => 0.0,
+            total => self.used_bytes() as f64 / total as f64,
+        }
+    }
+
+    /// Whether at least `threshold` (e.g. `0.9`) of the quota is in use
+    pub fn is_near_capacity(&self, threshold: f64) -> bool {
+        self.total_bytes() > 0 && self.usage_ratio() >= threshold
+    }
+
+    fn bytes(
+        &self,
+        counts: impl Fn(&KnowledgeCapacityData) -> Option<&KnowledgeUsageCounts>,
+    ) -> u64 {
+        self.data
+            .as_ref()
+            .and_then(counts)
+            .and_then(|c| c.length)
+            .unwrap_or(0)
+    }
+}
+
+/// `bytes` in B, KB, MB, GB or TB (powers of 1024), one decimal above bytes
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
 /// Document vectorization failure info
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct DocumentFailInfo {
@@ -128,6 +193,30 @@ pub struct DocumentFailInfo {
     pub embedding_msg: Option<String>,
 }
 
+/// Vectorization state of a document (`embedding_stat`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingStatus {
+    /// Still being parsed/embedded (0)
+    Embedding,
+    /// Embedded and queryable (1)
+    Completed,
+    /// Parsing or embedding failed (2); see `failInfo`
+    Failed,
+    /// A status code this SDK does not know
+    Other(i64),
+}
+
+impl From<i64> for EmbeddingStatus {
+    fn from(code: i64) -> Self {
+        match code {
+            0 => EmbeddingStatus::Embedding,
+            1 => EmbeddingStatus::Completed,
+            2 => EmbeddingStatus::Failed,
+            other => EmbeddingStatus::Other(other),
+        }
+    }
+}
+
 /// Document item in a knowledge base
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct DocumentItem {
@@ -164,7 +253,13 @@ pub struct DocumentItem {
     pub fail_info: Option<DocumentFailInfo>,
 }
 
-/// Document list data payload
+impl DocumentItem {
+    /// Typed [`embedding_stat`](Self::embedding_stat)
+    pub fn embedding_status(&self) -> Option<EmbeddingStatus> {
+        self.embedding_stat.map(EmbeddingStatus::from)
+    }
+}
+
 /// Document detail response envelope (data is a single document item)
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct DocumentDetailResponse {
@@ -182,6 +277,7 @@ pub struct DocumentDetailResponse {
     pub timestamp: Option<u64>,
 }
 
+/// Document list data payload
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct DocumentListData {
     /// Documents list
@@ -354,3 +450,48 @@ pub struct UploadFileResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn capacity(used: u64, total: u64) -> KnowledgeCapacityResponse {
+        serde_json::from_value(json!({
+            "code": 200,
+            "data": {"used": {"length": used}, "total": {"length": total}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_human_sizes() {
+        assert_eq!(human_bytes(0), "0 B");
+        assert_eq!(human_bytes(1023), "1023 B");
+        assert_eq!(human_bytes(1024), "1.0 KB");
+        assert_eq!(human_bytes(1024 * 1024), "1.0 MB");
+        assert_eq!(human_bytes(3 * 1024 * 1024 * 1024 / 2), "1.5 GB");
+        assert_eq!(human_bytes(5 * 1024u64.pow(4)), "5.0 TB");
+
+        let response = capacity(1024 * 1024, 3 * 1024 * 1024 * 1024 / 2);
+        assert_eq!(response.used_human(), "1.0 MB");
+        assert_eq!(response.total_human(), "1.5 GB");
+    }
+
+    #[test]
+    fn test_usage_ratio() {
+        let response = capacity(900, 1000);
+        assert!((response.usage_ratio() - 0.9).abs() < 1e-9);
+        assert!(response.is_near_capacity(0.9));
+        assert!(!response.is_near_capacity(0.95));
+
+        let unlimited = capacity(500, 0);
+        assert_eq!(unlimited.usage_ratio(), 0.0);
+        assert!(!unlimited.is_near_capacity(0.0));
+
+        let empty: KnowledgeCapacityResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty.used_human(), "0 B");
+        assert_eq!(empty.usage_ratio(), 0.0);
+    }
+}
@@ -39,6 +39,7 @@
 //! - [`agent`] — Agent API (creation, chat, history)
 //! - [`toolkits`] — Tool execution framework (registration, execution, caching,
 //!   RMCP bridge)
+//! - [`poll`] — Polling async tasks (video, file parsing, embedding) until done
 //! - [`realTime`] — Real-time audio/video communication (WebSocket)
 //!
 //! # Quick Start
@@ -82,6 +83,7 @@
 //! |---------|---------|-------------|
 //! | (default) | enabled | Core API functionality |
 //! | `rmcp-kits` | disabled | Enable RMCP protocol bridge for MCP tool calling |
+//! | `tracing` | enabled | Log through the `tracing` crate, with spans around tool execution; disable to drop the dependency |
 //! | `web-example` | disabled | Enable axum/tower dependencies for web examples |
 //!
 //! Enable in `Cargo.toml`:
@@ -118,6 +120,7 @@ pub mod file;
 pub mod knowledge;
 
 pub mod model;
+pub mod poll;
 #[allow(non_snake_case)]
 pub mod realTime;
 pub mod tool;
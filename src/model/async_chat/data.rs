@@ -4,8 +4,13 @@ use serde::Serialize;
 use validator::Validate;
 
 use super::super::{chat_base_request::*, tools::*, traits::*};
-use crate::client::http::HttpClient;
+use crate::client::{endpoint::Endpoint, http::HttpClient};
 
+/// Submit a chat completion as an async task.
+///
+/// Submitted tasks cannot be cancelled: the API has no cancel endpoint, so a
+/// task always runs to `SUCCESS` or `FAIL`. Poll it with
+/// [`AsyncChatGetRequest`](crate::model::async_chat_get::AsyncChatGetRequest).
 pub struct AsyncChatCompletion<N, M, S = StreamOff>
 where
     N: ModelName + AsyncChat,
@@ -14,6 +19,7 @@ where
     S: StreamState,
 {
     pub key: String,
+    endpoint: Option<Endpoint>,
     body: ChatBody<N, M>,
     _stream: PhantomData<S>,
 }
@@ -29,6 +35,7 @@ where
         Self {
             body,
             key,
+            endpoint: None,
             _stream: PhantomData,
         }
     }
@@ -84,12 +91,23 @@ where
         self.body = self.body.extend_tools(tools);
         self
     }
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.body = self.body.with_tool_choice(tool_choice);
+        self
+    }
     pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
         self.body = self.body.with_user_id(user_id);
         self
     }
-    pub fn with_stop(mut self, stop: String) -> Self {
-        self.body = self.body.with_stop(stop);
+    /// Stop generating at any of `sequences` (the API currently accepts
+    /// one); an empty list clears them
+    pub fn with_stop(mut self, sequences: Vec<String>) -> Self {
+        self.body = self.body.with_stop(sequences);
+        self
+    }
+    /// Append one stop sequence
+    pub fn with_stop_one(mut self, sequence: impl Into<String>) -> Self {
+        self.body = self.body.with_stop_one(sequence);
         self
     }
 
@@ -107,6 +125,7 @@ where
         self.body.stream = Some(true);
         AsyncChatCompletion {
             key: self.key,
+            endpoint: self.endpoint,
             body: self.body,
             _stream: PhantomData,
         }
@@ -146,6 +165,20 @@ where
     }
 }
 
+impl<N, M, S> AsyncChatCompletion<N, M, S>
+where
+    N: ModelName + AsyncChat,
+    (N, M): Bounded,
+    ChatBody<N, M>: Serialize,
+    S: StreamState,
+{
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+}
+
 impl<N, M> AsyncChatCompletion<N, M, StreamOn>
 where
     N: ModelName + AsyncChat,
@@ -167,6 +200,7 @@ where
         self.body.tool_stream = None;
         AsyncChatCompletion {
             key: self.key,
+            endpoint: self.endpoint,
             body: self.body,
             _stream: PhantomData,
         }
@@ -193,6 +227,9 @@ where
     fn body(&self) -> &Self::Body {
         &self.body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
 }
 
 impl<N, M> crate::model::traits::SseStreamable for AsyncChatCompletion<N, M, StreamOn>
@@ -1,12 +1,18 @@
-use std::marker::PhantomData;
+use std::{future::Future, marker::PhantomData};
 
 use super::super::traits::*;
-use crate::client::http::HttpClient;
+use crate::{
+    ZaiError, ZaiResult,
+    client::http::HttpClient,
+    model::chat_base_response::{ChatCompletionResponse, TaskStatus},
+    poll::{self, PollConfig},
+};
 pub struct AsyncChatGetRequest<N>
 where
     N: ModelName + AsyncChat,
 {
     pub key: String,
+    task_id: String,
     url: String,
     // Empty body placeholder to satisfy HttpClient::Body
     _body: (),
@@ -21,10 +27,11 @@ where
     pub fn new(_model: N, task_id: String, key: String) -> Self {
         let url = format!(
             "https://open.bigmodel.cn/api/paas/v4/async-result/{}",
-            task_id
+            &task_id
         );
         Self {
             key,
+            task_id,
             url,
             _body: (),
             _marker: PhantomData,
@@ -54,6 +61,67 @@ where
 
         Ok(parsed)
     }
+
+    /// Poll the task until it is no longer `PROCESSING`, e.g. an async chat
+    /// or a video generation submitted with
+    /// [`VideoGenRequest`](crate::model::gen_video_async::VideoGenRequest).
+    ///
+    /// Returns the result once the task succeeded. A failed task is an
+    /// `ApiError`; a timeout is an `HttpError` 408 (see
+    /// [`PollError`](crate::poll::PollError)).
+    ///
+    /// ```rust,ignore
+    /// let video = AsyncChatGetRequest::new(CogVideoX3 {}, task_id, key)
+    ///     .wait(PollConfig::new(Duration::from_secs(5), Duration::from_secs(600)))
+    ///     .await?;
+    /// let url = video.video_result().and_then(|v| v.first()?.url.clone());
+    /// ```
+    pub async fn wait(&self, config: PollConfig) -> ZaiResult<ChatCompletionResponse> {
+        wait_for_task(&self.task_id, config, || self.send(), |_| {}).await
+    }
+}
+
+/// Drive `fetch` until the task leaves `PROCESSING`, passing each
+/// `PROCESSING` response to `on_processing`; `FAIL` is an error.
+pub(crate) async fn wait_for_task<F, Fut>(
+    task_id: &str,
+    config: PollConfig,
+    fetch: F,
+    on_processing: impl Fn(&ChatCompletionResponse),
+) -> ZaiResult<ChatCompletionResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ZaiResult<ChatCompletionResponse>>,
+{
+    let resp = poll::until(
+        fetch,
+        |r: &ChatCompletionResponse| {
+            let processing = matches!(r.task_status, Some(TaskStatus::Processing));
+            if processing {
+                on_processing(r);
+            }
+            !processing
+        },
+        config,
+    )
+    .await
+    .map_err(|e| match ZaiError::from(e) {
+        ZaiError::HttpError {
+            status: 408,
+            message,
+        } => ZaiError::HttpError {
+            status: 408,
+            message: format!("async task '{}': {}", task_id, message),
+        },
+        e => e,
+    })?;
+    if matches!(resp.task_status, Some(TaskStatus::Fail)) {
+        return Err(ZaiError::ApiError {
+            code: 0,
+            message: format!("async task '{}' failed", task_id),
+        });
+    }
+    Ok(resp)
 }
 
 impl<N> HttpClient for AsyncChatGetRequest<N>
@@ -76,3 +144,58 @@ where
         &self._body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn task(status: &str) -> ZaiResult<ChatCompletionResponse> {
+        Ok(serde_json::from_value(json!({
+            "id": "task-1",
+            "task_status": status,
+            "video_result": [{"url": "https://example.com/v.mp4", "cover_image_url": "https://example.com/c.png"}]
+        }))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_video_task() {
+        let calls = AtomicUsize::new(0);
+        let config = PollConfig::new(Duration::from_millis(1), Duration::from_secs(5));
+        let resp = wait_for_task(
+            "task-1",
+            config.clone(),
+            || {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                async move { task(if n < 2 { "PROCESSING" } else { "SUCCESS" }) }
+            },
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(resp.video_result().unwrap().len(), 1);
+
+        let err = wait_for_task("task-1", config, || async { task("FAIL") }, |_| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'task-1' failed"), "{}", err);
+
+        let err = wait_for_task(
+            "task-1",
+            PollConfig::new(Duration::from_millis(2), Duration::from_millis(5)),
+            || async { task("PROCESSING") },
+            |_| {},
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ZaiError::HttpError { status: 408, .. }));
+    }
+}
@@ -0,0 +1,378 @@
+//! # Audio Duration
+//!
+//! Header-based duration measurement for voice input, used to enforce the
+//! documented 10-minute limit before a clip is uploaded.
+//!
+//! - **WAV** — exact: the `data` chunk size divided by the `fmt` byte rate.
+//! - **MP3** — estimated: when the first frame carries a Xing/Info header its
+//!   frame count is used (exact for VBR encoders that write one); otherwise
+//!   the stream is assumed to be constant-bitrate at the first frame's
+//!   bitrate. For VBR files without a Xing/Info header this may be off in
+//!   either direction, so treat values close to the limit with care.
+//! - **Opus** — exact: the granule position of the last Ogg page minus the
+//!   pre-skip from the `OpusHead` header, at Opus' fixed 48 kHz.
+//! - **PCM** — raw samples carry no sample rate, so no duration can be read.
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+use super::chat_message_types::VoiceFormat;
+
+/// Maximum duration of a voice input clip (10 minutes).
+pub const MAX_VOICE_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// Errors raised while validating voice input.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum VoiceError {
+    #[error("cannot read {format:?} header: {reason}")]
+    InvalidHeader { format: VoiceFormat, reason: String },
+
+    #[error("audio is {:.1}s long, exceeding the {}s limit", duration.as_secs_f64(), max.as_secs())]
+    TooLong { duration: Duration, max: Duration },
+
+    #[error("{format:?} audio is not accepted as chat voice input")]
+    UnsupportedFormat { format: VoiceFormat },
+}
+
+/// Measure the duration of an encoded clip from its header.
+pub fn audio_duration(data: &[u8], format: &VoiceFormat) -> Result<Duration, VoiceError> {
+    match format {
+        VoiceFormat::WAV => wav_duration(data),
+        VoiceFormat::MP3 => mp3_duration(data),
+        VoiceFormat::OPUS => opus_duration(data),
+        VoiceFormat::PCM => Err(invalid(
+            VoiceFormat::PCM,
+            "raw PCM has no header to read the duration from",
+        )),
+    }
+}
+
+fn invalid(format: VoiceFormat, reason: impl Into<String>) -> VoiceError {
+    VoiceError::InvalidHeader {
+        format,
+        reason: reason.into(),
+    }
+}
+
+fn u32_le(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u32_be(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Exact duration of a RIFF/WAVE clip.
+///
+/// A `data` chunk size larger than the bytes actually present (e.g. the
+/// `0xFFFFFFFF` placeholder written by streaming encoders) is clamped to the
+/// available data.
+pub fn wav_duration(data: &[u8]) -> Result<Duration, VoiceError> {
+    let err = |reason: &str| invalid(VoiceFormat::WAV, reason);
+
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(err("missing RIFF/WAVE signature"));
+    }
+
+    let mut byte_rate = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32_le(data, pos + 4).unwrap_or(0) as usize;
+        let body = pos + 8;
+        match id {
+            b"fmt " => {
+                byte_rate = u32_le(data, body + 8).filter(|&rate| rate > 0);
+                if byte_rate.is_none() {
+                    return Err(err("fmt chunk has no byte rate"));
+                }
+            },
+            b"data" => {
+                let rate = byte_rate.ok_or_else(|| err("data chunk before fmt chunk"))?;
+                let len = size.min(data.len() - body);
+                return Ok(Duration::from_secs_f64(len as f64 / rate as f64));
+            },
+            _ => {},
+        }
+        // Chunks are padded to an even size.
+        pos = body.saturating_add(size).saturating_add(size & 1);
+    }
+
+    Err(err("missing data chunk"))
+}
+
+/// Layer III bitrates in kbps, indexed by the header's bitrate field.
+const MPEG1_L3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MPEG2_L3_KBPS: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Fields of an MPEG audio Layer III frame header.
+struct Mp3Frame {
+    mpeg1: bool,
+    mono: bool,
+    bitrate: u32,
+    sample_rate: u32,
+}
+
+impl Mp3Frame {
+    fn parse(header: &[u8]) -> Option<Self> {
+        let h = u32::from_be_bytes(header.get(0..4)?.try_into().ok()?);
+        if h >> 21 != 0x7FF {
+            return None;
+        }
+        let version = (h >> 19) & 0b11;
+        let layer = (h >> 17) & 0b11;
+        let bitrate_idx = ((h >> 12) & 0b1111) as usize;
+        let rate_idx = ((h >> 10) & 0b11) as usize;
+        // Version 01 is reserved; only Layer III (01) is MP3.
+        if version == 0b01
+            || layer != 0b01
+            || bitrate_idx == 0
+            || bitrate_idx == 15
+            || rate_idx == 3
+        {
+            return None;
+        }
+
+        let mpeg1 = version == 0b11;
+        let base_rate = [44_100, 48_000, 32_000][rate_idx];
+        let sample_rate = match version {
+            0b11 => base_rate,
+            0b10 => base_rate / 2,
+            _ => base_rate / 4,
+        };
+        let kbps = if mpeg1 {
+            MPEG1_L3_KBPS[bitrate_idx]
+        } else {
+            MPEG2_L3_KBPS[bitrate_idx]
+        };
+
+        Some(Self {
+            mpeg1,
+            mono: (h >> 6) & 0b11 == 0b11,
+            bitrate: kbps * 1000,
+            sample_rate,
+        })
+    }
+
+    fn samples_per_frame(&self) -> u32 {
+        if self.mpeg1 { 1152 } else { 576 }
+    }
+
+    /// Offset of a Xing/Info tag from the start of the frame.
+    fn xing_offset(&self) -> usize {
+        4 + match (self.mpeg1, self.mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        }
+    }
+}
+
+/// Size of a leading ID3v2 tag, if present.
+fn id3v2_len(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return 0;
+    }
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |acc, b| (acc << 7) | (*b & 0x7F) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+/// Estimated duration of an MP3 clip; see the [module docs](self).
+pub fn mp3_duration(data: &[u8]) -> Result<Duration, VoiceError> {
+    let start = id3v2_len(data).min(data.len());
+    let (offset, frame) = (start..data.len().saturating_sub(3))
+        .find_map(|i| Mp3Frame::parse(&data[i..]).map(|f| (i, f)))
+        .ok_or_else(|| invalid(VoiceFormat::MP3, "no MPEG Layer III frame found"))?;
+
+    let xing = offset + frame.xing_offset();
+    if matches!(data.get(xing..xing + 4), Some(b"Xing") | Some(b"Info"))
+        && u32_be(data, xing + 4).is_some_and(|flags| flags & 1 != 0)
+        && let Some(frames) = u32_be(data, xing + 8)
+    {
+        let samples = frames as f64 * frame.samples_per_frame() as f64;
+        return Ok(Duration::from_secs_f64(samples / frame.sample_rate as f64));
+    }
+
+    let mut end = data.len();
+    if end >= offset + 128 && &data[end - 128..end - 125] == b"TAG" {
+        end -= 128;
+    }
+    let audio_bits = (end - offset) as f64 * 8.0;
+    Ok(Duration::from_secs_f64(audio_bits / frame.bitrate as f64))
+}
+
+/// Exact duration of an Ogg Opus clip; see the [module docs](self).
+pub fn opus_duration(data: &[u8]) -> Result<Duration, VoiceError> {
+    let err = |reason: &str| invalid(VoiceFormat::OPUS, reason);
+
+    // The first page carries a single OpusHead packet.
+    if data.len() < 28 || &data[0..4] != b"OggS" {
+        return Err(err("missing Ogg signature"));
+    }
+    let segments = data[26] as usize;
+    let head = 27 + segments;
+    if data.get(head..head + 8) != Some(b"OpusHead") {
+        return Err(err("missing OpusHead packet"));
+    }
+    let pre_skip = data
+        .get(head + 10..head + 12)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| err("truncated OpusHead packet"))?;
+
+    let last_page = data
+        .windows(4)
+        .rposition(|w| w == b"OggS")
+        .unwrap_or_default();
+    let granule = data
+        .get(last_page + 6..last_page + 14)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| err("truncated Ogg page header"))?;
+    let samples = granule.saturating_sub(pre_skip as u64);
+    Ok(Duration::from_secs_f64(samples as f64 / 48_000.0))
+}
+
+/// Reject clips longer than [`MAX_VOICE_DURATION`].
+pub fn check_voice_duration(data: &[u8], format: &VoiceFormat) -> Result<Duration, VoiceError> {
+    let duration = audio_duration(data, format)?;
+    if duration > MAX_VOICE_DURATION {
+        return Err(VoiceError::TooLong {
+            duration,
+            max: MAX_VOICE_DURATION,
+        });
+    }
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::chat_message_types::VoiceRichContent;
+
+    /// Build a PCM WAV clip of `seconds` at the given sample rate (8-bit
+    /// mono).
+    fn wav(sample_rate: u32, seconds: u32) -> Vec<u8> {
+        let data_len = sample_rate * seconds;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        out.extend_from_slice(&1u16.to_le_bytes()); // mono
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes()); // byte rate
+        out.extend_from_slice(&1u16.to_le_bytes()); // block align
+        out.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.resize(out.len() + data_len as usize, 0x80);
+        out
+    }
+
+    #[test]
+    fn test_wav_duration() {
+        assert_eq!(wav_duration(&wav(8000, 3)).unwrap(), Duration::from_secs(3));
+        assert!(wav_duration(b"not a wav").is_err());
+
+        // Truncated data is clamped to what is present
+        let mut clip = wav(1000, 2);
+        clip.truncate(clip.len() - 1000);
+        assert_eq!(wav_duration(&clip).unwrap(), Duration::from_secs(1));
+    }
+
+    /// MPEG1 Layer III, 128 kbps, 44.1 kHz, stereo
+    const MP3_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x00];
+
+    #[test]
+    fn test_mp3_duration_cbr() {
+        let mut clip = b"ID3\x04\x00\x00\x00\x00\x00\x02xx".to_vec();
+        clip.extend_from_slice(&MP3_HEADER);
+        clip.resize(12 + 16_000, 0);
+        assert_eq!(mp3_duration(&clip).unwrap(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_mp3_duration_xing() {
+        let mut clip = MP3_HEADER.to_vec();
+        clip.resize(36, 0);
+        clip.extend_from_slice(b"Xing");
+        clip.extend_from_slice(&1u32.to_be_bytes());
+        clip.extend_from_slice(&76u32.to_be_bytes());
+        clip.resize(417, 0);
+        let duration = mp3_duration(&clip).unwrap();
+        let expected = 76.0 * 1152.0 / 44_100.0;
+        assert!((duration.as_secs_f64() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mp3_without_frame_is_invalid() {
+        assert!(matches!(
+            mp3_duration(&[0u8; 64]),
+            Err(VoiceError::InvalidHeader {
+                format: VoiceFormat::MP3,
+                ..
+            })
+        ));
+    }
+
+    /// Ogg page with the given granule position and a single packet.
+    fn ogg_page(granule: u64, packet: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS\x00\x00".to_vec();
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&[0; 12]); // serial, sequence, checksum
+        page.push(1);
+        page.push(packet.len() as u8);
+        page.extend_from_slice(packet);
+        page
+    }
+
+    #[test]
+    fn test_opus_duration() {
+        let mut head = b"OpusHead\x01\x01".to_vec();
+        head.extend_from_slice(&312u16.to_le_bytes()); // pre-skip
+        head.extend_from_slice(&48_000u32.to_le_bytes());
+        let mut clip = ogg_page(0, &head);
+        clip.extend(ogg_page(0, b"OpusTags"));
+        clip.extend(ogg_page(48_000 * 2 + 312, &[0; 40]));
+
+        assert_eq!(opus_duration(&clip).unwrap(), Duration::from_secs(2));
+        assert!(opus_duration(&wav(1000, 1)).is_err());
+        assert!(audio_duration(&[0; 16], &VoiceFormat::PCM).is_err());
+    }
+
+    #[test]
+    fn test_check_voice_duration() {
+        assert!(check_voice_duration(&wav(1000, 600), &VoiceFormat::WAV).is_ok());
+        let err = check_voice_duration(&wav(1000, 601), &VoiceFormat::WAV).unwrap_err();
+        assert_eq!(
+            err,
+            VoiceError::TooLong {
+                duration: Duration::from_secs(601),
+                max: MAX_VOICE_DURATION,
+            }
+        );
+        assert!(err.to_string().contains("601.0s"));
+    }
+
+    #[test]
+    fn test_input_audio_checked() {
+        let ok = VoiceRichContent::input_audio_checked(wav(1000, 5), VoiceFormat::WAV).unwrap();
+        assert!(matches!(ok, VoiceRichContent::InputAudio { .. }));
+
+        assert!(matches!(
+            VoiceRichContent::input_audio_checked(wav(1000, 601), VoiceFormat::WAV),
+            Err(VoiceError::TooLong { .. })
+        ));
+        assert!(matches!(
+            VoiceRichContent::input_audio_checked(b"audio_bytes", VoiceFormat::WAV),
+            Err(VoiceError::InvalidHeader { .. })
+        ));
+    }
+}
@@ -19,13 +19,27 @@
 //! - **Parameter control** - Temperature, top-p, max tokens, and other
 //!   generation parameters
 
-use std::marker::PhantomData;
+use std::{future::Future, marker::PhantomData, pin::Pin, sync::Arc};
 
+use futures::{Stream, StreamExt, stream};
 use serde::Serialize;
 use validator::Validate;
 
 use super::super::{chat_base_request::*, tools::*, traits::*};
-use crate::client::http::HttpClient;
+use crate::{
+    client::{
+        auth::Auth,
+        endpoint::Endpoint,
+        http::{CustomHeaders, HttpClient},
+        transport::Transport,
+    },
+    model::{
+        chat_message_types::TextMessage, chat_stream_response::ChatStreamResponse,
+        moderation::InputModeration, stream_ext::StreamChatLikeExt,
+    },
+};
+
+type ChunkStream = Pin<Box<dyn Stream<Item = crate::ZaiResult<ChatStreamResponse>> + Send>>;
 
 // Type-state is defined in model::traits::{StreamState, StreamOn, StreamOff}
 
@@ -62,6 +76,22 @@ where
     /// but can be customized using the `with_url()` method.
     pub url: String,
 
+    /// Base URL override applied to `url` when sending; see
+    /// [`with_endpoint`](Self::with_endpoint).
+    endpoint: Option<Endpoint>,
+
+    /// Authentication override; see [`with_auth`](Self::with_auth).
+    auth: Option<Auth>,
+
+    /// Transport override; see [`with_transport`](Self::with_transport).
+    transport: Option<Arc<dyn Transport>>,
+
+    /// Extra headers; see [`with_header`](Self::with_header).
+    headers: CustomHeaders,
+
+    /// Input moderation run before each completion request.
+    moderation: Option<InputModeration>,
+
     /// The request body containing model, messages, and parameters.
     body: ChatBody<N, M>,
 
@@ -92,6 +122,11 @@ where
             body,
             key,
             url: "https://open.bigmodel.cn/api/paas/v4/chat/completions".to_string(),
+            endpoint: None,
+            auth: None,
+            transport: None,
+            headers: CustomHeaders::new(),
+            moderation: None,
             _stream: PhantomData,
         }
     }
@@ -149,12 +184,46 @@ where
         self.body = self.body.extend_tools(tools);
         self
     }
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.body = self.body.with_tool_choice(tool_choice);
+        self
+    }
     pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
         self.body = self.body.with_user_id(user_id);
         self
     }
-    pub fn with_stop(mut self, stop: String) -> Self {
-        self.body = self.body.with_stop(stop);
+    /// Stop generating at any of `sequences` (the API currently accepts
+    /// one); an empty list clears them
+    pub fn with_stop(mut self, sequences: Vec<String>) -> Self {
+        self.body = self.body.with_stop(sequences);
+        self
+    }
+    /// Append one stop sequence
+    pub fn with_stop_one(mut self, sequence: impl Into<String>) -> Self {
+        self.body = self.body.with_stop_one(sequence);
+        self
+    }
+    /// Sample `n` completions in one call, returned as `n` choices (see
+    /// [`ChatCompletionResponse::choice`](crate::model::chat_base_response::ChatCompletionResponse::choice)).
+    /// Output tokens, and so the cost, grow with every extra choice.
+    /// Validated to `1..=`[`MAX_N`].
+    pub fn with_n(mut self, n: u32) -> Self {
+        self.body = self.body.with_n(n);
+        self
+    }
+    /// Fix the sampling seed for reproducible output while debugging or
+    /// testing. Reproducibility is best-effort, as guaranteed by the API:
+    /// a changed [`system_fingerprint`](crate::model::chat_base_response::ChatCompletionResponse::system_fingerprint)
+    /// means the backend changed and outputs may differ.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.body = self.body.with_seed(seed);
+        self
+    }
+    /// Request JSON output (`JsonObject`) or schema-constrained output
+    /// (`JsonSchema`). A `JsonSchema` whose schema is not an object fails
+    /// validation before sending.
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.body = self.body.with_response_format(format);
         self
     }
 
@@ -212,6 +281,23 @@ where
         self
     }
 
+    /// Moderate `user` messages before sending.
+    ///
+    /// `send()` and the streaming methods first check the input with the
+    /// moderation API, using this request's endpoint, auth, transport and
+    /// custom headers, and fail with
+    /// [`ZaiError::ModerationBlocked`](crate::ZaiError::ModerationBlocked),
+    /// without requesting a completion, if any item is rated `REJECT`.
+    pub fn with_input_moderation(self, api_key: impl Into<String>) -> Self {
+        self.with_input_moderation_config(InputModeration::new(api_key))
+    }
+
+    /// Moderate input with a custom threshold and role selection
+    pub fn with_input_moderation_config(mut self, moderation: InputModeration) -> Self {
+        self.moderation = Some(moderation);
+        self
+    }
+
     /// Enables streaming for this chat completion request.
     ///
     /// This method transitions the request to streaming mode, allowing
@@ -225,6 +311,11 @@ where
         ChatCompletion {
             key: self.key,
             url: self.url,
+            endpoint: self.endpoint,
+            auth: self.auth,
+            transport: self.transport,
+            headers: self.headers,
+            moderation: self.moderation,
             body: self.body,
             _stream: PhantomData,
         }
@@ -272,6 +363,240 @@ where
     }
 }
 
+impl<N, M, S> ChatCompletion<N, M, S>
+where
+    N: ModelName + Chat,
+    (N, M): Bounded,
+    ChatBody<N, M>: Serialize,
+    S: StreamState,
+{
+    /// Send this request to another API base URL.
+    ///
+    /// The production base of `url` (including the coding-plan URL) is
+    /// replaced by the endpoint's base; a URL set with `with_url()` pointing
+    /// elsewhere is left as is.
+    ///
+    /// ```rust,ignore
+    /// let request = ChatCompletion::new(model, messages, api_key)
+    ///     .with_endpoint(Endpoint::new("http://localhost:8080/api"));
+    /// ```
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Authenticate with `auth` instead of sending `key` as is, e.g. a JWT
+    /// from [`Auth::jwt_from_api_key`].
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Send through `transport` instead of the network, e.g. a
+    /// [`MockTransport`](crate::client::transport) in tests (feature
+    /// `testing`).
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Check the request against the model's [`capabilities`]: no image,
+    /// video or file content for a model without vision, no audio for a
+    /// model without audio input, no tools for a model without tool support,
+    /// and `max_tokens` within the model's output limit.
+    ///
+    /// Models this crate does not know are not checked. Errors are
+    /// validation errors (code 1200).
+    ///
+    /// [`capabilities`]: crate::model::chat_models::capabilities
+    pub fn validate_capabilities(&self) -> crate::ZaiResult<()> {
+        use crate::model::chat_models::{ChatModel, capabilities};
+
+        let body = serde_json::to_value(&self.body)?;
+        let Some(model) = body["model"]
+            .as_str()
+            .and_then(|m| m.parse::<ChatModel>().ok())
+        else {
+            return Ok(());
+        };
+        let caps = capabilities(&model);
+        let unsupported = |feature: &str| crate::client::error::ZaiError::ApiError {
+            code: 1200,
+            message: format!("model {} does not support {}", model, feature),
+        };
+
+        let part_types = body["messages"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["content"].as_array())
+            .flatten()
+            .filter_map(|part| part["type"].as_str());
+        for part_type in part_types {
+            match part_type {
+                "image_url" | "video_url" | "file_url" if !caps.supports_vision => {
+                    return Err(unsupported("image, video or file input"));
+                },
+                "input_audio" if !caps.supports_audio => {
+                    return Err(unsupported("audio input"));
+                },
+                _ => {},
+            }
+        }
+        if !caps.supports_tools && body["tools"].as_array().is_some_and(|t| !t.is_empty()) {
+            return Err(unsupported("tools"));
+        }
+        if let Some(max_tokens) = self.body.max_tokens
+            && max_tokens > caps.max_output_tokens
+        {
+            return Err(crate::client::error::ZaiError::ApiError {
+                code: 1200,
+                message: format!(
+                    "max_tokens {} exceeds the {} output token limit of model {}",
+                    max_tokens, caps.max_output_tokens, model
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Send an extra header, e.g. one a gateway routes on. Sent after (and
+    /// over) the standard headers; `Authorization` is refused when sending,
+    /// see [`with_authorization_header`](Self::with_authorization_header).
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Send `value` as the `Authorization` header instead of
+    /// `Bearer <key>`, for gateways with their own credentials
+    pub fn with_authorization_header(mut self, value: impl Into<String>) -> Self {
+        self.headers.authorization(value);
+        self
+    }
+
+    /// Copy of this request without its credentials, for building variants
+    /// or storing a request safely.
+    ///
+    /// The body, URL, endpoint, transport and custom headers are kept; the
+    /// key, any [`with_auth`](Self::with_auth) or `Authorization` header
+    /// override and input moderation (which holds its own key) are not.
+    pub fn template(&self) -> ChatRequestTemplate<N, M, S>
+    where
+        ChatBody<N, M>: Clone,
+    {
+        ChatRequestTemplate {
+            url: self.url.clone(),
+            endpoint: self.endpoint.clone(),
+            transport: self.transport.clone(),
+            headers: self.headers.without_authorization(),
+            body: self.body.clone(),
+            _stream: PhantomData,
+        }
+    }
+}
+
+/// A [`ChatCompletion`] without an API key; see
+/// [`ChatCompletion::template`].
+///
+/// ```rust,ignore
+/// let template = ChatCompletion::new(model, TextMessage::user("hi"), String::new()).template();
+/// let a = template.with_key(key_a).with_temperature(0.2);
+/// let b = template.with_key(key_b).with_temperature(0.9);
+/// ```
+pub struct ChatRequestTemplate<N, M, S = StreamOff>
+where
+    N: ModelName + Chat,
+    (N, M): Bounded,
+    ChatBody<N, M>: Serialize,
+    S: StreamState,
+{
+    url: String,
+    endpoint: Option<Endpoint>,
+    transport: Option<Arc<dyn Transport>>,
+    headers: CustomHeaders,
+    body: ChatBody<N, M>,
+    _stream: PhantomData<S>,
+}
+
+impl<N, M, S> ChatRequestTemplate<N, M, S>
+where
+    N: ModelName + Chat,
+    (N, M): Bounded,
+    ChatBody<N, M>: Serialize + Clone,
+    S: StreamState,
+{
+    /// A request from this template, authenticated with `key`
+    pub fn with_key(&self, key: impl Into<String>) -> ChatCompletion<N, M, S> {
+        ChatCompletion {
+            key: key.into(),
+            url: self.url.clone(),
+            endpoint: self.endpoint.clone(),
+            auth: None,
+            transport: self.transport.clone(),
+            headers: self.headers.clone(),
+            moderation: None,
+            body: self.body.clone(),
+            _stream: PhantomData,
+        }
+    }
+
+    /// The request body
+    pub fn body(&self) -> &ChatBody<N, M> {
+        &self.body
+    }
+}
+
+impl<N, M, S> Clone for ChatRequestTemplate<N, M, S>
+where
+    N: ModelName + Chat,
+    (N, M): Bounded,
+    ChatBody<N, M>: Serialize + Clone,
+    S: StreamState,
+{
+    fn clone(&self) -> Self {
+        Self {
+            url: self.url.clone(),
+            endpoint: self.endpoint.clone(),
+            transport: self.transport.clone(),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+            _stream: PhantomData,
+        }
+    }
+}
+
+impl<N, S> ChatCompletion<N, TextMessage, S>
+where
+    N: ModelName + Chat,
+    (N, TextMessage): Bounded,
+    ChatBody<N, TextMessage>: Serialize,
+    S: StreamState,
+{
+    /// Prefill the start of the assistant's reply.
+    ///
+    /// Appends an assistant message flagged with `"prefix": true`, asking the
+    /// model to continue it instead of treating it as a completed turn (for
+    /// example `"{"` to force a JSON reply). Call this after all other
+    /// messages; the prefix must be the last message of the request.
+    ///
+    /// - **Streaming:** deltas carry only the continuation; prepend the prefix
+    ///   yourself when assembling the full reply.
+    /// - **Tool calls:** a prefilled reply is text, so the model will not
+    ///   answer with tool calls. Do not combine with a forced `tool_choice`.
+    ///
+    /// ```rust,ignore
+    /// let request = ChatCompletion::new(model, TextMessage::user("Weather in Paris as JSON"), key)
+    ///     .with_assistant_prefix("{");
+    /// ```
+    pub fn with_assistant_prefix(mut self, content: impl Into<String>) -> Self {
+        self.body = self
+            .body
+            .add_messages(TextMessage::assistant_prefix(content));
+        self
+    }
+}
+
 impl<N, M> ChatCompletion<N, M, StreamOn>
 where
     N: ModelName + Chat,
@@ -302,6 +627,11 @@ where
         ChatCompletion {
             key: self.key,
             url: self.url,
+            endpoint: self.endpoint,
+            auth: self.auth,
+            transport: self.transport,
+            headers: self.headers,
+            moderation: self.moderation,
             body: self.body,
             _stream: PhantomData,
         }
@@ -329,6 +659,27 @@ where
     fn body(&self) -> &Self::Body {
         &self.body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+    fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+    fn transport(&self) -> Option<Arc<dyn Transport>> {
+        self.transport.clone()
+    }
+    fn custom_headers(&self) -> Option<&CustomHeaders> {
+        Some(&self.headers)
+    }
+    fn precheck(&self) -> impl Future<Output = crate::ZaiResult<()>> + Send + 'static {
+        let check = self.moderation.as_ref().map(|m| m.check(self));
+        async move {
+            match check {
+                Some(check) => check.await,
+                None => Ok(()),
+            }
+        }
+    }
 }
 
 /// Enables Server-Sent Events (SSE) streaming for streaming-enabled chat
@@ -355,3 +706,578 @@ where
     (N, M): Bounded,
 {
 }
+
+impl<N> ChatCompletion<N, TextMessage, StreamOn>
+where
+    N: ModelName + Chat + Serialize + Send + 'static,
+    (N, TextMessage): Bounded,
+{
+    /// Stream the reply, reconnecting if the connection drops mid-reply.
+    ///
+    /// When the chunk stream fails after some text has arrived, the request
+    /// is re-sent with the text received so far as an assistant prefill (see
+    /// [`with_assistant_prefix`](Self::with_assistant_prefix)) and the
+    /// continuation is streamed on, so the concatenated deltas read as one
+    /// reply. At most `max_reconnects` reconnects are made; an error before
+    /// any text arrives, once they are used up, or sent by the server as an
+    /// `event: error` event, is yielded as is.
+    ///
+    /// Each reconnect is a new request: the prompt (now including the
+    /// prefill) is billed again, so token usage across the combined stream
+    /// may be counted twice. Tool-call deltas are not resumed.
+    ///
+    /// ```rust,ignore
+    /// let mut stream = ChatCompletion::new(model, messages, key)
+    ///     .enable_stream()
+    ///     .send_stream_resumable(3)
+    ///     .await?;
+    /// while let Some(chunk) = stream.next().await {
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn send_stream_resumable(
+        mut self,
+        max_reconnects: usize,
+    ) -> crate::ZaiResult<ChunkStream> {
+        let first = self.to_stream().await?;
+        let state = ResumeState {
+            request: self,
+            inner: first,
+            pending: String::new(),
+            received: false,
+            reconnects_left: max_reconnects,
+            done: false,
+        };
+
+        let out = stream::unfold(state, |mut state| async move {
+            loop {
+                if state.done {
+                    return None;
+                }
+                match state.inner.next().await {
+                    Some(Ok(chunk)) => {
+                        for delta in chunk.choices.iter().filter_map(|c| c.delta.as_ref()) {
+                            if let Some(text) = delta.content.as_deref().filter(|t| !t.is_empty()) {
+                                state.pending.push_str(text);
+                                state.received = true;
+                            }
+                        }
+                        return Some((Ok(chunk), state));
+                    },
+                    Some(Err(e))
+                        if !state.received
+                            || state.reconnects_left == 0
+                            || !matches!(e.inner(), crate::ZaiError::NetworkError(_)) =>
+                    {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    },
+                    Some(Err(_)) => {
+                        state.reconnects_left -= 1;
+                        state.extend_prefill();
+                        match state.request.to_stream().await {
+                            Ok(inner) => state.inner = inner,
+                            Err(e) => {
+                                state.done = true;
+                                return Some((Err(e), state));
+                            },
+                        }
+                    },
+                    None => return None,
+                }
+            }
+        });
+        Ok(out.boxed())
+    }
+}
+
+struct ResumeState<N>
+where
+    N: ModelName + Chat,
+    (N, TextMessage): Bounded,
+    ChatBody<N, TextMessage>: Serialize,
+{
+    request: ChatCompletion<N, TextMessage, StreamOn>,
+    inner: ChunkStream,
+    /// Text received since the prefill was last updated
+    pending: String,
+    received: bool,
+    reconnects_left: usize,
+    done: bool,
+}
+
+impl<N> ResumeState<N>
+where
+    N: ModelName + Chat,
+    (N, TextMessage): Bounded,
+    ChatBody<N, TextMessage>: Serialize,
+{
+    /// Append the pending text to the trailing prefill message, adding one if
+    /// the request does not end with a prefill yet.
+    fn extend_prefill(&mut self) {
+        let text = std::mem::take(&mut self.pending);
+        match self.request.body.messages.last_mut() {
+            Some(TextMessage::Assistant {
+                content,
+                prefix: true,
+                ..
+            }) => content.get_or_insert_with(String::new).push_str(&text),
+            _ => self
+                .request
+                .body
+                .messages
+                .push(TextMessage::assistant_prefix(text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        client::test_server::{Reply, serve, serve_in_order},
+        model::chat_models::GLM4_5_flash,
+    };
+
+    #[test]
+    fn test_assistant_prefix_body_shape() {
+        let request = ChatCompletion::new(
+            GLM4_5_flash {},
+            TextMessage::user("Weather in Paris as JSON"),
+            "key".to_string(),
+        )
+        .with_assistant_prefix("{");
+
+        let body = serde_json::to_value(request.body()).unwrap();
+        assert_eq!(
+            body["messages"],
+            json!([
+                {"role": "user", "content": "Weather in Paris as JSON"},
+                {"role": "assistant", "content": "{", "prefix": true}
+            ])
+        );
+
+        // Ordinary assistant turns do not carry the flag.
+        let plain = serde_json::to_value(TextMessage::assistant("hi")).unwrap();
+        assert!(plain.get("prefix").is_none());
+
+        let streamed = request.enable_stream();
+        let body = serde_json::to_value(streamed.body()).unwrap();
+        assert_eq!(body["messages"][1]["prefix"], true);
+        assert_eq!(body["stream"], true);
+    }
+
+    #[test]
+    fn test_response_format_body_shape() {
+        let request =
+            || ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string());
+
+        let text = request().with_response_format(ResponseFormat::Text);
+        let body = serde_json::to_value(text.body()).unwrap();
+        assert!(body.get("response_format").is_none());
+
+        let object = request().with_response_format(ResponseFormat::JsonObject);
+        let body = serde_json::to_value(object.body()).unwrap();
+        assert_eq!(body["response_format"], json!({"type": "json_object"}));
+
+        let schema = json!({"type": "object", "properties": {"city": {"type": "string"}}});
+        let strict = request().with_response_format(ResponseFormat::JsonSchema {
+            schema: schema.clone(),
+            strict: false,
+        });
+        let body = serde_json::to_value(strict.body()).unwrap();
+        assert_eq!(
+            body["response_format"],
+            json!({"type": "json_schema", "json_schema": {"schema": schema, "strict": false}})
+        );
+        assert!(strict.validate().is_ok());
+
+        let invalid = request().with_response_format(ResponseFormat::json_schema(json!([])));
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_sampling_parameters() {
+        let request =
+            || ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string());
+
+        let unset = serde_json::to_value(request().body()).unwrap();
+        for key in ["temperature", "top_p", "do_sample"] {
+            assert!(unset.get(key).is_none(), "{} serialized when unset", key);
+        }
+
+        let set = request()
+            .with_temperature(0.25)
+            .with_top_p(1.0)
+            .with_do_sample(false);
+        assert!(set.validate().is_ok());
+        let body = serde_json::to_value(set.body()).unwrap();
+        assert_eq!(body["temperature"], json!(0.25));
+        assert_eq!(body["top_p"], json!(1.0));
+        assert_eq!(body["do_sample"], json!(false));
+
+        for bad in [-0.1, 1.5, f32::NAN] {
+            assert!(request().with_temperature(bad).validate().is_err());
+            assert!(request().with_top_p(bad).validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_n_choices() {
+        let request =
+            || ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string());
+        assert!(
+            serde_json::to_value(request().body())
+                .unwrap()
+                .get("n")
+                .is_none()
+        );
+
+        let three = request().with_n(3);
+        assert!(three.validate().is_ok());
+        assert_eq!(serde_json::to_value(three.body()).unwrap()["n"], json!(3));
+        assert!(request().with_n(0).validate().is_err());
+        assert!(request().with_n(MAX_N + 1).validate().is_err());
+
+        let choices: Vec<_> = (0..3)
+            .map(|i| {
+                json!({
+                    "index": i,
+                    "finish_reason": "stop",
+                    "message": {"role": "assistant", "content": format!("candidate {}", i)}
+                })
+            })
+            .collect();
+        let response: crate::model::chat_base_response::ChatCompletionResponse =
+            serde_json::from_value(json!({
+                "id": "resp",
+                "choices": choices
+            }))
+            .unwrap();
+        assert_eq!(response.choices_len(), 3);
+        let texts: Vec<_> = (0..3)
+            .map(|i| response.choice(i).unwrap().message().text().unwrap())
+            .collect();
+        assert_eq!(texts, ["candidate 0", "candidate 1", "candidate 2"]);
+        assert!(response.choice(3).is_none());
+    }
+
+    #[test]
+    fn test_seed_and_system_fingerprint() {
+        let request =
+            || ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string());
+        assert!(
+            serde_json::to_value(request().body())
+                .unwrap()
+                .get("seed")
+                .is_none()
+        );
+        let seeded = serde_json::to_value(request().with_seed(42).body()).unwrap();
+        assert_eq!(seeded["seed"], json!(42));
+
+        let response: crate::model::chat_base_response::ChatCompletionResponse =
+            serde_json::from_value(json!({
+                "id": "resp",
+                "system_fingerprint": "fp_2024a",
+                "choices": []
+            }))
+            .unwrap();
+        assert_eq!(response.system_fingerprint(), Some("fp_2024a"));
+        assert!(response.extra().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_input_moderation_follows_chat_routing() {
+        let (base, mut requests) = serve(|req| {
+            if req.path().ends_with("/moderations") {
+                Reply::json(
+                    200,
+                    json!({"request_id": "m1", "result_list": [
+                        {"content_type": "text", "risk_level": "REJECT", "risk_type": ["x"]}
+                    ]}),
+                )
+            } else {
+                Reply::json(200, json!({"id": "c1", "choices": []}))
+            }
+        })
+        .await;
+        let chat = || {
+            ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string())
+                .with_endpoint(Endpoint::new(format!("{}/api", base)))
+                .with_header("X-Org-Id", "org-42")
+                .with_input_moderation("mkey")
+        };
+
+        let err = chat().send().await.unwrap_err();
+        assert!(
+            matches!(err, crate::ZaiError::ModerationBlocked { .. }),
+            "{:?}",
+            err
+        );
+        let err = chat().enable_stream().to_stream().await.err().unwrap();
+        assert!(
+            matches!(err, crate::ZaiError::ModerationBlocked { .. }),
+            "{:?}",
+            err
+        );
+
+        // Only the moderation requests reached the server, via the chat's
+        // endpoint and headers.
+        for _ in 0..2 {
+            let request = requests.recv().await.unwrap();
+            assert_eq!(request.path(), "/api/paas/v4/moderations");
+            assert_eq!(request.header("x-org-id"), Some("org-42"));
+            assert_eq!(request.header("authorization"), Some("Bearer mkey"));
+        }
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_custom_header_reaches_request() {
+        let (base, mut requests) =
+            serve(|_| Reply::json(200, json!({"id": "c1", "choices": []}))).await;
+
+        ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string())
+            .with_endpoint(Endpoint::new(format!("{}/api", base)))
+            .with_header("X-Org-Id", "org-42")
+            .send()
+            .await
+            .unwrap();
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request.header("x-org-id"), Some("org-42"));
+        assert_eq!(request.header("authorization"), Some("Bearer key"));
+
+        let err = ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string())
+            .with_endpoint(Endpoint::new("http://127.0.0.1:1/api"))
+            .with_header("Authorization", "Basic abc")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, crate::ZaiError::ApiError { code: 1200, .. }),
+            "{:?}",
+            err
+        );
+
+        let overridden =
+            ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string())
+                .with_authorization_header("Basic abc");
+        let headers = overridden
+            .custom_headers()
+            .unwrap()
+            .to_header_map()
+            .unwrap();
+        assert_eq!(headers["authorization"], "Basic abc");
+        assert!(overridden.template().with_key("k").headers.is_empty());
+    }
+
+    #[test]
+    fn test_validate_capabilities() {
+        use crate::model::{
+            chat_message_types::{VisionMessage, VisionRichContent, VoiceMessage},
+            chat_models::{ChatModel, GLM4_6v},
+        };
+
+        let image = || VisionMessage::user(VisionRichContent::image("https://example.com/a.png"));
+        let text_model = ChatModel::Glm4_5Flash.as_dyn();
+        let err = ChatCompletion::new(text_model.clone(), image(), "key".to_string())
+            .validate_capabilities()
+            .unwrap_err();
+        assert!(err.message().contains("image"), "{}", err);
+        assert!(
+            ChatCompletion::new(GLM4_6v {}, image(), "key".to_string())
+                .validate_capabilities()
+                .is_ok()
+        );
+
+        let tool = Tools::Function {
+            function: Function::new("f", "A function", json!({"type": "object"})),
+        };
+        let voice = ChatCompletion::new(
+            ChatModel::Glm4Voice.as_dyn(),
+            VoiceMessage::new_user(),
+            "key".to_string(),
+        )
+        .add_tool(tool.clone());
+        assert!(
+            voice
+                .validate_capabilities()
+                .unwrap_err()
+                .message()
+                .contains("tools")
+        );
+
+        let text = ChatCompletion::new(text_model, TextMessage::user("hi"), "key".to_string())
+            .add_tool(tool);
+        assert!(text.validate_capabilities().is_ok());
+        let err = text
+            .with_max_tokens(200_000)
+            .validate_capabilities()
+            .unwrap_err();
+        assert!(err.message().contains("max_tokens 200000"), "{}", err);
+
+        // The body accepts the full output limit of the largest models.
+        let long = ChatCompletion::new(
+            ChatModel::Glm4_6.as_dyn(),
+            TextMessage::user("hi"),
+            "key".to_string(),
+        )
+        .with_max_tokens(128_000);
+        assert!(long.validate().is_ok());
+        assert!(long.validate_capabilities().is_ok());
+    }
+
+    #[test]
+    fn test_stop_body_shape() {
+        let request =
+            || ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string());
+
+        let unset = serde_json::to_value(request().body()).unwrap();
+        assert!(unset.get("stop").is_none());
+
+        let one = request().with_stop_one("\n\n");
+        let body = serde_json::to_value(one.body()).unwrap();
+        assert_eq!(body["stop"], json!(["\n\n"]));
+        assert!(one.validate().is_ok());
+
+        let list = request().with_stop(vec!["END".to_string()]);
+        assert_eq!(
+            serde_json::to_value(list.body()).unwrap()["stop"],
+            json!(["END"])
+        );
+        let cleared = request().with_stop_one("END").with_stop(Vec::new());
+        assert!(
+            serde_json::to_value(cleared.body())
+                .unwrap()
+                .get("stop")
+                .is_none()
+        );
+
+        let too_many = request().with_stop(vec!["a".to_string(), "b".to_string()]);
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_template_fans_out_keyed_requests() {
+        let original = ChatCompletion::new(
+            GLM4_5_flash {},
+            TextMessage::user("hi"),
+            "secret-key".to_string(),
+        )
+        .with_temperature(0.5)
+        .with_endpoint(Endpoint::new("http://localhost:1/api"));
+        let template = original.template();
+
+        let a = template.with_key("key-a");
+        let b = template.clone().with_key("key-b");
+        assert_eq!(a.key, "key-a");
+        assert_eq!(b.key, "key-b");
+        let body = serde_json::to_value(original.body()).unwrap();
+        assert_eq!(serde_json::to_value(a.body()).unwrap(), body);
+        assert_eq!(serde_json::to_value(b.body()).unwrap(), body);
+        assert_eq!(a.request_url(), original.request_url());
+
+        let varied = template.with_key("key-c").with_temperature(0.25);
+        assert_eq!(
+            serde_json::to_value(varied.body()).unwrap()["temperature"],
+            json!(0.25)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_stream_resumable_stitches_after_disconnect() {
+        fn sse(content: &str, finish: Option<&str>) -> String {
+            let chunk = json!({
+                "id": "chatcmpl-1",
+                "choices": [{"index": 0, "delta": {"content": content}, "finish_reason": finish}]
+            });
+            format!("data: {}\n\n", chunk)
+        }
+
+        // The first reply is cut off: fewer bytes than content-length.
+        let first = sse("The quick ", None) + &sse("brown ", None);
+        let second = sse("fox jumps.", Some("stop")) + "data: [DONE]\n\n";
+        let (base, mut requests) = serve_in_order(vec![
+            Reply::status(200)
+                .body("text/event-stream", first.clone())
+                .declared_length(first.len() + 64),
+            Reply::status(200).body("text/event-stream", second),
+        ])
+        .await;
+
+        let stream = ChatCompletion::new(
+            GLM4_5_flash {},
+            TextMessage::user("Say the pangram"),
+            "key".to_string(),
+        )
+        .with_endpoint(Endpoint::new(format!("{}/api", base)))
+        .enable_stream()
+        .send_stream_resumable(1)
+        .await
+        .unwrap();
+
+        let chunks: Vec<_> = stream.collect().await;
+        let text: String = chunks
+            .iter()
+            .map(|c| c.as_ref().unwrap())
+            .filter_map(|c| c.choices[0].delta.as_ref()?.content.clone())
+            .collect();
+        assert_eq!(text, "The quick brown fox jumps.");
+
+        requests.recv().await.unwrap();
+        let retry = requests.recv().await.unwrap();
+        assert!(requests.try_recv().is_err());
+        assert_eq!(
+            retry.json()["messages"][1],
+            json!({"role": "assistant", "content": "The quick brown ", "prefix": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_event_ends_stream_with_api_error() {
+        let body = [
+            r#"data: {"id":"c1","choices":[{"index":0,"delta":{"content":"Hel"}}]}"#,
+            "",
+            "event: message",
+            r#"data: {"id":"c1","choices":[{"index":0,"delta":{"content":"lo"}}]}"#,
+            "",
+            "event: error",
+            r#"data: {"error":{"code":"1301","message":"unsafe content"}}"#,
+            "",
+            r#"data: {"id":"c1","choices":[{"index":0,"delta":{"content":"!"}}]}"#,
+            "",
+            "data: [DONE]",
+            "",
+            "",
+        ]
+        .join("\n");
+        let (base, _) =
+            serve(move |_| Reply::status(200).body("text/event-stream", body.clone())).await;
+
+        let mut request =
+            ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hi"), "key".to_string())
+                .with_endpoint(Endpoint::new(format!("{}/api", base)))
+                .enable_stream();
+        let items: Vec<_> = request.to_stream().await.unwrap().collect().await;
+
+        assert_eq!(items.len(), 3, "{:?}", items);
+        let text: String = items[..2]
+            .iter()
+            .filter_map(|c| {
+                c.as_ref().unwrap().choices[0]
+                    .delta
+                    .as_ref()?
+                    .content
+                    .clone()
+            })
+            .collect();
+        assert_eq!(text, "Hello");
+        assert!(
+            matches!(&items[2], Err(crate::ZaiError::ApiError { code: 1301, message }) if message == "unsafe content"),
+            "{:?}",
+            items[2]
+        );
+    }
+}
@@ -14,7 +14,10 @@
 use serde::Serialize;
 use validator::*;
 
-use super::{tools::*, traits::*};
+use super::{chat_models::MAX_OUTPUT_TOKENS, tools::*, traits::*};
+
+/// Largest number of choices [`ChatBody::n`] may request.
+pub const MAX_N: u32 = 10;
 
 /// Main request body structure for chat API calls.
 ///
@@ -88,29 +91,48 @@ where
     /// the output more random, while lower values (closer to 0.0) make it
     /// more deterministic. Must be between 0.0 and 1.0.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[validate(range(min = 0.0, max = 1.0))]
+    #[validate(custom(function = "super::model_validate::validate_unit_interval"))]
     pub temperature: Option<f32>,
 
     /// Controls diversity via nucleus sampling. Only tokens with cumulative
     /// probability up to `top_p` are considered. Must be between 0.0 and
     /// 1.0.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[validate(range(min = 0.0, max = 1.0))]
+    #[validate(custom(function = "super::model_validate::validate_unit_interval"))]
     pub top_p: Option<f32>,
 
     /// The maximum number of tokens to generate in the completion.
-    /// Must be between 1 and 98304.
+    /// Must be between 1 and [`MAX_OUTPUT_TOKENS`]; the model's own limit is
+    /// checked by `ChatCompletion::validate_capabilities`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[validate(range(min = 1, max = 98304))]
+    #[validate(range(min = 1, max = MAX_OUTPUT_TOKENS))]
     pub max_tokens: Option<u32>,
 
+    /// Number of completions to sample, returned as separate choices.
+    /// Must be between 1 and [`MAX_N`]; each choice is billed as a
+    /// separate completion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = MAX_N))]
+    pub n: Option<u32>,
+
+    /// Sampling seed. Repeating a request with the same seed and
+    /// parameters aims to return the same output, on a best-effort basis:
+    /// compare the response's `system_fingerprint` to detect backend
+    /// changes that break it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
     /// A list of tools the model may call. Currently supports function calling,
     /// web search, and retrieval tools.
     /// Note: server expects an array; we model this as a vector of tool items.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tools>>,
 
-    // tool_choice: enum<string>, but we don't need it for now
+    /// Controls which (if any) tool the model calls. The API defaults to
+    /// `auto` when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+
     /// A unique identifier representing your end-user, which can help monitor
     /// and detect abuse. Must be between 6 and 128 characters long.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -123,8 +145,9 @@ where
     pub stop: Option<Vec<String>>,
 
     /// An object specifying the format that the model must output.
-    /// Can be either text or JSON object format.
+    /// Can be text, a JSON object, or JSON matching a schema.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "super::model_validate::validate_response_format"))]
     pub response_format: Option<ResponseFormat>,
 }
 
@@ -145,7 +168,10 @@ where
             temperature: None,
             top_p: None,
             max_tokens: None,
+            n: None,
+            seed: None,
             tools: None,
+            tool_choice: None,
             user_id: None,
             stop: None,
             response_format: None,
@@ -207,12 +233,40 @@ where
         self.tools.get_or_insert(Vec::new()).extend(tools);
         self
     }
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
     pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
         self.user_id = Some(user_id.into());
         self
     }
-    pub fn with_stop(mut self, stop: String) -> Self {
-        self.stop.get_or_insert_with(Vec::new).push(stop);
+    /// Replace the stop sequences; an empty list clears them. The API
+    /// currently accepts a single sequence, which validation enforces.
+    pub fn with_stop(mut self, sequences: Vec<String>) -> Self {
+        self.stop = (!sequences.is_empty()).then_some(sequences);
+        self
+    }
+    /// Append one stop sequence
+    pub fn with_stop_one(mut self, sequence: impl Into<String>) -> Self {
+        self.stop.get_or_insert_with(Vec::new).push(sequence.into());
+        self
+    }
+    pub fn with_n(mut self, n: u32) -> Self {
+        self.n = Some(n);
+        self
+    }
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    /// Set the output format. `Text` is the API default and is omitted from
+    /// the body.
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = match format {
+            ResponseFormat::Text => None,
+            format => Some(format),
+        };
         self
     }
 }
@@ -313,6 +367,19 @@ mod tests {
         assert_eq!(body.tools.unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_with_tool_choice() {
+        let body: ChatBody<GLM4_6, TextMessage> =
+            ChatBody::new(GLM4_6 {}, TextMessage::user("test"));
+        let json = serde_json::to_value(&body).unwrap();
+        assert!(json.get("tool_choice").is_none());
+
+        let body = body.with_tool_choice(ToolChoice::function("get_weather"));
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["tool_choice"]["type"], "function");
+        assert_eq!(json["tool_choice"]["function"]["name"], "get_weather");
+    }
+
     #[test]
     fn test_extend_messages() {
         let body: ChatBody<GLM4_6, TextMessage> =
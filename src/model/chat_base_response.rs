@@ -70,6 +70,11 @@ pub struct ChatCompletionResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
 
+    /// Identifies the backend configuration that served the request; a
+    /// change means seeded requests may no longer reproduce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+
     /// Model response list
     #[serde(skip_serializing_if = "Option::is_none")]
     pub choices: Option<Vec<Choice>>,
@@ -95,6 +100,10 @@ pub struct ChatCompletionResponse {
     /// to be retrieved via a subsequent query.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub task_status: Option<TaskStatus>,
+
+    /// Fields not modelled by this crate, kept as received
+    #[serde(flatten, default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl std::fmt::Debug for ChatCompletionResponse {
@@ -132,6 +141,67 @@ impl std::fmt::Display for TaskStatus {
     }
 }
 
+/// Why the model stopped generating.
+///
+/// Deserializes from the API's `finish_reason` string; values this crate does
+/// not know are kept in [`FinishReason::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FinishReason {
+    /// Natural end of the answer or a stop sequence
+    Stop,
+    /// `max_tokens` or the context limit was reached; the answer is truncated
+    Length,
+    /// The model asked for tool calls
+    ToolCalls,
+    /// Output was blocked by content safety (`sensitive` on the wire)
+    ContentFilter,
+    /// Any other value, as received
+    Other(String),
+}
+
+impl FinishReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FinishReason::Stop => "stop",
+            FinishReason::Length => "length",
+            FinishReason::ToolCalls => "tool_calls",
+            FinishReason::ContentFilter => "sensitive",
+            FinishReason::Other(other) => other,
+        }
+    }
+}
+
+impl From<&str> for FinishReason {
+    fn from(value: &str) -> Self {
+        match value {
+            "stop" => FinishReason::Stop,
+            "length" => FinishReason::Length,
+            "tool_calls" => FinishReason::ToolCalls,
+            "sensitive" | "content_filter" => FinishReason::ContentFilter,
+            other => FinishReason::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for FinishReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for FinishReason {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FinishReason {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(FinishReason::from(value.as_str()))
+    }
+}
+
 /// One choice item in the response.
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct Choice {
@@ -145,6 +215,10 @@ pub struct Choice {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finish_reason: Option<String>,
+
+    /// Fields not modelled by this crate, kept as received
+    #[serde(flatten, default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Notes:
@@ -178,6 +252,10 @@ pub struct Message {
     /// Generated tool/function calls
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCallMessage>>,
+
+    /// Fields not modelled by this crate, kept as received
+    #[serde(flatten, default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Tool/function call description inside message
@@ -188,7 +266,13 @@ pub struct Message {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct ToolCallMessage {
+    /// Position of the call in the message; set on streamed deltas, where
+    /// fragments of the same call share an index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// Omitted on streamed fragments after the first one.
     #[serde(
+        default,
         skip_serializing_if = "Option::is_none",
         deserialize_with = "de_opt_string_from_number_or_string"
     )]
@@ -417,6 +501,10 @@ pub struct ContentFilterInfo {
 
 // Getter implementations
 impl ChatCompletionResponse {
+    /// Unrecognized fields, e.g. ones added to the API after this release
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra
+    }
     pub fn id(&self) -> Option<&str> {
         self.id.as_deref()
     }
@@ -429,9 +517,21 @@ impl ChatCompletionResponse {
     pub fn model(&self) -> Option<&str> {
         self.model.as_deref()
     }
+    pub fn system_fingerprint(&self) -> Option<&str> {
+        self.system_fingerprint.as_deref()
+    }
     pub fn choices(&self) -> Option<&[Choice]> {
         self.choices.as_deref()
     }
+    /// Choice at `index` in the response's list (requests with `n > 1`
+    /// return several)
+    pub fn choice(&self, index: usize) -> Option<&Choice> {
+        self.choices()?.get(index)
+    }
+    /// Number of choices returned
+    pub fn choices_len(&self) -> usize {
+        self.choices().map_or(0, <[Choice]>::len)
+    }
     pub fn usage(&self) -> Option<&Usage> {
         self.usage.as_ref()
     }
@@ -447,9 +547,31 @@ impl ChatCompletionResponse {
     pub fn task_status(&self) -> Option<&TaskStatus> {
         self.task_status.as_ref()
     }
+
+    /// Final text of the first choice.
+    ///
+    /// String content is returned as-is, array-of-parts content is
+    /// concatenated, and any other JSON is stringified. Returns `None` when
+    /// there is no choice or no content.
+    pub fn final_text(&self) -> Option<String> {
+        self.choices()?.first()?.message().text()
+    }
+
+    /// Text of every content part across all choices, in order.
+    pub fn all_text_parts(&self) -> Vec<String> {
+        self.choices()
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|c| c.message().text_parts())
+            .collect()
+    }
 }
 
 impl Choice {
+    /// Unrecognized fields, e.g. ones added to the API after this release
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra
+    }
     pub fn index(&self) -> i32 {
         self.index
     }
@@ -459,15 +581,60 @@ impl Choice {
     pub fn finish_reason(&self) -> Option<&str> {
         self.finish_reason.as_deref()
     }
+
+    /// [`finish_reason`](Self::finish_reason) as a [`FinishReason`]
+    pub fn finish_reason_typed(&self) -> Option<FinishReason> {
+        self.finish_reason.as_deref().map(FinishReason::from)
+    }
 }
 
 impl Message {
+    /// Unrecognized fields, e.g. ones added to the API after this release
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra
+    }
     pub fn role(&self) -> Option<&str> {
         self.role.as_deref()
     }
     pub fn content(&self) -> Option<&serde_json::Value> {
         self.content.as_ref()
     }
+
+    /// Content as text; see [`ChatCompletionResponse::final_text`].
+    pub fn text(&self) -> Option<String> {
+        match self.content.as_ref()? {
+            serde_json::Value::Null => None,
+            serde_json::Value::Array(_) => Some(self.text_parts().concat()),
+            _ => self.text_parts().into_iter().next(),
+        }
+    }
+
+    /// Content split into text parts.
+    ///
+    /// Handles plain strings, arrays of strings or `{"type":"text","text":..}`
+    /// objects, and falls back to the JSON string for anything else.
+    pub fn text_parts(&self) -> Vec<String> {
+        fn part_text(v: &serde_json::Value) -> String {
+            match v {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Object(map) => match map.get("text") {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    _ => v.to_string(),
+                },
+                other => other.to_string(),
+            }
+        }
+
+        match self.content.as_ref() {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(serde_json::Value::Array(parts)) => parts.iter().map(part_text).collect(),
+            Some(other) => vec![part_text(other)],
+        }
+    }
+
+    /// The model's reasoning, returned separately from
+    /// [`content`](Self::content) when thinking is enabled; `None` for
+    /// models or requests without it.
     pub fn reasoning_content(&self) -> Option<&str> {
         self.reasoning_content.as_deref()
     }
@@ -480,6 +647,9 @@ impl Message {
 }
 
 impl ToolCallMessage {
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
     pub fn id(&self) -> Option<&str> {
         self.id.as_deref()
     }
@@ -585,6 +755,10 @@ impl Usage {
     pub fn prompt_tokens_details(&self) -> Option<&PromptTokensDetails> {
         self.prompt_tokens_details.as_ref()
     }
+    /// Prompt tokens served from the prompt cache, if reported
+    pub fn cached_tokens(&self) -> Option<u32> {
+        self.prompt_tokens_details.as_ref()?.cached_tokens
+    }
 }
 
 impl PromptTokensDetails {
@@ -634,3 +808,170 @@ impl ContentFilterInfo {
         self.level
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn response(contents: Vec<serde_json::Value>) -> ChatCompletionResponse {
+        let choices: Vec<_> = contents
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| json!({"index": i, "message": {"role": "assistant", "content": c}}))
+            .collect();
+        serde_json::from_value(json!({ "choices": choices })).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_fields_kept_in_extra() {
+        let raw = json!({
+            "id": "resp",
+            "brand_new": {"enabled": true},
+            "choices": [{
+                "index": 0,
+                "finish_reason": "stop",
+                "logprobs": null,
+                "message": {"role": "assistant", "content": "hi", "annotations": [1, 2]}
+            }]
+        });
+        let resp: ChatCompletionResponse = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(resp.id(), Some("resp"));
+        assert_eq!(resp.extra().len(), 1);
+        assert_eq!(resp.extra()["brand_new"], json!({"enabled": true}));
+        let choice = &resp.choices().unwrap()[0];
+        assert_eq!(choice.extra()["logprobs"], serde_json::Value::Null);
+        assert_eq!(choice.message().extra()["annotations"], json!([1, 2]));
+        assert_eq!(serde_json::to_value(&resp).unwrap(), raw);
+
+        let plain = response(vec![json!("x")]);
+        assert!(plain.extra().is_empty());
+        assert!(serde_json::to_value(&plain).unwrap().get("extra").is_none());
+    }
+
+    #[test]
+    fn test_usage_cached_tokens() {
+        let usage: Usage = serde_json::from_value(json!({
+            "prompt_tokens": 1200,
+            "completion_tokens": 30,
+            "total_tokens": 1230,
+            "prompt_tokens_details": {"cached_tokens": 1024}
+        }))
+        .unwrap();
+        assert_eq!(usage.cached_tokens(), Some(1024));
+
+        let usage: Usage = serde_json::from_value(json!({"prompt_tokens": 5})).unwrap();
+        assert_eq!(usage.cached_tokens(), None);
+    }
+
+    #[test]
+    fn test_finish_reason_typed() {
+        let cases = [
+            ("stop", FinishReason::Stop),
+            ("length", FinishReason::Length),
+            ("tool_calls", FinishReason::ToolCalls),
+            ("sensitive", FinishReason::ContentFilter),
+            ("content_filter", FinishReason::ContentFilter),
+            (
+                "network_error",
+                FinishReason::Other("network_error".to_string()),
+            ),
+        ];
+        for (raw, expected) in cases {
+            let resp: ChatCompletionResponse = serde_json::from_value(json!({
+                "choices": [{"index": 0, "message": {"role": "assistant"}, "finish_reason": raw}]
+            }))
+            .unwrap();
+            let choice = &resp.choices().unwrap()[0];
+            assert_eq!(choice.finish_reason_typed(), Some(expected.clone()));
+            assert_eq!(
+                serde_json::from_value::<FinishReason>(json!(raw)).unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            serde_json::to_value(FinishReason::ToolCalls).unwrap(),
+            json!("tool_calls")
+        );
+
+        let resp = response(vec![json!("hi")]);
+        assert_eq!(resp.choices().unwrap()[0].finish_reason_typed(), None);
+    }
+
+    #[test]
+    fn test_reasoning_content_separate_from_content() {
+        let resp: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "r1",
+            "choices": [{
+                "index": 0,
+                "finish_reason": "stop",
+                "message": {
+                    "role": "assistant",
+                    "reasoning_content": "The user greets me; reply briefly.",
+                    "content": "Hello!"
+                }
+            }]
+        }))
+        .unwrap();
+        let message = resp.choices().unwrap()[0].message();
+        assert_eq!(
+            message.reasoning_content(),
+            Some("The user greets me; reply briefly.")
+        );
+        assert_eq!(resp.final_text().as_deref(), Some("Hello!"));
+        assert_eq!(
+            response(vec![json!("hi")]).choices().unwrap()[0]
+                .message()
+                .reasoning_content(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_final_text_string() {
+        let resp = response(vec![json!("hello")]);
+        assert_eq!(resp.final_text().as_deref(), Some("hello"));
+        assert_eq!(resp.all_text_parts(), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_final_text_array_of_parts() {
+        let resp = response(vec![json!([
+            {"type": "text", "text": "Hello, "},
+            "world",
+            {"type": "image_url", "image_url": {"url": "x"}}
+        ])]);
+        assert_eq!(
+            resp.final_text().as_deref(),
+            Some(r#"Hello, world{"image_url":{"url":"x"},"type":"image_url"}"#)
+        );
+        assert_eq!(resp.all_text_parts().len(), 3);
+    }
+
+    #[test]
+    fn test_final_text_other_json_is_stringified() {
+        let resp = response(vec![json!({"answer": 42})]);
+        assert_eq!(resp.final_text().as_deref(), Some(r#"{"answer":42}"#));
+        let resp = response(vec![json!(42)]);
+        assert_eq!(resp.final_text().as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_final_text_missing() {
+        assert_eq!(ChatCompletionResponse::default().final_text(), None);
+        assert_eq!(response(vec![json!(null)]).final_text(), None);
+        assert!(
+            ChatCompletionResponse::default()
+                .all_text_parts()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_all_text_parts_across_choices() {
+        let resp = response(vec![json!("a"), json!([{"type": "text", "text": "b"}])]);
+        assert_eq!(resp.all_text_parts(), vec!["a", "b"]);
+        assert_eq!(resp.final_text().as_deref(), Some("a"));
+    }
+}
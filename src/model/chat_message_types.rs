@@ -36,6 +36,9 @@
 //! - Conditional serialization of optional fields
 //! - Custom serialization logic for complex types like `ToolCall`
 //!
+//! Text conversations ([`TextMessages`], [`TextMessage`], [`ToolCall`]) also
+//! implement `Deserialize`, so saved histories can be loaded back.
+//!
 //! ## Validation
 //! Built-in validation ensures data integrity:
 //! - Message count limits for collections
@@ -120,10 +123,18 @@
 //! - New content formats can be supported through enum variants
 //! - Tool types can be extended for new capabilities
 
+use std::path::Path;
+
 use base64::{Engine, prelude::*};
 use serde::{Deserialize, Serialize};
 use validator::*;
 
+use super::{
+    audio_duration::{VoiceError, check_voice_duration},
+    chat_models::ChatModel,
+};
+use crate::client::error::{ZaiError, ZaiResult};
+
 /// A collection of text messages with validation constraints.
 ///
 /// This structure wraps a vector of [`TextMessage`] instances and ensures that
@@ -134,7 +145,7 @@ use validator::*;
 ///
 /// - Must contain at least 1 message
 /// - Must not contain more than 1000 messages
-#[derive(Clone, Serialize, Validate)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
 pub struct TextMessages {
     /// The collection of text messages. Must contain between 1 and 1000
     /// messages.
@@ -188,6 +199,116 @@ impl TextMessages {
         self.messages.push(msg);
         self
     }
+
+    /// Merges all system messages into one leading system message.
+    ///
+    /// System contents are joined with newlines in their original order; the
+    /// order of the other messages is preserved. Without system messages the
+    /// collection is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let messages = TextMessages::new(TextMessage::user("Hi"))
+    ///     .add_message(TextMessage::system("Be brief."))
+    ///     .normalize_system();
+    /// // [system("Be brief."), user("Hi")]
+    /// ```
+    pub fn normalize_system(self) -> Self {
+        let (system, mut rest): (Vec<_>, Vec<_>) = self
+            .messages
+            .into_iter()
+            .partition(|m| matches!(m, TextMessage::System { .. }));
+        if system.is_empty() {
+            return Self { messages: rest };
+        }
+        let content = system
+            .into_iter()
+            .filter_map(|m| match m {
+                TextMessage::System { content } => Some(content),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        rest.insert(0, TextMessage::System { content });
+        Self { messages: rest }
+    }
+
+    /// Validates the collection.
+    ///
+    /// In addition to the field constraints (1 to 1000 messages), there may be
+    /// at most one system message and it must come first; see
+    /// [`normalize_system`](Self::normalize_system). A violation is reported
+    /// on `messages` with code `system_message_position` and the offending
+    /// indices in the `indices` parameter.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let result = Validate::validate(self);
+        let misplaced: Vec<usize> = self
+            .messages
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, m)| matches!(m, TextMessage::System { .. }))
+            .map(|(i, _)| i)
+            .collect();
+        if misplaced.is_empty() {
+            return result;
+        }
+
+        let mut error = ValidationError::new("system_message_position").with_message(
+            format!(
+                "system messages must be merged into a single leading message; found at index {}",
+                misplaced
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .into(),
+        );
+        error.add_param("indices".into(), &misplaced);
+        let mut errors = result.err().unwrap_or_default();
+        errors.add("messages", error);
+        Err(errors)
+    }
+
+    /// Serializes the conversation as JSON Lines, one message per line.
+    ///
+    /// Each line is the same JSON object the message is sent as, so tool
+    /// calls keep their ids and tool results their `tool_call_id`.
+    pub fn to_jsonl(&self) -> ZaiResult<String> {
+        let mut out = String::new();
+        for message in &self.messages {
+            out.push_str(&serde_json::to_string(message)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Parses a conversation written by [`to_jsonl`](Self::to_jsonl).
+    ///
+    /// Blank lines are skipped. A line that is not a valid message, or an
+    /// input without any message, is reported with its 1-based line number.
+    pub fn from_jsonl(s: &str) -> ZaiResult<Self> {
+        let mut messages = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message = serde_json::from_str(line).map_err(|e| ZaiError::ApiError {
+                code: 1200,
+                message: format!("invalid message on line {}: {}", index + 1, e),
+            })?;
+            messages.push(message);
+        }
+        if messages.is_empty() {
+            return Err(ZaiError::ApiError {
+                code: 1200,
+                message: "JSONL conversation contains no messages".to_string(),
+            });
+        }
+        Ok(Self { messages })
+    }
 }
 
 /// Represents different types of messages in a chat conversation.
@@ -209,7 +330,7 @@ impl TextMessages {
 /// let system_msg = TextMessage::system("You are a helpful assistant.");
 /// let assistant_msg = TextMessage::assistant("I can help you with that!");
 /// ```
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "role")]
 #[serde(rename_all = "lowercase")]
 pub enum TextMessage {
@@ -222,12 +343,16 @@ pub enum TextMessage {
     Assistant {
         /// The text content of the assistant's response. Optional when tool
         /// calls are present.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         content: Option<String>,
         /// Tool calls made by the assistant. Empty vector is omitted from
         /// serialization.
-        #[serde(skip_serializing_if = "Vec::is_empty")]
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
         tool_calls: Vec<ToolCall>,
+        /// Marks the last message as the beginning of the reply for the model
+        /// to continue (prefill). Omitted from serialization when false.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        prefix: bool,
     },
     /// A system message that provides instructions or context to the assistant.
     System {
@@ -240,7 +365,7 @@ pub enum TextMessage {
         content: String,
         /// The ID of the tool call this message is responding to. Optional
         /// field.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         tool_call_id: Option<String>,
     },
 }
@@ -286,6 +411,25 @@ impl TextMessage {
         TextMessage::Assistant {
             content: Some(content.into()),
             tool_calls: Vec::new(),
+            prefix: false,
+        }
+    }
+
+    /// Creates the start of an assistant reply for the model to continue.
+    ///
+    /// The message must be the last one in the request; see
+    /// [`ChatCompletion::with_assistant_prefix`](crate::model::chat::ChatCompletion::with_assistant_prefix).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let msg = TextMessage::assistant_prefix("{\"city\": \"");
+    /// ```
+    pub fn assistant_prefix(content: impl Into<String>) -> Self {
+        TextMessage::Assistant {
+            content: Some(content.into()),
+            tool_calls: Vec::new(),
+            prefix: true,
         }
     }
 
@@ -318,6 +462,7 @@ impl TextMessage {
         TextMessage::Assistant {
             content,
             tool_calls,
+            prefix: false,
         }
     }
 
@@ -472,6 +617,9 @@ pub enum VisionRichContent {
     /// - GLM4.5V: maximum 50 images
     /// - GLM-4V-Plus-0111: maximum 5 images
     /// - GLM-4V-Flash: maximum 1 image (Base64 encoding not supported)
+    ///
+    /// See [`VisionMessage::validate_for_model`] for the limits checked
+    /// client-side.
     ImageUrl {
         image_url: ImageUrlInfo,
     },
@@ -496,6 +644,12 @@ pub enum VisionRichContent {
     },
 }
 
+/// Maximum size of an image sent as content (5 MB).
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Maximum number of files in one vision message.
+pub const MAX_FILES: usize = 50;
+
 impl VisionRichContent {
     /// Creates a new text content item.
     ///
@@ -538,6 +692,65 @@ impl VisionRichContent {
         }
     }
 
+    /// Creates an image content item from a local file.
+    ///
+    /// Reads the file, infers the MIME type from its extension (jpg, jpeg or
+    /// png) and embeds it as a `data:image/...;base64,` URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::ErrorKind::InvalidInput`] error for unsupported
+    /// extensions, [`std::io::ErrorKind::FileTooLarge`] for files over
+    /// [`MAX_IMAGE_BYTES`], and any error from reading the file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let image = VisionRichContent::image_from_path("photo.png")?;
+    /// ```
+    pub fn image_from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let mime = match extension.as_str() {
+            "jpg" | "jpeg" => "image/jpeg",
+            "png" => "image/png",
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "unsupported image extension '{}' (expected jpg, jpeg or png): {}",
+                        extension,
+                        path.display()
+                    ),
+                ));
+            },
+        };
+
+        let size = std::fs::metadata(path)?.len();
+        if size > MAX_IMAGE_BYTES {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::FileTooLarge,
+                format!(
+                    "image is {} bytes, exceeding the {} byte limit: {}",
+                    size,
+                    MAX_IMAGE_BYTES,
+                    path.display()
+                ),
+            ));
+        }
+
+        let bytes = std::fs::read(path)?;
+        Ok(Self::image(format!(
+            "data:{};base64,{}",
+            mime,
+            BASE64_STANDARD.encode(bytes)
+        )))
+    }
+
     /// Creates a new video content item.
     ///
     /// # Arguments
@@ -582,6 +795,95 @@ impl VisionRichContent {
 }
 
 impl VisionMessage {
+    /// Creates a user message with a single content item.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let msg = VisionMessage::user(VisionRichContent::image("https://example.com/image.jpg"));
+    /// ```
+    pub fn user(content: VisionRichContent) -> Self {
+        VisionMessage::User {
+            content: vec![content],
+        }
+    }
+
+    /// Creates a user message from several content items, kept in order.
+    ///
+    /// Serializes to the content-array form, e.g.
+    /// `{"role":"user","content":[{"type":"text",...},{"type":"image_url",...}]}`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let msg = VisionMessage::user_parts(vec![
+    ///     VisionRichContent::text("Compare these two images"),
+    ///     VisionRichContent::image("https://example.com/a.jpg"),
+    ///     VisionRichContent::image("https://example.com/b.jpg"),
+    /// ]);
+    /// ```
+    pub fn user_parts(parts: Vec<VisionRichContent>) -> Self {
+        VisionMessage::User { content: parts }
+    }
+
+    /// Starts a [`VisionMessageBuilder`] for a multi-part user message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let msg = VisionMessage::builder()
+    ///     .text("What changed between these frames?")
+    ///     .image("https://example.com/a.jpg")
+    ///     .image("https://example.com/b.jpg")
+    ///     .build();
+    /// ```
+    pub fn builder() -> VisionMessageBuilder {
+        VisionMessageBuilder::default()
+    }
+
+    /// Checks the message against the content limits of `model`.
+    ///
+    /// Fails with an `ApiError` (code 1200) if `model` does not accept vision
+    /// messages, or if a user message carries more images than
+    /// [`ChatModel::max_images`] or more than [`MAX_FILES`] files.
+    pub fn validate_for_model(&self, model: &ChatModel) -> ZaiResult<()> {
+        let invalid = |message: String| ZaiError::ApiError {
+            code: 1200,
+            message,
+        };
+        let Some(max_images) = model.max_images() else {
+            return Err(invalid(format!(
+                "model {} does not accept vision messages",
+                model
+            )));
+        };
+        let VisionMessage::User { content } = self else {
+            return Ok(());
+        };
+
+        let images = content
+            .iter()
+            .filter(|c| matches!(c, VisionRichContent::ImageUrl { .. }))
+            .count();
+        if images > max_images {
+            return Err(invalid(format!(
+                "model {} accepts at most {} image(s) per message, got {}",
+                model, max_images, images
+            )));
+        }
+        let files = content
+            .iter()
+            .filter(|c| matches!(c, VisionRichContent::FileUrl { .. }))
+            .count();
+        if files > MAX_FILES {
+            return Err(invalid(format!(
+                "at most {} files are accepted per message, got {}",
+                MAX_FILES, files
+            )));
+        }
+        Ok(())
+    }
+
     /// Creates a new empty user message.
     ///
     /// # Returns
@@ -695,6 +997,135 @@ impl VisionMessage {
     }
 }
 
+/// Builder for a multi-part [`VisionMessage::User`] message.
+///
+/// Parts are serialized in the order they are added.
+#[derive(Debug, Clone, Default)]
+pub struct VisionMessageBuilder {
+    parts: Vec<VisionRichContent>,
+}
+
+impl VisionMessageBuilder {
+    /// Appends a text part.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.part(VisionRichContent::text(text))
+    }
+
+    /// Appends an image part (URL or Base64 data URL).
+    pub fn image(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::image(url))
+    }
+
+    /// Appends a video part.
+    pub fn video(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::video(url))
+    }
+
+    /// Appends a file part.
+    pub fn file(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::file(url))
+    }
+
+    /// Appends an already built content item.
+    pub fn part(mut self, part: VisionRichContent) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// Builds the user message.
+    pub fn build(self) -> VisionMessage {
+        VisionMessage::user_parts(self.parts)
+    }
+}
+
+/// Ordered content of a vision prompt with several images.
+///
+/// Parts are kept in insertion order, so a caption always precedes the
+/// image it describes. Unlike [`VisionMessageBuilder`], the prompt can be
+/// checked against a model's image cap before it is turned into a message.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let prompt = VisionPrompt::new()
+///     .text("Which room is brighter?")
+///     .describe_image("https://example.com/kitchen.jpg", "Kitchen:")
+///     .describe_image("https://example.com/bedroom.jpg", "Bedroom:");
+/// prompt.validate_for_model(&ChatModel::Glm4_6v)?;
+/// let msg = prompt.into_message();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VisionPrompt {
+    parts: Vec<VisionRichContent>,
+}
+
+impl VisionPrompt {
+    /// Creates an empty prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a text part.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.part(VisionRichContent::text(text))
+    }
+
+    /// Appends an image part (URL or Base64 data URL).
+    pub fn image(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::image(url))
+    }
+
+    /// Appends `caption` as a text part immediately followed by the image.
+    pub fn describe_image(self, url: impl Into<String>, caption: impl Into<String>) -> Self {
+        self.text(caption).image(url)
+    }
+
+    /// Appends a video part.
+    pub fn video(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::video(url))
+    }
+
+    /// Appends a file part.
+    pub fn file(self, url: impl Into<String>) -> Self {
+        self.part(VisionRichContent::file(url))
+    }
+
+    /// Appends an already built content item.
+    pub fn part(mut self, part: VisionRichContent) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// The parts added so far, in order.
+    pub fn parts(&self) -> &[VisionRichContent] {
+        &self.parts
+    }
+
+    /// Number of image parts.
+    pub fn image_count(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|c| matches!(c, VisionRichContent::ImageUrl { .. }))
+            .count()
+    }
+
+    /// Checks the prompt against the content limits of `model`, as
+    /// [`VisionMessage::validate_for_model`] does for the built message.
+    pub fn validate_for_model(&self, model: &ChatModel) -> ZaiResult<()> {
+        self.clone().into_message().validate_for_model(model)
+    }
+
+    /// The content array, in insertion order.
+    pub fn into_parts(self) -> Vec<VisionRichContent> {
+        self.parts
+    }
+
+    /// Builds the user message.
+    pub fn into_message(self) -> VisionMessage {
+        VisionMessage::user_parts(self.parts)
+    }
+}
+
 /// Represents messages in voice-enabled chat conversations.
 ///
 /// This enum defines message types for conversations that can include audio
@@ -731,7 +1162,7 @@ impl VisionMessage {
 /// let audio_response = Audio::with_id("audio_123");
 /// let assistant_msg = VoiceMessage::assistant_audio_only(audio_response);
 /// ```
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "role")]
 #[serde(rename_all = "lowercase")]
 pub enum VoiceMessage {
@@ -749,10 +1180,10 @@ pub enum VoiceMessage {
     Assistant {
         /// The text content of the assistant's response. Optional when audio is
         /// present.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         content: Option<String>,
         /// Audio response data generated by the assistant. Optional field.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         audio: Option<Audio>,
     },
 }
@@ -762,6 +1193,8 @@ pub enum VoiceMessage {
 /// This enum defines the various types of content that can be included in
 /// voice-enabled conversations, including text messages and audio input.
 /// Each variant is serialized with a "type" field to distinguish content types.
+/// Note the tag is `type`, like [`VisionRichContent`], not the `role` used
+/// by the message enums.
 ///
 /// # Serialization
 ///
@@ -795,7 +1228,7 @@ pub enum VoiceMessage {
 /// let audio_data = b"raw audio data";
 /// let audio_content = VoiceRichContent::input_audio(audio_data, VoiceFormat::WAV);
 /// ```
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum VoiceRichContent {
@@ -825,7 +1258,7 @@ pub enum VoiceRichContent {
 ///
 /// This structure contains the base64 encoded audio data and format information
 /// for voice input in chat messages.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InputAudioData {
     /// Base64 encoded audio file data. Maximum audio duration is 10 minutes.
     /// 1 second of audio = 12.5 tokens, rounded up.
@@ -880,6 +1313,31 @@ impl VoiceRichContent {
             },
         }
     }
+
+    /// Creates an input audio content item after checking the clip against
+    /// the 10-minute limit.
+    ///
+    /// The duration is read from the WAV/MP3 header; MP3 durations are
+    /// estimated (see [`audio_duration`](super::audio_duration)). Formats the
+    /// chat API does not accept (PCM, OPUS) are rejected. Use
+    /// [`input_audio`](Self::input_audio) for clips that were already
+    /// validated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let audio = VoiceRichContent::input_audio_checked(std::fs::read("q.wav")?, VoiceFormat::WAV)?;
+    /// ```
+    pub fn input_audio_checked(
+        data: impl AsRef<[u8]>,
+        format: VoiceFormat,
+    ) -> Result<Self, VoiceError> {
+        if !format.is_chat_supported() {
+            return Err(VoiceError::UnsupportedFormat { format });
+        }
+        check_voice_duration(data.as_ref(), &format)?;
+        Ok(Self::input_audio(data, format))
+    }
 }
 
 /// Represents supported audio formats for voice interactions.
@@ -892,11 +1350,16 @@ impl VoiceRichContent {
 ///
 /// - **MP3**: Compressed audio format, widely supported, good for general use
 /// - **WAV**: Uncompressed audio format, higher quality, larger file sizes
+/// - **PCM**: Raw, headerless samples as streamed by the realtime API
+/// - **OPUS**: Opus in an Ogg container, compact for speech
 ///
 /// # Model Compatibility
 ///
 /// Different voice-capable models may have different format support:
-/// - GLM-4-Voice: Supports both MP3 and WAV formats
+/// - GLM-4-Voice chat input: MP3 and WAV only
+///   ([`is_chat_supported`](Self::is_chat_supported));
+///   [`VoiceRichContent::input_audio_checked`] rejects PCM and OPUS
+/// - GLM-Realtime: PCM and OPUS as well (see `realTime::types::AudioFormat`)
 /// - Other models: Check specific model documentation
 ///
 /// # File Size Considerations
@@ -918,7 +1381,7 @@ impl VoiceRichContent {
 /// // Detect format from file extension
 /// let format = VoiceFormat::from_extension("mp3").unwrap();
 /// ```
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum VoiceFormat {
     /// MPEG Audio Layer III format.
@@ -934,6 +1397,16 @@ pub enum VoiceFormat {
     /// in larger file sizes. Suitable for applications where audio quality is
     /// critical.
     WAV,
+
+    /// Raw PCM samples without a header.
+    ///
+    /// Used by the realtime API. Not accepted as chat voice input.
+    PCM,
+
+    /// Opus audio in an Ogg container.
+    ///
+    /// Used by the realtime API. Not accepted as chat voice input.
+    OPUS,
 }
 
 impl VoiceFormat {
@@ -959,6 +1432,8 @@ impl VoiceFormat {
         match extension.to_lowercase().as_str() {
             "mp3" => Some(VoiceFormat::MP3),
             "wav" => Some(VoiceFormat::WAV),
+            "pcm" => Some(VoiceFormat::PCM),
+            "opus" => Some(VoiceFormat::OPUS),
             _ => None,
         }
     }
@@ -985,9 +1460,16 @@ impl VoiceFormat {
         match mime_type.to_lowercase().as_str() {
             "audio/mpeg" => Some(VoiceFormat::MP3),
             "audio/wav" | "audio/x-wav" => Some(VoiceFormat::WAV),
+            "audio/pcm" => Some(VoiceFormat::PCM),
+            "audio/opus" => Some(VoiceFormat::OPUS),
             _ => None,
         }
     }
+
+    /// Whether chat voice input (GLM-4-Voice) accepts this format.
+    pub fn is_chat_supported(&self) -> bool {
+        matches!(self, VoiceFormat::MP3 | VoiceFormat::WAV)
+    }
 }
 
 /// Represents audio response data generated by the assistant.
@@ -1030,13 +1512,13 @@ impl VoiceFormat {
 ///     .set_id("audio_456")
 ///     .clear_id(); // Remove ID if needed
 /// ```
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Audio {
     /// Optional unique identifier for the audio response.
     ///
     /// When present, this ID can be used to reference specific audio segments
     /// in playback, caching, or analytics systems.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 }
 
@@ -1278,7 +1760,7 @@ impl VoiceMessage {
 ///
 /// The struct implements custom serialization logic to ensure that the
 /// `function` field is only included when appropriate for the tool call type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ToolCall {
     id: String,
     type_: ToolCallType,
@@ -1333,6 +1815,39 @@ impl serde::Serialize for ToolCall {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for ToolCall {
+    /// Custom deserialization mirroring the `Serialize` implementation.
+    ///
+    /// Reads the wire field `type` into `type_` and rejects a `function` tool
+    /// call without a `function` payload.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw {
+            id: String,
+            #[serde(rename = "type")]
+            type_: ToolCallType,
+            #[serde(default)]
+            function: Option<FunctionParams>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        if matches!(raw.type_, ToolCallType::Function) && raw.function.is_none() {
+            return Err(serde::de::Error::custom(
+                "function field is required when type is 'function'",
+            ));
+        }
+
+        Ok(Self {
+            id: raw.id,
+            type_: raw.type_,
+            function: raw.function,
+        })
+    }
+}
+
 /// Specifies the type of tool being called.
 ///
 /// This enum defines the different types of tools that can be invoked by the
@@ -1343,7 +1858,7 @@ impl serde::Serialize for ToolCall {
 /// * `Function` - Call a user-defined function with specific parameters
 /// * `WebSearch` - Perform a web search operation
 /// * `Retrieval` - Access a retrieval/knowledge system
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolCallType {
     /// A function call with custom parameters.
@@ -1402,7 +1917,7 @@ pub enum ToolCallType {
 /// // Function with no parameters
 /// let params = FunctionParams::new("get_system_time", "{}");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FunctionParams {
     /// The name of the function to be called.
     ///
@@ -1544,8 +2059,6 @@ impl FunctionParams {
 
 #[cfg(test)]
 mod tests {
-    use validator::Validate;
-
     use super::*;
 
     // TextMessages tests
@@ -1564,9 +2077,84 @@ mod tests {
             .add_message(TextMessage::system("You are helpful"));
 
         assert_eq!(messages.messages.len(), 3);
+        // A trailing system message must be moved to the front first.
+        assert!(messages.validate().is_err());
+        assert!(messages.normalize_system().validate().is_ok());
+    }
+
+    #[test]
+    fn test_jsonl_round_trip_keeps_tool_linkage() {
+        let messages = TextMessages::new(TextMessage::system("Be brief."))
+            .add_message(TextMessage::user("Weather in Paris?"))
+            .add_message(TextMessage::assistant_with_tools(
+                None,
+                vec![ToolCall::new_function(
+                    "call_7",
+                    FunctionParams::new("get_weather", r#"{"city":"Paris"}"#),
+                )],
+            ))
+            .add_message(TextMessage::tool_with_id(r#"{"temp":21}"#, "call_7"))
+            .add_message(TextMessage::assistant("21 degrees."));
+
+        let jsonl = messages.to_jsonl().unwrap();
+        assert_eq!(jsonl.lines().count(), 5);
+        assert!(
+            jsonl
+                .lines()
+                .nth(3)
+                .unwrap()
+                .contains("\"tool_call_id\":\"call_7\"")
+        );
+        assert_eq!(TextMessages::from_jsonl(&jsonl).unwrap(), messages);
+
+        let err = TextMessages::from_jsonl("{\"role\":\"user\",\"content\":\"hi\"}\n\nnot json\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{}", err);
+        assert!(TextMessages::from_jsonl("\n").is_err());
+    }
+
+    #[test]
+    fn test_normalize_system_without_system_messages() {
+        let messages =
+            TextMessages::new(TextMessage::user("Hello")).add_message(TextMessage::assistant("Hi"));
+        assert_eq!(messages.clone().normalize_system(), messages);
+        assert!(messages.validate().is_ok());
+    }
+
+    #[test]
+    fn test_normalize_system_single_leading_is_unchanged() {
+        let messages = TextMessages::new(TextMessage::system("Be brief"))
+            .add_message(TextMessage::user("Hello"));
+        assert_eq!(messages.clone().normalize_system(), messages);
         assert!(messages.validate().is_ok());
     }
 
+    #[test]
+    fn test_normalize_system_merges_interleaved() {
+        let messages = TextMessages::new(TextMessage::user("q1"))
+            .add_message(TextMessage::system("rule one"))
+            .add_message(TextMessage::assistant("a1"))
+            .add_message(TextMessage::system("rule two"))
+            .add_message(TextMessage::user("q2"));
+
+        let errors = messages.validate().unwrap_err();
+        let field = &errors.field_errors()["messages"];
+        assert_eq!(field[0].code, "system_message_position");
+        assert_eq!(field[0].params["indices"], serde_json::json!([1, 3]));
+
+        let normalized = messages.normalize_system();
+        assert_eq!(
+            normalized.messages,
+            vec![
+                TextMessage::system("rule one\nrule two"),
+                TextMessage::user("q1"),
+                TextMessage::assistant("a1"),
+                TextMessage::user("q2"),
+            ]
+        );
+        assert!(normalized.validate().is_ok());
+    }
+
     #[test]
     fn test_text_messages_validation() {
         let messages = TextMessages::new(TextMessage::user("Test"));
@@ -1672,6 +2260,98 @@ mod tests {
         assert!(json.contains("\"role\":\"assistant\""));
     }
 
+    #[test]
+    fn test_vision_message_multi_part_serialization() {
+        let expected = serde_json::json!({
+            "role": "user",
+            "content": [
+                {"type": "text", "text": "Compare these"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/a.jpg"}},
+                {"type": "image_url", "image_url": {"url": "https://example.com/b.jpg"}},
+                {"type": "video_url", "video_url": {"url": "https://example.com/c.mp4"}}
+            ]
+        });
+        let built = VisionMessage::builder()
+            .text("Compare these")
+            .image("https://example.com/a.jpg")
+            .image("https://example.com/b.jpg")
+            .video("https://example.com/c.mp4")
+            .build();
+        let from_parts = VisionMessage::user_parts(vec![
+            VisionRichContent::text("Compare these"),
+            VisionRichContent::image("https://example.com/a.jpg"),
+            VisionRichContent::image("https://example.com/b.jpg"),
+            VisionRichContent::video("https://example.com/c.mp4"),
+        ]);
+        assert_eq!(serde_json::to_value(&built).unwrap(), expected);
+        assert_eq!(serde_json::to_value(&from_parts).unwrap(), expected);
+
+        let single = VisionMessage::user(VisionRichContent::text("hi"));
+        assert_eq!(
+            serde_json::to_value(&single).unwrap(),
+            serde_json::json!({"role": "user", "content": [{"type": "text", "text": "hi"}]})
+        );
+    }
+
+    #[test]
+    fn test_vision_prompt_keeps_captions_before_images() {
+        let prompt = VisionPrompt::new()
+            .text("Which room is brighter?")
+            .describe_image("https://example.com/kitchen.jpg", "Kitchen:")
+            .describe_image("https://example.com/bedroom.jpg", "Bedroom:")
+            .text("Answer in one word.");
+        assert_eq!(prompt.image_count(), 2);
+        assert!(prompt.validate_for_model(&ChatModel::Glm4_6v).is_ok());
+        assert!(prompt.validate_for_model(&ChatModel::Glm4_5Flash).is_err());
+        assert_eq!(
+            serde_json::to_value(prompt.into_parts()).unwrap(),
+            serde_json::json!([
+                {"type": "text", "text": "Which room is brighter?"},
+                {"type": "text", "text": "Kitchen:"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/kitchen.jpg"}},
+                {"type": "text", "text": "Bedroom:"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/bedroom.jpg"}},
+                {"type": "text", "text": "Answer in one word."}
+            ])
+        );
+
+        let crowded = (0..51).fold(VisionPrompt::new(), |p, i| {
+            p.describe_image(format!("https://example.com/{}.jpg", i), format!("#{}", i))
+        });
+        let err = crowded.validate_for_model(&ChatModel::Glm4_6v).unwrap_err();
+        assert!(err.to_string().contains("got 51"), "{}", err);
+    }
+
+    #[test]
+    fn test_vision_message_validate_for_model() {
+        let images = |n: usize| {
+            (0..n)
+                .fold(VisionMessage::builder().text("look"), |b, i| {
+                    b.image(format!("https://example.com/{}.jpg", i))
+                })
+                .build()
+        };
+        assert!(images(50).validate_for_model(&ChatModel::Glm4_5v).is_ok());
+        let err = images(51)
+            .validate_for_model(&ChatModel::Glm4_5v)
+            .unwrap_err();
+        assert!(matches!(err, ZaiError::ApiError { code: 1200, .. }));
+        assert!(err.to_string().contains("got 51"), "{}", err);
+
+        assert!(images(1).validate_for_model(&ChatModel::Glm4_7).is_err());
+        assert!(
+            VisionMessage::system("s")
+                .validate_for_model(&ChatModel::Glm4_6v)
+                .is_ok()
+        );
+        let files = (0..=MAX_FILES)
+            .fold(VisionMessage::builder(), |b, i| {
+                b.file(format!("f{}.pdf", i))
+            })
+            .build();
+        assert!(files.validate_for_model(&ChatModel::Glm4_6v).is_err());
+    }
+
     // VisionRichContent tests
     #[test]
     fn test_vision_rich_content_text() {
@@ -1689,6 +2369,35 @@ mod tests {
         assert!(json.contains("https://example.com/img.jpg"));
     }
 
+    #[test]
+    fn test_vision_rich_content_image_from_path() {
+        let dir = std::env::temp_dir().join(format!("zai-rs-image-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let png = dir.join("pixel.PNG");
+        std::fs::write(&png, [0x89, b'P', b'N', b'G']).unwrap();
+        match VisionRichContent::image_from_path(&png).unwrap() {
+            VisionRichContent::ImageUrl { image_url } => {
+                assert_eq!(image_url.url, "data:image/png;base64,iVBORw==")
+            },
+            other => panic!("unexpected content: {:?}", other),
+        }
+
+        let gif = dir.join("anim.gif");
+        std::fs::write(&gif, b"GIF89a").unwrap();
+        let err = VisionRichContent::image_from_path(&gif).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let big = dir.join("big.jpg");
+        let file = std::fs::File::create(&big).unwrap();
+        file.set_len(MAX_IMAGE_BYTES + 1).unwrap();
+        let err = VisionRichContent::image_from_path(&big).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::FileTooLarge);
+
+        assert!(VisionRichContent::image_from_path(dir.join("missing.jpeg")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_vision_rich_content_video() {
         let content = VisionRichContent::video("https://example.com/video.mp4");
@@ -1781,6 +2490,21 @@ mod tests {
         assert!(json.contains("\"data\":"));
     }
 
+    #[test]
+    fn test_voice_rich_content_exact_shape() {
+        assert_eq!(
+            serde_json::to_value(VoiceRichContent::text("hi")).unwrap(),
+            serde_json::json!({"type": "text", "text": "hi"})
+        );
+        assert_eq!(
+            serde_json::to_value(VoiceRichContent::input_audio(b"abc", VoiceFormat::WAV)).unwrap(),
+            serde_json::json!({
+                "type": "input_audio",
+                "input_audio": {"data": BASE64_STANDARD.encode(b"abc"), "format": "wav"}
+            })
+        );
+    }
+
     // VoiceFormat tests
     #[test]
     fn test_voice_format_from_extension() {
@@ -1790,6 +2514,30 @@ mod tests {
         assert_eq!(VoiceFormat::from_extension("WAV"), Some(VoiceFormat::WAV));
         assert_eq!(VoiceFormat::from_extension("ogg"), None);
         assert_eq!(VoiceFormat::from_extension("flac"), None);
+        assert_eq!(VoiceFormat::from_extension("pcm"), Some(VoiceFormat::PCM));
+        assert_eq!(VoiceFormat::from_extension("Opus"), Some(VoiceFormat::OPUS));
+    }
+
+    #[test]
+    fn test_voice_format_pcm_opus() {
+        assert_eq!(
+            VoiceFormat::from_mime_type("audio/pcm"),
+            Some(VoiceFormat::PCM)
+        );
+        assert_eq!(
+            VoiceFormat::from_mime_type("AUDIO/OPUS"),
+            Some(VoiceFormat::OPUS)
+        );
+        assert_eq!(serde_json::to_value(VoiceFormat::PCM).unwrap(), "pcm");
+        assert_eq!(serde_json::to_value(VoiceFormat::OPUS).unwrap(), "opus");
+        assert!(VoiceFormat::MP3.is_chat_supported());
+        assert!(!VoiceFormat::PCM.is_chat_supported());
+        assert!(matches!(
+            VoiceRichContent::input_audio_checked(b"raw", VoiceFormat::OPUS),
+            Err(VoiceError::UnsupportedFormat {
+                format: VoiceFormat::OPUS
+            })
+        ));
     }
 
     #[test]
@@ -1913,4 +2661,79 @@ mod tests {
         assert_eq!(params.name, "test_func");
         assert_eq!(params.arguments, r#"{"arg":"value"}"#);
     }
+
+    // Deserialization round-trip tests
+    #[test]
+    fn test_text_conversation_round_trip() {
+        let messages = TextMessages::new(TextMessage::system("You are helpful."))
+            .add_message(TextMessage::user("What's the weather in Tokyo?"))
+            .add_message(TextMessage::assistant_with_tools(
+                None,
+                vec![
+                    ToolCall::new_function(
+                        "call_1",
+                        FunctionParams::new("get_weather", r#"{"location":"Tokyo"}"#),
+                    ),
+                    ToolCall::new_web_search("call_2"),
+                ],
+            ))
+            .add_message(TextMessage::tool_with_id("Sunny, 25C", "call_1"))
+            .add_message(TextMessage::tool("search results"))
+            .add_message(TextMessage::assistant("It's sunny in Tokyo."));
+
+        let json = serde_json::to_string(&messages).unwrap();
+        let restored: TextMessages = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, messages);
+    }
+
+    #[test]
+    fn test_voice_conversation_round_trip() {
+        let messages = vec![
+            VoiceMessage::system("Answer briefly."),
+            VoiceMessage::new_user()
+                .add_user(VoiceRichContent::text("What is said here?"))
+                .add_user(VoiceRichContent::input_audio(b"RIFF....", VoiceFormat::WAV)),
+            VoiceMessage::assistant_with_audio(
+                Some("Hello".to_string()),
+                Some(Audio::with_id("audio_1")),
+            ),
+            VoiceMessage::assistant_audio_only(Audio::new()),
+        ];
+
+        let json = serde_json::to_value(&messages).unwrap();
+        assert_eq!(json[1]["content"][0]["type"], "text");
+        assert_eq!(json[1]["content"][1]["type"], "input_audio");
+        assert_eq!(json[1]["content"][1]["input_audio"]["format"], "wav");
+        assert!(json[1]["content"][1].get("role").is_none());
+
+        let restored: Vec<VoiceMessage> = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, messages);
+
+        let sparse: VoiceMessage = serde_json::from_str(r#"{"role":"assistant"}"#).unwrap();
+        assert_eq!(
+            sparse,
+            VoiceMessage::Assistant {
+                content: None,
+                audio: None
+            }
+        );
+        assert!(
+            serde_json::from_str::<VoiceRichContent>(r#"{"role":"text","text":"hi"}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_text_message_deserialize_optional_fields() {
+        let msg: TextMessage = serde_json::from_str(r#"{"role":"assistant"}"#).unwrap();
+        assert_eq!(msg, TextMessage::assistant_with_tools(None, vec![]));
+
+        let msg: TextMessage = serde_json::from_str(r#"{"role":"tool","content":"ok"}"#).unwrap();
+        assert_eq!(msg, TextMessage::tool("ok"));
+    }
+
+    #[test]
+    fn test_tool_call_deserialize_function_without_params_fails() {
+        let result = serde_json::from_str::<ToolCall>(r#"{"id":"call_1","type":"function"}"#);
+        assert!(result.is_err());
+    }
 }
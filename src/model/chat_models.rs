@@ -50,6 +50,17 @@
 //! let client = ChatCompletion::new(model, messages, api_key);
 //! ```
 //!
+//! # Runtime Selection
+//!
+//! [`ChatModel`] maps API model strings (e.g. from configuration) to a typed
+//! enum; [`ChatModel::as_dyn`] produces a [`DynChatModel`] that can be passed
+//! to `ChatCompletion::new`.
+//!
+//! [`capabilities`] tells what a model accepts (images, audio, tools) and
+//! its token limits, which
+//! [`ChatCompletion::validate_capabilities`](crate::model::chat::data::ChatCompletion::validate_capabilities)
+//! checks a request against.
+//!
 //! # Defining New Models
 //!
 //! Use the [`define_model_type!`](crate::define_model_type) macro to create
@@ -57,9 +68,11 @@
 //! bind compatible message types, and
 //! [`impl_model_markers!`](crate::impl_model_markers) to declare capabilities.
 
+use serde::{Deserialize, Serialize};
+
 use super::traits::*;
 use crate::{
-    define_model_type, impl_message_binding, impl_model_markers,
+    ZaiError, define_model_type, impl_message_binding, impl_model_markers,
     model::chat_message_types::{TextMessage, VisionMessage, VoiceMessage},
 };
 
@@ -69,7 +82,7 @@ use crate::{
 
 define_model_type!(GLM5_1, "glm-5.1");
 impl_message_binding!(GLM5_1, TextMessage);
-impl_model_markers!(GLM5_1: Chat, AsyncChat, ThinkEnable, ToolStreamEnable);
+impl_model_markers!(GLM5_1: Chat, AsyncChat, ThinkEnable);
 
 define_model_type!(
     #[allow(non_camel_case_types)]
@@ -77,15 +90,15 @@ define_model_type!(
     "glm-5-turbo"
 );
 impl_message_binding!(GLM5_turbo, TextMessage);
-impl_model_markers!(GLM5_turbo: Chat, AsyncChat, ThinkEnable, ToolStreamEnable);
+impl_model_markers!(GLM5_turbo: Chat, AsyncChat, ThinkEnable);
 
 define_model_type!(GLM5, "glm-5");
 impl_message_binding!(GLM5, TextMessage);
-impl_model_markers!(GLM5: Chat, AsyncChat, ThinkEnable, ToolStreamEnable);
+impl_model_markers!(GLM5: Chat, AsyncChat, ThinkEnable);
 
 define_model_type!(GLM4_7, "glm-4.7");
 impl_message_binding!(GLM4_7, TextMessage);
-impl_model_markers!(GLM4_7: Chat, AsyncChat, ThinkEnable, ToolStreamEnable);
+impl_model_markers!(GLM4_7: Chat, AsyncChat, ThinkEnable);
 
 define_model_type!(
     #[allow(non_camel_case_types)]
@@ -106,7 +119,6 @@ impl_model_markers!(GLM4_7_flashx: Chat, AsyncChat, ThinkEnable);
 define_model_type!(GLM4_6, "glm-4.6");
 impl_message_binding!(GLM4_6, TextMessage);
 impl_model_markers!(GLM4_6: Chat, AsyncChat, ThinkEnable);
-impl ToolStreamEnable for GLM4_6 {}
 
 define_model_type!(GLM4_5, "glm-4.5");
 impl_message_binding!(GLM4_5, TextMessage);
@@ -144,6 +156,29 @@ define_model_type!(
 impl_message_binding!(GLM4_5_airx, TextMessage);
 impl_model_markers!(GLM4_5_airx: Chat, AsyncChat, ThinkEnable);
 
+/// Implements [`ToolStreamEnable`] for each model struct and records the
+/// matching [`ChatModel`] variants, so the compile-time marker and
+/// [`ChatModel::supports_tool_stream`] come from the same list.
+macro_rules! tool_stream_models {
+    ($($model:ident => $variant:ident),+ $(,)?) => {
+        $( impl ToolStreamEnable for $model {} )+
+        const TOOL_STREAM_MODELS: &[ChatModel] = &[$(ChatModel::$variant),+];
+
+        #[cfg(test)]
+        fn tool_stream_model_names() -> Vec<(String, ChatModel)> {
+            vec![$((String::from($model {}), ChatModel::$variant)),+]
+        }
+    };
+}
+
+tool_stream_models!(
+    GLM5_1 => Glm5_1,
+    GLM5_turbo => Glm5Turbo,
+    GLM5 => Glm5,
+    GLM4_7 => Glm4_7,
+    GLM4_6 => Glm4_6,
+);
+
 // ============================================================================
 // Multimodal Models - Vision
 // ============================================================================
@@ -199,3 +234,400 @@ define_model_type!(
 );
 impl_message_binding!(GLM4_voice, VoiceMessage);
 impl_model_markers!(GLM4_voice: Chat, AsyncChat);
+
+// ============================================================================
+// Runtime model selection
+// ============================================================================
+
+/// Kind of message a chat model accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// [`TextMessage`]
+    Text,
+    /// [`VisionMessage`]
+    Vision,
+    /// [`VoiceMessage`]
+    Voice,
+}
+
+/// Largest `max_tokens` any known model accepts, the bound
+/// [`ChatBody`](crate::model::chat_base_request::ChatBody) validates against;
+/// [`capabilities`] gives each model's own limit.
+pub const MAX_OUTPUT_TOKENS: u32 = 128_000;
+
+/// What a chat model accepts, and its token limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Accepts image, video and file content
+    pub supports_vision: bool,
+    /// Accepts audio input
+    pub supports_audio: bool,
+    /// Accepts function and retrieval tools
+    pub supports_tools: bool,
+    /// Images per user message, for vision models
+    pub max_images: Option<usize>,
+    /// Input plus output tokens per request
+    pub max_context_tokens: u32,
+    /// Largest `max_tokens` accepted
+    pub max_output_tokens: u32,
+}
+
+/// Capabilities of `model`, per the model documentation.
+///
+/// ```rust,ignore
+/// let caps = capabilities(&ChatModel::Glm4_6v);
+/// assert!(caps.supports_vision);
+/// ```
+pub fn capabilities(model: &ChatModel) -> ModelCapabilities {
+    let (max_context_tokens, max_output_tokens) = match model {
+        ChatModel::Glm5_1
+        | ChatModel::Glm5Turbo
+        | ChatModel::Glm5
+        | ChatModel::Glm4_7
+        | ChatModel::Glm4_7Flash
+        | ChatModel::Glm4_7Flashx
+        | ChatModel::Glm4_6 => (200_000, 128_000),
+        ChatModel::Glm4_5
+        | ChatModel::Glm4_5X
+        | ChatModel::Glm4_5Flash
+        | ChatModel::Glm4_5Air
+        | ChatModel::Glm4_5Airx => (128_000, 96_000),
+        ChatModel::Glm4_6v | ChatModel::Glm4_6vFlash | ChatModel::Glm4_6vFlashx => {
+            (128_000, 32_000)
+        },
+        ChatModel::Glm4_5v | ChatModel::AutoglmPhone => (64_000, 16_000),
+        ChatModel::Glm4Voice => (8_000, 4_000),
+    };
+    let kind = model.message_kind();
+    ModelCapabilities {
+        supports_vision: kind == MessageKind::Vision,
+        supports_audio: kind == MessageKind::Voice,
+        supports_tools: match kind {
+            MessageKind::Text => true,
+            MessageKind::Vision => matches!(
+                model,
+                ChatModel::Glm4_6v | ChatModel::Glm4_6vFlash | ChatModel::Glm4_6vFlashx
+            ),
+            MessageKind::Voice => false,
+        },
+        max_images: model.max_images(),
+        max_context_tokens,
+        max_output_tokens,
+    }
+}
+
+/// Runtime counterpart of the chat model unit structs.
+///
+/// Use this when the model name comes from configuration rather than code.
+/// It parses from and displays as the exact API model string, and
+/// [`ChatModel::as_dyn`] yields a model usable with
+/// [`ChatCompletion::new`](crate::model::chat::data::ChatCompletion::new).
+///
+/// ```rust,ignore
+/// let model: ChatModel = config.model.parse()?;
+/// let client = ChatCompletion::new(model.as_dyn(), TextMessage::user("Hi"), api_key);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChatModel {
+    #[serde(rename = "glm-5.1")]
+    Glm5_1,
+    #[serde(rename = "glm-5-turbo")]
+    Glm5Turbo,
+    #[serde(rename = "glm-5")]
+    Glm5,
+    #[serde(rename = "glm-4.7")]
+    Glm4_7,
+    #[serde(rename = "glm-4.7-flash")]
+    Glm4_7Flash,
+    #[serde(rename = "glm-4.7-flashx")]
+    Glm4_7Flashx,
+    #[serde(rename = "glm-4.6")]
+    Glm4_6,
+    #[serde(rename = "glm-4.5")]
+    Glm4_5,
+    #[serde(rename = "glm-4.5-X")]
+    Glm4_5X,
+    #[serde(rename = "glm-4.5-flash")]
+    Glm4_5Flash,
+    #[serde(rename = "glm-4.5-air")]
+    Glm4_5Air,
+    #[serde(rename = "glm-4.5-airx")]
+    Glm4_5Airx,
+    #[serde(rename = "autoglm-phone")]
+    AutoglmPhone,
+    #[serde(rename = "glm-4.6v")]
+    Glm4_6v,
+    #[serde(rename = "glm-4.6v-flash")]
+    Glm4_6vFlash,
+    #[serde(rename = "glm-4.6v-flashx")]
+    Glm4_6vFlashx,
+    #[serde(rename = "glm-4.5v")]
+    Glm4_5v,
+    #[serde(rename = "glm-4-voice")]
+    Glm4Voice,
+}
+
+impl ChatModel {
+    /// Every supported chat model.
+    pub const ALL: [ChatModel; 18] = [
+        Self::Glm5_1,
+        Self::Glm5Turbo,
+        Self::Glm5,
+        Self::Glm4_7,
+        Self::Glm4_7Flash,
+        Self::Glm4_7Flashx,
+        Self::Glm4_6,
+        Self::Glm4_5,
+        Self::Glm4_5X,
+        Self::Glm4_5Flash,
+        Self::Glm4_5Air,
+        Self::Glm4_5Airx,
+        Self::AutoglmPhone,
+        Self::Glm4_6v,
+        Self::Glm4_6vFlash,
+        Self::Glm4_6vFlashx,
+        Self::Glm4_5v,
+        Self::Glm4Voice,
+    ];
+
+    /// Get the API model identifier string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Glm5_1 => "glm-5.1",
+            Self::Glm5Turbo => "glm-5-turbo",
+            Self::Glm5 => "glm-5",
+            Self::Glm4_7 => "glm-4.7",
+            Self::Glm4_7Flash => "glm-4.7-flash",
+            Self::Glm4_7Flashx => "glm-4.7-flashx",
+            Self::Glm4_6 => "glm-4.6",
+            Self::Glm4_5 => "glm-4.5",
+            Self::Glm4_5X => "glm-4.5-X",
+            Self::Glm4_5Flash => "glm-4.5-flash",
+            Self::Glm4_5Air => "glm-4.5-air",
+            Self::Glm4_5Airx => "glm-4.5-airx",
+            Self::AutoglmPhone => "autoglm-phone",
+            Self::Glm4_6v => "glm-4.6v",
+            Self::Glm4_6vFlash => "glm-4.6v-flash",
+            Self::Glm4_6vFlashx => "glm-4.6v-flashx",
+            Self::Glm4_5v => "glm-4.5v",
+            Self::Glm4Voice => "glm-4-voice",
+        }
+    }
+
+    /// Message type accepted by this model
+    pub fn message_kind(&self) -> MessageKind {
+        match self {
+            Self::AutoglmPhone
+            | Self::Glm4_6v
+            | Self::Glm4_6vFlash
+            | Self::Glm4_6vFlashx
+            | Self::Glm4_5v => MessageKind::Vision,
+            Self::Glm4Voice => MessageKind::Voice,
+            _ => MessageKind::Text,
+        }
+    }
+
+    /// Maximum number of images in one vision message, or `None` for models
+    /// that do not accept vision messages
+    pub fn max_images(&self) -> Option<usize> {
+        match self.message_kind() {
+            MessageKind::Vision => Some(50),
+            _ => None,
+        }
+    }
+
+    /// Check if the model supports thinking mode
+    pub fn supports_thinking(&self) -> bool {
+        self.message_kind() == MessageKind::Text
+    }
+
+    /// Check if the model supports streaming tool calls
+    pub fn supports_tool_stream(&self) -> bool {
+        TOOL_STREAM_MODELS.contains(self)
+    }
+
+    /// Runtime model usable with `ChatCompletion::new`.
+    ///
+    /// The returned [`DynChatModel`] is bound to all message types, so the
+    /// model/message pairing is no longer checked at compile time; use
+    /// [`ChatModel::message_kind`] to pick the right message type.
+    pub fn as_dyn(&self) -> DynChatModel {
+        DynChatModel { model: *self }
+    }
+}
+
+impl std::fmt::Display for ChatModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ChatModel {
+    type Err = ZaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| ZaiError::ApiError {
+                code: 1211,
+                message: format!("unknown chat model: {}", s),
+            })
+    }
+}
+
+macro_rules! impl_chat_model_from {
+    ($($name:ident => $variant:ident),+ $(,)?) => {
+        $(
+            impl From<$name> for ChatModel {
+                fn from(_val: $name) -> Self { ChatModel::$variant }
+            }
+        )+
+    };
+}
+
+impl_chat_model_from!(
+    GLM5_1 => Glm5_1,
+    GLM5_turbo => Glm5Turbo,
+    GLM5 => Glm5,
+    GLM4_7 => Glm4_7,
+    GLM4_7_flash => Glm4_7Flash,
+    GLM4_7_flashx => Glm4_7Flashx,
+    GLM4_6 => Glm4_6,
+    GLM4_5 => Glm4_5,
+    GLM4_5_x => Glm4_5X,
+    GLM4_5_flash => Glm4_5Flash,
+    GLM4_5_air => Glm4_5Air,
+    GLM4_5_airx => Glm4_5Airx,
+    autoglm_phone => AutoglmPhone,
+    GLM4_6v => Glm4_6v,
+    GLM4_6v_flash => Glm4_6vFlash,
+    GLM4_6v_flashx => Glm4_6vFlashx,
+    GLM4_5v => Glm4_5v,
+    GLM4_voice => Glm4Voice,
+);
+
+/// Chat model chosen at runtime, returned by [`ChatModel::as_dyn`].
+#[derive(Debug, Clone)]
+pub struct DynChatModel {
+    model: ChatModel,
+}
+
+impl DynChatModel {
+    /// The underlying [`ChatModel`]
+    pub fn model(&self) -> ChatModel {
+        self.model
+    }
+}
+
+impl From<DynChatModel> for String {
+    fn from(val: DynChatModel) -> Self {
+        val.model.as_str().to_string()
+    }
+}
+
+impl Serialize for DynChatModel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.model.as_str())
+    }
+}
+
+impl ModelName for DynChatModel {}
+impl_message_binding!(DynChatModel, TextMessage, VisionMessage, VoiceMessage);
+impl_model_markers!(DynChatModel: Chat, AsyncChat);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_model_round_trip() {
+        for model in ChatModel::ALL {
+            let s = model.to_string();
+            let parsed: ChatModel = s.parse().unwrap();
+            assert_eq!(parsed, model);
+            assert_eq!(serde_json::to_value(model).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn test_chat_model_matches_unit_structs() {
+        assert_eq!(
+            String::from(GLM4_5_flash {}),
+            ChatModel::from(GLM4_5_flash {}).as_str()
+        );
+        assert_eq!(String::from(GLM4_5_x {}), ChatModel::Glm4_5X.to_string());
+        assert_eq!(String::from(GLM4_voice {}), ChatModel::Glm4Voice.as_str());
+    }
+
+    #[test]
+    fn test_tool_stream_models_match_markers() {
+        for (name, model) in tool_stream_model_names() {
+            assert_eq!(name, model.as_str());
+            assert!(model.supports_tool_stream());
+        }
+        assert!(!ChatModel::Glm4_7Flash.supports_tool_stream());
+        assert!(!ChatModel::Glm4_6v.supports_tool_stream());
+    }
+
+    #[test]
+    fn test_chat_model_unknown() {
+        let err = "gpt-4".parse::<ChatModel>().unwrap_err();
+        assert!(err.message().contains("gpt-4"));
+    }
+
+    #[test]
+    fn test_capabilities_of_representative_models() {
+        let text = capabilities(&ChatModel::Glm4_5Flash);
+        assert!(text.supports_tools && !text.supports_vision && !text.supports_audio);
+        assert_eq!(text.max_output_tokens, 96_000);
+
+        let vision = capabilities(&ChatModel::Glm4_6v);
+        assert!(vision.supports_vision && vision.supports_tools);
+        assert_eq!(vision.max_images, Some(50));
+        assert_eq!(text.max_images, None);
+        assert!(!capabilities(&ChatModel::Glm4_5v).supports_tools);
+
+        let voice = capabilities(&ChatModel::Glm4Voice);
+        assert!(voice.supports_audio && !voice.supports_tools && !voice.supports_vision);
+
+        for model in ChatModel::ALL {
+            let caps = capabilities(&model);
+            assert!(
+                caps.max_output_tokens < caps.max_context_tokens,
+                "{}",
+                model
+            );
+        }
+        let largest = ChatModel::ALL
+            .into_iter()
+            .map(|m| capabilities(&m).max_output_tokens)
+            .max();
+        assert_eq!(largest, Some(MAX_OUTPUT_TOKENS));
+    }
+
+    #[test]
+    fn test_dyn_chat_model_serializes_api_name() {
+        let model = ChatModel::Glm4_6v.as_dyn();
+        assert_eq!(serde_json::to_string(&model).unwrap(), "\"glm-4.6v\"");
+        assert_eq!(model.model().message_kind(), MessageKind::Vision);
+        assert_eq!(String::from(model), "glm-4.6v");
+    }
+
+    #[test]
+    fn test_dyn_chat_model_builds_chat_completion() {
+        use crate::client::http::HttpClient;
+
+        let model: ChatModel = "glm-4.5-flash".parse().unwrap();
+        let client = crate::model::chat::data::ChatCompletion::new(
+            model.as_dyn(),
+            TextMessage::user("hi"),
+            "key".to_string(),
+        );
+        let body = serde_json::to_value(client.body()).unwrap();
+        assert_eq!(body["model"], "glm-4.5-flash");
+    }
+}
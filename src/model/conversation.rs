@@ -0,0 +1,205 @@
+//! # Conversation Buffer
+//!
+//! Multi-turn history that stays within a token budget. [`ConversationBuffer`]
+//! evicts the oldest turns as new messages are pushed, using the offline
+//! estimator from [`tokens`](super::tokens).
+//!
+//! Eviction rules:
+//!
+//! - a leading system message is never evicted;
+//! - an assistant message with tool calls and the tool results that follow it
+//!   are evicted together, so a tool-call/tool-result pair is never split;
+//! - the most recent turn is always kept, even if it alone exceeds the
+//!   budget.
+//!
+//! ```rust,ignore
+//! use zai_rs::model::{conversation::ConversationBuffer, *};
+//!
+//! let mut history = ConversationBuffer::new(4096);
+//! history.push(TextMessage::system("You are a helpful assistant."));
+//! history.push(TextMessage::user("Hello"));
+//! let messages = history.to_messages();
+//! ```
+
+use super::{
+    chat_message_types::{TextMessage, TextMessages},
+    tokens::{REPLY_OVERHEAD, estimate_message},
+};
+
+/// Message history bounded by an estimated token budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversationBuffer {
+    messages: Vec<TextMessage>,
+    max_tokens: usize,
+    estimated_tokens: usize,
+}
+
+impl ConversationBuffer {
+    /// Create an empty buffer with the given prompt token budget
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            messages: Vec::new(),
+            max_tokens,
+            estimated_tokens: REPLY_OVERHEAD,
+        }
+    }
+
+    /// Append a message, evicting the oldest turns while over budget
+    pub fn push(&mut self, message: TextMessage) {
+        self.estimated_tokens += estimate_message(&message);
+        self.messages.push(message);
+        self.trim();
+    }
+
+    /// Change the budget, evicting immediately if the history no longer fits
+    pub fn set_max_tokens(&mut self, max_tokens: usize) {
+        self.max_tokens = max_tokens;
+        self.trim();
+    }
+
+    /// Token budget
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Estimated prompt tokens of the current history
+    pub fn estimated_tokens(&self) -> usize {
+        self.estimated_tokens
+    }
+
+    /// Messages currently held, oldest first
+    pub fn messages(&self) -> &[TextMessage] {
+        &self.messages
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Drop all messages except a leading system message
+    pub fn clear(&mut self) {
+        let keep = self.pinned();
+        for message in self.messages.drain(keep..) {
+            self.estimated_tokens -= estimate_message(&message);
+        }
+    }
+
+    /// Copy the history into a [`TextMessages`] collection for a request
+    pub fn to_messages(&self) -> TextMessages {
+        TextMessages {
+            messages: self.messages.clone(),
+        }
+    }
+
+    /// Number of leading messages that are never evicted
+    fn pinned(&self) -> usize {
+        usize::from(matches!(
+            self.messages.first(),
+            Some(TextMessage::System { .. })
+        ))
+    }
+
+    /// Length of the oldest evictable turn starting at `start`.
+    fn turn_len(&self, start: usize) -> usize {
+        match &self.messages[start] {
+            TextMessage::Assistant { tool_calls, .. } if !tool_calls.is_empty() => {
+                1 + self.messages[start + 1..]
+                    .iter()
+                    .take_while(|m| matches!(m, TextMessage::Tool { .. }))
+                    .count()
+            },
+            _ => 1,
+        }
+    }
+
+    fn trim(&mut self) {
+        let start = self.pinned();
+        while self.estimated_tokens > self.max_tokens && start < self.messages.len() {
+            let len = self.turn_len(start);
+            // Always keep the most recent turn.
+            if start + len >= self.messages.len() {
+                break;
+            }
+            for message in self.messages.drain(start..start + len) {
+                self.estimated_tokens -= estimate_message(&message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        chat_message_types::{FunctionParams, ToolCall},
+        tokens::{MESSAGE_OVERHEAD, estimate_messages},
+    };
+
+    /// Each of these messages estimates to 1 + MESSAGE_OVERHEAD tokens.
+    const MSG: usize = 1 + MESSAGE_OVERHEAD;
+
+    fn contents(buffer: &ConversationBuffer) -> Vec<String> {
+        buffer
+            .messages()
+            .iter()
+            .map(|m| match m {
+                TextMessage::System { content }
+                | TextMessage::User { content }
+                | TextMessage::Tool { content, .. } => content.clone(),
+                TextMessage::Assistant { content, .. } => content.clone().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evicts_oldest_first_and_keeps_system() {
+        let mut buffer = ConversationBuffer::new(REPLY_OVERHEAD + 3 * MSG);
+        buffer.push(TextMessage::system("s"));
+        for text in ["a", "b", "c", "d"] {
+            buffer.push(TextMessage::user(text));
+        }
+
+        assert_eq!(contents(&buffer), vec!["s", "c", "d"]);
+        assert_eq!(
+            buffer.estimated_tokens(),
+            estimate_messages(&buffer.to_messages())
+        );
+        assert!(buffer.estimated_tokens() <= buffer.max_tokens());
+    }
+
+    #[test]
+    fn test_never_splits_tool_call_pair() {
+        let call = TextMessage::assistant_with_tools(
+            Some("x".to_string()),
+            vec![ToolCall::new_function(
+                "call_1",
+                FunctionParams::new("f", "{}"),
+            )],
+        );
+        let mut buffer = ConversationBuffer::new(usize::MAX);
+        buffer.push(TextMessage::system("s"));
+        buffer.push(call);
+        buffer.push(TextMessage::tool_with_id("r", "call_1"));
+        buffer.push(TextMessage::user("q"));
+
+        // Evicting the assistant message must take its tool result with it.
+        buffer.set_max_tokens(REPLY_OVERHEAD + 2 * MSG);
+        assert_eq!(contents(&buffer), vec!["s", "q"]);
+    }
+
+    #[test]
+    fn test_keeps_latest_turn_over_budget() {
+        let mut buffer = ConversationBuffer::new(0);
+        buffer.push(TextMessage::user("a"));
+        buffer.push(TextMessage::user("b"));
+        assert_eq!(contents(&buffer), vec!["b"]);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.estimated_tokens(), REPLY_OVERHEAD);
+    }
+}
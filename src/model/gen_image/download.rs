@@ -0,0 +1,248 @@
+//! Saving generated images to disk.
+//!
+//! Results are either temporary URLs (fetched with an HTTP GET) or base64
+//! payloads (decoded in place, with or without a `data:` URL prefix). Files
+//! are named `image_{index}.{ext}`, the extension taken from the response
+//! content type, the URL path or the image's magic bytes, in that order.
+//!
+//! ```rust,ignore
+//! let response = ImageGenRequest::new(model, key).with_prompt("a cat").send().await?;
+//! let paths = response.download_all("./out").await?;
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use futures::{StreamExt, stream};
+
+use super::image_response::{ImageDataItem, ImageResponse};
+use crate::client::{
+    error::{ZaiError, ZaiResult},
+    http::{HttpClientConfig, http_client_with_config},
+};
+
+/// Downloads run at the same time by [`ImageResponse::download_all`].
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 4;
+
+impl ImageResponse {
+    /// Save every result image into `dir`, returning the saved paths in
+    /// result order.
+    ///
+    /// All downloads are attempted; if any fails, the others are still saved
+    /// and the error lists the failed indices. Use
+    /// [`download_each`](Self::download_each) for per-file results.
+    pub async fn download_all(&self, dir: impl AsRef<Path>) -> ZaiResult<Vec<PathBuf>> {
+        let results = self
+            .download_each(dir, DEFAULT_DOWNLOAD_CONCURRENCY)
+            .await?;
+        let mut paths = Vec::with_capacity(results.len());
+        let mut failures = Vec::new();
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(path) => paths.push(path),
+                Err(e) => failures.push(format!("#{}: {}", index, e.compact())),
+            }
+        }
+        if failures.is_empty() {
+            Ok(paths)
+        } else {
+            Err(ZaiError::FileError {
+                code: 1400,
+                message: format!(
+                    "{} of {} image downloads failed ({})",
+                    failures.len(),
+                    failures.len() + paths.len(),
+                    failures.join("; ")
+                ),
+            })
+        }
+    }
+
+    /// Save every result image into `dir`, at most `concurrency` at a time.
+    ///
+    /// Fails only if `dir` cannot be created; otherwise returns one result
+    /// per image, in result order.
+    pub async fn download_each(
+        &self,
+        dir: impl AsRef<Path>,
+        concurrency: usize,
+    ) -> ZaiResult<Vec<ZaiResult<PathBuf>>> {
+        let dir = dir.as_ref();
+        tokio::fs::create_dir_all(dir).await?;
+
+        let client = http_client_with_config(&HttpClientConfig::default());
+        let items = self.data().unwrap_or_default();
+        let results = stream::iter(items.iter().enumerate())
+            .map(|(index, item)| {
+                let client = client.clone();
+                async move {
+                    let (bytes, ext) = fetch(&client, item).await?;
+                    let path = dir.join(format!("image_{}.{}", index, ext));
+                    tokio::fs::write(&path, bytes).await?;
+                    Ok(path)
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+        Ok(results)
+    }
+}
+
+/// Image bytes and file extension for one result.
+async fn fetch(client: &reqwest::Client, item: &ImageDataItem) -> ZaiResult<(Vec<u8>, String)> {
+    if let Some(b64) = item.b64_json() {
+        return decode_base64(b64);
+    }
+    let Some(url) = item.url() else {
+        return Err(ZaiError::FileError {
+            code: 1400,
+            message: "image result has neither url nor b64_json".to_string(),
+        });
+    };
+    if url.starts_with("data:") {
+        return decode_base64(url);
+    }
+
+    let resp = client.get(url).send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(ZaiError::HttpError {
+            status: status.as_u16(),
+            message: format!("failed to download {}", url),
+        });
+    }
+    let mime = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
+    let bytes = resp.bytes().await?.to_vec();
+    let ext = mime
+        .as_deref()
+        .and_then(ext_from_mime)
+        .or_else(|| ext_from_url(url))
+        .or_else(|| ext_from_magic(&bytes))
+        .unwrap_or("png");
+    Ok((bytes, ext.to_string()))
+}
+
+fn decode_base64(data: &str) -> ZaiResult<(Vec<u8>, String)> {
+    let (mime, payload) = match data.strip_prefix("data:") {
+        Some(rest) => match rest.split_once(',') {
+            Some((meta, payload)) => (meta.split(';').next(), payload),
+            None => (None, rest),
+        },
+        None => (None, data),
+    };
+    let bytes = STANDARD
+        .decode(payload.trim())
+        .map_err(|e| ZaiError::FileError {
+            code: 1400,
+            message: format!("invalid base64 image: {}", e),
+        })?;
+    let ext = mime
+        .and_then(ext_from_mime)
+        .or_else(|| ext_from_magic(&bytes))
+        .unwrap_or("png");
+    Ok((bytes, ext.to_string()))
+}
+
+fn ext_from_mime(mime: &str) -> Option<&'static str> {
+    match mime.split(';').next()?.trim() {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/webp" => Some("webp"),
+        "image/gif" => Some("gif"),
+        _ => None,
+    }
+}
+
+fn ext_from_url(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next()?;
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("png"),
+        "jpg" | "jpeg" => Some("jpg"),
+        "webp" => Some("webp"),
+        "gif" => Some("gif"),
+        _ => None,
+    }
+}
+
+fn ext_from_magic(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::test_server::{Reply, serve};
+
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nrest";
+
+    /// Serve `/ok` with a JPEG and anything else with a 404.
+    async fn serve_images() -> String {
+        let (base, _) = serve(|req| match req.path() {
+            "/ok" => Reply::status(200).body("image/jpeg", JPEG),
+            _ => Reply::status(404),
+        })
+        .await;
+        base
+    }
+
+    #[tokio::test]
+    async fn test_download_each_reports_per_file() {
+        let base = serve_images().await;
+        let response: ImageResponse = serde_json::from_value(json!({
+            "data": [
+                {"url": format!("{}/ok", base)},
+                {"b64_json": STANDARD.encode(PNG)},
+                {"url": format!("{}/missing.png", base)},
+                {"url": format!("data:image/webp;base64,{}", STANDARD.encode(b"RIFF0000WEBP"))},
+            ]
+        }))
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!("zai-images-{}", uuid::Uuid::new_v4()));
+
+        let results = response.download_each(&dir, 2).await.unwrap();
+        assert_eq!(results.len(), 4);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first, &dir.join("image_0.jpg"));
+        assert_eq!(std::fs::read(first).unwrap(), JPEG);
+        let second = results[1].as_ref().unwrap();
+        assert_eq!(second, &dir.join("image_1.png"));
+        assert_eq!(std::fs::read(second).unwrap(), PNG);
+        assert!(matches!(
+            results[2],
+            Err(ZaiError::HttpError { status: 404, .. })
+        ));
+        assert_eq!(results[3].as_ref().unwrap(), &dir.join("image_3.webp"));
+
+        let err = response.download_all(&dir).await.unwrap_err();
+        assert!(err.to_string().contains("1 of 4"), "{}", err);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extension_inference() {
+        assert_eq!(ext_from_mime("image/jpeg; charset=binary"), Some("jpg"));
+        assert_eq!(ext_from_url("https://x/y/a.JPEG?sig=1"), Some("jpg"));
+        assert_eq!(ext_from_url("https://x/y/noext"), None);
+        assert_eq!(ext_from_magic(b"GIF89a"), Some("gif"));
+        assert!(decode_base64("not base64!").is_err());
+    }
+}
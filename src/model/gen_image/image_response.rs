@@ -38,6 +38,10 @@ pub struct ImageDataItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(url)]
     pub url: Option<String>,
+
+    /// Base64-encoded image, when the image is embedded instead of linked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b64_json: Option<String>,
 }
 
 // --- Getters ---
@@ -57,4 +61,7 @@ impl ImageDataItem {
     pub fn url(&self) -> Option<&str> {
         self.url.as_deref()
     }
+    pub fn b64_json(&self) -> Option<&str> {
+        self.b64_json.as_deref()
+    }
 }
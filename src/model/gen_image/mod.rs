@@ -1,9 +1,11 @@
 pub mod data;
+pub mod download;
 pub mod image_model;
 pub mod image_request;
 pub mod image_response;
 
 pub use data::*;
+pub use download::*;
 pub use image_model::*;
 pub use image_request::*;
 pub use image_response::*;
@@ -1,3 +1,10 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt, channel::mpsc, stream};
 use serde::Serialize;
 use validator::Validate;
 
@@ -5,7 +12,32 @@ use super::{
     super::traits::*,
     video_request::{Fps, ImageUrl, VideoBody, VideoDuration, VideoQuality, VideoSize},
 };
-use crate::client::http::HttpClient;
+use crate::{
+    ZaiError, ZaiResult,
+    client::http::HttpClient,
+    model::{
+        async_chat_get::data::{AsyncChatGetRequest, wait_for_task},
+        chat_base_response::{ChatCompletionResponse, TaskStatus},
+    },
+    poll::PollConfig,
+};
+
+/// An update from [`VideoGenRequest::send_stream`].
+///
+/// The API reports no percentage or stage for video tasks, only their
+/// status, so progress is a status snapshot per poll.
+#[derive(Debug, Clone)]
+pub enum VideoProgress {
+    /// The task was accepted; first item of the stream
+    Submitted { task_id: String },
+    /// The task is still running
+    Status {
+        status: TaskStatus,
+        elapsed: Duration,
+    },
+    /// The task succeeded; last item of the stream, carrying `video_result`
+    Completed(Box<ChatCompletionResponse>),
+}
 
 /// Video generation request structure
 /// Handles HTTP requests for video generation API
@@ -106,6 +138,98 @@ where
             .map_err(crate::client::error::ZaiError::from)?;
         Ok(())
     }
+
+    /// Submit the task; the response `id` is the task id to poll
+    pub async fn send(&self) -> ZaiResult<ChatCompletionResponse> {
+        self.validate()?;
+        let resp = self.post().await?;
+        Ok(resp.json::<ChatCompletionResponse>().await?)
+    }
+
+    /// Submit the task and stream its progress until the video is ready.
+    ///
+    /// The API has no server-sent progress channel for video generation, so
+    /// the stream is backed by polling the async-result endpoint with
+    /// `config` (interval, backoff, timeout, transient retries). It yields
+    /// [`VideoProgress::Submitted`], a [`VideoProgress::Status`] per poll
+    /// while the task runs, then [`VideoProgress::Completed`]. A failed task
+    /// or a timeout is yielded as an error and ends the stream.
+    ///
+    /// ```rust,ignore
+    /// let mut progress = VideoGenRequest::new(CogVideoX3 {}, key)
+    ///     .with_prompt("A cat playing with a ball")
+    ///     .send_stream(PollConfig::new(Duration::from_secs(5), Duration::from_secs(600)));
+    /// while let Some(update) = progress.next().await {
+    ///     if let VideoProgress::Completed(resp) = update? {
+    ///         println!("{:?}", resp.video_result());
+    ///     }
+    /// }
+    /// ```
+    pub fn send_stream(
+        self,
+        config: PollConfig,
+    ) -> impl Stream<Item = ZaiResult<VideoProgress>> + Send
+    where
+        N: AsyncChat + Clone + Send + Sync + 'static,
+    {
+        let model = self.body.model.clone();
+        let key = self.key.clone();
+        stream::once(async move {
+            let submitted = self.send().await?;
+            submitted.id.ok_or_else(|| ZaiError::ApiError {
+                code: 0,
+                message: "video generation response has no task id".to_string(),
+            })
+        })
+        .flat_map(move |task_id| match task_id {
+            Ok(task_id) => {
+                let get = Arc::new(AsyncChatGetRequest::new(
+                    model.clone(),
+                    task_id.clone(),
+                    key.clone(),
+                ));
+                let fetch = move || {
+                    let get = get.clone();
+                    async move { get.send().await }
+                };
+                stream::iter([Ok(VideoProgress::Submitted {
+                    task_id: task_id.clone(),
+                })])
+                .chain(progress_stream(task_id, config.clone(), fetch))
+                .left_stream()
+            },
+            Err(e) => stream::iter([Err(e)]).right_stream(),
+        })
+    }
+}
+
+/// A status snapshot per `PROCESSING` poll of task `task_id`, then the
+/// outcome of [`wait_for_task`].
+fn progress_stream<F, Fut>(
+    task_id: String,
+    config: PollConfig,
+    fetch: F,
+) -> impl Stream<Item = ZaiResult<VideoProgress>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ZaiResult<ChatCompletionResponse>>,
+{
+    // Updates and the outcome share one channel so they arrive in order;
+    // the wait itself is driven alongside it.
+    let (tx, rx) = mpsc::unbounded();
+    let start = Instant::now();
+    let wait = async move {
+        let on_processing = |_: &ChatCompletionResponse| {
+            let _ = tx.unbounded_send(Ok(VideoProgress::Status {
+                status: TaskStatus::Processing,
+                elapsed: start.elapsed(),
+            }));
+        };
+        let outcome = wait_for_task(&task_id, config, fetch, on_processing).await;
+        let _ = tx.unbounded_send(outcome.map(|resp| VideoProgress::Completed(Box::new(resp))));
+    };
+    stream::select(rx.map(Some), stream::once(wait).map(|()| None))
+        .filter_map(|item| async move { item })
 }
 
 impl<N> HttpClient for VideoGenRequest<N>
@@ -133,3 +257,84 @@ where
         &self.body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn task(status: &str) -> ZaiResult<ChatCompletionResponse> {
+        Ok(serde_json::from_value(json!({
+            "id": "task-1",
+            "task_status": status,
+            "video_result": [{"url": "https://example.com/v.mp4"}]
+        }))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_progress_stream_until_completed() {
+        let calls = AtomicUsize::new(0);
+        let config = PollConfig::new(Duration::from_millis(1), Duration::from_secs(5))
+            .with_transient_retries(1);
+        let updates: Vec<_> = progress_stream("task-1".to_string(), config, || {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match n {
+                    0 | 2 => task("PROCESSING"),
+                    // A transient failure is retried without an update.
+                    1 => Err(ZaiError::HttpError {
+                        status: 502,
+                        message: "bad gateway".to_string(),
+                    }),
+                    _ => task("SUCCESS"),
+                }
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(updates.len(), 3, "{:?}", updates);
+        for update in &updates[..2] {
+            assert!(matches!(
+                update,
+                Ok(VideoProgress::Status {
+                    status: TaskStatus::Processing,
+                    ..
+                })
+            ));
+        }
+        let Ok(VideoProgress::Completed(resp)) = &updates[2] else {
+            panic!("expected completion, got {:?}", updates[2]);
+        };
+        assert_eq!(resp.video_result().unwrap().len(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_progress_stream_failure_and_timeout() {
+        let config = PollConfig::new(Duration::from_millis(1), Duration::from_secs(5));
+        let updates: Vec<_> =
+            progress_stream("task-1".to_string(), config, || async { task("FAIL") })
+                .collect()
+                .await;
+        assert_eq!(updates.len(), 1);
+        let err = updates[0].as_ref().unwrap_err();
+        assert!(err.to_string().contains("'task-1' failed"), "{}", err);
+
+        let config = PollConfig::new(Duration::from_millis(2), Duration::from_millis(5));
+        let updates: Vec<_> = progress_stream("task-1".to_string(), config, || async {
+            task("PROCESSING")
+        })
+        .collect()
+        .await;
+        assert!(updates.len() >= 2);
+        assert!(matches!(
+            updates.last().unwrap(),
+            Err(ZaiError::HttpError { status: 408, .. })
+        ));
+    }
+}
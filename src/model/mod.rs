@@ -15,12 +15,14 @@
 //! - [`chat_base_request`] — Shared request body (`ChatBody`)
 //! - [`chat_base_response`] — Shared response structures
 //! - [`chat_stream_response`] — Streaming response deserialization
+//! - [`conversation`] — Message history trimmed to a token budget
 //!
 //! ## Multimodal AI
 //!
 //! - [`gen_image`] — Image generation
 //! - [`gen_video_async`] — Async video generation
 //! - [`audio_to_text`] — Speech recognition (ASR)
+//! - [`audio_duration`] — Voice clip duration checks (10-minute limit)
 //! - [`text_to_audio`] — Text-to-speech synthesis (TTS)
 //! - [`ocr`] — Optical character recognition
 //!
@@ -30,6 +32,8 @@
 //! - [`text_rerank`] — Re-ranking
 //! - [`text_tokenizer`] — Tokenization
 //! - [`moderation`] — Content moderation / safety analysis
+//! - [`tokens`] — Offline token-count estimation for chat messages
+//! - [`usage`] — Token usage totals and cost estimates across requests
 //!
 //! ## Voice Management
 //!
@@ -70,6 +74,7 @@
 
 pub mod async_chat;
 pub mod async_chat_get;
+pub mod audio_duration;
 pub mod audio_to_text;
 pub mod chat;
 pub mod chat_base_request;
@@ -77,6 +82,7 @@ pub mod chat_base_response;
 pub mod chat_message_types;
 pub mod chat_models;
 pub mod chat_stream_response;
+pub mod conversation;
 pub mod gen_image;
 pub mod gen_video_async;
 pub mod model_validate;
@@ -88,8 +94,10 @@ pub mod text_embedded;
 pub mod text_rerank;
 pub mod text_to_audio;
 pub mod text_tokenizer;
+pub mod tokens;
 pub mod tools;
 pub mod traits;
+pub mod usage;
 pub mod voice_clone;
 pub mod voice_delete;
 pub mod voice_list;
@@ -99,13 +107,13 @@ pub mod voice_list;
 // Selective type re-exports for convenience
 pub use async_chat::data::AsyncChatCompletion;
 pub use async_chat_get::data::AsyncChatGetRequest;
-pub use chat::data::ChatCompletion;
+pub use chat::data::{ChatCompletion, ChatRequestTemplate};
 pub use chat_base_response::TaskStatus;
 pub use chat_message_types::*;
 pub use chat_models::*;
 pub use chat_stream_response::ChatStreamResponse;
 pub use gen_video_async::*;
 pub use moderation::data::Moderation;
-pub use stream_ext::StreamChatLikeExt;
+pub use stream_ext::{StreamAggregator, StreamChatLikeExt, StreamItem};
 pub use tools::*;
 pub use traits::SseStreamable;
@@ -10,6 +10,7 @@
 //! - [`validate_json_schema`] - Validates JSON Schema from string input
 //! - [`validate_json_schema_value`] - Validates JSON Schema from parsed JSON
 //!   value
+//! - [`validate_unit_interval`] - Validates a sampling parameter in `[0, 1]`
 //!
 //! ## Error Handling
 //!
@@ -143,6 +144,30 @@ pub fn validate_json_schema_value(parameters: &serde_json::Value) -> Result<(),
     Ok(())
 }
 
+/// Validates a [`ResponseFormat`](super::tools::ResponseFormat): a
+/// `JsonSchema` format must carry an object schema.
+pub fn validate_response_format(
+    format: &super::tools::ResponseFormat,
+) -> Result<(), ValidationError> {
+    match format {
+        super::tools::ResponseFormat::JsonSchema { schema, .. } if !schema.is_object() => {
+            Err(ValidationError::new("response_format_schema_not_object"))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Validates a sampling parameter (`temperature`, `top_p`): it must be a
+/// number in `[0, 1]`. Unlike a plain range check this also rejects NaN,
+/// which would otherwise be sent as `null`.
+pub fn validate_unit_interval(value: f32) -> Result<(), ValidationError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("range").with_message("must be a number between 0 and 1".into()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,8 +3,15 @@
 //! This module provides the content moderation client for analyzing text,
 //! image, audio, and video content for safety risks.
 
+use std::sync::Arc;
+
 use super::models::*;
-use crate::client::http::HttpClient;
+use crate::client::{
+    auth::Auth,
+    endpoint::Endpoint,
+    http::{CustomHeaders, HttpClient},
+    transport::Transport,
+};
 
 /// Content moderation client.
 ///
@@ -23,6 +30,13 @@ pub struct Moderation {
     pub key: String,
     /// Moderation request body
     body: ModerationRequest,
+    /// Base URL override; see [`with_endpoint`](Self::with_endpoint).
+    endpoint: Option<Endpoint>,
+    /// Connection settings taken over from another request; see
+    /// [`routed_like`](Self::routed_like).
+    auth: Option<Auth>,
+    transport: Option<Arc<dyn Transport>>,
+    headers: Option<CustomHeaders>,
 }
 
 impl Moderation {
@@ -38,7 +52,7 @@ impl Moderation {
     /// A new `Moderation` instance configured for text moderation.
     pub fn new_text(text: impl Into<String>, key: String) -> Self {
         let body = ModerationRequest::new_text(text);
-        Self { body, key }
+        Self::from_request(body, key)
     }
 
     /// Creates a new moderation request with multimedia content.
@@ -54,7 +68,35 @@ impl Moderation {
     /// A new `Moderation` instance configured for multimedia moderation.
     pub fn new_multimedia(content_type: MediaType, url: impl Into<String>, key: String) -> Self {
         let body = ModerationRequest::new_multimedia(content_type, url);
-        Self { body, key }
+        Self::from_request(body, key)
+    }
+
+    /// Creates a moderation request from a prepared request body.
+    pub fn from_request(body: ModerationRequest, key: String) -> Self {
+        Self {
+            key,
+            body,
+            endpoint: None,
+            auth: None,
+            transport: None,
+            headers: None,
+        }
+    }
+
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Send this request the way `request` is sent: same endpoint, auth,
+    /// transport and custom headers.
+    pub(crate) fn routed_like<C: HttpClient>(mut self, request: &C) -> Self {
+        self.endpoint = request.endpoint().cloned();
+        self.auth = request.auth().cloned();
+        self.transport = request.transport();
+        self.headers = request.custom_headers().cloned();
+        self
     }
 
     /// Gets mutable access to the request body for further customization.
@@ -105,4 +147,20 @@ impl HttpClient for Moderation {
     fn body(&self) -> &Self::Body {
         &self.body
     }
+
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+
+    fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+
+    fn transport(&self) -> Option<Arc<dyn Transport>> {
+        self.transport.clone()
+    }
+
+    fn custom_headers(&self) -> Option<&CustomHeaders> {
+        self.headers.as_ref()
+    }
 }
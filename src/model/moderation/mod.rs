@@ -27,11 +27,17 @@
 //!     api_key
 //! );
 //! let result = moderation.send().await?;
+//!
+//! // Check user input before every chat completion
+//! let chat = ChatCompletion::new(model, messages, api_key.clone())
+//!     .with_input_moderation(api_key);
 //! ```
 
 pub mod data;
 pub mod models;
+pub mod precheck;
 
 // Re-export main types for convenience
 pub use data::Moderation;
 pub use models::*;
+pub use precheck::InputModeration;
@@ -135,7 +135,7 @@ impl ModerationRequest {
 }
 
 /// Risk level for moderated content.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RiskLevel {
     /// Normal content, no risks detected
     #[serde(rename = "PASS")]
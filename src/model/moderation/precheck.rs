@@ -0,0 +1,224 @@
+//! Moderation of chat input before a completion is requested.
+//!
+//! [`InputModeration`] extracts the content of selected message roles from a
+//! chat request body and runs it through the moderation API:
+//!
+//! - text is checked with [`Moderation::new_text`], split into pieces of at
+//!   most [`MAX_TEXT_BYTES`];
+//! - `image_url` / `video_url` parts with an `http(s)` URL are checked with
+//!   [`Moderation::new_multimedia`];
+//! - inline data (`data:` URLs, base64 audio) and file parts are skipped, as
+//!   the moderation API only accepts URLs.
+//!
+//! Attach it with `ChatCompletion::with_input_moderation`; every completion
+//! request, streaming or not, then fails with [`ZaiError::ModerationBlocked`]
+//! when any item reaches the threshold. The moderation requests go through
+//! the chat's endpoint, auth, transport and custom headers.
+
+use std::future::Future;
+
+use futures::future::try_join_all;
+use serde_json::Value;
+
+use super::{data::Moderation, models::*};
+use crate::{ZaiError, ZaiResult, client::http::HttpClient};
+
+/// Maximum text length accepted by a single moderation request.
+pub const MAX_TEXT_BYTES: usize = 2000;
+
+/// Pre-send moderation settings for chat input.
+#[derive(Debug, Clone)]
+pub struct InputModeration {
+    key: String,
+    threshold: RiskLevel,
+    roles: Vec<String>,
+}
+
+impl InputModeration {
+    /// Check `user` messages and block on [`RiskLevel::Reject`]
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            threshold: RiskLevel::Reject,
+            roles: vec!["user".to_string()],
+        }
+    }
+
+    /// Lowest risk level that blocks the request (e.g. `Review` to also
+    /// block suspicious content)
+    pub fn with_threshold(mut self, threshold: RiskLevel) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Message roles whose content is checked (`"user"`, `"system"`, ...)
+    pub fn with_roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn threshold(&self) -> RiskLevel {
+        self.threshold
+    }
+
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// Moderation requests for the checked messages of a serialized chat
+    /// body (`{"messages": [...]}`)
+    pub fn requests(&self, body: &Value) -> Vec<ModerationRequest> {
+        let mut requests = Vec::new();
+        let messages = body["messages"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for message in messages {
+            let role = message["role"].as_str().unwrap_or_default();
+            if !self.roles.iter().any(|r| r == role) {
+                continue;
+            }
+            match &message["content"] {
+                Value::String(text) => push_text(&mut requests, text),
+                Value::Array(parts) => {
+                    for part in parts {
+                        push_part(&mut requests, part);
+                    }
+                },
+                _ => {},
+            }
+        }
+        requests
+    }
+
+    /// Moderate the messages of `request`'s body, failing with
+    /// [`ZaiError::ModerationBlocked`] if any item reaches the threshold.
+    ///
+    /// The moderation requests are sent like `request` itself: same
+    /// endpoint, auth, transport and custom headers.
+    pub fn check<C: HttpClient>(
+        &self,
+        request: &C,
+    ) -> impl Future<Output = ZaiResult<()>> + Send + 'static {
+        let moderations = serde_json::to_value(request.body()).map(|body| {
+            self.requests(&body)
+                .into_iter()
+                .map(|r| Moderation::from_request(r, self.key.clone()).routed_like(request))
+                .collect::<Vec<_>>()
+        });
+        let threshold = self.threshold;
+
+        async move {
+            let checks = moderations?
+                .into_iter()
+                .map(|moderation| async move { moderation.send().await });
+            let responses = try_join_all(checks).await?;
+
+            let details: Vec<ModerationResult> = responses
+                .into_iter()
+                .flat_map(|r| r.result_list.unwrap_or_default())
+                .filter(|r| r.risk_level >= threshold)
+                .collect();
+            if details.is_empty() {
+                Ok(())
+            } else {
+                Err(ZaiError::ModerationBlocked { details })
+            }
+        }
+    }
+}
+
+fn push_text(requests: &mut Vec<ModerationRequest>, text: &str) {
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let mut end = rest.len().min(MAX_TEXT_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        requests.push(ModerationRequest::new_text(&rest[..end]));
+        rest = &rest[end..];
+    }
+}
+
+fn push_part(requests: &mut Vec<ModerationRequest>, part: &Value) {
+    let media = match part["type"].as_str() {
+        Some("text") => {
+            push_text(requests, part["text"].as_str().unwrap_or_default());
+            return;
+        },
+        Some("image_url") => (MediaType::Image, &part["image_url"]["url"]),
+        Some("video_url") => (MediaType::Video, &part["video_url"]["url"]),
+        _ => return,
+    };
+    if let (media_type, Value::String(url)) = media
+        && (url.starts_with("http://") || url.starts_with("https://"))
+    {
+        requests.push(ModerationRequest::new_multimedia(media_type, url));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn texts(requests: &[ModerationRequest]) -> Vec<String> {
+        requests
+            .iter()
+            .map(|r| match &r.input {
+                ModerationInput::Text(t) => t.clone(),
+                ModerationInput::Multimedia(m) => format!("{:?}:{}", m.content_type, m.url),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_requests_select_roles_and_media() {
+        let body = json!({"messages": [
+            {"role": "system", "content": "be nice"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "look"},
+                {"type": "image_url", "image_url": {"url": "https://x/a.png"}},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}},
+                {"type": "video_url", "video_url": {"url": "https://x/v.mp4"}}
+            ]},
+            {"role": "assistant", "content": "ok"}
+        ]});
+
+        let check = InputModeration::new("key");
+        assert_eq!(
+            texts(&check.requests(&body)),
+            vec!["look", "Image:https://x/a.png", "Video:https://x/v.mp4"]
+        );
+
+        let check = check.with_roles(["system", "assistant"]);
+        assert_eq!(texts(&check.requests(&body)), vec!["be nice", "ok"]);
+    }
+
+    #[test]
+    fn test_long_text_is_split() {
+        let text = "审".repeat(1000); // 3000 bytes
+        let body = json!({"messages": [{"role": "user", "content": text}]});
+        let requests = InputModeration::new("key").requests(&body);
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.validate().is_ok()));
+        assert_eq!(texts(&requests).concat(), text);
+    }
+
+    #[test]
+    fn test_risk_level_order() {
+        assert!(RiskLevel::Reject > RiskLevel::Review);
+        assert!(RiskLevel::Review > RiskLevel::Pass);
+        assert_eq!(
+            InputModeration::new("k")
+                .with_threshold(RiskLevel::Review)
+                .threshold(),
+            RiskLevel::Review
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Markdown rendering of OCR results.
+//!
+//! The OCR endpoint returns recognized lines with bounding boxes but no block
+//! types, so layout is reconstructed from geometry:
+//!
+//! - lines are grouped into rows by vertical overlap and sorted top-to-bottom,
+//!   left-to-right (reading order);
+//! - a single-line row noticeably taller than the median line height becomes a
+//!   heading;
+//! - two or more consecutive rows with the same number (≥ 2) of cells become a
+//!   table, the first row being the header;
+//! - everything else becomes paragraphs, split where the vertical gap exceeds
+//!   one line height.
+//!
+//! Lines without a location are emitted as paragraph lines in response order.
+
+use super::response::{OcrResponse, WordsResultItem};
+
+/// Options for [`OcrResponse::to_markdown_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrMarkdownOptions {
+    /// Drop lines whose average confidence is below this value. Lines without
+    /// confidence information are always kept.
+    pub min_confidence: Option<f32>,
+
+    /// Render tall single-line rows as headings
+    pub detect_headings: bool,
+
+    /// Render aligned multi-cell rows as tables
+    pub detect_tables: bool,
+
+    /// Height relative to the median line height from which a row counts as
+    /// a heading
+    pub heading_ratio: f32,
+}
+
+impl Default for OcrMarkdownOptions {
+    fn default() -> Self {
+        Self {
+            min_confidence: None,
+            detect_headings: true,
+            detect_tables: true,
+            heading_ratio: 1.5,
+        }
+    }
+}
+
+impl OcrMarkdownOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    pub fn with_headings(mut self, enable: bool) -> Self {
+        self.detect_headings = enable;
+        self
+    }
+
+    pub fn with_tables(mut self, enable: bool) -> Self {
+        self.detect_tables = enable;
+        self
+    }
+
+    pub fn with_heading_ratio(mut self, ratio: f32) -> Self {
+        self.heading_ratio = ratio;
+        self
+    }
+}
+
+/// A recognized line with resolved geometry.
+struct Line {
+    text: String,
+    left: i32,
+    top: i32,
+    height: i32,
+}
+
+impl Line {
+    fn bottom(&self) -> i32 {
+        self.top + self.height
+    }
+
+    fn center(&self) -> i32 {
+        self.top + self.height / 2
+    }
+}
+
+/// A row of lines sharing the same vertical band, ordered left-to-right.
+struct Row {
+    cells: Vec<Line>,
+}
+
+impl Row {
+    fn top(&self) -> i32 {
+        self.cells.iter().map(|c| c.top).min().unwrap_or(0)
+    }
+
+    fn bottom(&self) -> i32 {
+        self.cells.iter().map(Line::bottom).max().unwrap_or(0)
+    }
+
+    fn height(&self) -> i32 {
+        self.cells.iter().map(|c| c.height).max().unwrap_or(0)
+    }
+
+    fn text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|c| c.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+enum Block {
+    Heading(String),
+    Paragraph(Vec<String>),
+    Table(Vec<Vec<String>>),
+}
+
+impl OcrResponse {
+    /// Render the recognized text as markdown using default options.
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with(&OcrMarkdownOptions::default())
+    }
+
+    /// Render the recognized text as markdown.
+    pub fn to_markdown_with(&self, options: &OcrMarkdownOptions) -> String {
+        let items: Vec<&WordsResultItem> = self
+            .words_result
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|item| keep_item(item, options.min_confidence))
+            .collect();
+
+        let positioned = items.iter().all(|item| {
+            item.location
+                .as_ref()
+                .is_some_and(|l| l.top.is_some() && l.height.is_some())
+        });
+        if !positioned {
+            let lines: Vec<String> = items.iter().filter_map(|item| item_text(item)).collect();
+            return render(&[Block::Paragraph(lines)]);
+        }
+
+        let mut lines: Vec<Line> = items
+            .iter()
+            .filter_map(|item| {
+                let loc = item.location.as_ref()?;
+                Some(Line {
+                    text: item_text(item)?,
+                    left: loc.left.unwrap_or(0),
+                    top: loc.top?,
+                    height: loc.height?.max(1),
+                })
+            })
+            .collect();
+        lines.sort_by_key(|l| (l.top, l.left));
+
+        let rows = group_rows(lines);
+        let median = median_height(&rows);
+        render(&layout(rows, median, options))
+    }
+}
+
+fn keep_item(item: &WordsResultItem, min_confidence: Option<f32>) -> bool {
+    if item_text(item).is_none() {
+        return false;
+    }
+    match (
+        min_confidence,
+        item.probability.as_ref().and_then(|p| p.average),
+    ) {
+        (Some(min), Some(avg)) => avg >= min,
+        _ => true,
+    }
+}
+
+fn item_text(item: &WordsResultItem) -> Option<String> {
+    let text = item.words.as_deref()?.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Group lines (sorted by top) into rows by vertical center overlap.
+fn group_rows(lines: Vec<Line>) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+    for line in lines {
+        match rows.last_mut() {
+            Some(row) if line.center() >= row.top() && line.center() <= row.bottom() => {
+                row.cells.push(line)
+            },
+            _ => rows.push(Row { cells: vec![line] }),
+        }
+    }
+    for row in &mut rows {
+        row.cells.sort_by_key(|c| c.left);
+    }
+    rows
+}
+
+fn median_height(rows: &[Row]) -> i32 {
+    let mut heights: Vec<i32> = rows
+        .iter()
+        .flat_map(|r| r.cells.iter().map(|c| c.height))
+        .collect();
+    if heights.is_empty() {
+        return 1;
+    }
+    heights.sort_unstable();
+    heights[heights.len() / 2]
+}
+
+fn layout(rows: Vec<Row>, median: i32, options: &OcrMarkdownOptions) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut prev_bottom: Option<i32> = None;
+    let mut i = 0;
+
+    while i < rows.len() {
+        let row = &rows[i];
+        let cols = row.cells.len();
+
+        if options.detect_tables && cols >= 2 {
+            let end = rows[i..]
+                .iter()
+                .position(|r| r.cells.len() != cols)
+                .map_or(rows.len(), |p| i + p);
+            if end - i >= 2 {
+                let table = rows[i..end]
+                    .iter()
+                    .map(|r| r.cells.iter().map(|c| c.text.clone()).collect())
+                    .collect();
+                blocks.push(Block::Table(table));
+                prev_bottom = Some(rows[end - 1].bottom());
+                i = end;
+                continue;
+            }
+        }
+
+        let is_heading = options.detect_headings
+            && cols == 1
+            && row.height() as f32 >= median as f32 * options.heading_ratio;
+
+        if is_heading {
+            blocks.push(Block::Heading(row.text()));
+        } else {
+            let gap = prev_bottom.map(|b| row.top() - b);
+            match blocks.last_mut() {
+                Some(Block::Paragraph(lines)) if gap.is_none_or(|g| g <= median) => {
+                    lines.push(row.text())
+                },
+                _ => blocks.push(Block::Paragraph(vec![row.text()])),
+            }
+        }
+
+        prev_bottom = Some(row.bottom());
+        i += 1;
+    }
+
+    blocks
+}
+
+fn render(blocks: &[Block]) -> String {
+    let mut out: Vec<String> = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Heading(text) => out.push(format!("## {}", text)),
+            Block::Paragraph(lines) if lines.is_empty() => {},
+            Block::Paragraph(lines) => out.push(lines.join("\n")),
+            Block::Table(rows) => {
+                let fmt_row = |cells: &Vec<String>| {
+                    let cells: Vec<String> = cells.iter().map(|c| c.replace('|', "\\|")).collect();
+                    format!("| {} |", cells.join(" | "))
+                };
+                let mut table = vec![fmt_row(&rows[0])];
+                table.push(format!("|{}", " --- |".repeat(rows[0].len())));
+                table.extend(rows[1..].iter().map(fmt_row));
+                out.push(table.join("\n"));
+            },
+        }
+    }
+    out.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn item(text: &str, left: i32, top: i32, height: i32, avg: f32) -> serde_json::Value {
+        json!({
+            "words": text,
+            "location": {"left": left, "top": top, "width": 100, "height": height},
+            "probability": {"average": avg, "variance": 0.0, "min": avg}
+        })
+    }
+
+    fn synthetic() -> OcrResponse {
+        // Deliberately shuffled to check reading order.
+        serde_json::from_value(json!({
+            "words_result": [
+                item("second line", 10, 90, 20, 0.95),
+                item("Report Title", 10, 10, 40, 0.99),
+                item("Name", 10, 160, 20, 0.9),
+                item("Score", 200, 160, 20, 0.9),
+                item("first line", 10, 65, 20, 0.98),
+                item("Alice", 10, 185, 20, 0.9),
+                item("90", 200, 186, 20, 0.9),
+                item("noise", 10, 260, 20, 0.2),
+                item("Closing remark", 10, 290, 20, 0.97),
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_markdown_layout() {
+        let md = synthetic().to_markdown();
+        assert_eq!(
+            md,
+            "## Report Title\n\n\
+             first line\nsecond line\n\n\
+             | Name | Score |\n| --- | --- |\n| Alice | 90 |\n\n\
+             noise\nClosing remark"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_min_confidence() {
+        let md = synthetic().to_markdown_with(&OcrMarkdownOptions::new().with_min_confidence(0.5));
+        assert!(!md.contains("noise"));
+        assert!(md.ends_with("| Alice | 90 |\n\nClosing remark"));
+    }
+
+    #[test]
+    fn test_to_markdown_plain_fallback() {
+        let resp: OcrResponse = serde_json::from_value(json!({
+            "words_result": [{"words": "b"}, {"words": "  "}, {"words": "a"}]
+        }))
+        .unwrap();
+        assert_eq!(resp.to_markdown(), "b\na");
+
+        let md = synthetic().to_markdown_with(
+            &OcrMarkdownOptions::new()
+                .with_headings(false)
+                .with_tables(false),
+        );
+        assert!(!md.contains("##"));
+        assert!(md.contains("Name Score\nAlice 90"));
+    }
+
+    #[test]
+    fn test_to_markdown_empty() {
+        let resp: OcrResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(resp.to_markdown(), "");
+    }
+}
@@ -1,8 +1,10 @@
 pub mod data;
+pub mod markdown;
 pub mod model;
 pub mod request;
 pub mod response;
 
 pub use data::*;
+pub use markdown::OcrMarkdownOptions;
 pub use request::*;
 pub use response::*;
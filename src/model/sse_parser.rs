@@ -2,6 +2,10 @@
 //!
 //! Extracts the common logic of buffering raw byte chunks, splitting on `\n`,
 //! trimming `\r\n`, and yielding `data: ` prefixed payload lines.
+//!
+//! This is a line-level helper; the built-in streaming APIs use the
+//! spec-compliant [`SseDecoder`](crate::client::sse::SseDecoder), which also
+//! joins multi-line `data:` fields.
 
 /// Process a new chunk of bytes, extract completed SSE data lines.
 ///
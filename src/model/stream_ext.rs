@@ -9,7 +9,8 @@
 //!   chunks
 //! - **Stream-based API** - Composable, testable, and reusable stream interface
 //! - **Type-safe parsing** - Automatic deserialization of SSE data chunks
-//! - **Error handling** - Comprehensive error propagation and handling
+//! - **Error handling** - Comprehensive error propagation and handling; an
+//!   `event: error` server-sent event ends the stream with a typed API error
 //!
 //! ## Usage Patterns
 //!
@@ -31,15 +32,38 @@
 //!     }
 //! }
 //! ```
+//!
+//! ### Text and Complete Tool Calls
+//! ```rust,ignore
+//! let mut items = StreamAggregator::aggregate(client.to_stream().await?);
+//! while let Some(item) = items.next().await {
+//!     match item? {
+//!         StreamItem::ReasoningDelta(text) => eprint!("{}", text),
+//!         StreamItem::TextDelta(text) => print!("{}", text),
+//!         StreamItem::ToolCall(call) => run_tool(call).await?,
+//!         StreamItem::Done => break,
+//!     }
+//! }
+//! ```
 
-use std::{collections::VecDeque, pin::Pin};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    pin::Pin,
+};
 
 use futures::{Stream, StreamExt, stream};
-use tracing::info;
 
 use crate::{
-    client::http::HttpClient,
-    model::{chat_stream_response::ChatStreamResponse, traits::SseStreamable},
+    client::{
+        error::ZaiError,
+        http::HttpClient,
+        sse::{SseDecoder, SseEvent},
+    },
+    model::{
+        chat_base_response::{ToolCallMessage, ToolFunction},
+        chat_stream_response::ChatStreamResponse,
+        traits::SseStreamable,
+    },
 };
 
 /// Streaming extension trait for chat-like endpoints.
@@ -88,7 +112,7 @@ pub trait StreamChatLikeExt: SseStreamable + HttpClient {
         async move {
             let resp = self.post().await?;
             let mut stream = resp.bytes_stream();
-            let mut buf: Vec<u8> = Vec::new();
+            let mut decoder = SseDecoder::new();
 
             while let Some(next) = stream.next().await {
                 let bytes = match next {
@@ -99,16 +123,21 @@ pub trait StreamChatLikeExt: SseStreamable + HttpClient {
                         ));
                     },
                 };
-                let lines = crate::model::sse_parser::extract_sse_data_lines(&mut buf, &bytes);
-                for rest in lines {
-                    info!("SSE data: {}", String::from_utf8_lossy(&rest));
-                    if rest == b"[DONE]" {
-                        return Ok(());
-                    }
-                    if let Ok(chunk) = serde_json::from_slice::<ChatStreamResponse>(&rest) {
-                        on_chunk(chunk).await?;
+                for event in decoder.push_events(&bytes) {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!("SSE data: {}", event.data);
+                    if let Some(chunk) = decode_event(event) {
+                        on_chunk(chunk?).await?;
                     }
                 }
+                if decoder.is_done() {
+                    return Ok(());
+                }
+            }
+            for event in decoder.finish_events() {
+                if let Some(chunk) = decode_event(event) {
+                    on_chunk(chunk?).await?;
+                }
             }
             Ok(())
         }
@@ -118,7 +147,8 @@ pub trait StreamChatLikeExt: SseStreamable + HttpClient {
     ///
     /// This method returns a `Stream` that yields `ChatStreamResponse` chunks,
     /// enabling advanced stream processing operations like filtering, mapping,
-    /// and combination with other streams.
+    /// and combination with other streams. An `event: error` event is
+    /// yielded as an error and ends the stream.
     ///
     /// ## Returns
     ///
@@ -148,49 +178,50 @@ pub trait StreamChatLikeExt: SseStreamable + HttpClient {
             let s = byte_stream;
 
             let out = stream::unfold(
-                (s, Vec::<u8>::new(), VecDeque::<ChatStreamResponse>::new()),
-                |(mut s, mut buf, mut pending)| async move {
-                    if let Some(item) = pending.pop_front() {
-                        return Some((Ok(item), (s, buf, pending)));
-                    }
-
+                (
+                    s,
+                    SseDecoder::new(),
+                    VecDeque::<crate::ZaiResult<ChatStreamResponse>>::new(),
+                    false,
+                ),
+                |(mut s, mut decoder, mut pending, mut failed)| async move {
                     loop {
+                        if let Some(item) = pending.pop_front() {
+                            if item.is_err() {
+                                // Nothing after a server error is delivered.
+                                pending.clear();
+                                failed = true;
+                            }
+                            return Some((item, (s, decoder, pending, failed)));
+                        }
+                        if failed || decoder.is_done() {
+                            return None; // end stream gracefully after [DONE]
+                        }
+
                         // Need more bytes first to populate buffer
-                        match s.next().await {
-                            Some(Ok(bytes)) => {
-                                let lines = crate::model::sse_parser::extract_sse_data_lines(
-                                    &mut buf, &bytes,
-                                );
-                                for rest in lines {
-                                    info!("SSE data: {}", String::from_utf8_lossy(&rest));
-                                    if rest == b"[DONE]" {
-                                        return None; // end stream gracefully
-                                    }
-                                    if let Ok(item) =
-                                        serde_json::from_slice::<ChatStreamResponse>(&rest)
-                                    {
-                                        pending.push_back(item);
-                                    }
-                                    // skip invalid json line, continue
-                                    // processing
-                                    // remaining lines
-                                }
-                                if let Some(item) = pending.pop_front() {
-                                    return Some((Ok(item), (s, buf, pending)));
-                                }
-                                // All lines processed but no valid
-                                // ChatStreamResponse yielded,
-                                // loop back to get more bytes
-                            },
+                        let events = match s.next().await {
+                            Some(Ok(bytes)) => decoder.push_events(&bytes),
                             Some(Err(e)) => {
                                 return Some((
                                     Err(crate::client::error::ZaiError::NetworkError(
                                         std::sync::Arc::new(e),
                                     )),
-                                    (s, buf, pending),
+                                    (s, decoder, pending, failed),
                                 ));
                             },
-                            None => return None,
+                            None => {
+                                let events = decoder.finish_events();
+                                if events.is_empty() {
+                                    return None;
+                                }
+                                events
+                            },
+                        };
+                        for event in events {
+                            #[cfg(feature = "tracing")]
+                            tracing::info!("SSE data: {}", event.data);
+                            // skip invalid json events
+                            pending.extend(decode_event(event));
                         }
                     }
                 },
@@ -201,3 +232,247 @@ pub trait StreamChatLikeExt: SseStreamable + HttpClient {
         }
     }
 }
+
+/// The chunk carried by a default event, or the error carried by an
+/// `event: error` event; `None` for data that is not a chunk.
+fn decode_event(event: SseEvent) -> Option<crate::ZaiResult<ChatStreamResponse>> {
+    if event.is_error() {
+        return Some(Err(event_error(&event.data)));
+    }
+    serde_json::from_str(&event.data).ok().map(Ok)
+}
+
+/// API error from the data of an `event: error` event, which is either
+/// `{"error": {"code", "message"}}` or `{"code", "message"}`; codes may be
+/// numbers or strings.
+fn event_error(data: &str) -> ZaiError {
+    match serde_json::from_str::<serde_json::Value>(data) {
+        Ok(value) => ZaiError::from_error_object(&value),
+        Err(_) => ZaiError::ApiError {
+            code: 0,
+            message: data.to_string(),
+        },
+    }
+}
+
+/// Higher-level streaming event produced by [`StreamAggregator`].
+#[derive(Debug, Clone)]
+pub enum StreamItem {
+    /// Incremental reasoning (`reasoning_content`) from a thinking model,
+    /// kept apart from the answer text
+    ReasoningDelta(String),
+    /// Incremental assistant text
+    TextDelta(String),
+    /// A tool call with its arguments fully reassembled
+    ToolCall(Box<ToolCallMessage>),
+    /// The model finished; emitted once, after any pending tool calls
+    Done,
+}
+
+/// Reassembles tool calls that arrive split across stream chunks.
+///
+/// Tool-call deltas are merged by their `index` (or by position within the
+/// chunk when the server omits it): the first fragment's `id`, `type` and
+/// function name are kept and `arguments` fragments are concatenated.
+/// Completed calls are emitted, in index order, when a choice reports a
+/// `finish_reason` or when the stream ends.
+#[derive(Debug, Default)]
+pub struct StreamAggregator {
+    calls: BTreeMap<u32, ToolCallMessage>,
+    done: bool,
+}
+
+impl StreamAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one chunk, returning the items it produces
+    pub fn push(&mut self, chunk: &ChatStreamResponse) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        for choice in &chunk.choices {
+            if let Some(delta) = &choice.delta {
+                if let Some(reasoning) = delta.reasoning_content.as_ref().filter(|t| !t.is_empty())
+                {
+                    items.push(StreamItem::ReasoningDelta(reasoning.clone()));
+                }
+                if let Some(text) = delta.content.as_ref().filter(|t| !t.is_empty()) {
+                    items.push(StreamItem::TextDelta(text.clone()));
+                }
+                for (position, fragment) in delta.tool_calls.iter().flatten().enumerate() {
+                    self.merge(fragment.index.unwrap_or(position as u32), fragment);
+                }
+            }
+            if choice.finish_reason.is_some() {
+                items.extend(self.finish());
+            }
+        }
+        items
+    }
+
+    /// Flush pending tool calls and emit [`StreamItem::Done`] if not already
+    /// done. Call this when the chunk stream ends.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        if self.done {
+            return Vec::new();
+        }
+        self.done = true;
+        let mut items: Vec<StreamItem> = std::mem::take(&mut self.calls)
+            .into_values()
+            .map(|call| StreamItem::ToolCall(Box::new(call)))
+            .collect();
+        items.push(StreamItem::Done);
+        items
+    }
+
+    fn merge(&mut self, index: u32, fragment: &ToolCallMessage) {
+        let call = self.calls.entry(index).or_insert_with(|| ToolCallMessage {
+            index: Some(index),
+            id: None,
+            type_: None,
+            function: None,
+            mcp: None,
+        });
+        if call.id.is_none() {
+            call.id = fragment.id.clone();
+        }
+        if call.type_.is_none() {
+            call.type_ = fragment.type_.clone();
+        }
+        if fragment.mcp.is_some() {
+            call.mcp = fragment.mcp.clone();
+        }
+        if let Some(part) = &fragment.function {
+            let function = call.function.get_or_insert(ToolFunction {
+                name: None,
+                arguments: None,
+            });
+            if function.name.is_none() {
+                function.name = part.name.clone();
+            }
+            if let Some(args) = &part.arguments {
+                function
+                    .arguments
+                    .get_or_insert_with(String::new)
+                    .push_str(args);
+            }
+        }
+    }
+
+    /// Adapt a chunk stream (e.g. from [`StreamChatLikeExt::to_stream`]) into
+    /// a stream of [`StreamItem`]s. Errors are passed through.
+    pub fn aggregate<S>(chunks: S) -> impl Stream<Item = crate::ZaiResult<StreamItem>> + Send
+    where
+        S: Stream<Item = crate::ZaiResult<ChatStreamResponse>> + Send + Unpin,
+    {
+        stream::unfold(
+            (chunks, Self::new(), VecDeque::new()),
+            |(mut chunks, mut aggregator, mut pending)| async move {
+                loop {
+                    if let Some(item) = pending.pop_front() {
+                        return Some((Ok(item), (chunks, aggregator, pending)));
+                    }
+                    match chunks.next().await {
+                        Some(Ok(chunk)) => pending.extend(aggregator.push(&chunk)),
+                        Some(Err(e)) => return Some((Err(e), (chunks, aggregator, pending))),
+                        None if !aggregator.done => pending.extend(aggregator.finish()),
+                        None => return None,
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn chunk(mut value: serde_json::Value) -> crate::ZaiResult<ChatStreamResponse> {
+        value["id"] = json!("chatcmpl-1");
+        Ok(serde_json::from_value(value).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_reassembles_split_tool_call() {
+        let chunks = vec![
+            chunk(
+                json!({"choices": [{"delta": {"role": "assistant", "content": "Let me check"}}]}),
+            ),
+            chunk(json!({"choices": [{"delta": {"tool_calls": [{
+                "index": 0, "id": "call_1", "type": "function",
+                "function": {"name": "get_weather", "arguments": "{\"ci"}
+            }]}}]})),
+            chunk(json!({"choices": [{"delta": {"tool_calls": [{
+                "index": 0, "function": {"arguments": "ty\": \"Tok"}
+            }]}}]})),
+            chunk(json!({"choices": [{"delta": {"tool_calls": [{
+                "index": 0, "function": {"arguments": "yo\"}"}
+            }]}, "finish_reason": "tool_calls"}]})),
+        ];
+
+        let items: Vec<StreamItem> = StreamAggregator::aggregate(stream::iter(chunks))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 3);
+        assert!(matches!(&items[0], StreamItem::TextDelta(t) if t == "Let me check"));
+        let StreamItem::ToolCall(call) = &items[1] else {
+            panic!("expected a tool call, got {:?}", items[1]);
+        };
+        assert_eq!(call.id(), Some("call_1"));
+        let function = call.function().unwrap();
+        assert_eq!(function.name(), Some("get_weather"));
+        assert_eq!(function.arguments(), Some(r#"{"city": "Tokyo"}"#));
+        assert!(matches!(items[2], StreamItem::Done));
+    }
+
+    #[test]
+    fn test_reasoning_deltas_are_distinct() {
+        let mut aggregator = StreamAggregator::new();
+        let mut items = Vec::new();
+        for value in [
+            json!({"choices": [{"delta": {"role": "assistant", "reasoning_content": "Think"}}]}),
+            json!({"choices": [{"delta": {"reasoning_content": "ing."}}]}),
+            json!({"choices": [{"delta": {"content": "Answer"}, "finish_reason": "stop"}]}),
+        ] {
+            items.extend(aggregator.push(&chunk(value).unwrap()));
+        }
+        let reasoning: String = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::ReasoningDelta(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reasoning, "Thinking.");
+        assert!(matches!(&items[2], StreamItem::TextDelta(t) if t == "Answer"));
+        assert!(matches!(items[3], StreamItem::Done));
+    }
+
+    #[test]
+    fn test_finish_flushes_calls_by_index_once() {
+        let mut aggregator = StreamAggregator::new();
+        let delta = chunk(json!({"choices": [{"delta": {"tool_calls": [
+            {"index": 1, "id": "b", "function": {"name": "f", "arguments": "{}"}},
+            {"index": 0, "id": "a", "function": {"name": "g", "arguments": "{}"}}
+        ]}}]}))
+        .unwrap();
+        assert!(aggregator.push(&delta).is_empty());
+
+        let items = aggregator.finish();
+        let ids: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::ToolCall(call) => call.id(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(matches!(items.last(), Some(StreamItem::Done)));
+        assert!(aggregator.finish().is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 use super::{
-    request::{EmbeddingBody, EmbeddingDimensions, EmbeddingInput, EmbeddingModel},
+    request::{
+        EmbeddingBody, EmbeddingDimensions, EmbeddingInput, EmbeddingModel, MAX_BATCH_INPUTS,
+    },
     response::EmbeddingResponse,
 };
 use crate::client::http::HttpClient;
@@ -16,6 +18,12 @@ impl EmbeddingRequest {
         Self { key, body }
     }
 
+    /// Embed a list of strings; see [`send_batched`](Self::send_batched) for
+    /// inputs longer than [`MAX_BATCH_INPUTS`].
+    pub fn batch(key: String, model: EmbeddingModel, inputs: Vec<String>) -> Self {
+        Self::new(key, model, EmbeddingInput::Batch(inputs))
+    }
+
     pub fn with_dimensions(mut self, dims: EmbeddingDimensions) -> Self {
         self.body = self.body.with_dimensions(dims);
         self
@@ -45,6 +53,37 @@ impl EmbeddingRequest {
         Ok(parsed)
     }
 
+    /// Send any number of inputs, split into requests of at most
+    /// [`MAX_BATCH_INPUTS`] strings.
+    ///
+    /// Chunks are sent one after another and merged into a single response
+    /// whose `index` values refer to positions in the full input and whose
+    /// usage is the sum over all chunks. The first failing chunk aborts the
+    /// whole call.
+    pub async fn send_batched(&self) -> crate::ZaiResult<EmbeddingResponse> {
+        let inputs = match &self.body.input {
+            EmbeddingInput::Batch(v) if v.len() > MAX_BATCH_INPUTS => v,
+            _ => return self.send().await,
+        };
+
+        let mut merged: Option<EmbeddingResponse> = None;
+        for (i, chunk) in inputs.chunks(MAX_BATCH_INPUTS).enumerate() {
+            let request = Self {
+                key: self.key.clone(),
+                body: EmbeddingBody {
+                    input: EmbeddingInput::Batch(chunk.to_vec()),
+                    ..self.body.clone()
+                },
+            };
+            let resp = request.send().await?;
+            match merged.as_mut() {
+                Some(merged) => merged.append_chunk(i * MAX_BATCH_INPUTS, resp),
+                None => merged = Some(resp),
+            }
+        }
+        Ok(merged.expect("input has more than MAX_BATCH_INPUTS strings"))
+    }
+
     #[deprecated(note = "Use send() instead")]
     /// Deprecated: use `send()`.
     pub async fn execute(&self) -> crate::ZaiResult<EmbeddingResponse> {
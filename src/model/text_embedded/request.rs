@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Maximum number of strings per embedding-3 request (per API doc)
+pub const MAX_BATCH_INPUTS: usize = 64;
+
 /// Embedding model enum
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -18,6 +21,38 @@ pub enum EmbeddingInput {
     Batch(Vec<String>),
 }
 
+impl EmbeddingInput {
+    /// Number of strings to embed
+    pub fn len(&self) -> usize {
+        match self {
+            EmbeddingInput::Single(_) => 1,
+            EmbeddingInput::Batch(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<String> for EmbeddingInput {
+    fn from(text: String) -> Self {
+        EmbeddingInput::Single(text)
+    }
+}
+
+impl From<&str> for EmbeddingInput {
+    fn from(text: &str) -> Self {
+        EmbeddingInput::Single(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for EmbeddingInput {
+    fn from(texts: Vec<String>) -> Self {
+        EmbeddingInput::Batch(texts)
+    }
+}
+
 /// Output vector dimensions for embeddings
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EmbeddingDimensions {
@@ -78,7 +113,7 @@ impl EmbeddingBody {
         // If input is Batch for embedding-3, enforce max 64 items (per API doc)
         if let EmbeddingModel::Embedding3 = self.model
             && let EmbeddingInput::Batch(ref v) = self.input
-            && v.len() > 64
+            && v.len() > MAX_BATCH_INPUTS
         {
             return Err(ValidationError::new("batch_too_long"));
         }
@@ -8,6 +8,29 @@ pub struct EmbeddingResponse {
     pub usage: EmbeddingUsage,
 }
 
+impl EmbeddingResponse {
+    /// Embedding vectors ordered by input index
+    pub fn vectors(&self) -> Vec<&[f32]> {
+        let mut data: Vec<&EmbeddingData> = self.data.iter().collect();
+        data.sort_by_key(|d| d.index);
+        data.into_iter().map(|d| d.embedding.as_slice()).collect()
+    }
+
+    /// Append the response for a later chunk of the same input.
+    ///
+    /// `offset` is the position of the chunk's first string in the full
+    /// input; its indices are shifted by it and usage is summed.
+    pub(crate) fn append_chunk(&mut self, offset: usize, chunk: EmbeddingResponse) {
+        self.data.extend(chunk.data.into_iter().map(|mut d| {
+            d.index += offset;
+            d
+        }));
+        self.usage.prompt_tokens += chunk.usage.prompt_tokens;
+        self.usage.completion_tokens += chunk.usage.completion_tokens;
+        self.usage.total_tokens += chunk.usage.total_tokens;
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ResponseObjectKind {
@@ -33,3 +56,40 @@ pub struct EmbeddingUsage {
     pub completion_tokens: u64,
     pub total_tokens: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn response(vectors: &[f32], tokens: u64) -> EmbeddingResponse {
+        serde_json::from_value(json!({
+            "model": "embedding-3",
+            "object": "list",
+            "data": vectors.iter().enumerate().map(|(i, v)| json!({
+                "index": i, "object": "embedding", "embedding": [v]
+            })).collect::<Vec<_>>(),
+            "usage": {"prompt_tokens": tokens, "completion_tokens": 0, "total_tokens": tokens}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_chunk_reindexes_and_sums_usage() {
+        let mut merged = response(&[0.0, 1.0], 4);
+        merged.append_chunk(2, response(&[2.0], 3));
+
+        let indices: Vec<usize> = merged.data.iter().map(|d| d.index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert_eq!(merged.usage.total_tokens, 7);
+        assert_eq!(merged.vectors(), vec![&[0.0][..], &[1.0], &[2.0]]);
+    }
+
+    #[test]
+    fn test_vectors_sorted_by_index() {
+        let mut resp = response(&[0.0, 1.0], 1);
+        resp.data.reverse();
+        assert_eq!(resp.vectors(), vec![&[0.0][..], &[1.0]]);
+    }
+}
@@ -0,0 +1,179 @@
+//! # Token Estimation
+//!
+//! Offline, approximate token counting for chat messages, useful for trimming
+//! history before a request exceeds the model's context window.
+//!
+//! The numbers produced here are **estimates**: the real GLM tokenizer is not
+//! bundled. Use the [`text_tokenizer`](super::text_tokenizer) endpoint when an
+//! exact count is required.
+//!
+//! # Heuristic
+//!
+//! | Input | Estimated tokens |
+//! |-------|------------------|
+//! | CJK character | 1 per character |
+//! | Latin word / number | 1 per 4 characters, rounded up |
+//! | Punctuation / symbol | 1 each |
+//! | Whitespace | 0 |
+//! | Audio | 12.5 per second, rounded up |
+//! | Message framing | [`MESSAGE_OVERHEAD`] per message, [`REPLY_OVERHEAD`] per request |
+//!
+//! ```rust,ignore
+//! use zai_rs::model::{tokens, TextMessage, TextMessages};
+//!
+//! let messages = TextMessages::new(TextMessage::user("你好, world"));
+//! if tokens::estimate_messages(&messages) > budget {
+//!     // trim history
+//! }
+//! ```
+
+use super::chat_message_types::{TextMessage, TextMessages};
+
+/// Estimated tokens added per message for role and framing.
+pub const MESSAGE_OVERHEAD: usize = 4;
+
+/// Estimated tokens added once per request to prime the reply.
+pub const REPLY_OVERHEAD: usize = 3;
+
+/// Audio tokens per second of input (1 second = 12.5 tokens).
+pub const AUDIO_TOKENS_PER_SECOND: f64 = 12.5;
+
+/// Approximate number of characters in a Latin-script token.
+const LATIN_CHARS_PER_TOKEN: usize = 4;
+
+/// Returns true for characters in the common CJK blocks.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Hiragana, Katakana
+        0x3040..=0x30FF
+            // CJK punctuation
+            | 0x3000..=0x303F
+            // CJK Extension A and Unified Ideographs
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            // Hangul syllables
+            | 0xAC00..=0xD7AF
+            // CJK Compatibility Ideographs and full-width forms
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFFEF
+            // Extensions B-F and supplement
+            | 0x20000..=0x2FA1F
+    )
+}
+
+/// Estimate the token count of a piece of mixed Chinese/English text.
+pub fn estimate_text(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run: usize = 0;
+
+    for c in text.chars() {
+        if (c.is_alphanumeric() || c == '_') && !is_cjk(c) {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(LATIN_CHARS_PER_TOKEN);
+        run = 0;
+
+        // CJK characters and punctuation count as one token each.
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+
+    tokens + run.div_ceil(LATIN_CHARS_PER_TOKEN)
+}
+
+/// Estimate the token count of audio input of the given duration.
+pub fn estimate_audio(seconds: f64) -> usize {
+    if seconds <= 0.0 {
+        return 0;
+    }
+    (seconds * AUDIO_TOKENS_PER_SECOND).ceil() as usize
+}
+
+/// Estimate the token count of a single message, including framing.
+pub fn estimate_message(message: &TextMessage) -> usize {
+    let content = match message {
+        TextMessage::User { content } | TextMessage::System { content } => estimate_text(content),
+        TextMessage::Assistant {
+            content,
+            tool_calls,
+            ..
+        } => {
+            let text = content.as_deref().map(estimate_text).unwrap_or(0);
+            let calls: usize = tool_calls
+                .iter()
+                .filter_map(|call| serde_json::to_string(call).ok())
+                .map(|json| estimate_text(&json))
+                .sum();
+            text + calls
+        },
+        TextMessage::Tool {
+            content,
+            tool_call_id,
+        } => estimate_text(content) + tool_call_id.as_deref().map(estimate_text).unwrap_or(0),
+    };
+
+    content + MESSAGE_OVERHEAD
+}
+
+/// Estimate the prompt token count of a message collection.
+///
+/// This is an approximation; see the [module docs](self) for the heuristic.
+pub fn estimate_messages(messages: &TextMessages) -> usize {
+    messages
+        .messages
+        .iter()
+        .map(estimate_message)
+        .sum::<usize>()
+        + REPLY_OVERHEAD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::chat_message_types::{FunctionParams, ToolCall};
+
+    #[test]
+    fn test_estimate_text_known_strings() {
+        assert_eq!(estimate_text(""), 0);
+        assert_eq!(estimate_text("   "), 0);
+        assert_eq!(estimate_text("hello world"), 4);
+        assert_eq!(estimate_text("你好世界"), 4);
+        assert_eq!(estimate_text("Hello, 世界!"), 6);
+        assert_eq!(estimate_text("2024年"), 2);
+    }
+
+    #[test]
+    fn test_estimate_audio() {
+        assert_eq!(estimate_audio(0.0), 0);
+        assert_eq!(estimate_audio(1.0), 13);
+        assert_eq!(estimate_audio(2.0), 25);
+        assert_eq!(estimate_audio(-1.0), 0);
+    }
+
+    #[test]
+    fn test_estimate_messages() {
+        let messages = TextMessages::new(TextMessage::system("你好世界"))
+            .add_message(TextMessage::user("hello world"));
+        assert_eq!(
+            estimate_messages(&messages),
+            (4 + MESSAGE_OVERHEAD) * 2 + REPLY_OVERHEAD
+        );
+    }
+
+    #[test]
+    fn test_estimate_message_counts_tool_calls() {
+        let plain = TextMessage::assistant_with_tools(None, vec![]);
+        let with_call = TextMessage::assistant_with_tools(
+            None,
+            vec![ToolCall::new_function(
+                "call_1",
+                FunctionParams::new("get_weather", r#"{"city":"Tokyo"}"#),
+            )],
+        );
+        assert_eq!(estimate_message(&plain), MESSAGE_OVERHEAD);
+        assert!(estimate_message(&with_call) > MESSAGE_OVERHEAD);
+    }
+}
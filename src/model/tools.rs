@@ -12,10 +12,13 @@
 //! - [`Retrieval`] — Enables knowledge-base retrieval
 //! - [`ToolChoice`] — Controls tool-selection behaviour (`auto`, `none`, or
 //!   specific function)
+//!
+//! With the `schemars` feature, [`Function::from_type`] derives the parameter
+//! schema from a Rust type at compile time.
 
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Serialize, Serializer, ser::SerializeMap};
 use validator::*;
 
 use super::model_validate::validate_json_schema_value;
@@ -230,6 +233,35 @@ impl Function {
             parameters: Some(parameters),
         }
     }
+
+    /// Creates a function whose `parameters` schema is derived from `T`
+    /// (feature `schemars`).
+    ///
+    /// Subschemas are inlined and the `$schema`/`title` keys dropped, so the
+    /// result is a plain object schema matching what `T` deserializes from.
+    ///
+    /// ```rust,ignore
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct WeatherArgs { city: String, days: Option<u8> }
+    ///
+    /// let func = Function::from_type::<WeatherArgs>("get_weather", "Weather forecast");
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn from_type<T: schemars::JsonSchema>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        let mut parameters = schemars::generate::SchemaSettings::draft07()
+            .with(|s| s.inline_subschemas = true)
+            .into_generator()
+            .into_root_schema_for::<T>()
+            .to_value();
+        if let Some(obj) = parameters.as_object_mut() {
+            obj.remove("$schema");
+            obj.remove("title");
+        }
+        Self::new(name, description, parameters)
+    }
 }
 
 /// Configuration for retrieval tool capabilities.
@@ -477,29 +509,128 @@ pub enum MCPTransportType {
     StreamableHttp,
 }
 
+/// Controls whether and which tool the model calls.
+///
+/// Serializes to the OpenAI-compatible `tool_choice` shape: `"auto"`,
+/// `"none"`, `"required"`, or
+/// `{"type": "function", "function": {"name": "..."}}`.
+///
+/// The Zhipu API documents `auto` as the default (and currently the only
+/// guaranteed value); the other variants are passed through for
+/// compatible endpoints and models that accept them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// Let the model decide whether to call a tool (default)
+    #[default]
+    Auto,
+    /// Never call a tool
+    None,
+    /// Call at least one tool
+    Required,
+    /// Call the named function
+    Function { name: String },
+}
+
+impl ToolChoice {
+    /// Force a call to the named function
+    pub fn function(name: impl Into<String>) -> Self {
+        Self::Function { name: name.into() }
+    }
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ToolChoice::Auto => serializer.serialize_str("auto"),
+            ToolChoice::None => serializer.serialize_str("none"),
+            ToolChoice::Required => serializer.serialize_str("required"),
+            ToolChoice::Function { name } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "function")?;
+                map.serialize_entry("function", &serde_json::json!({ "name": name }))?;
+                map.end()
+            },
+        }
+    }
+}
+
 /// Specifies the format for the model's response.
 ///
-/// This enum controls how the model should structure its output, either as
-/// plain text or as a structured JSON object.
+/// This enum controls how the model should structure its output: plain text,
+/// any JSON object, or JSON conforming to a given schema.
 ///
 /// # Variants
 ///
-/// * `Text` - Plain text response format
+/// * `Text` - Plain text response format (the API default)
 /// * `JsonObject` - Structured JSON object response format
-#[derive(Debug, Clone, Copy, Serialize)]
-#[serde(rename_all = "snake_case")]
-#[serde(tag = "type")]
+/// * `JsonSchema` - JSON output constrained by `schema`; with `strict` the
+///   model must follow it exactly
+#[derive(Debug, Clone, PartialEq)]
 pub enum ResponseFormat {
     /// Plain text response format.
     Text,
     /// Structured JSON object response format.
     JsonObject,
+    /// JSON output matching a schema.
+    JsonSchema {
+        schema: serde_json::Value,
+        strict: bool,
+    },
+}
+
+impl ResponseFormat {
+    /// Strict JSON-schema output
+    pub fn json_schema(schema: serde_json::Value) -> Self {
+        ResponseFormat::JsonSchema {
+            schema,
+            strict: true,
+        }
+    }
+}
+
+impl Serialize for ResponseFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self {
+            ResponseFormat::Text => map.serialize_entry("type", "text")?,
+            ResponseFormat::JsonObject => map.serialize_entry("type", "json_object")?,
+            ResponseFormat::JsonSchema { schema, strict } => {
+                map.serialize_entry("type", "json_schema")?;
+                map.serialize_entry(
+                    "json_schema",
+                    &serde_json::json!({"schema": schema, "strict": strict}),
+                )?;
+            },
+        }
+        map.end()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ToolChoice tests
+    #[test]
+    fn test_tool_choice_serialization() {
+        assert_eq!(
+            serde_json::to_value(ToolChoice::function("get_weather")).unwrap(),
+            serde_json::json!({"type": "function", "function": {"name": "get_weather"}})
+        );
+        assert_eq!(
+            serde_json::to_value(ToolChoice::default()).unwrap(),
+            serde_json::json!("auto")
+        );
+        assert_eq!(
+            serde_json::to_value(ToolChoice::None).unwrap(),
+            serde_json::json!("none")
+        );
+        assert_eq!(
+            serde_json::to_value(ToolChoice::Required).unwrap(),
+            serde_json::json!("required")
+        );
+    }
+
     // ThinkingType tests
     #[test]
     fn test_thinking_type_enabled_serialization() {
@@ -799,6 +930,28 @@ mod tests {
         assert!(json.contains("\"type\":\"json_object\""));
     }
 
+    #[test]
+    fn test_response_format_json_schema_serialization() {
+        let schema = serde_json::json!({"type": "object", "properties": {"a": {"type": "string"}}});
+        assert_eq!(
+            serde_json::to_value(ResponseFormat::json_schema(schema.clone())).unwrap(),
+            serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {"schema": schema, "strict": true}
+            })
+        );
+        assert!(
+            super::super::model_validate::validate_response_format(&ResponseFormat::json_schema(
+                serde_json::json!("x")
+            ))
+            .is_err()
+        );
+        assert!(
+            super::super::model_validate::validate_response_format(&ResponseFormat::JsonObject)
+                .is_ok()
+        );
+    }
+
     // Tools enum tests
     #[test]
     fn test_tools_function_serialization() {
@@ -851,4 +1004,37 @@ mod tests {
         let json = serde_json::to_string(&seq).unwrap();
         assert!(json.contains("\"after\""));
     }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_function_from_type() {
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Unit {
+            symbol: String,
+        }
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct WeatherArgs {
+            /// City name
+            city: String,
+            days: Option<u8>,
+            unit: Option<Unit>,
+        }
+
+        let func = Function::from_type::<WeatherArgs>("get_weather", "Weather forecast");
+        assert!(func.validate().is_ok());
+        let params = func.parameters.unwrap();
+        assert_eq!(params["type"], "object");
+        assert_eq!(params["required"], serde_json::json!(["city"]));
+        assert_eq!(params["properties"]["city"]["type"], "string");
+        assert_eq!(params["properties"]["city"]["description"], "City name");
+        assert_eq!(
+            params["properties"]["days"]["type"],
+            serde_json::json!(["integer", "null"])
+        );
+        assert!(params.get("$schema").is_none());
+        assert!(params.get("definitions").is_none());
+    }
 }
@@ -141,7 +141,6 @@ impl StreamState for StreamOn {}
 impl StreamState for StreamOff {}
 
 use futures::StreamExt;
-use tracing::info;
 
 use crate::client::http::HttpClient;
 
@@ -176,19 +175,18 @@ pub trait SseStreamable: HttpClient {
         async move {
             let resp = self.post().await?;
             let mut stream = resp.bytes_stream();
-            let mut buf: Vec<u8> = Vec::new();
+            let mut decoder = crate::client::sse::SseDecoder::new();
 
             while let Some(next) = stream.next().await {
                 match next {
                     Ok(bytes) => {
-                        let lines =
-                            crate::model::sse_parser::extract_sse_data_lines(&mut buf, &bytes);
-                        for rest in lines {
-                            info!("SSE data: {}", String::from_utf8_lossy(&rest));
-                            if rest == b"[DONE]" {
-                                return Ok(());
-                            }
-                            on_data(&rest);
+                        for data in decoder.push_data(&bytes) {
+                            #[cfg(feature = "tracing")]
+                            tracing::info!("SSE data: {}", data);
+                            on_data(data.as_bytes());
+                        }
+                        if decoder.is_done() {
+                            return Ok(());
                         }
                     },
                     Err(e) => {
@@ -198,6 +196,9 @@ pub trait SseStreamable: HttpClient {
                     },
                 }
             }
+            for data in decoder.finish_data() {
+                on_data(data.as_bytes());
+            }
             Ok(())
         }
     }
@@ -0,0 +1,239 @@
+//! # Usage Accounting
+//!
+//! Running token totals across many requests, with an optional cost
+//! estimate from per-model prices.
+//!
+//! Missing usage fields count as zero. `total_tokens` is summed as reported;
+//! when a payload omits it, prompt + completion tokens are used instead.
+//!
+//! ```rust,ignore
+//! use zai_rs::model::usage::{PriceTable, UsageAccumulator};
+//!
+//! let mut usage = UsageAccumulator::new();
+//! let reply = executor.drive_chat_with_usage(chat, 5, &mut usage).await?;
+//! let prices = PriceTable::new().with_price("glm-4.5", 0.0008, 0.002);
+//! println!("{} tokens, ~{:.4}", usage.total().total_tokens.unwrap_or(0), usage.cost_estimate(&prices));
+//! ```
+
+use std::collections::HashMap;
+
+use super::chat_base_response::{ChatCompletionResponse, PromptTokensDetails, Usage};
+
+/// Price of one model, per 1,000 tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPrice {
+    pub prompt_per_1k: f64,
+    pub completion_per_1k: f64,
+}
+
+/// Per-model token prices, keyed by model name (e.g. `glm-4.5`).
+#[derive(Debug, Clone, Default)]
+pub struct PriceTable {
+    prices: HashMap<String, TokenPrice>,
+    fallback: Option<TokenPrice>,
+}
+
+impl PriceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the price of `model`
+    pub fn with_price(
+        mut self,
+        model: impl Into<String>,
+        prompt_per_1k: f64,
+        completion_per_1k: f64,
+    ) -> Self {
+        self.prices.insert(
+            model.into(),
+            TokenPrice {
+                prompt_per_1k,
+                completion_per_1k,
+            },
+        );
+        self
+    }
+
+    /// Price for usage whose model is unknown or not in the table
+    pub fn with_fallback(mut self, prompt_per_1k: f64, completion_per_1k: f64) -> Self {
+        self.fallback = Some(TokenPrice {
+            prompt_per_1k,
+            completion_per_1k,
+        });
+        self
+    }
+
+    /// Price of `model`, or the fallback
+    pub fn price(&self, model: Option<&str>) -> Option<TokenPrice> {
+        model
+            .and_then(|m| self.prices.get(m).copied())
+            .or(self.fallback)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Totals {
+    prompt: u64,
+    completion: u64,
+    total: u64,
+    cached: u64,
+}
+
+impl Totals {
+    fn add(&mut self, usage: &Usage) {
+        let prompt = u64::from(usage.prompt_tokens.unwrap_or(0));
+        let completion = u64::from(usage.completion_tokens.unwrap_or(0));
+        self.prompt += prompt;
+        self.completion += completion;
+        self.total += usage.total_tokens.map_or(prompt + completion, u64::from);
+        self.cached += u64::from(usage.cached_tokens().unwrap_or(0));
+    }
+
+    fn merge(&mut self, other: &Totals) {
+        self.prompt += other.prompt;
+        self.completion += other.completion;
+        self.total += other.total;
+        self.cached += other.cached;
+    }
+}
+
+fn clamp(n: u64) -> u32 {
+    n.min(u64::from(u32::MAX)) as u32
+}
+
+/// Sums token usage over many responses, per model.
+#[derive(Debug, Clone, Default)]
+pub struct UsageAccumulator {
+    /// Totals keyed by model name; `None` for usage added without one
+    by_model: HashMap<Option<String>, Totals>,
+    requests: usize,
+}
+
+impl UsageAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add usage not attributed to a model
+    pub fn add(&mut self, usage: &Usage) {
+        self.add_for_model(None, usage);
+    }
+
+    /// Add usage reported for `model`
+    pub fn add_for_model(&mut self, model: Option<&str>, usage: &Usage) {
+        self.by_model
+            .entry(model.map(str::to_string))
+            .or_default()
+            .add(usage);
+        self.requests += 1;
+    }
+
+    /// Add a response's usage under its model; responses without usage are
+    /// ignored
+    pub fn add_response(&mut self, response: &ChatCompletionResponse) {
+        if let Some(usage) = response.usage() {
+            self.add_for_model(response.model(), usage);
+        }
+    }
+
+    /// Number of usage payloads added
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+
+    /// Combined usage over all models. Counts beyond `u32::MAX` saturate.
+    pub fn total(&self) -> Usage {
+        let mut sum = Totals::default();
+        for totals in self.by_model.values() {
+            sum.merge(totals);
+        }
+        to_usage(&sum)
+    }
+
+    /// Usage added for `model`
+    pub fn for_model(&self, model: &str) -> Option<Usage> {
+        self.by_model.get(&Some(model.to_string())).map(to_usage)
+    }
+
+    /// Estimated cost of the accumulated usage. Usage with no price in
+    /// `prices` (and no fallback) adds nothing.
+    pub fn cost_estimate(&self, prices: &PriceTable) -> f64 {
+        self.by_model
+            .iter()
+            .filter_map(|(model, totals)| {
+                let price = prices.price(model.as_deref())?;
+                Some(
+                    totals.prompt as f64 / 1000.0 * price.prompt_per_1k
+                        + totals.completion as f64 / 1000.0 * price.completion_per_1k,
+                )
+            })
+            .sum()
+    }
+}
+
+fn to_usage(totals: &Totals) -> Usage {
+    Usage {
+        prompt_tokens: Some(clamp(totals.prompt)),
+        completion_tokens: Some(clamp(totals.completion)),
+        total_tokens: Some(clamp(totals.total)),
+        prompt_tokens_details: Some(PromptTokensDetails {
+            cached_tokens: Some(clamp(totals.cached)),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn usage(value: serde_json::Value) -> Usage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_sums_with_missing_fields() {
+        let mut acc = UsageAccumulator::new();
+        acc.add(&usage(json!({
+            "prompt_tokens": 100,
+            "completion_tokens": 20,
+            "total_tokens": 120,
+            "prompt_tokens_details": {"cached_tokens": 64}
+        })));
+        acc.add(&usage(json!({"prompt_tokens": 50, "completion_tokens": 5})));
+        acc.add(&usage(json!({"completion_tokens": 7})));
+        acc.add(&usage(json!({})));
+
+        let total = acc.total();
+        assert_eq!(acc.requests(), 4);
+        assert_eq!(total.prompt_tokens, Some(150));
+        assert_eq!(total.completion_tokens, Some(32));
+        assert_eq!(total.total_tokens, Some(182));
+        assert_eq!(total.cached_tokens(), Some(64));
+    }
+
+    #[test]
+    fn test_cost_estimate_per_model() {
+        let response = |model: &str, prompt: u32, completion: u32| -> ChatCompletionResponse {
+            serde_json::from_value(json!({
+                "model": model,
+                "usage": {"prompt_tokens": prompt, "completion_tokens": completion}
+            }))
+            .unwrap()
+        };
+        let mut acc = UsageAccumulator::new();
+        acc.add_response(&response("glm-4.5", 2000, 1000));
+        acc.add_response(&response("glm-4.5", 1000, 0));
+        acc.add_response(&response("glm-4.5-flash", 5000, 5000));
+        acc.add_response(&serde_json::from_value(json!({"model": "glm-4.5"})).unwrap());
+        assert_eq!(acc.requests(), 3);
+        assert_eq!(acc.for_model("glm-4.5").unwrap().prompt_tokens, Some(3000));
+
+        let prices = PriceTable::new().with_price("glm-4.5", 1.0, 2.0);
+        assert!((acc.cost_estimate(&prices) - 5.0).abs() < 1e-9);
+        let prices = prices.with_fallback(0.1, 0.1);
+        assert!((acc.cost_estimate(&prices) - 6.0).abs() < 1e-9);
+    }
+}
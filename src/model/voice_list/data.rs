@@ -1,12 +1,22 @@
+use std::collections::{HashSet, VecDeque};
+
+use futures::{Stream, stream};
 use url::Url;
 
-use super::request::VoiceListQuery;
+use super::{
+    request::{VoiceFilter, VoiceListQuery},
+    response::{VoiceItem, VoiceListResponse},
+};
 use crate::{ZaiResult, client::http::HttpClient};
 
+/// Page size used by [`VoiceListRequest::stream_all`] when none is set.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
 /// GET voice list request
 pub struct VoiceListRequest {
     pub key: String,
     url: String,
+    query: VoiceListQuery,
     // Empty body placeholder to satisfy HttpClient::Body
     _body: (),
 }
@@ -17,11 +27,13 @@ impl VoiceListRequest {
         Self {
             key,
             url,
+            query: VoiceListQuery::new(),
             _body: (),
         }
     }
 
-    fn rebuild_url(&mut self, q: &VoiceListQuery) {
+    fn rebuild_url(&mut self) {
+        let q = &self.query;
         let mut url = Url::parse("https://open.bigmodel.cn/api/paas/v4/voice/list").unwrap();
         {
             let mut pairs = url.query_pairs_mut();
@@ -31,6 +43,18 @@ impl VoiceListRequest {
             if let Some(ref t) = q.voice_type {
                 pairs.append_pair("voiceType", t.as_str());
             }
+            if let Some(limit) = q.limit {
+                pairs.append_pair("limit", &limit.to_string());
+            }
+            if let Some(ref after) = q.after {
+                pairs.append_pair("after", after);
+            }
+            if let Some(ref language) = q.filter.language {
+                pairs.append_pair("language", language);
+            }
+            if let Some(ref gender) = q.filter.gender {
+                pairs.append_pair("gender", gender);
+            }
         }
         self.url = url.to_string();
     }
@@ -41,17 +65,145 @@ impl VoiceListRequest {
         Ok(())
     }
 
-    pub async fn send(&self) -> ZaiResult<super::response::VoiceListResponse> {
+    /// Send the request.
+    ///
+    /// The server may ignore the language/gender filter, so it is also
+    /// applied to the returned list.
+    pub async fn send(&self) -> ZaiResult<VoiceListResponse> {
         self.validate()?;
+        let mut parsed = self.fetch().await?;
+        if !self.query.filter.is_empty()
+            && let Some(voices) = parsed.voice_list.as_mut()
+        {
+            voices.retain(|v| self.query.filter.matches(v));
+        }
+        Ok(parsed)
+    }
+
+    /// One page as returned by the server
+    async fn fetch(&self) -> ZaiResult<VoiceListResponse> {
         let resp = self.get().await?;
-        let parsed = resp.json::<super::response::VoiceListResponse>().await?;
+        let parsed = resp.json::<VoiceListResponse>().await?;
         Ok(parsed)
     }
 
     pub fn with_query(mut self, q: VoiceListQuery) -> Self {
-        self.rebuild_url(&q);
+        self.query = q;
+        self.rebuild_url();
+        self
+    }
+
+    /// Maximum number of voices per page
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.query.limit = Some(limit);
+        self.rebuild_url();
+        self
+    }
+
+    /// Start after the voice with this id
+    pub fn with_after(mut self, cursor: impl Into<String>) -> Self {
+        self.query.after = Some(cursor.into());
+        self.rebuild_url();
+        self
+    }
+
+    /// Only return voices matching `filter`
+    pub fn with_filter(mut self, filter: VoiceFilter) -> Self {
+        self.query.filter = filter;
+        self.rebuild_url();
         self
     }
+
+    /// Page through all voices, yielding each one.
+    ///
+    /// Pages are requested with `limit` (default [`DEFAULT_PAGE_SIZE`]) and
+    /// `after` set to the last voice id of the previous page. Paging stops on
+    /// a short page, and also when the server ignores the cursor or the
+    /// limit (a page without new voices, or a page larger than the limit),
+    /// so an unpaginated endpoint costs at most one extra request. Voices
+    /// are de-duplicated by id and filtered client-side like
+    /// [`send`](Self::send).
+    pub fn stream_all(self) -> impl Stream<Item = ZaiResult<VoiceItem>> {
+        let limit = self.query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let this = self.with_limit(limit);
+        let filter = this.query.filter.clone();
+        paginate(limit, filter, move |after| {
+            let mut page = VoiceListRequest {
+                key: this.key.clone(),
+                url: String::new(),
+                query: this.query.clone(),
+                _body: (),
+            };
+            page.query.after = after.or_else(|| this.query.after.clone());
+            page.rebuild_url();
+            async move { Ok(page.fetch().await?.voice_list.unwrap_or_default()) }
+        })
+    }
+}
+
+/// Drive a cursor-paginated listing; `fetch` receives the cursor of the next
+/// page (`None` for the first) and returns the unfiltered page. `filter` is
+/// applied only after the cursor and the short-page check have seen the
+/// whole page.
+fn paginate<F, Fut>(
+    limit: u32,
+    filter: VoiceFilter,
+    fetch: F,
+) -> impl Stream<Item = ZaiResult<VoiceItem>>
+where
+    F: Fn(Option<String>) -> Fut,
+    Fut: std::future::Future<Output = ZaiResult<Vec<VoiceItem>>>,
+{
+    struct State<F> {
+        fetch: F,
+        filter: VoiceFilter,
+        cursor: Option<String>,
+        seen: HashSet<String>,
+        buffered: VecDeque<VoiceItem>,
+        finished: bool,
+    }
+
+    let state = State {
+        fetch,
+        filter,
+        cursor: None,
+        seen: HashSet::new(),
+        buffered: VecDeque::new(),
+        finished: false,
+    };
+    stream::unfold(state, move |mut st| async move {
+        loop {
+            if let Some(voice) = st.buffered.pop_front() {
+                return Some((Ok(voice), st));
+            }
+            if st.finished {
+                return None;
+            }
+            let page = match (st.fetch)(st.cursor.take()).await {
+                Ok(page) => page,
+                Err(e) => {
+                    st.finished = true;
+                    return Some((Err(e), st));
+                },
+            };
+            let len = page.len();
+            let mut last_id = None;
+            let mut fresh = false;
+            for voice in page {
+                last_id = voice.voice.clone().or(last_id);
+                let new = voice
+                    .voice
+                    .as_ref()
+                    .is_none_or(|id| st.seen.insert(id.clone()));
+                fresh |= new;
+                if new && st.filter.matches(&voice) {
+                    st.buffered.push_back(voice);
+                }
+            }
+            st.cursor = last_id;
+            st.finished = !fresh || len != limit as usize || st.cursor.is_none();
+        }
+    })
 }
 
 impl HttpClient for VoiceListRequest {
@@ -69,3 +221,108 @@ impl HttpClient for VoiceListRequest {
         &self._body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::StreamExt;
+
+    use super::*;
+
+    fn voice(id: &str, language: Option<&str>) -> VoiceItem {
+        VoiceItem {
+            voice: Some(id.to_string()),
+            voice_name: None,
+            voice_type: None,
+            download_url: None,
+            create_time: None,
+            language: language.map(str::to_string),
+            gender: None,
+        }
+    }
+
+    fn ids(items: Vec<ZaiResult<VoiceItem>>) -> Vec<String> {
+        items
+            .into_iter()
+            .map(|v| v.unwrap().voice.unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_paginate_follows_cursor() {
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+        let seen = cursors.clone();
+        let items = paginate(2, VoiceFilter::new(), move |after| {
+            seen.lock().unwrap().push(after.clone());
+            let page = match after.as_deref() {
+                None => vec![voice("a", None), voice("b", None)],
+                Some("b") => vec![voice("c", None), voice("d", None)],
+                _ => vec![voice("e", None)],
+            };
+            async move { Ok(page) }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(ids(items), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(
+            *cursors.lock().unwrap(),
+            vec![None, Some("b".to_string()), Some("d".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_when_server_ignores_cursor() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let items = paginate(2, VoiceFilter::new(), move |_| {
+            *counter.lock().unwrap() += 1;
+            async { Ok(vec![voice("a", None), voice("b", None)]) }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(ids(items), vec!["a", "b"]);
+        assert_eq!(*calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_filters_after_paging() {
+        // The first page has no matching voice but is full, so paging must
+        // continue from its last raw id.
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+        let seen = cursors.clone();
+        let items = paginate(2, VoiceFilter::new().with_language("zh"), move |after| {
+            seen.lock().unwrap().push(after.clone());
+            let page = match after.as_deref() {
+                None => vec![voice("a", Some("en")), voice("b", Some("en"))],
+                _ => vec![voice("c", Some("zh"))],
+            };
+            async move { Ok(page) }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(ids(items), vec!["c"]);
+        assert_eq!(*cursors.lock().unwrap(), vec![None, Some("b".to_string())]);
+    }
+
+    #[test]
+    fn test_filter_matches_and_url() {
+        let filter = VoiceFilter::new().with_language("zh");
+        assert!(filter.matches(&voice("a", Some("ZH"))));
+        assert!(!filter.matches(&voice("b", Some("en"))));
+        assert!(!filter.matches(&voice("c", None)));
+        assert!(VoiceFilter::new().matches(&voice("c", None)));
+
+        let request = VoiceListRequest::new("k".to_string())
+            .with_limit(10)
+            .with_after("v1")
+            .with_filter(filter);
+        assert_eq!(
+            request.api_url(),
+            "https://open.bigmodel.cn/api/paas/v4/voice/list?limit=10&after=v1&language=zh"
+        );
+    }
+}
@@ -9,6 +9,15 @@ pub struct VoiceListQuery {
     /// 音色类型: OFFICIAL / PRIVATE
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voice_type: Option<VoiceType>,
+    /// Page size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Cursor: return voices after this voice id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Language / gender filter
+    #[serde(flatten)]
+    pub filter: VoiceFilter,
 }
 
 impl Default for VoiceListQuery {
@@ -22,6 +31,9 @@ impl VoiceListQuery {
         Self {
             voice_name: None,
             voice_type: None,
+            limit: None,
+            after: None,
+            filter: VoiceFilter::default(),
         }
     }
     pub fn with_voice_name(mut self, name: impl Into<String>) -> Self {
@@ -32,6 +44,61 @@ impl VoiceListQuery {
         self.voice_type = Some(vt);
         self
     }
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    pub fn with_after(mut self, cursor: impl Into<String>) -> Self {
+        self.after = Some(cursor.into());
+        self
+    }
+    pub fn with_filter(mut self, filter: VoiceFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Voice attributes to filter on.
+///
+/// The filter is sent to the server and, since the server may ignore it, also
+/// applied to the returned voices. Matching is case-insensitive; a voice that
+/// does not report an attribute is excluded when filtering by it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VoiceFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+}
+
+impl VoiceFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+    pub fn with_gender(mut self, gender: impl Into<String>) -> Self {
+        self.gender = Some(gender.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.language.is_none() && self.gender.is_none()
+    }
+
+    /// Whether `voice` satisfies every attribute set on the filter
+    pub fn matches(&self, voice: &super::response::VoiceItem) -> bool {
+        fn check(want: &Option<String>, have: &Option<String>) -> bool {
+            match (want, have) {
+                (None, _) => true,
+                (Some(want), Some(have)) => want.eq_ignore_ascii_case(have),
+                (Some(_), None) => false,
+            }
+        }
+        check(&self.language, &voice.language) && check(&self.gender, &voice.gender)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,4 +21,10 @@ pub struct VoiceItem {
     pub download_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub create_time: Option<String>,
+    /// Voice language, when reported by the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Voice gender, when reported by the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
 }
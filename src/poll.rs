@@ -0,0 +1,309 @@
+//! Polling asynchronous tasks until they finish
+//!
+//! Async chat and video generation, file parsing and document embedding all
+//! hand back a task that has to be queried until it reaches a terminal
+//! status. [`until`] does that loop once for all of them:
+//!
+//! - polls immediately, then every [`PollConfig::interval`], growing the
+//!   interval by [`PollConfig::backoff`] up to [`PollConfig::max_interval`];
+//! - stops with [`PollError::TimedOut`] (carrying the last value) once
+//!   [`PollConfig::timeout`] has elapsed;
+//! - retries network, rate-limit and server errors up to
+//!   [`PollConfig::transient_retries`] times in a row, and fails on any other
+//!   error;
+//! - reports each wait to the [`PollConfig::on_progress`] callback.
+//!
+//! Whether a terminal value means success or failure is up to the caller.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use zai_rs::poll::{self, PollConfig};
+//!
+//! let config = PollConfig::new(Duration::from_secs(2), Duration::from_secs(300))
+//!     .with_backoff(1.5, Duration::from_secs(10))
+//!     .with_transient_retries(3)
+//!     .on_progress(|p| eprintln!("still running after {:?}", p.elapsed));
+//! let result = poll::until(|| request.send(), |r| r.is_done(), config).await?;
+//! ```
+
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::client::error::ZaiError;
+
+/// State reported to [`PollConfig::on_progress`] before each wait
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollProgress {
+    /// Polls made so far
+    pub attempt: u32,
+    /// Time since polling started
+    pub elapsed: Duration,
+    /// Wait before the next poll
+    pub next_delay: Duration,
+}
+
+type ProgressFn = Arc<dyn Fn(&PollProgress) + Send + Sync>;
+
+/// Interval, timeout and retry settings of [`until`]
+#[derive(Clone)]
+pub struct PollConfig {
+    /// Wait between the first polls
+    pub interval: Duration,
+    /// Give up once this much time has passed
+    pub timeout: Duration,
+    /// Factor applied to the wait after each poll (1.0 keeps it fixed)
+    pub backoff: f64,
+    /// Upper bound of the wait when backing off
+    pub max_interval: Duration,
+    /// Consecutive transient errors tolerated before failing
+    pub transient_retries: u32,
+    on_progress: Option<ProgressFn>,
+}
+
+impl std::fmt::Debug for PollConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PollConfig")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("backoff", &self.backoff)
+            .field("max_interval", &self.max_interval)
+            .field("transient_retries", &self.transient_retries)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for PollConfig {
+    /// Every 2 seconds for up to 5 minutes, no backoff or retries
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2), Duration::from_secs(300))
+    }
+}
+
+impl PollConfig {
+    /// Poll every `interval` for up to `timeout`
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout,
+            backoff: 1.0,
+            max_interval: interval,
+            transient_retries: 0,
+            on_progress: None,
+        }
+    }
+
+    /// Multiply the wait by `factor` after each poll, up to `max_interval`
+    pub fn with_backoff(mut self, factor: f64, max_interval: Duration) -> Self {
+        self.backoff = factor.max(1.0);
+        self.max_interval = max_interval.max(self.interval);
+        self
+    }
+
+    /// Tolerate up to `retries` transient errors in a row
+    pub fn with_transient_retries(mut self, retries: u32) -> Self {
+        self.transient_retries = retries;
+        self
+    }
+
+    /// Call `f` before each wait
+    pub fn on_progress(mut self, f: impl Fn(&PollProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(f));
+        self
+    }
+
+    fn next_interval(&self, current: Duration) -> Duration {
+        current.mul_f64(self.backoff).min(self.max_interval)
+    }
+}
+
+/// Why [`until`] stopped without a terminal value
+#[derive(Debug)]
+pub enum PollError<T> {
+    /// The timeout elapsed; `last` is the most recent non-terminal value
+    TimedOut { last: T, elapsed: Duration },
+    /// A poll failed with a non-transient error, or retries ran out
+    Failed(ZaiError),
+}
+
+impl<T: std::fmt::Debug> From<PollError<T>> for ZaiError {
+    /// Timeouts become `HttpError` 408 naming the last value
+    fn from(err: PollError<T>) -> Self {
+        match err {
+            PollError::TimedOut { last, elapsed } => ZaiError::HttpError {
+                status: 408,
+                message: format!(
+                    "task not finished after {:?} (last status: {:?})",
+                    elapsed, last
+                ),
+            },
+            PollError::Failed(e) => e,
+        }
+    }
+}
+
+/// Network, rate-limit and server errors are worth polling again.
+fn is_transient(err: &ZaiError) -> bool {
+    matches!(err.inner(), ZaiError::NetworkError(_)) || err.is_rate_limit() || err.is_server_error()
+}
+
+/// Call `poll_fn` until `is_terminal` accepts its value; see the
+/// [module docs](self). At least one poll is always made.
+pub async fn until<T, F, Fut, P>(
+    mut poll_fn: F,
+    is_terminal: P,
+    config: PollConfig,
+) -> Result<T, PollError<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ZaiError>>,
+    P: Fn(&T) -> bool,
+{
+    let start = Instant::now();
+    let mut delay = config.interval;
+    let mut attempt = 0;
+    let mut retries_left = config.transient_retries;
+    loop {
+        attempt += 1;
+        let value = match poll_fn().await {
+            Ok(value) => {
+                retries_left = config.transient_retries;
+                Some(value)
+            },
+            Err(e) if retries_left > 0 && is_transient(&e) && start.elapsed() < config.timeout => {
+                retries_left -= 1;
+                None
+            },
+            Err(e) => return Err(PollError::Failed(e)),
+        };
+
+        let elapsed = start.elapsed();
+        if let Some(value) = value {
+            if is_terminal(&value) {
+                return Ok(value);
+            }
+            if elapsed >= config.timeout {
+                return Err(PollError::TimedOut {
+                    last: value,
+                    elapsed,
+                });
+            }
+        }
+        let wait = delay.min(config.timeout.saturating_sub(elapsed));
+        if let Some(on_progress) = &config.on_progress {
+            on_progress(&PollProgress {
+                attempt,
+                elapsed,
+                next_delay: wait,
+            });
+        }
+        tokio::time::sleep(wait).await;
+        delay = config.next_interval(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_until_reaches_terminal_status() {
+        let statuses = Mutex::new(vec!["queued", "running", "running", "done"].into_iter());
+        let failures = AtomicUsize::new(0);
+        let waits = Arc::new(Mutex::new(Vec::new()));
+        let recorded = waits.clone();
+        let config = PollConfig::new(Duration::from_millis(1), Duration::from_secs(5))
+            .with_backoff(2.0, Duration::from_millis(4))
+            .with_transient_retries(1)
+            .on_progress(move |p| recorded.lock().unwrap().push((p.attempt, p.next_delay)));
+
+        let status = until(
+            || {
+                // The second poll hits a transient server error.
+                let fail = failures.fetch_add(1, Ordering::SeqCst) == 1;
+                let next = if fail {
+                    None
+                } else {
+                    statuses.lock().unwrap().next()
+                };
+                async move {
+                    match next {
+                        Some(status) => Ok(status),
+                        None => Err(ZaiError::HttpError {
+                            status: 503,
+                            message: "busy".to_string(),
+                        }),
+                    }
+                }
+            },
+            |s| *s == "done",
+            config,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, "done");
+        let waits = waits.lock().unwrap();
+        let attempts: Vec<u32> = waits.iter().map(|(a, _)| *a).collect();
+        assert_eq!(attempts, [1, 2, 3, 4]);
+        let delays: Vec<u64> = waits.iter().map(|(_, d)| d.as_millis() as u64).collect();
+        assert_eq!(delays, [1, 2, 4, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_until_times_out_with_last_value() {
+        let calls = AtomicUsize::new(0);
+        let err = until(
+            || {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(n) }
+            },
+            |_| false,
+            PollConfig::new(Duration::from_millis(5), Duration::from_millis(20)),
+        )
+        .await
+        .unwrap_err();
+
+        let PollError::TimedOut { last, elapsed } = err else {
+            panic!("expected a timeout, got {:?}", err);
+        };
+        assert_eq!(last, calls.load(Ordering::SeqCst) - 1);
+        assert!(elapsed >= Duration::from_millis(20));
+        let err = ZaiError::from(PollError::TimedOut { last, elapsed });
+        assert!(matches!(err, ZaiError::HttpError { status: 408, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_until_fails_on_non_transient_error() {
+        let calls = AtomicUsize::new(0);
+        let err = until(
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Err::<(), _>(ZaiError::ApiError {
+                        code: 1214,
+                        message: "bad request".to_string(),
+                    })
+                }
+            },
+            |_| true,
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5))
+                .with_transient_retries(3),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            PollError::Failed(ZaiError::ApiError { code: 1214, .. })
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,231 @@
+//! Real-time audio helpers
+//!
+//! GLM-Realtime streams its spoken reply as base64-encoded raw PCM: 24 kHz,
+//! 16-bit signed little-endian, mono. Raw PCM carries no format information,
+//! so it must be wrapped in a WAV (RIFF/WAVE) container before most players
+//! or audio libraries can open it.
+//!
+//! ```rust,ignore
+//! let mut sink = AudioSink::glm_realtime();
+//! for delta in audio_deltas {
+//!     sink.push_delta(&delta)?;
+//! }
+//! std::fs::write("reply.wav", sink.finish()?)?;
+//! ```
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+use crate::client::error::{ZaiError, ZaiResult};
+
+/// Sample rate of GLM-Realtime audio output, in Hz.
+pub const GLM_REALTIME_SAMPLE_RATE: u32 = 24_000;
+
+/// Bits per sample of GLM-Realtime audio output.
+pub const GLM_REALTIME_BITS_PER_SAMPLE: u16 = 16;
+
+/// Channel count of GLM-Realtime audio output.
+pub const GLM_REALTIME_CHANNELS: u16 = 1;
+
+/// Size of the header written by [`pcm_to_wav`].
+pub const WAV_HEADER_LEN: usize = 44;
+
+/// Wrap raw little-endian PCM samples in a 44-byte RIFF/WAVE header.
+///
+/// The data chunk size is the length of `pcm`; a trailing partial sample is
+/// kept as is. Fails when the format does not fit the header's fields (no
+/// channels or bits, a block or byte rate too large) or `pcm` exceeds the
+/// 4 GiB a WAV file can hold.
+pub fn pcm_to_wav(pcm: &[u8], sample_rate: u32, channels: u16, bits: u16) -> ZaiResult<Vec<u8>> {
+    let (block_align, byte_rate) = wav_rates(sample_rate, channels, bits)?;
+    let data_len = u32::try_from(pcm.len())
+        .ok()
+        .filter(|len| *len <= u32::MAX - 36)
+        .ok_or_else(|| {
+            invalid_format(format!(
+                "{} bytes of PCM do not fit in a WAV file",
+                pcm.len()
+            ))
+        })?;
+
+    let mut wav = Vec::with_capacity(WAV_HEADER_LEN + pcm.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&bits.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.extend_from_slice(pcm);
+    Ok(wav)
+}
+
+/// Block align and byte rate of a PCM format, checked against the `u16` and
+/// `u32` header fields they are written to.
+fn wav_rates(sample_rate: u32, channels: u16, bits: u16) -> ZaiResult<(u16, u32)> {
+    if channels == 0 || bits == 0 {
+        return Err(invalid_format(format!(
+            "{} channels of {}-bit samples is not a PCM format",
+            channels, bits
+        )));
+    }
+    let block_align = u32::from(channels) * u32::from(bits.div_ceil(8));
+    let block_align = u16::try_from(block_align).map_err(|_| {
+        invalid_format(format!(
+            "block align of {} bytes exceeds the WAV limit",
+            block_align
+        ))
+    })?;
+    let byte_rate = u64::from(sample_rate) * u64::from(block_align);
+    let byte_rate = u32::try_from(byte_rate).map_err(|_| {
+        invalid_format(format!(
+            "byte rate of {} bytes/s exceeds the WAV limit",
+            byte_rate
+        ))
+    })?;
+    Ok((block_align, byte_rate))
+}
+
+fn invalid_format(message: String) -> ZaiError {
+    ZaiError::ApiError {
+        code: 1200,
+        message,
+    }
+}
+
+/// Collects streamed PCM audio and produces a WAV file once the audio is
+/// done.
+#[derive(Debug, Clone)]
+pub struct AudioSink {
+    pcm: Vec<u8>,
+    sample_rate: u32,
+    channels: u16,
+    bits: u16,
+}
+
+impl AudioSink {
+    /// Sink for PCM in the given format; fails when the format cannot be
+    /// written to a WAV header (see [`pcm_to_wav`]).
+    pub fn new(sample_rate: u32, channels: u16, bits: u16) -> ZaiResult<Self> {
+        wav_rates(sample_rate, channels, bits)?;
+        Ok(Self {
+            pcm: Vec::new(),
+            sample_rate,
+            channels,
+            bits,
+        })
+    }
+
+    /// Sink for GLM-Realtime output (24 kHz, 16-bit, mono).
+    pub fn glm_realtime() -> Self {
+        Self {
+            pcm: Vec::new(),
+            sample_rate: GLM_REALTIME_SAMPLE_RATE,
+            channels: GLM_REALTIME_CHANNELS,
+            bits: GLM_REALTIME_BITS_PER_SAMPLE,
+        }
+    }
+
+    /// Append one base64-encoded audio delta.
+    pub fn push_delta(&mut self, delta: &str) -> ZaiResult<()> {
+        let bytes = STANDARD
+            .decode(delta.trim())
+            .map_err(|e| ZaiError::ApiError {
+                code: 1200,
+                message: format!("invalid base64 audio delta: {}", e),
+            })?;
+        self.pcm.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Append already decoded PCM bytes.
+    pub fn push_pcm(&mut self, pcm: &[u8]) {
+        self.pcm.extend_from_slice(pcm);
+    }
+
+    /// PCM bytes collected so far.
+    pub fn pcm(&self) -> &[u8] {
+        &self.pcm
+    }
+
+    /// Playback length of the collected audio.
+    pub fn duration(&self) -> std::time::Duration {
+        let bytes_per_second = u64::from(self.sample_rate)
+            * u64::from(self.channels)
+            * u64::from(self.bits.div_ceil(8));
+        if bytes_per_second == 0 {
+            return std::time::Duration::ZERO;
+        }
+        std::time::Duration::from_secs_f64(self.pcm.len() as f64 / bytes_per_second as f64)
+    }
+
+    /// Finish the audio, returning a complete WAV file.
+    pub fn finish(self) -> ZaiResult<Vec<u8>> {
+        pcm_to_wav(&self.pcm, self.sample_rate, self.channels, self.bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_pcm_to_wav_header_layout() {
+        let pcm = [1u8, 0, 2, 0, 3, 0];
+        let wav = pcm_to_wav(&pcm, 24_000, 1, 16).unwrap();
+
+        assert_eq!(wav.len(), WAV_HEADER_LEN + pcm.len());
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 4), 36 + 6);
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(&wav[12..16], b"fmt ");
+        assert_eq!(u32_at(&wav, 16), 16);
+        assert_eq!(u16_at(&wav, 20), 1);
+        assert_eq!(u16_at(&wav, 22), 1);
+        assert_eq!(u32_at(&wav, 24), 24_000);
+        assert_eq!(u32_at(&wav, 28), 48_000);
+        assert_eq!(u16_at(&wav, 32), 2);
+        assert_eq!(u16_at(&wav, 34), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(&wav, 40), 6);
+        assert_eq!(&wav[44..], &pcm);
+    }
+
+    #[test]
+    fn test_audio_sink_collects_deltas() {
+        let mut sink = AudioSink::glm_realtime();
+        sink.push_delta(&STANDARD.encode([0u8; 24_000])).unwrap();
+        sink.push_delta(&STANDARD.encode([0u8; 24_000])).unwrap();
+        assert!(sink.push_delta("not base64!").is_err());
+        assert_eq!(sink.pcm().len(), 48_000);
+        assert_eq!(sink.duration(), std::time::Duration::from_secs(1));
+
+        let wav = sink.finish().unwrap();
+        assert_eq!(u32_at(&wav, 40), 48_000);
+        assert_eq!(wav.len(), WAV_HEADER_LEN + 48_000);
+    }
+
+    #[test]
+    fn test_invalid_formats_are_rejected() {
+        // 65535 channels of 16-bit samples overflow the u16 block align.
+        assert!(AudioSink::new(24_000, u16::MAX, 16).is_err());
+        assert!(AudioSink::new(24_000, 0, 16).is_err());
+        assert!(AudioSink::new(24_000, 1, 0).is_err());
+        // 8 bytes per block at u32::MAX Hz overflow the u32 byte rate.
+        assert!(pcm_to_wav(&[], u32::MAX, 2, 32).is_err());
+        assert!(AudioSink::new(48_000, 2, 24).is_ok());
+    }
+}
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use super::{models::*, session::*, types::*};
+use crate::client::{auth::Auth, error::ZaiResult};
 
 /// Real-time API client
 ///
@@ -26,6 +27,7 @@ use super::{models::*, session::*, types::*};
 pub struct RealTimeClient {
     api_key: Arc<String>,
     base_url: String,
+    auth: Option<Auth>,
 }
 
 impl RealTimeClient {
@@ -34,9 +36,26 @@ impl RealTimeClient {
         Self {
             api_key: Arc::new(api_key.into()),
             base_url: "wss://open.bigmodel.cn/api/realtime".to_string(),
+            auth: None,
         }
     }
 
+    /// Authenticate the WebSocket handshake with `auth` instead of the raw
+    /// API key
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Value of the `Authorization` header for the WebSocket handshake
+    pub fn authorization_header(&self) -> ZaiResult<String> {
+        let token = match &self.auth {
+            Some(auth) => auth.bearer_token()?,
+            None => self.api_key.to_string(),
+        };
+        Ok(format!("Bearer {}", token))
+    }
+
     /// Create a new client with custom base URL
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
@@ -68,6 +87,7 @@ impl Clone for RealTimeClient {
         Self {
             api_key: Arc::clone(&self.api_key),
             base_url: self.base_url.clone(),
+            auth: self.auth.clone(),
         }
     }
 }
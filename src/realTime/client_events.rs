@@ -0,0 +1,236 @@
+//! Real-time client events
+//!
+//! Events the client sends over the GLM-Realtime WebSocket. Every event
+//! carries an `event_id` and a millisecond `client_timestamp`; the
+//! constructors below fill both in, so sending audio is just:
+//!
+//! ```rust,ignore
+//! use zai_rs::realTime::ClientEvent;
+//!
+//! let update = ClientEvent::session_update(json!({"voice": "tongtong"}));
+//! let chunk = ClientEvent::input_audio_append(base64_pcm);
+//! let commit = ClientEvent::input_audio_commit();
+//! socket.send(chunk.to_json()?).await?;
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields shared by every client event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventBase {
+    /// Client-generated id echoed back in related server events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+
+    /// Milliseconds since the Unix epoch when the event was created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_timestamp: Option<i64>,
+}
+
+impl EventBase {
+    /// Base with a fresh `event_id` and the current time
+    pub fn now() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Self {
+            event_id: Some(generate_event_id()),
+            client_timestamp: Some(millis as i64),
+        }
+    }
+}
+
+/// Unique id for an outgoing event
+pub fn generate_event_id() -> String {
+    format!("event_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Events sent from the client to the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientEvent {
+    /// Update the session configuration
+    #[serde(rename = "session.update")]
+    SessionUpdate {
+        #[serde(flatten)]
+        base: EventBase,
+        session: Value,
+    },
+
+    /// Append base64-encoded audio to the input buffer
+    #[serde(rename = "input_audio_buffer.append")]
+    InputAudioBufferAppend {
+        #[serde(flatten)]
+        base: EventBase,
+        audio: String,
+    },
+
+    /// Commit the input buffer as a user message
+    #[serde(rename = "input_audio_buffer.commit")]
+    InputAudioBufferCommit {
+        #[serde(flatten)]
+        base: EventBase,
+    },
+
+    /// Discard the input buffer
+    #[serde(rename = "input_audio_buffer.clear")]
+    InputAudioBufferClear {
+        #[serde(flatten)]
+        base: EventBase,
+    },
+
+    /// Add an item to the conversation
+    #[serde(rename = "conversation.item.create")]
+    ConversationItemCreate {
+        #[serde(flatten)]
+        base: EventBase,
+        item: Value,
+    },
+
+    /// Ask the server to generate a response
+    #[serde(rename = "response.create")]
+    ResponseCreate {
+        #[serde(flatten)]
+        base: EventBase,
+    },
+
+    /// Cancel the response in progress
+    #[serde(rename = "response.cancel")]
+    ResponseCancel {
+        #[serde(flatten)]
+        base: EventBase,
+    },
+}
+
+impl ClientEvent {
+    /// `session.update` with the given session object
+    pub fn session_update(session: Value) -> Self {
+        ClientEvent::SessionUpdate {
+            base: EventBase::now(),
+            session,
+        }
+    }
+
+    /// `input_audio_buffer.append` with base64-encoded audio
+    pub fn input_audio_append(audio: impl Into<String>) -> Self {
+        ClientEvent::InputAudioBufferAppend {
+            base: EventBase::now(),
+            audio: audio.into(),
+        }
+    }
+
+    /// `input_audio_buffer.commit`
+    pub fn input_audio_commit() -> Self {
+        ClientEvent::InputAudioBufferCommit {
+            base: EventBase::now(),
+        }
+    }
+
+    /// `input_audio_buffer.clear`
+    pub fn input_audio_clear() -> Self {
+        ClientEvent::InputAudioBufferClear {
+            base: EventBase::now(),
+        }
+    }
+
+    /// `conversation.item.create` with the given item
+    pub fn conversation_item_create(item: Value) -> Self {
+        ClientEvent::ConversationItemCreate {
+            base: EventBase::now(),
+            item,
+        }
+    }
+
+    /// `response.create`
+    pub fn response_create() -> Self {
+        ClientEvent::ResponseCreate {
+            base: EventBase::now(),
+        }
+    }
+
+    /// `response.cancel`
+    pub fn response_cancel() -> Self {
+        ClientEvent::ResponseCancel {
+            base: EventBase::now(),
+        }
+    }
+
+    /// Fields shared by every event
+    pub fn base(&self) -> &EventBase {
+        match self {
+            ClientEvent::SessionUpdate { base, .. }
+            | ClientEvent::InputAudioBufferAppend { base, .. }
+            | ClientEvent::InputAudioBufferCommit { base }
+            | ClientEvent::InputAudioBufferClear { base }
+            | ClientEvent::ConversationItemCreate { base, .. }
+            | ClientEvent::ResponseCreate { base }
+            | ClientEvent::ResponseCancel { base } => base,
+        }
+    }
+
+    /// The event's `event_id`
+    pub fn event_id(&self) -> Option<&str> {
+        self.base().event_id.as_deref()
+    }
+
+    /// Serialize to the JSON text sent over the socket
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_constructors_set_type_and_ids() {
+        let cases = [
+            (
+                ClientEvent::session_update(json!({"voice": "tongtong"})),
+                "session.update",
+            ),
+            (
+                ClientEvent::input_audio_append("AAAA"),
+                "input_audio_buffer.append",
+            ),
+            (
+                ClientEvent::input_audio_commit(),
+                "input_audio_buffer.commit",
+            ),
+            (ClientEvent::input_audio_clear(), "input_audio_buffer.clear"),
+            (
+                ClientEvent::conversation_item_create(json!({"type": "message"})),
+                "conversation.item.create",
+            ),
+            (ClientEvent::response_create(), "response.create"),
+            (ClientEvent::response_cancel(), "response.cancel"),
+        ];
+        for (event, kind) in cases {
+            let value: Value = serde_json::from_str(&event.to_json().unwrap()).unwrap();
+            assert_eq!(value["type"], kind);
+            let id = value["event_id"].as_str().unwrap();
+            assert!(id.starts_with("event_") && id.len() > 6, "{}", id);
+            assert_eq!(Some(id), event.event_id());
+            assert!(value["client_timestamp"].as_i64().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_event_ids_are_unique_and_round_trip() {
+        let a = ClientEvent::input_audio_append("AAAA");
+        let b = ClientEvent::input_audio_append("AAAA");
+        assert_ne!(a.event_id(), b.event_id());
+
+        let value = serde_json::to_value(&a).unwrap();
+        assert_eq!(value["audio"], "AAAA");
+        let back: ClientEvent = serde_json::from_value(value).unwrap();
+        assert_eq!(back, a);
+    }
+}
@@ -11,6 +11,10 @@
 //! - [`RealTimeClient`] — Entry point for real-time sessions
 //! - [`RealTimeModel`] — Supported model identifiers
 //! - [`RealTimeSession`] — Manages a single real-time session
+//! - [`ClientEvent`] — Outgoing events with generated `event_id`s
+//! - [`ServerEventStream`] — Incoming events as an ordered `Stream`
+//! - [`AudioTranscriptionStream`] — Live transcription of streamed audio
+//! - [`audio`] — WAV wrapping for streamed PCM output ([`AudioSink`])
 //!
 //! # Usage
 //!
@@ -24,12 +28,20 @@
 //!     .await?;
 //! ```
 
+pub mod audio;
 pub mod client;
+pub mod client_events;
 pub mod models;
+pub mod server_events;
 pub mod session;
+pub mod transcription;
 pub mod types;
 
+pub use audio::{AudioSink, pcm_to_wav};
 pub use client::*;
+pub use client_events::*;
 pub use models::*;
+pub use server_events::*;
 pub use session::*;
+pub use transcription::*;
 pub use types::*;
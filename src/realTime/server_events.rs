@@ -0,0 +1,310 @@
+//! Real-time server events
+//!
+//! Events the GLM-Realtime server sends over the WebSocket, parsed from
+//! their JSON text frames. [`ServerEventStream`] turns any stream of text
+//! frames into an ordered stream of [`ServerEvent`]s, so events can be
+//! consumed with `while let` instead of a callback:
+//!
+//! ```rust,ignore
+//! use futures::StreamExt;
+//! use zai_rs::realTime::{AudioSink, ServerEvent, ServerEventStream};
+//!
+//! let mut events = ServerEventStream::new(text_frames);
+//! let mut sink = AudioSink::glm_realtime();
+//! while let Some(event) = events.next().await {
+//!     match event {
+//!         ServerEvent::ResponseAudioDelta { delta, .. } => sink.push_delta(&delta)?,
+//!         ServerEvent::ResponseAudioDone { .. } => break,
+//!         ServerEvent::Error { error, .. } => eprintln!("{}", error),
+//!         _ => {},
+//!     }
+//! }
+//! ```
+//!
+//! The stream does not own the socket; map the socket's incoming text
+//! messages to `String`s and pass that stream in.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Events sent from the server to the client.
+///
+/// Event types this crate does not model, and frames that fail to parse,
+/// are returned as [`ServerEvent::Unknown`] with the raw JSON (or the raw
+/// text, as a JSON string, when it is not JSON at all).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum ServerEvent {
+    /// The server rejected a client event or hit an internal error
+    #[serde(rename = "error")]
+    Error {
+        #[serde(default)]
+        event_id: Option<String>,
+        error: Value,
+    },
+
+    /// A session was created on connect
+    #[serde(rename = "session.created")]
+    SessionCreated {
+        #[serde(default)]
+        event_id: Option<String>,
+        session: Value,
+    },
+
+    /// The session configuration changed after `session.update`
+    #[serde(rename = "session.updated")]
+    SessionUpdated {
+        #[serde(default)]
+        event_id: Option<String>,
+        session: Value,
+    },
+
+    /// Server VAD detected the start of speech
+    #[serde(rename = "input_audio_buffer.speech_started")]
+    SpeechStarted {
+        #[serde(default)]
+        event_id: Option<String>,
+    },
+
+    /// Server VAD detected the end of speech
+    #[serde(rename = "input_audio_buffer.speech_stopped")]
+    SpeechStopped {
+        #[serde(default)]
+        event_id: Option<String>,
+    },
+
+    /// The input audio buffer was committed as a user message
+    #[serde(rename = "input_audio_buffer.committed")]
+    InputAudioCommitted {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        item_id: Option<String>,
+    },
+
+    /// Transcript of the user's committed audio
+    #[serde(rename = "conversation.item.input_audio_transcription.completed")]
+    InputTranscriptionCompleted {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        item_id: Option<String>,
+        transcript: String,
+    },
+
+    /// Partial transcript of the user's audio, while it is transcribed
+    #[serde(rename = "conversation.item.input_audio_transcription.delta")]
+    InputTranscriptionDelta {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        item_id: Option<String>,
+        delta: String,
+    },
+
+    /// The user's audio could not be transcribed
+    #[serde(rename = "conversation.item.input_audio_transcription.failed")]
+    InputTranscriptionFailed {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        item_id: Option<String>,
+        error: Value,
+    },
+
+    /// A response started
+    #[serde(rename = "response.created")]
+    ResponseCreated {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        response: Value,
+    },
+
+    /// Chunk of response text
+    #[serde(rename = "response.text.delta")]
+    ResponseTextDelta {
+        #[serde(default)]
+        event_id: Option<String>,
+        delta: String,
+    },
+
+    /// Chunk of the transcript of the spoken response
+    #[serde(rename = "response.audio_transcript.delta")]
+    ResponseAudioTranscriptDelta {
+        #[serde(default)]
+        event_id: Option<String>,
+        delta: String,
+    },
+
+    /// Chunk of response audio: base64-encoded PCM, see
+    /// [`AudioSink`](super::AudioSink)
+    #[serde(rename = "response.audio.delta")]
+    ResponseAudioDelta {
+        #[serde(default)]
+        event_id: Option<String>,
+        delta: String,
+    },
+
+    /// All audio of the response has been sent
+    #[serde(rename = "response.audio.done")]
+    ResponseAudioDone {
+        #[serde(default)]
+        event_id: Option<String>,
+    },
+
+    /// The response finished; `response` carries its status and usage
+    #[serde(rename = "response.done")]
+    ResponseDone {
+        #[serde(default)]
+        event_id: Option<String>,
+        #[serde(default)]
+        response: Value,
+    },
+
+    /// Keep-alive
+    #[serde(rename = "heartbeat")]
+    Heartbeat {
+        #[serde(default)]
+        event_id: Option<String>,
+    },
+
+    /// Any other event, as received
+    #[serde(skip)]
+    Unknown(Value),
+}
+
+impl ServerEvent {
+    /// Parse one text frame, falling back to [`ServerEvent::Unknown`]
+    pub fn parse(text: &str) -> Self {
+        match serde_json::from_str::<Value>(text) {
+            Ok(value) => {
+                serde_json::from_value(value.clone()).unwrap_or(ServerEvent::Unknown(value))
+            },
+            Err(_) => ServerEvent::Unknown(Value::String(text.to_string())),
+        }
+    }
+
+    /// The event's `type`, if it has one
+    pub fn event_type(&self) -> Option<&str> {
+        Some(match self {
+            ServerEvent::Error { .. } => "error",
+            ServerEvent::SessionCreated { .. } => "session.created",
+            ServerEvent::SessionUpdated { .. } => "session.updated",
+            ServerEvent::SpeechStarted { .. } => "input_audio_buffer.speech_started",
+            ServerEvent::SpeechStopped { .. } => "input_audio_buffer.speech_stopped",
+            ServerEvent::InputAudioCommitted { .. } => "input_audio_buffer.committed",
+            ServerEvent::InputTranscriptionCompleted { .. } => {
+                "conversation.item.input_audio_transcription.completed"
+            },
+            ServerEvent::InputTranscriptionDelta { .. } => {
+                "conversation.item.input_audio_transcription.delta"
+            },
+            ServerEvent::InputTranscriptionFailed { .. } => {
+                "conversation.item.input_audio_transcription.failed"
+            },
+            ServerEvent::ResponseCreated { .. } => "response.created",
+            ServerEvent::ResponseTextDelta { .. } => "response.text.delta",
+            ServerEvent::ResponseAudioTranscriptDelta { .. } => "response.audio_transcript.delta",
+            ServerEvent::ResponseAudioDelta { .. } => "response.audio.delta",
+            ServerEvent::ResponseAudioDone { .. } => "response.audio.done",
+            ServerEvent::ResponseDone { .. } => "response.done",
+            ServerEvent::Heartbeat { .. } => "heartbeat",
+            ServerEvent::Unknown(value) => return value.get("type")?.as_str(),
+        })
+    }
+}
+
+/// Ordered stream of [`ServerEvent`]s parsed from a stream of text frames.
+pub struct ServerEventStream<S> {
+    frames: S,
+}
+
+impl<S> ServerEventStream<S>
+where
+    S: Stream<Item = String> + Unpin,
+{
+    /// Parse the text frames yielded by `frames`
+    pub fn new(frames: S) -> Self {
+        Self { frames }
+    }
+
+    /// The wrapped frame stream
+    pub fn into_inner(self) -> S {
+        self.frames
+    }
+}
+
+impl<S> Stream for ServerEventStream<S>
+where
+    S: Stream<Item = String> + Unpin,
+{
+    type Item = ServerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ServerEvent>> {
+        Pin::new(&mut self.frames)
+            .poll_next(cx)
+            .map(|frame| frame.map(|text| ServerEvent::parse(&text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_parses_frames_in_order() {
+        let frames = vec![
+            json!({"type": "session.created", "event_id": "e1", "session": {"id": "s"}})
+                .to_string(),
+            json!({"type": "response.audio.delta", "delta": "AAAA"}).to_string(),
+            json!({"type": "response.brand_new", "x": 1}).to_string(),
+            "not json".to_string(),
+            json!({"type": "response.audio.done"}).to_string(),
+        ];
+        let events: Vec<ServerEvent> = ServerEventStream::new(futures::stream::iter(frames))
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[0],
+            ServerEvent::SessionCreated {
+                event_id: Some("e1".to_string()),
+                session: json!({"id": "s"}),
+            }
+        );
+        assert_eq!(
+            events[1],
+            ServerEvent::ResponseAudioDelta {
+                event_id: None,
+                delta: "AAAA".to_string(),
+            }
+        );
+        assert_eq!(
+            events[2],
+            ServerEvent::Unknown(json!({"type": "response.brand_new", "x": 1}))
+        );
+        assert_eq!(events[2].event_type(), Some("response.brand_new"));
+        assert_eq!(events[3], ServerEvent::Unknown(json!("not json")));
+        assert_eq!(events[4].event_type(), Some("response.audio.done"));
+    }
+
+    #[test]
+    fn test_known_type_with_bad_shape_is_unknown() {
+        let event = ServerEvent::parse(r#"{"type": "response.text.delta"}"#);
+        assert_eq!(
+            event,
+            ServerEvent::Unknown(json!({"type": "response.text.delta"}))
+        );
+    }
+}
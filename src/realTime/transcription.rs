@@ -0,0 +1,291 @@
+//! Streaming transcription
+//!
+//! [`AudioTranscriptionStream`] transcribes live audio over a GLM-Realtime
+//! transcription session. It sends each audio chunk as an
+//! `input_audio_buffer.append` event, commits the buffer when the audio ends,
+//! and turns the server's transcription events into [`TranscriptSegment`]s:
+//! partial text for `...input_audio_transcription.delta` and a final
+//! segment for `...input_audio_transcription.completed`. A
+//! `...input_audio_transcription.failed` or `error` event is yielded as an
+//! error.
+//!
+//! Like [`ServerEventStream`], it does not own the socket: pass the socket's
+//! outgoing half as a `Sink<String>` and its incoming text frames as a
+//! `Stream<Item = String>`.
+//!
+//! ```rust,ignore
+//! use futures::StreamExt;
+//! use zai_rs::realTime::AudioTranscriptionStream;
+//!
+//! let mut transcripts = AudioTranscriptionStream::new(pcm_chunks, outgoing, incoming)
+//!     .with_session(json!({"input_audio_format": "pcm"}));
+//! while let Some(segment) = transcripts.next().await {
+//!     let segment = segment?;
+//!     if segment.is_final {
+//!         println!("{}", segment.text);
+//!     }
+//! }
+//! ```
+
+use std::pin::Pin;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use futures::{Sink, SinkExt, Stream, StreamExt, stream};
+use serde_json::Value;
+
+use super::{ClientEvent, ServerEvent, ServerEventStream};
+use crate::client::error::{ZaiError, ZaiResult};
+
+/// A piece of transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptSegment {
+    /// Partial text for a delta, the whole utterance when final
+    pub text: String,
+    /// Whether the server finished transcribing this utterance
+    pub is_final: bool,
+    /// Conversation item the transcript belongs to
+    pub item_id: Option<String>,
+}
+
+impl TranscriptSegment {
+    /// Segment for a transcription event; errors for `failed` and `error`
+    /// events, `None` for events unrelated to transcription
+    pub fn from_event(event: ServerEvent) -> Option<ZaiResult<Self>> {
+        match event {
+            ServerEvent::InputTranscriptionDelta { item_id, delta, .. } => Some(Ok(Self {
+                text: delta,
+                is_final: false,
+                item_id,
+            })),
+            ServerEvent::InputTranscriptionCompleted {
+                item_id,
+                transcript,
+                ..
+            } => Some(Ok(Self {
+                text: transcript,
+                is_final: true,
+                item_id,
+            })),
+            ServerEvent::InputTranscriptionFailed { error, .. }
+            | ServerEvent::Error { error, .. } => Some(Err(ZaiError::from_error_object(&error))),
+            _ => None,
+        }
+    }
+}
+
+type BoxedAudio = Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>;
+type BoxedSink = Pin<Box<dyn Sink<String, Error = ZaiError> + Send>>;
+type BoxedFrames = Pin<Box<dyn Stream<Item = String> + Send>>;
+
+struct State {
+    audio: Option<BoxedAudio>,
+    outgoing: BoxedSink,
+    events: ServerEventStream<BoxedFrames>,
+    session: Option<Value>,
+    /// The audio ended and the buffer was committed
+    committed: bool,
+    done: bool,
+}
+
+/// Transcript segments of a stream of audio chunks; see the
+/// [module docs](self).
+///
+/// Audio is sent as soon as it is available. The stream ends with the
+/// first completed (or failed) transcription that arrives after the audio
+/// has ended, or when the incoming frames end.
+pub struct AudioTranscriptionStream {
+    /// Session not started yet, so it can still be configured
+    state: Option<State>,
+    segments: Option<Pin<Box<dyn Stream<Item = ZaiResult<TranscriptSegment>> + Send>>>,
+}
+
+impl AudioTranscriptionStream {
+    /// Transcribe `audio` (PCM or WAV chunks, as configured for the
+    /// session), sending client events to `outgoing` and reading server
+    /// events from `incoming`
+    pub fn new<A, K, S>(audio: A, outgoing: K, incoming: S) -> Self
+    where
+        A: Stream<Item = Vec<u8>> + Send + 'static,
+        K: Sink<String> + Send + 'static,
+        K::Error: std::fmt::Display,
+        S: Stream<Item = String> + Send + 'static,
+    {
+        let outgoing = outgoing.sink_map_err(|e| ZaiError::Unknown {
+            code: 0,
+            message: format!("failed to send realtime event: {}", e),
+        });
+        let incoming: BoxedFrames = Box::pin(incoming);
+        Self {
+            state: Some(State {
+                audio: Some(Box::pin(audio)),
+                outgoing: Box::pin(outgoing),
+                events: ServerEventStream::new(incoming),
+                session: None,
+                committed: false,
+                done: false,
+            }),
+            segments: None,
+        }
+    }
+
+    /// Send `session.update` with `session` (e.g. the input audio format)
+    /// before any audio
+    pub fn with_session(mut self, session: Value) -> Self {
+        if let Some(state) = &mut self.state {
+            state.session = Some(session);
+        }
+        self
+    }
+}
+
+impl Stream for AudioTranscriptionStream {
+    type Item = ZaiResult<TranscriptSegment>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        if let Some(state) = self.state.take() {
+            self.segments = Some(Box::pin(transcribe(state)));
+        }
+        match self.segments.as_mut() {
+            Some(segments) => segments.as_mut().poll_next(cx),
+            None => std::task::Poll::Ready(None),
+        }
+    }
+}
+
+fn transcribe(state: State) -> impl Stream<Item = ZaiResult<TranscriptSegment>> + Send {
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        if let Some(session) = state.session.take()
+            && let Err(e) = send(&mut state.outgoing, ClientEvent::session_update(session)).await
+        {
+            state.done = true;
+            return Some((Err(e), state));
+        }
+        loop {
+            tokio::select! {
+                biased;
+                chunk = next_chunk(&mut state.audio), if state.audio.is_some() => {
+                    let event = match chunk {
+                        Some(bytes) => ClientEvent::input_audio_append(STANDARD.encode(bytes)),
+                        None => {
+                            state.audio = None;
+                            state.committed = true;
+                            ClientEvent::input_audio_commit()
+                        },
+                    };
+                    if let Err(e) = send(&mut state.outgoing, event).await {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                },
+                frame = state.events.next() => {
+                    let event = frame?;
+                    let utterance_done = matches!(
+                        event,
+                        ServerEvent::InputTranscriptionCompleted { .. }
+                            | ServerEvent::InputTranscriptionFailed { .. }
+                    );
+                    if let Some(item) = TranscriptSegment::from_event(event) {
+                        state.done = state.committed && utterance_done;
+                        return Some((item, state));
+                    }
+                },
+            }
+        }
+    })
+}
+
+async fn next_chunk(audio: &mut Option<BoxedAudio>) -> Option<Vec<u8>> {
+    match audio {
+        Some(audio) => audio.next().await,
+        None => None,
+    }
+}
+
+async fn send(outgoing: &mut BoxedSink, event: ClientEvent) -> ZaiResult<()> {
+    outgoing.send(event.to_json()?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_synthetic_session() {
+        let (out_tx, mut out_rx) = mpsc::unbounded::<String>();
+        let (in_tx, in_rx) = mpsc::unbounded::<String>();
+        let audio = stream::iter(vec![vec![1u8, 2], vec![3u8, 4]]);
+        let mut transcripts = AudioTranscriptionStream::new(audio, out_tx, in_rx)
+            .with_session(json!({"input_audio_format": "pcm"}));
+
+        let frames = [
+            json!({"type": "session.created", "session": {}}),
+            json!({"type": "conversation.item.input_audio_transcription.delta",
+                   "item_id": "item_1", "delta": "hel"}),
+            json!({"type": "error", "error": {"code": "1214", "message": "bad audio"}}),
+            json!({"type": "conversation.item.input_audio_transcription.completed",
+                   "item_id": "item_1", "transcript": "hello"}),
+            json!({"type": "response.done"}),
+        ];
+        for frame in frames {
+            in_tx.unbounded_send(frame.to_string()).unwrap();
+        }
+
+        let partial = transcripts.next().await.unwrap().unwrap();
+        assert_eq!(partial.text, "hel");
+        assert!(!partial.is_final);
+        let err = transcripts.next().await.unwrap().unwrap_err();
+        assert!(
+            matches!(&err, ZaiError::ApiError { code: 1214, message } if message == "bad audio"),
+            "{:?}",
+            err
+        );
+        let last = transcripts.next().await.unwrap().unwrap();
+        assert_eq!(
+            last,
+            TranscriptSegment {
+                text: "hello".to_string(),
+                is_final: true,
+                item_id: Some("item_1".to_string()),
+            }
+        );
+        // The audio was committed and transcribed, so the stream is done
+        // even though the socket is still open.
+        assert!(transcripts.next().await.is_none());
+
+        let sent: Vec<Value> = std::iter::from_fn(|| out_rx.try_recv().ok())
+            .map(|frame| serde_json::from_str(&frame).unwrap())
+            .collect();
+        let kinds: Vec<&str> = sent.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "session.update",
+                "input_audio_buffer.append",
+                "input_audio_buffer.append",
+                "input_audio_buffer.commit"
+            ]
+        );
+        assert_eq!(sent[1]["audio"], STANDARD.encode([1u8, 2]));
+    }
+
+    #[test]
+    fn test_failed_transcription_is_an_error() {
+        let event = ServerEvent::parse(
+            &json!({"type": "conversation.item.input_audio_transcription.failed",
+                    "item_id": "item_1", "error": {"message": "no speech"}})
+            .to_string(),
+        );
+        let err = TranscriptSegment::from_event(event).unwrap().unwrap_err();
+        assert!(matches!(err, ZaiError::ApiError { code: 0, .. }));
+        assert!(TranscriptSegment::from_event(ServerEvent::Heartbeat { event_id: None }).is_none());
+    }
+}
@@ -3,10 +3,16 @@
 //! This module provides the file parser result client for retrieving file
 //! parsing results.
 
+use std::{borrow::Cow, future::Future, time::Duration};
+
 use serde_json;
 
 use super::{request::*, response::*};
-use crate::ZaiResult;
+use crate::{
+    ZaiError, ZaiResult,
+    poll::{self, PollConfig, PollError},
+    toolkits::error::{ToolError, ToolResult, error_context},
+};
 
 /// File parser result client.
 ///
@@ -68,29 +74,20 @@ impl FileParserResultRequest {
             self.task_id, format_type
         );
 
-        println!("📤 Sending request to: {}", url);
-        println!("🔑 Using API key: {}...", &self.key[..10]);
-
         let client = reqwest::Client::new();
         let response = client.get(&url).bearer_auth(&self.key).send().await?;
 
         let status = response.status();
-        println!("📡 Response status: {}", status);
-
         if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            println!("❌ Error response: {}", error_text);
-            return Err(crate::client::error::ZaiError::HttpError {
+            return Err(ZaiError::HttpError {
                 status: status.as_u16(),
                 message: error_text,
             });
         }
 
         let response_body = response.text().await?;
-        println!("📄 Raw response body: {}", response_body);
-
         let result_response: FileParserResultResponse = serde_json::from_str(&response_body)?;
-        println!("✅ Parsed response: {:?}", result_response);
         Ok(result_response)
     }
 
@@ -105,53 +102,79 @@ impl FileParserResultRequest {
     /// ## Returns
     ///
     /// A `FileParserResultResponse` containing the parsing result.
+    ///
+    /// ## Errors
+    ///
+    /// * `ApiError` with the server's message if parsing failed
+    /// * `HttpError` 408 naming the last status if the timeout elapses
+    /// * the error of a failed status request
     pub async fn wait_for_result(
         &self,
         format_type: FormatType,
         timeout_seconds: u64,
         poll_interval_seconds: u64,
     ) -> ZaiResult<FileParserResultResponse> {
-        println!(
-            "⏳ Starting polling for result (timeout: {}s, interval: {}s)",
-            timeout_seconds, poll_interval_seconds
+        let config = PollConfig::new(
+            Duration::from_secs(poll_interval_seconds),
+            Duration::from_secs(timeout_seconds),
         );
-        let start_time = std::time::Instant::now();
-
-        loop {
-            println!("⏰ Checking result status...");
-            let result = self.get_result(format_type.clone()).await?;
-
-            match result.status {
-                ParserStatus::Succeeded => {
-                    println!("🎉 Parsing completed successfully!");
-                    return Ok(result);
-                },
-                ParserStatus::Failed => {
-                    println!("💥 Parsing failed: {}", result.message);
-                    return Err(crate::client::error::ZaiError::ApiError {
-                        code: 0,
-                        message: format!("Parsing failed: {}", result.message),
-                    });
-                },
-                ParserStatus::Processing => {
-                    let elapsed = start_time.elapsed().as_secs();
-                    println!("⏳ Still processing... ({}s elapsed)", elapsed);
-                    if elapsed > timeout_seconds {
-                        println!("⏰ Timeout reached!");
-                        return Err(crate::client::error::ZaiError::RateLimitError {
-                            code: 0,
-                            message: "Timeout waiting for parsing result".to_string(),
-                        });
-                    }
-                    println!(
-                        "⏱️  Waiting {} seconds before next check...",
-                        poll_interval_seconds
-                    );
-                    tokio::time::sleep(tokio::time::Duration::from_secs(poll_interval_seconds))
-                        .await;
-                },
-            }
+        let result = poll::until(
+            || self.get_result(format_type.clone()),
+            |r: &FileParserResultResponse| r.status != ParserStatus::Processing,
+            config,
+        )
+        .await
+        .map_err(|e| match e {
+            PollError::TimedOut { last, elapsed } => ZaiError::from(PollError::TimedOut {
+                last: last.status,
+                elapsed,
+            }),
+            PollError::Failed(e) => e,
+        })?;
+
+        if result.status == ParserStatus::Failed {
+            return Err(ZaiError::ApiError {
+                code: 0,
+                message: format!("Parsing failed: {}", result.message),
+            });
         }
+        Ok(result)
+    }
+
+    /// Polls the task every `interval` until it leaves `Processing`.
+    ///
+    /// Returns the response once the task has succeeded; the parsed content
+    /// is available via [`FileParserResultResponse::get_result`].
+    ///
+    /// ## Errors
+    ///
+    /// * [`ToolError::ParserFailed`] with the server's message if the task
+    ///   failed
+    /// * [`ToolError::PollTimeout`] if `timeout` elapses first; it carries
+    ///   the last observed [`ParserStatus`] (as text) so the caller can keep
+    ///   waiting
+    /// * [`ToolError::ExecutionFailed`] if a status request fails
+    pub async fn poll_result(
+        &self,
+        format_type: FormatType,
+        interval: Duration,
+        timeout: Duration,
+    ) -> ToolResult<FileParserResultResponse> {
+        self.poll_result_with(format_type, PollConfig::new(interval, timeout))
+            .await
+    }
+
+    /// [`poll_result`](Self::poll_result) with backoff, transient-error
+    /// retries or a progress callback from `config`
+    pub async fn poll_result_with(
+        &self,
+        format_type: FormatType,
+        config: PollConfig,
+    ) -> ToolResult<FileParserResultResponse> {
+        poll_until_done(&self.task_id, config, || {
+            self.get_result(format_type.clone())
+        })
+        .await
     }
 
     /// Gets both text and download link results in a single request.
@@ -167,3 +190,136 @@ impl FileParserResultRequest {
         Ok((text_result, download_result))
     }
 }
+
+/// Drive `fetch` until the task leaves `Processing`, mapping the outcome to
+/// tool errors.
+async fn poll_until_done<F, Fut>(
+    task_id: &str,
+    config: PollConfig,
+    fetch: F,
+) -> ToolResult<FileParserResultResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ZaiResult<FileParserResultResponse>>,
+{
+    let outcome = poll::until(
+        fetch,
+        |r: &FileParserResultResponse| r.status != ParserStatus::Processing,
+        config,
+    )
+    .await;
+    match outcome {
+        Ok(result) if result.status == ParserStatus::Failed => Err(ToolError::ParserFailed {
+            task_id: Cow::Owned(task_id.to_string()),
+            reason: Cow::Owned(result.message),
+        }),
+        Ok(result) => Ok(result),
+        Err(PollError::TimedOut { last, elapsed }) => Err(ToolError::PollTimeout {
+            task_id: Cow::Owned(task_id.to_string()),
+            elapsed,
+            last_status: Cow::Owned(last.status.to_string()),
+        }),
+        Err(PollError::Failed(e)) => Err(error_context()
+            .with_tool("file_parser")
+            .execution_failed(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn response(status: ParserStatus, message: &str) -> FileParserResultResponse {
+        FileParserResultResponse {
+            status,
+            message: message.to_string(),
+            task_id: "task-1".to_string(),
+            content: Some("parsed".to_string()),
+            parsing_result_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_processing_then_success() {
+        let calls = AtomicUsize::new(0);
+        let result = poll_until_done(
+            "task-1",
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5)),
+            || {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                let status = if n < 2 {
+                    ParserStatus::Processing
+                } else {
+                    ParserStatus::Succeeded
+                };
+                async move { Ok(response(status, "ok")) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(result.get_result(&FormatType::Text), Some("parsed"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_failed_reports_reason() {
+        let err = poll_until_done(
+            "task-1",
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5)),
+            || async { Ok(response(ParserStatus::Failed, "unsupported file")) },
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            ToolError::ParserFailed { task_id, reason } => {
+                assert_eq!(task_id, "task-1");
+                assert_eq!(reason, "unsupported file");
+            },
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_timeout_exposes_last_status() {
+        let err = poll_until_done(
+            "task-1",
+            PollConfig::new(Duration::from_millis(5), Duration::from_millis(20)),
+            || async { Ok(response(ParserStatus::Processing, "")) },
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            ToolError::PollTimeout {
+                elapsed,
+                last_status,
+                ..
+            } => {
+                assert_eq!(last_status, ParserStatus::Processing.to_string());
+                assert!(elapsed >= Duration::from_millis(20));
+            },
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_request_error() {
+        let err = poll_until_done(
+            "task-1",
+            PollConfig::new(Duration::from_millis(1), Duration::from_secs(5)),
+            || async {
+                Err(ZaiError::HttpError {
+                    status: 500,
+                    message: "boom".to_string(),
+                })
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::ExecutionFailed { .. }));
+    }
+}
@@ -1,15 +1,24 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
+use futures::{Stream, StreamExt, stream};
 
 use crate::{
-    ZaiResult,
-    client::http::HttpClient,
-    tool::web_search::{request::*, response::*},
+    ZaiError, ZaiResult,
+    client::{
+        endpoint::Endpoint,
+        http::{HttpClient, http_client_with_config, parse_api_error_response},
+    },
+    tool::web_search::{request::*, response, response::*},
+    toolkits::executor::RetryConfig,
 };
 
 /// Web search API client
 pub struct WebSearchRequest {
     /// API key for authentication
     pub key: String,
+    /// Base URL override
+    endpoint: Option<Endpoint>,
     /// Request body
     body: WebSearchBody,
 }
@@ -24,13 +33,24 @@ impl WebSearchRequest {
     pub fn new(key: String, search_query: String, search_engine: SearchEngine) -> Self {
         Self {
             key,
+            endpoint: None,
             body: WebSearchBody::new(search_query, search_engine),
         }
     }
 
+    /// Send this request to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     /// Create a web search request with a pre-configured body
     pub fn with_body(key: String, body: WebSearchBody) -> Self {
-        Self { key, body }
+        Self {
+            key,
+            endpoint: None,
+            body,
+        }
     }
 
     /// Enable search intent recognition
@@ -39,7 +59,9 @@ impl WebSearchRequest {
         self
     }
 
-    /// Set the number of results to return
+    /// Set the number of results to return. Counts above the engine's
+    /// [`max_results`](SearchEngine::max_results) are rejected by
+    /// [`validate`](Self::validate) before anything is sent.
     pub fn with_count(mut self, count: i32) -> Self {
         self.body = self.body.with_count(count);
         self
@@ -80,13 +102,109 @@ impl WebSearchRequest {
         self.body.validate_constraints()
     }
 
-    /// Send the web search request and return the response
+    /// Send the web search request and return the response.
+    ///
+    /// This makes no search-level retries: a rate-limited (429) response
+    /// fails immediately. Use [`send_with_retry`](Self::send_with_retry) to
+    /// back off and try again.
     pub async fn send(&self) -> ZaiResult<WebSearchResponse> {
         self.validate()?;
         let resp: reqwest::Response = self.post().await?;
         let parsed = resp.json::<WebSearchResponse>().await?;
         Ok(parsed)
     }
+
+    /// Send the request, retrying rate limits (429), server errors (5xx)
+    /// and network failures with exponential backoff.
+    ///
+    /// A `Retry-After` header (in seconds) replaces the computed delay, capped
+    /// at `retry.max_delay`. Other errors, such as an invalid query, fail
+    /// without retrying.
+    pub async fn send_with_retry(&self, retry: RetryConfig) -> ZaiResult<WebSearchResponse> {
+        self.send_with_retry_count(retry)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    /// Like [`send_with_retry`](Self::send_with_retry), also returning how
+    /// many retries were used.
+    pub async fn send_with_retry_count(
+        &self,
+        retry: RetryConfig,
+    ) -> ZaiResult<(WebSearchResponse, u32)> {
+        self.validate()?;
+        let mut retries = 0;
+        loop {
+            match self.attempt().await {
+                Ok(response) => return Ok((response, retries)),
+                Err((err, retry_after)) => {
+                    if retries >= retry.max_retries || !is_retryable(&err) {
+                        return Err(err);
+                    }
+                    retries += 1;
+                    let delay = retry_after
+                        .map(|d| d.min(retry.max_delay))
+                        .unwrap_or_else(|| retry.calculate_delay(retries));
+                    tokio::time::sleep(delay).await;
+                },
+            }
+        }
+    }
+
+    /// One request without HTTP-level retries, returning any `Retry-After`
+    /// delay alongside an error.
+    async fn attempt(&self) -> Result<WebSearchResponse, (ZaiError, Option<Duration>)> {
+        let token = self.bearer_token().map_err(|e| (e, None))?;
+        let client = http_client_with_config(&self.http_config());
+        let resp = client
+            .post(self.request_url())
+            .bearer_auth(token)
+            .json(&self.body)
+            .send()
+            .await
+            .map_err(|e| (ZaiError::from(e), None))?;
+
+        let status = resp.status();
+        if status.is_success() {
+            return resp
+                .json::<WebSearchResponse>()
+                .await
+                .map_err(|e| (ZaiError::from(e), None));
+        }
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let text = resp.text().await.unwrap_or_default();
+        Err((parse_api_error_response(status.as_u16(), text), retry_after))
+    }
+
+    /// Stream search results one at a time.
+    ///
+    /// Sends the request once and yields the results of the response
+    /// individually; the web search API has no continuation token to page
+    /// further. HTTP and validation errors are yielded as the only stream
+    /// item.
+    pub fn stream(self) -> impl Stream<Item = ZaiResult<response::SearchResult>> + Send + 'static {
+        stream::once(async move { self.send().await }).flat_map(|response| {
+            let items = match response {
+                Ok(response) => response.search_result.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(items)
+        })
+    }
+}
+
+/// Whether [`WebSearchRequest::send_with_retry`] retries after `err`.
+fn is_retryable(err: &ZaiError) -> bool {
+    match err.inner() {
+        ZaiError::HttpError { status, .. } => *status == 429 || (500..600).contains(status),
+        ZaiError::RateLimitError { .. } | ZaiError::NetworkError(_) => true,
+        _ => false,
+    }
 }
 
 #[async_trait]
@@ -106,4 +224,123 @@ impl HttpClient for WebSearchRequest {
     fn body(&self) -> &Self::Body {
         &self.body
     }
+    fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ZaiError,
+        client::test_server::{Reply, received, serve_in_order},
+    };
+
+    fn rate_limited() -> Reply {
+        Reply::status(429).header("retry-after", "0")
+    }
+
+    fn ok() -> Reply {
+        Reply::json(
+            200,
+            r#"{"id":"ws-1","created":1,"request_id":"r","search_intent":[],"search_result":[]}"#,
+        )
+    }
+
+    fn quick_retry() -> RetryConfig {
+        RetryConfig {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            backoff_multiplier: 2.0,
+        }
+    }
+
+    fn request(base: &str) -> WebSearchRequest {
+        WebSearchRequest::new(
+            "key".to_string(),
+            "rust".to_string(),
+            SearchEngine::SearchStd,
+        )
+        .with_endpoint(Endpoint::new(format!("{}/api", base)))
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_backs_off_on_rate_limit() {
+        let (base, mut requests) = serve_in_order(vec![rate_limited(), rate_limited(), ok()]).await;
+        let (response, retries) = request(&base)
+            .send_with_retry_count(quick_retry())
+            .await
+            .unwrap();
+        assert_eq!(response.task_id(), "ws-1");
+        assert_eq!(retries, 2);
+        assert_eq!(received(&mut requests).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_fails_fast_on_client_error() {
+        let (base, mut requests) = serve_in_order(vec![Reply::status(400), ok()]).await;
+        let err = request(&base)
+            .send_with_retry(quick_retry())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZaiError::HttpError { status: 400, .. }));
+        assert_eq!(received(&mut requests).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_gives_up_after_max_retries() {
+        let (base, mut requests) = serve_in_order(vec![rate_limited(), rate_limited()]).await;
+        let retry = RetryConfig {
+            max_retries: 1,
+            ..quick_retry()
+        };
+        let err = request(&base).send_with_retry(retry).await.unwrap_err();
+        assert!(matches!(err, ZaiError::HttpError { status: 429, .. }));
+        assert_eq!(received(&mut requests).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_over_count_fails_before_sending() {
+        // Nothing listens on port 9; validation must fail first.
+        let request = WebSearchRequest::new(
+            "key".to_string(),
+            "rust".to_string(),
+            SearchEngine::SearchPro,
+        )
+        .with_endpoint(Endpoint::new("http://127.0.0.1:9/api"))
+        .with_count(51);
+        let err = request.send().await.unwrap_err();
+        assert!(
+            matches!(err, ZaiError::ApiError { code: 1200, .. }),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_results_of_one_response() {
+        let (base, mut requests) = serve_in_order(vec![Reply::json(
+            200,
+            r#"{"id":"ws-1","created":1,"request_id":"r","search_intent":[],"search_result":[
+                {"title":"a","content":"","link":"https://a","media":"","icon":"","refer":"1","publish_date":""},
+                {"title":"b","content":"","link":"https://b","media":"","icon":"","refer":"2","publish_date":""}
+            ]}"#,
+        )])
+        .await;
+        let items: Vec<_> = request(&base).stream().collect().await;
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(Result::is_ok));
+        assert_eq!(received(&mut requests).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_validation_error() {
+        let request =
+            WebSearchRequest::new("key".to_string(), "x".repeat(71), SearchEngine::SearchStd);
+        let items: Vec<_> = request.stream().collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+    }
 }
@@ -15,6 +15,17 @@ pub enum SearchEngine {
     SearchProQuark,
 }
 
+impl SearchEngine {
+    /// Largest `count` this engine accepts.
+    ///
+    /// The API documents the same limit of 50 for every engine;
+    /// `search_pro_sogou` additionally only accepts multiples of 10, which
+    /// [`WebSearchBody::validate_constraints`] checks.
+    pub fn max_results(&self) -> u32 {
+        50
+    }
+}
+
 /// Search result recency filter options
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -138,7 +149,9 @@ impl WebSearchBody {
         self
     }
 
-    /// Set the number of results to return
+    /// Set the number of results to return. Counts above
+    /// [`SearchEngine::max_results`] fail
+    /// [`validate_constraints`](Self::validate_constraints).
     pub fn with_count(mut self, count: i32) -> Self {
         self.count = Some(count);
         self
@@ -176,6 +189,22 @@ impl WebSearchBody {
 
     /// Validate the request body constraints
     pub fn validate_constraints(&self) -> crate::ZaiResult<()> {
+        // Engine limit first, for a clearer message than the range check
+        if let Some(count) = self.count
+            && count > 0
+            && count as u32 > self.search_engine.max_results()
+        {
+            return Err(crate::client::error::ZaiError::ApiError {
+                code: 1200,
+                message: format!(
+                    "count {} exceeds the {} results {:?} returns at most",
+                    count,
+                    self.search_engine.max_results(),
+                    self.search_engine
+                ),
+            });
+        }
+
         self.validate()
             .map_err(|e| crate::client::error::ZaiError::ApiError {
                 code: 1200,
@@ -201,3 +230,33 @@ impl WebSearchBody {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGINES: [SearchEngine; 4] = [
+        SearchEngine::SearchStd,
+        SearchEngine::SearchPro,
+        SearchEngine::SearchProSogou,
+        SearchEngine::SearchProQuark,
+    ];
+
+    #[test]
+    fn test_engine_capabilities() {
+        for engine in ENGINES {
+            assert_eq!(engine.max_results(), 50, "{:?}", engine);
+        }
+    }
+
+    #[test]
+    fn test_count_over_engine_limit_fails_client_side() {
+        for engine in ENGINES {
+            let body = WebSearchBody::new("rust".to_string(), engine.clone());
+            let max = engine.max_results() as i32;
+            assert!(body.clone().with_count(max).validate_constraints().is_ok());
+            let err = body.with_count(max + 1).validate_constraints().unwrap_err();
+            assert!(err.to_string().contains("exceeds"), "{}", err);
+        }
+    }
+}
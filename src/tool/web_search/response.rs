@@ -41,7 +41,9 @@ pub struct WebSearchResponse {
     pub created: i64,
     /// Request identifier
     pub request_id: String,
-    /// Search intent results
+    /// Search intent results (may be absent when intent recognition is
+    /// disabled)
+    #[serde(default)]
     pub search_intent: Vec<SearchIntent>,
     /// Search results
     pub search_result: Vec<SearchResult>,
@@ -93,6 +95,25 @@ impl WebSearchResponse {
         &self.search_result
     }
 
+    /// The first recognized intent, usually the only one.
+    ///
+    /// With intent recognition disabled (`with_search_intent(false)`) the
+    /// service does not analyse the query: the entry, if any, echoes the
+    /// original query with intent `SEARCH_ALWAYS` rather than a rewritten
+    /// query and keywords, and `None` is returned when it is left out.
+    pub fn primary_intent(&self) -> Option<&SearchIntent> {
+        self.search_intent.first()
+    }
+
+    /// Results ordered by their reference index (`ref_1`, `ref_2`, ...),
+    /// which the service assigns from most to least relevant. Results
+    /// without a parsable index keep their relative order at the end.
+    pub fn results_sorted_by_relevance(&self) -> Vec<&SearchResult> {
+        let mut results: Vec<&SearchResult> = self.search_result.iter().collect();
+        results.sort_by_key(|r| r.rank().unwrap_or(u32::MAX));
+        results
+    }
+
     /// Get the task ID
     pub fn task_id(&self) -> &str {
         &self.id
@@ -108,3 +129,85 @@ impl WebSearchResponse {
         &self.request_id
     }
 }
+
+impl SearchResult {
+    /// Quoted text from the result page
+    pub fn snippet(&self) -> &str {
+        &self.content
+    }
+
+    /// URL of the result page
+    pub fn link(&self) -> &str {
+        &self.link
+    }
+
+    /// Position in the service's relevance order, parsed from `refer`
+    /// (`"ref_3"` -> 3)
+    pub fn rank(&self) -> Option<u32> {
+        let digits = self.refer.trim_start_matches(|c: char| !c.is_ascii_digit());
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn result(refer: &str, link: &str) -> serde_json::Value {
+        json!({
+            "title": "t", "content": format!("about {}", link), "link": link,
+            "media": "m", "icon": "", "refer": refer, "publish_date": "2025-01-01"
+        })
+    }
+
+    #[test]
+    fn test_accessors_over_sample_response() {
+        let response: WebSearchResponse = serde_json::from_value(json!({
+            "id": "ws-1",
+            "created": 1,
+            "request_id": "r",
+            "search_intent": [
+                {"query": "rust async runtime", "intent": "SEARCH_ALL", "keywords": "rust async"}
+            ],
+            "search_result": [
+                result("ref_2", "https://b.example"),
+                result("", "https://z.example"),
+                result("ref_10", "https://c.example"),
+                result("ref_1", "https://a.example"),
+            ]
+        }))
+        .unwrap();
+
+        let intent = response.primary_intent().unwrap();
+        assert_eq!(intent.query, "rust async runtime");
+        assert_eq!(intent.intent, "SEARCH_ALL");
+
+        let links: Vec<&str> = response
+            .results_sorted_by_relevance()
+            .into_iter()
+            .map(SearchResult::link)
+            .collect();
+        assert_eq!(
+            links,
+            [
+                "https://a.example",
+                "https://b.example",
+                "https://c.example",
+                "https://z.example"
+            ]
+        );
+        assert_eq!(response.results()[0].snippet(), "about https://b.example");
+    }
+
+    #[test]
+    fn test_missing_intent_list() {
+        let response: WebSearchResponse = serde_json::from_value(json!({
+            "id": "ws-2", "created": 1, "request_id": "r", "search_result": []
+        }))
+        .unwrap();
+        assert!(response.primary_intent().is_none());
+        assert!(response.results_sorted_by_relevance().is_empty());
+    }
+}
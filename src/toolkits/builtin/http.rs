@@ -0,0 +1,378 @@
+//! A [`DynTool`] that forwards its input to an HTTP endpoint.
+//!
+//! The tool is configured with a method and a URL template. Placeholders like
+//! `{city}` are filled from the top-level fields of the tool input (values
+//! are percent-encoded); the remaining fields are sent according to the
+//! [`InputMapping`]. The parsed JSON response becomes the tool output, a
+//! non-JSON body is returned as a string, and non-2xx responses fail with
+//! [`ToolError::ExecutionFailed`](crate::toolkits::error::ToolError).
+//!
+//! The tool sets no timeout of its own; executions are bounded by the
+//! executor's [`timeout`](crate::toolkits::executor::ExecutorBuilder::timeout).
+//!
+//! ```rust,ignore
+//! use zai_rs::toolkits::{builtin::HttpTool, prelude::*};
+//!
+//! let weather = HttpTool::builder(
+//!     "get_weather",
+//!     "Current weather for a city",
+//!     reqwest::Method::GET,
+//!     "https://api.example.com/weather/{city}",
+//! )
+//! .header("X-Api-Key", "secret")
+//! .schema(json!({
+//!     "type": "object",
+//!     "properties": { "city": {"type": "string"}, "units": {"type": "string"} },
+//!     "required": ["city"]
+//! }))
+//! .build()?;
+//! // {"city": "Tokyo", "units": "metric"} -> GET .../weather/Tokyo?units=metric
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::{
+    Method,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use serde_json::{Map, Value};
+
+use crate::toolkits::{
+    core::{DynTool, ToolMetadata, compile_schema_cached},
+    error::{ToolResult, error_context},
+};
+
+/// How input fields not consumed by the URL template are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMapping {
+    /// As a JSON request body
+    Body,
+    /// As query parameters (nested values are sent as JSON text)
+    Query,
+    /// Not sent
+    Ignore,
+}
+
+impl InputMapping {
+    /// Query parameters for GET/HEAD/DELETE, a JSON body otherwise
+    fn default_for(method: &Method) -> Self {
+        if matches!(*method, Method::GET | Method::HEAD | Method::DELETE) {
+            Self::Query
+        } else {
+            Self::Body
+        }
+    }
+}
+
+/// Tool backed by an HTTP endpoint.
+#[derive(Clone)]
+pub struct HttpTool {
+    metadata: ToolMetadata,
+    input_schema: Value,
+    compiled_schema: Arc<jsonschema::Validator>,
+    method: Method,
+    url_template: String,
+    headers: HeaderMap,
+    mapping: InputMapping,
+    client: reqwest::Client,
+}
+
+impl std::fmt::Debug for HttpTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values may hold credentials
+        f.debug_struct("HttpTool")
+            .field("name", &self.metadata.name)
+            .field("method", &self.method)
+            .field("url_template", &self.url_template)
+            .field("mapping", &self.mapping)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpTool {
+    pub fn builder(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        method: Method,
+        url_template: impl Into<String>,
+    ) -> HttpToolBuilder {
+        HttpToolBuilder::new(name, description, method, url_template)
+    }
+
+    /// Fill the URL template from `input`, returning the URL and the input
+    /// fields it did not use.
+    fn render_url(&self, input: &Value) -> ToolResult<(String, Map<String, Value>)> {
+        let mut rest = match input {
+            Value::Object(map) => map.clone(),
+            Value::Null => Map::new(),
+            _ => {
+                return Err(error_context()
+                    .with_tool(self.name())
+                    .invalid_parameters("HTTP tool input must be a JSON object"));
+            },
+        };
+
+        let mut url = String::with_capacity(self.url_template.len());
+        let mut tail = self.url_template.as_str();
+        while let Some(start) = tail.find('{') {
+            let Some(len) = tail[start..].find('}') else {
+                break;
+            };
+            let key = &tail[start + 1..start + len];
+            let value = rest.remove(key).ok_or_else(|| {
+                error_context()
+                    .with_tool(self.name())
+                    .invalid_parameters(format!("Missing URL parameter '{}'", key))
+            })?;
+            url.push_str(&tail[..start]);
+            url.push_str(&percent_encode(&scalar_text(&value)));
+            tail = &tail[start + len + 1..];
+        }
+        url.push_str(tail);
+        Ok((url, rest))
+    }
+}
+
+/// Text form of a value for URLs: strings unquoted, other values as JSON.
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl DynTool for HttpTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    async fn execute_json(&self, input: Value) -> ToolResult<Value> {
+        if let Err(validation_error) = self.compiled_schema.validate(&input) {
+            return Err(error_context()
+                .with_tool(self.name())
+                .invalid_parameters(format!("Input validation failed: {}", validation_error)));
+        }
+
+        let (url, rest) = self.render_url(&input)?;
+        let mut url = url::Url::parse(&url).map_err(|e| {
+            error_context()
+                .with_tool(self.name())
+                .invalid_parameters(format!("Invalid URL '{}': {}", url, e))
+        })?;
+        if self.mapping == InputMapping::Query && !rest.is_empty() {
+            let mut pairs = url.query_pairs_mut();
+            for (key, value) in &rest {
+                pairs.append_pair(key, &scalar_text(value));
+            }
+        }
+
+        let mut request = self
+            .client
+            .request(self.method.clone(), url.as_str())
+            .headers(self.headers.clone());
+        if self.mapping == InputMapping::Body {
+            request = request.json(&rest);
+        }
+
+        let failed = |message: String| {
+            error_context()
+                .with_tool(self.name())
+                .execution_failed(message)
+        };
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| failed(format!("{} {} failed: {}", self.method, url, e)))?;
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .map_err(|e| failed(format!("Failed to read response body: {}", e)))?;
+
+        if !status.is_success() {
+            return Err(failed(format!("HTTP {}: {}", status.as_u16(), text)));
+        }
+        Ok(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTool> {
+        Box::new(self.clone())
+    }
+}
+
+/// Builder for [`HttpTool`].
+pub struct HttpToolBuilder {
+    name: String,
+    description: String,
+    method: Method,
+    url_template: String,
+    headers: Vec<(String, String)>,
+    schema: Option<Value>,
+    mapping: Option<InputMapping>,
+    client: Option<reqwest::Client>,
+}
+
+impl HttpToolBuilder {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        method: Method,
+        url_template: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            method,
+            url_template: url_template.into(),
+            headers: Vec::new(),
+            schema: None,
+            mapping: None,
+            client: None,
+        }
+    }
+
+    /// Add a header sent with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Input JSON schema (defaults to any object)
+    pub fn schema(mut self, schema: Value) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// How non-template input fields are sent; see [`InputMapping`]
+    pub fn input_mapping(mut self, mapping: InputMapping) -> Self {
+        self.mapping = Some(mapping);
+        self
+    }
+
+    /// Use a preconfigured client (proxies, TLS, default timeouts, ...)
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> ToolResult<HttpTool> {
+        let metadata = ToolMetadata::new(self.name, self.description)?;
+        let invalid = |message: String| {
+            error_context()
+                .with_tool(metadata.name.to_string())
+                .invalid_parameters(message)
+        };
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| invalid(format!("Invalid header name '{}': {}", name, e)))?;
+            let header_value = HeaderValue::from_str(&value)
+                .map_err(|e| invalid(format!("Invalid value for header '{}': {}", name, e)))?;
+            headers.append(header_name, header_value);
+        }
+
+        let input_schema = self
+            .schema
+            .unwrap_or_else(|| serde_json::json!({"type": "object"}));
+        let compiled_schema = compile_schema_cached(&input_schema)?;
+
+        Ok(HttpTool {
+            metadata,
+            input_schema,
+            compiled_schema,
+            mapping: self
+                .mapping
+                .unwrap_or_else(|| InputMapping::default_for(&self.method)),
+            method: self.method,
+            url_template: self.url_template,
+            headers,
+            client: self.client.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        client::test_server::{Reply, serve},
+        toolkits::error::ToolError,
+    };
+
+    #[test]
+    fn test_render_url_interpolates_and_encodes() {
+        let tool = HttpTool::builder("t", "d", Method::GET, "http://h/{city}/{day}")
+            .build()
+            .unwrap();
+        let (url, rest) = tool
+            .render_url(&json!({"city": "New York", "day": 3, "units": "metric"}))
+            .unwrap();
+        assert_eq!(url, "http://h/New%20York/3");
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest["units"], "metric");
+
+        let err = tool.render_url(&json!({"city": "x"})).unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_with_path_and_query() {
+        let (base, mut requests) = serve(|_| Reply::json(200, r#"{"temp": 21}"#)).await;
+        let tool = HttpTool::builder(
+            "weather",
+            "Weather",
+            Method::GET,
+            format!("{}/weather/{{city}}", base),
+        )
+        .header("X-Api-Key", "secret")
+        .build()
+        .unwrap();
+
+        let out = tool
+            .execute_json(json!({"city": "Tokyo", "units": "metric"}))
+            .await
+            .unwrap();
+        assert_eq!(out, json!({"temp": 21}));
+
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request.line(), "GET /weather/Tokyo?units=metric HTTP/1.1");
+        assert_eq!(request.header("x-api-key"), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_post_body_and_error_status() {
+        let (base, mut requests) = serve(|_| Reply::json(404, r#"{"error":"nope"}"#)).await;
+        let tool = HttpTool::builder("create", "Create", Method::POST, format!("{}/items", base))
+            .build()
+            .unwrap();
+
+        let err = tool.execute_json(json!({"name": "a"})).await.unwrap_err();
+        match err {
+            ToolError::ExecutionFailed { message, .. } => assert!(message.contains("404")),
+            other => panic!("expected ExecutionFailed, got {:?}", other),
+        }
+        assert_eq!(requests.recv().await.unwrap().json(), json!({"name": "a"}));
+    }
+}
@@ -0,0 +1,242 @@
+//! A [`DynTool`] that searches a knowledge base.
+//!
+//! The tool takes `{"question": string, "top_k": integer}` (`top_k`
+//! optional) and answers with the passages found by
+//! [`KnowledgeSearchRequest`]:
+//!
+//! ```json
+//! {"passages": [{"text": "...", "score": 0.87, "source": {...}}], "count": 1}
+//! ```
+//!
+//! When nothing matches, `passages` is empty and a `message` says so, which
+//! lets the model answer that the knowledge base does not cover the
+//! question instead of treating it as a failure.
+//!
+//! ```rust,ignore
+//! use zai_rs::toolkits::{builtin::KnowledgeTool, prelude::*};
+//!
+//! let executor = ToolExecutor::new();
+//! executor.add_dyn_tool(Box::new(KnowledgeTool::new("kb-123", api_key)?))?;
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+
+use crate::{
+    client::endpoint::Endpoint,
+    knowledge::KnowledgeSearchRequest,
+    toolkits::{
+        core::{DynTool, ToolMetadata, compile_schema_cached},
+        error::{ToolResult, error_context},
+    },
+};
+
+/// Passages returned when the input does not set `top_k`.
+pub const DEFAULT_KNOWLEDGE_TOP_K: u32 = 5;
+
+/// Tool answering questions from a knowledge base.
+#[derive(Clone)]
+pub struct KnowledgeTool {
+    metadata: ToolMetadata,
+    input_schema: Value,
+    compiled_schema: Arc<jsonschema::Validator>,
+    knowledge_id: String,
+    api_key: String,
+    default_top_k: u32,
+    endpoint: Option<Endpoint>,
+}
+
+impl KnowledgeTool {
+    /// Tool named `search_knowledge` over `knowledge_id`
+    pub fn new(knowledge_id: impl Into<String>, api_key: impl Into<String>) -> ToolResult<Self> {
+        Self::named(
+            "search_knowledge",
+            "Search the knowledge base for passages relevant to a question",
+            knowledge_id,
+            api_key,
+        )
+    }
+
+    /// Tool with a custom name and description, e.g. to register several
+    /// knowledge bases side by side
+    pub fn named(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        knowledge_id: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> ToolResult<Self> {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "question": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "The question to look up"
+                },
+                "top_k": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "How many passages to return"
+                }
+            },
+            "required": ["question"],
+            "additionalProperties": false
+        });
+        Ok(Self {
+            metadata: ToolMetadata::new(name, description)?,
+            compiled_schema: compile_schema_cached(&input_schema)?,
+            input_schema,
+            knowledge_id: knowledge_id.into(),
+            api_key: api_key.into(),
+            default_top_k: DEFAULT_KNOWLEDGE_TOP_K,
+            endpoint: None,
+        })
+    }
+
+    /// Passages returned when the input does not set `top_k`
+    pub fn with_default_top_k(mut self, top_k: u32) -> Self {
+        self.default_top_k = top_k.max(1);
+        self
+    }
+
+    /// Send searches to another API base URL
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn knowledge_id(&self) -> &str {
+        &self.knowledge_id
+    }
+}
+
+#[async_trait]
+impl DynTool for KnowledgeTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    async fn execute_json(&self, input: Value) -> ToolResult<Value> {
+        if let Err(validation_error) = self.compiled_schema.validate(&input) {
+            return Err(error_context()
+                .with_tool(self.name())
+                .invalid_parameters(format!("Input validation failed: {}", validation_error)));
+        }
+        let question = input["question"].as_str().unwrap_or_default();
+        let top_k = input["top_k"]
+            .as_u64()
+            .map_or(self.default_top_k, |k| k.min(u32::MAX as u64) as u32);
+
+        let mut request =
+            KnowledgeSearchRequest::new(self.api_key.clone(), &self.knowledge_id, question)
+                .with_top_k(top_k);
+        if let Some(endpoint) = &self.endpoint {
+            request = request.with_endpoint(endpoint.clone());
+        }
+        let failed = |message: String| {
+            error_context()
+                .with_tool(self.name())
+                .execution_failed(message)
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| failed(format!("Knowledge search failed: {}", e.compact())))?;
+        if let Some(code) = response.code.filter(|c| *c != 200) {
+            return Err(failed(format!(
+                "Knowledge search failed [{}]: {}",
+                code,
+                response.message.as_deref().unwrap_or("unknown error")
+            )));
+        }
+
+        let passages: Vec<Value> = response
+            .passages()
+            .iter()
+            .map(|p| json!({"text": p.text, "score": p.score, "source": p.metadata}))
+            .collect();
+        if passages.is_empty() {
+            return Ok(json!({
+                "passages": [],
+                "count": 0,
+                "message": "No relevant passages found in the knowledge base"
+            }));
+        }
+        Ok(json!({"count": passages.len(), "passages": passages}))
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTool> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::test_server::{Reply, serve},
+        toolkits::error::ToolError,
+    };
+
+    #[tokio::test]
+    async fn test_returns_passages() {
+        let (base, mut requests) = serve(|_| {
+            Reply::json(
+                200,
+                json!({
+                    "code": 200,
+                    "message": "success",
+                    "data": [
+                        {"text": "Refunds take 7 days.", "score": 0.5, "metadata": {"doc_name": "faq.md"}}
+                    ]
+                }),
+            )
+        })
+        .await;
+        let tool = KnowledgeTool::new("kb-1", "key")
+            .unwrap()
+            .with_endpoint(Endpoint::new(format!("{}/api", base)));
+
+        let out = tool
+            .execute_json(json!({"question": "How long do refunds take?", "top_k": 3}))
+            .await
+            .unwrap();
+        assert_eq!(out["count"], 1);
+        assert_eq!(out["passages"][0]["text"], "Refunds take 7 days.");
+        assert_eq!(out["passages"][0]["source"]["doc_name"], "faq.md");
+
+        let request = requests.recv().await.unwrap();
+        assert_eq!(
+            request.path(),
+            "/api/llm-application/open/knowledge/retrieve"
+        );
+        assert_eq!(
+            request.json(),
+            json!({"query": "How long do refunds take?", "knowledge_ids": ["kb-1"], "top_k": 3})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_results_and_bad_input() {
+        let (base, _) = serve(|_| Reply::json(200, json!({"code": 200, "data": []}))).await;
+        let tool = KnowledgeTool::new("kb-1", "key")
+            .unwrap()
+            .with_endpoint(Endpoint::new(format!("{}/api", base)));
+
+        let out = tool
+            .execute_json(json!({"question": "Unrelated?"}))
+            .await
+            .unwrap();
+        assert_eq!(out["count"], 0);
+        assert!(out["message"].as_str().unwrap().contains("No relevant"));
+
+        let err = tool.execute_json(json!({"top_k": 2})).await.unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters { .. }));
+    }
+}
@@ -0,0 +1,10 @@
+//! Ready-made tools (feature `builtin-tools`).
+//!
+//! - [`http`] — [`HttpTool`], a tool backed by an external REST endpoint
+//! - [`knowledge`] — [`KnowledgeTool`], a tool that searches a knowledge base
+
+pub mod http;
+pub mod knowledge;
+
+pub use http::{HttpTool, HttpToolBuilder, InputMapping};
+pub use knowledge::{DEFAULT_KNOWLEDGE_TOP_K, KnowledgeTool};
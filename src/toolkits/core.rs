@@ -125,6 +125,29 @@ impl ToolMetadata {
     }
 }
 
+/// Serializable summary of a registered tool, e.g. for a tool palette in a
+/// UI. See [`ToolExecutor::list_tools`](crate::toolkits::executor::ToolExecutor::list_tools).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDescriptor {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+    pub version: String,
+}
+
+impl ToolDescriptor {
+    /// Describe `tool` from its metadata and input schema
+    pub fn from_tool(tool: &dyn DynTool) -> Self {
+        let meta = tool.metadata();
+        Self {
+            name: meta.name.to_string(),
+            description: meta.description.to_string(),
+            input_schema: tool.input_schema(),
+            version: meta.version.to_string(),
+        }
+    }
+}
+
 /// Helper functions for type conversions (avoiding orphan rule issues)
 pub mod conversions {
     use crate::toolkits::error::{ToolResult, error_context};
@@ -276,7 +299,9 @@ impl FunctionTool {
 }
 
 /// Compile JSON schema with caching for better performance
-fn compile_schema_cached(schema: &serde_json::Value) -> ToolResult<Arc<jsonschema::Validator>> {
+pub(crate) fn compile_schema_cached(
+    schema: &serde_json::Value,
+) -> ToolResult<Arc<jsonschema::Validator>> {
     let mut hasher = DefaultHasher::new();
     schema.to_string().hash(&mut hasher);
     let hash = hasher.finish();
@@ -375,11 +400,14 @@ impl FunctionToolBuilder {
         let name_str = name.into();
         let desc_str = description.into();
         let metadata = ToolMetadata::new(&name_str, &desc_str).unwrap_or_else(|e| {
+            #[cfg(feature = "tracing")]
             tracing::warn!(
                 "Invalid tool name '{}': {}. Falling back to 'unknown'.",
                 name_str,
                 e
             );
+            #[cfg(not(feature = "tracing"))]
+            let _ = e;
             ToolMetadata {
                 name: Cow::Borrowed("unknown"),
                 description: Cow::Owned(desc_str),
@@ -440,7 +468,8 @@ impl FunctionToolBuilder {
     }
 
     /// Chain API: mark a property as required. Will be merged with any provided
-    /// schema's `required`.
+    /// schema's `required`; [`build`](Self::build) fails if the name is not a
+    /// declared property.
     pub fn required(mut self, name: impl Into<String>) -> Self {
         self.staged_required.push(name.into());
         self
@@ -517,6 +546,25 @@ impl FunctionToolBuilder {
                     serde_json::Value::Object(serde_json::Map::new()),
                 );
             }
+
+            // Every required name must be a declared property
+            let properties = obj.get("properties").and_then(|v| v.as_object());
+            let missing: Vec<&str> = obj
+                .get("required")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .filter(|name| !properties.is_some_and(|p| p.contains_key(*name)))
+                .collect();
+            if !missing.is_empty() {
+                return Err(error_context()
+                    .with_tool(self.metadata.name.clone())
+                    .schema_validation(format!(
+                        "required properties not defined in properties: {}",
+                        missing.join(", ")
+                    )));
+            }
         }
 
         let compiled_schema = compile_schema_cached(&schema).map_err(|e| {
@@ -665,6 +713,38 @@ mod tests {
         assert_eq!(tool.name(), "test_tool");
     }
 
+    #[test]
+    fn test_function_tool_builder_rejects_undefined_required() {
+        let result = FunctionTool::builder("test_tool", "A test tool")
+            .property("param1", serde_json::json!({"type": "string"}))
+            .required("param1")
+            .required("missing_a")
+            .schema(serde_json::json!({"required": ["missing_b"]}))
+            .handler(|_args| async move { Ok(serde_json::json!({})) })
+            .build();
+
+        let message = result.err().expect("build should fail").to_string();
+        assert!(message.contains("missing_a"), "{}", message);
+        assert!(message.contains("missing_b"), "{}", message);
+        assert!(!message.contains("param1"), "{}", message);
+    }
+
+    #[test]
+    fn test_function_tool_builder_keeps_schema_defaults() {
+        let tool = FunctionTool::builder("test_tool", "A test tool")
+            .schema(serde_json::json!({
+                "properties": {"param1": {"type": "string"}},
+                "required": ["param1"]
+            }))
+            .handler(|_args| async move { Ok(serde_json::json!({})) })
+            .build()
+            .unwrap();
+
+        let schema = tool.input_schema();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"], false);
+    }
+
     #[test]
     fn test_function_tool_clone() {
         let tool1 = FunctionTool::builder("test_tool", "A test tool")
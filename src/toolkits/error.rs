@@ -4,6 +4,8 @@ use std::borrow::Cow;
 
 use thiserror::Error;
 
+use crate::ZaiError;
+
 /// Result type for tool operations
 pub type ToolResult<T> = Result<T, ToolError>;
 
@@ -16,12 +18,41 @@ pub enum ErrorSeverity {
     Critical,  // Critical error, log and alert
 }
 
+/// Category of a [`ToolError`], for branching without matching on messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolErrorKind {
+    /// No tool, or more than one tool, matches the requested name
+    NotFound,
+    /// Arguments were rejected (schema, validation or malformed JSON)
+    InvalidInput,
+    /// The tool ran and failed
+    Execution,
+    /// The tool or a poll did not finish in time
+    Timeout,
+    /// The call was cancelled
+    Cancelled,
+    /// Retries were used up
+    RetryExhausted,
+    /// Contention on shared executor state
+    Concurrency,
+    /// The tool could not be registered
+    Registration,
+    /// A bug or broken invariant
+    Internal,
+}
+
 /// Enhanced error type with better context and error chaining
 #[derive(Error, Debug)]
 pub enum ToolError {
     #[error("Tool '{name}' not found")]
     ToolNotFound { name: Cow<'static, str> },
 
+    #[error("Tool name '{name}' is ambiguous; candidates: {}", candidates.join(", "))]
+    AmbiguousToolName {
+        name: Cow<'static, str>,
+        candidates: Vec<String>,
+    },
+
     #[error("Invalid parameters for tool '{tool}': {message}")]
     InvalidParameters {
         tool: Cow<'static, str>,
@@ -56,6 +87,24 @@ pub enum ToolError {
         timeout: std::time::Duration,
     },
 
+    #[error("Tool '{tool}' execution was cancelled")]
+    Cancelled { tool: Cow<'static, str> },
+
+    #[error("File parser task '{task_id}' failed: {reason}")]
+    ParserFailed {
+        task_id: Cow<'static, str>,
+        reason: Cow<'static, str>,
+    },
+
+    #[error("Timed out after {elapsed:?} polling task '{task_id}' (last status: {last_status})")]
+    PollTimeout {
+        task_id: Cow<'static, str>,
+        elapsed: std::time::Duration,
+        /// Status observed on the last poll, as reported by the API (e.g.
+        /// `processing`); callers may keep waiting while it is not final
+        last_status: Cow<'static, str>,
+    },
+
     #[error("Retry limit exceeded for tool '{tool}': failed after {attempts} attempts")]
     RetryLimitExceeded {
         tool: Cow<'static, str>,
@@ -76,7 +125,32 @@ pub enum ToolError {
 }
 
 impl ToolError {
-    /// Determine if the error is retryable
+    /// Category of the error
+    pub fn kind(&self) -> ToolErrorKind {
+        match self {
+            ToolError::ToolNotFound { .. } | ToolError::AmbiguousToolName { .. } => {
+                ToolErrorKind::NotFound
+            },
+            ToolError::InvalidParameters { .. }
+            | ToolError::SchemaValidation { .. }
+            | ToolError::SerializationError { .. }
+            | ToolError::ValidationError { .. } => ToolErrorKind::InvalidInput,
+            ToolError::ExecutionFailed { .. } | ToolError::ParserFailed { .. } => {
+                ToolErrorKind::Execution
+            },
+            ToolError::TimeoutError { .. } | ToolError::PollTimeout { .. } => {
+                ToolErrorKind::Timeout
+            },
+            ToolError::Cancelled { .. } => ToolErrorKind::Cancelled,
+            ToolError::RetryLimitExceeded { .. } => ToolErrorKind::RetryExhausted,
+            ToolError::ConcurrentAccessError { .. } => ToolErrorKind::Concurrency,
+            ToolError::RegistrationError { .. } => ToolErrorKind::Registration,
+            ToolError::Internal(_) => ToolErrorKind::Internal,
+        }
+    }
+
+    /// Determine if the error is retryable: timeouts, execution failures
+    /// and contention are; missing tools and bad arguments are not
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
@@ -90,8 +164,10 @@ impl ToolError {
     pub fn severity(&self) -> ErrorSeverity {
         match self {
             ToolError::ToolNotFound { .. } => ErrorSeverity::User,
+            ToolError::AmbiguousToolName { .. } => ErrorSeverity::User,
             ToolError::InvalidParameters { .. } => ErrorSeverity::User,
             ToolError::ValidationError { .. } => ErrorSeverity::User,
+            ToolError::Cancelled { .. } => ErrorSeverity::User,
             ToolError::TimeoutError { .. } => ErrorSeverity::Transient,
             ToolError::ConcurrentAccessError { .. } => ErrorSeverity::Transient,
             ToolError::Internal(_) => ErrorSeverity::Critical,
@@ -187,6 +263,12 @@ impl ErrorContext {
         }
     }
 
+    pub fn cancelled(self) -> ToolError {
+        ToolError::Cancelled {
+            tool: Cow::Owned(self.get_tool_name()),
+        }
+    }
+
     pub fn retry_limit_exceeded(self, attempts: u32) -> ToolError {
         ToolError::RetryLimitExceeded {
             tool: Cow::Owned(self.get_tool_name()),
@@ -222,3 +304,189 @@ impl Default for ErrorContext {
 pub fn error_context() -> ErrorContext {
     ErrorContext::new()
 }
+
+/// Bridge tool failures into API flows.
+///
+/// The message keeps the full `ToolError` text, including the tool name.
+/// User errors become `ApiError` with the validation code 1200, timeouts a
+/// 504 `HttpError`, and execution failures an `Unknown` 500 so that
+/// [`ZaiError::is_server_error`] agrees with [`ToolError::is_retryable`].
+impl From<ToolError> for ZaiError {
+    fn from(err: ToolError) -> Self {
+        let message = err.to_string();
+        match err {
+            ToolError::ToolNotFound { .. }
+            | ToolError::AmbiguousToolName { .. }
+            | ToolError::InvalidParameters { .. }
+            | ToolError::SchemaValidation { .. }
+            | ToolError::ValidationError { .. } => ZaiError::ApiError {
+                code: 1200,
+                message,
+            },
+            ToolError::TimeoutError { .. } | ToolError::PollTimeout { .. } => ZaiError::HttpError {
+                status: 504,
+                message,
+            },
+            ToolError::ExecutionFailed { .. }
+            | ToolError::RetryLimitExceeded { .. }
+            | ToolError::ConcurrentAccessError { .. } => ZaiError::Unknown { code: 500, message },
+            ToolError::SerializationError { source, .. } => ZaiError::JsonError(source.into()),
+            _ => ZaiError::Unknown { code: 0, message },
+        }
+    }
+}
+
+/// Surface an API failure inside a tool handler.
+///
+/// The tool name is not known here and is reported as `unknown`, as with
+/// [`error_context`]. Validation errors (code 1200) become
+/// `InvalidParameters`; everything else is an `ExecutionFailed`.
+impl From<ZaiError> for ToolError {
+    fn from(err: ZaiError) -> Self {
+        match err {
+            ZaiError::ApiError {
+                code: 1200,
+                message,
+            } => error_context().invalid_parameters(message),
+            err => error_context().execution_failed(err.compact()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_constructor_kinds_and_retryability() {
+        let ctx = || error_context().with_tool("weather");
+        let cases = [
+            (ctx().tool_not_found(), ToolErrorKind::NotFound, false),
+            (
+                ctx().invalid_parameters("city is required"),
+                ToolErrorKind::InvalidInput,
+                false,
+            ),
+            (
+                ctx().schema_validation("bad schema"),
+                ToolErrorKind::InvalidInput,
+                false,
+            ),
+            (
+                ctx().validation_error("city", "empty"),
+                ToolErrorKind::InvalidInput,
+                false,
+            ),
+            (
+                ctx().execution_failed("upstream down"),
+                ToolErrorKind::Execution,
+                true,
+            ),
+            (
+                ctx().timeout_error(Duration::from_secs(5)),
+                ToolErrorKind::Timeout,
+                true,
+            ),
+            (ctx().cancelled(), ToolErrorKind::Cancelled, false),
+            (
+                ctx().retry_limit_exceeded(3),
+                ToolErrorKind::RetryExhausted,
+                false,
+            ),
+            (
+                ctx().concurrent_access_error("locked"),
+                ToolErrorKind::Concurrency,
+                true,
+            ),
+        ];
+        for (err, kind, retryable) in cases {
+            assert_eq!(err.kind(), kind, "{}", err);
+            assert_eq!(err.is_retryable(), retryable, "{}", err);
+        }
+        let bad_json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(
+            ctx().serialization_error(bad_json).kind(),
+            ToolErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_tool_not_found_into_zai_error() {
+        let err: ZaiError = error_context().with_tool("weather").tool_not_found().into();
+        assert!(matches!(err, ZaiError::ApiError { code: 1200, .. }));
+        assert!(err.is_client_error());
+        assert_eq!(err.message(), "Tool 'weather' not found");
+    }
+
+    #[test]
+    fn test_invalid_parameters_into_zai_error() {
+        let err: ZaiError = error_context()
+            .with_tool("weather")
+            .invalid_parameters("city is required")
+            .into();
+        assert_eq!(err.code(), Some(1200));
+        assert_eq!(
+            err.message(),
+            "Invalid parameters for tool 'weather': city is required"
+        );
+    }
+
+    #[test]
+    fn test_timeout_into_zai_error() {
+        let err: ZaiError = error_context()
+            .with_tool("weather")
+            .timeout_error(Duration::from_secs(5))
+            .into();
+        assert!(matches!(err, ZaiError::HttpError { status: 504, .. }));
+        assert!(err.is_server_error());
+        assert!(err.message().contains("'weather'"));
+        assert!(err.message().contains("5s"));
+
+        let err: ZaiError = ToolError::PollTimeout {
+            task_id: "task-1".into(),
+            elapsed: Duration::from_secs(30),
+            last_status: "processing".into(),
+        }
+        .into();
+        assert!(matches!(err, ZaiError::HttpError { status: 504, .. }));
+        assert!(err.message().contains("last status: processing"));
+    }
+
+    #[test]
+    fn test_execution_failed_into_zai_error() {
+        let err: ZaiError = error_context()
+            .with_tool("weather")
+            .execution_failed("upstream down")
+            .into();
+        assert!(matches!(err, ZaiError::Unknown { code: 500, .. }));
+        assert!(err.is_server_error());
+        assert_eq!(
+            err.message(),
+            "Tool 'weather' execution failed: upstream down"
+        );
+    }
+
+    #[test]
+    fn test_question_mark_bridges_both_ways() {
+        fn api_flow() -> crate::ZaiResult<()> {
+            Err(error_context().with_tool("t").tool_not_found())?
+        }
+        assert!(api_flow().is_err());
+
+        fn tool_flow(code: u16) -> ToolResult<()> {
+            Err(ZaiError::ApiError {
+                code,
+                message: "bad".to_string(),
+            })?
+        }
+        assert!(matches!(
+            tool_flow(1200),
+            Err(ToolError::InvalidParameters { ref message, .. }) if message == "bad"
+        ));
+        let err = tool_flow(1210).unwrap_err();
+        assert!(matches!(err, ToolError::ExecutionFailed { .. }));
+        assert!(err.to_string().contains("API[1210]: bad"));
+    }
+}
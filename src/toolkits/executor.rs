@@ -1,30 +1,87 @@
 //! Enhanced tool executor with type-safe builder pattern
+//!
+//! With the `tracing` feature, every execution runs inside a `tool.execute`
+//! span (fields `tool_name`, `attempt`, `duration_ms`, `cache_hit`), each
+//! handler invocation inside a child `tool.attempt` span, and batches of LLM
+//! tool calls inside a `tool.calls` span, so tool activity shows up in the
+//! application's span tree.
 
 use std::{
+    collections::{HashMap, VecDeque},
+    panic::AssertUnwindSafe,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use dashmap::DashMap;
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
 use tokio::{task::JoinSet, time::timeout};
+use tokio_util::sync::CancellationToken;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
 
 use super::{
     cache::{CacheKey, ToolCallCache},
-    core::ToolHandler,
+    core::{ToolHandler, compile_schema_cached},
+    metrics::{MetricsSink, percentile},
 };
 use crate::{
+    ZaiError, ZaiResult,
     model::{
-        chat_base_response::ToolCallMessage,
-        chat_message_types::TextMessage,
+        chat::ChatCompletion,
+        chat_base_request::ChatBody,
+        chat_base_response::{ChatCompletionResponse, Message, ToolCallMessage},
+        chat_message_types::{FunctionParams, TextMessage, ToolCall},
         tools::{Function, Tools},
+        traits::{Bounded, Chat, ModelName, StreamOff},
+        usage::UsageAccumulator,
     },
     toolkits::{
-        core::DynTool,
+        core::{DynTool, ToolDescriptor},
         error::{ToolError, ToolResult, error_context},
     },
 };
 
+/// Create a span when the `tracing` feature is on, or a disabled span
+/// otherwise, so call sites need no `cfg` of their own.
+#[cfg(feature = "tracing")]
+macro_rules! tool_span {
+    ($($args:tt)*) => {
+        tracing::info_span!($($args)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! tool_span {
+    ($($args:tt)*) => {
+        no_tracing::Span
+    };
+}
+
+/// The slice of the span API used here, as no-ops, for builds without the
+/// `tracing` dependency.
+#[cfg(not(feature = "tracing"))]
+mod no_tracing {
+    #[derive(Clone)]
+    pub(super) struct Span;
+
+    impl Span {
+        pub(super) fn record<V>(&self, _field: &str, _value: V) {}
+    }
+
+    pub(super) trait Instrument: Sized {
+        fn instrument(self, _span: Span) -> Self {
+            self
+        }
+    }
+
+    impl<F: std::future::Future> Instrument for F {}
+}
+
+#[cfg(not(feature = "tracing"))]
+use no_tracing::Instrument;
+
 /// Enhanced retry configuration with exponential backoff
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -65,7 +122,18 @@ pub struct ExecutionConfig {
     pub timeout: Option<Duration>,
     pub retry_config: RetryConfig,
     pub validate_parameters: bool,
+    /// Report failed attempts: as `tracing::warn!` events with the
+    /// `tracing` feature, printed to stderr otherwise.
     pub enable_logging: bool,
+    /// Resolve unknown tool names to a close registered name; see
+    /// [`ToolExecutor::with_fuzzy_tool_matching`].
+    pub fuzzy_tool_matching: bool,
+    /// Repair malformed tool-call arguments from the model; see
+    /// [`ToolExecutor::with_json_repair`].
+    pub json_repair: bool,
+    /// Derive each tool's timeout from its recent latencies instead of
+    /// using `timeout`; see [`AdaptiveTimeout`].
+    pub adaptive_timeout: Option<AdaptiveTimeout>,
 }
 
 impl Default for ExecutionConfig {
@@ -75,7 +143,61 @@ impl Default for ExecutionConfig {
             retry_config: RetryConfig::default(),
             validate_parameters: true,
             enable_logging: false,
+            fuzzy_tool_matching: false,
+            json_repair: false,
+            adaptive_timeout: None,
+        }
+    }
+}
+
+/// Per-tool timeout derived from observed latencies.
+///
+/// Once a tool has `min_samples` successful calls, its timeout is the p95 of
+/// the last `window` successful call latencies times `factor`, clamped to
+/// `min..=max`. Until then the static [`ExecutionConfig::timeout`] applies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveTimeout {
+    /// Multiplier applied to the p95 latency
+    pub factor: f64,
+    /// Shortest timeout used
+    pub min: Duration,
+    /// Longest timeout used
+    pub max: Duration,
+    /// Successful calls needed before the adaptive timeout takes over
+    pub min_samples: usize,
+    /// Most recent latencies kept per tool
+    pub window: usize,
+}
+
+impl AdaptiveTimeout {
+    /// p95 times `factor`, clamped to `min..=max`, after 5 samples of the
+    /// last 100. A non-positive or non-finite `factor` becomes 1.0 and a
+    /// `min` above `max` becomes `max`.
+    pub fn new(factor: f64, min: Duration, max: Duration) -> Self {
+        Self {
+            factor: if factor.is_finite() && factor > 0.0 {
+                factor
+            } else {
+                1.0
+            },
+            min: min.min(max),
+            max,
+            min_samples: 5,
+            window: 100,
+        }
+    }
+
+    /// Timeout for a tool with these successful call latencies, or `None`
+    /// while there are fewer than `min_samples`
+    pub fn compute(&self, latencies: &VecDeque<Duration>) -> Option<Duration> {
+        if latencies.len() < self.min_samples.max(1) {
+            return None;
         }
+        let mut sorted: Vec<Duration> = latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let scaled = percentile(&sorted, 95).as_secs_f64() * self.factor;
+        let scaled = Duration::from_secs_f64(scaled.min(self.max.as_secs_f64()));
+        Some(scaled.max(self.min).min(self.max))
     }
 }
 
@@ -128,14 +250,56 @@ impl ExecutionResult {
         self.metadata.insert(key.into(), value);
         self
     }
+
+    /// Tool message answering `tool_call_id` with this result.
+    ///
+    /// On success the content is `result` serialized as JSON; on failure it
+    /// is `{"error": {"type": "execution_failed", "message": ...}}`, the
+    /// encoding used by the `execute_tool_calls_*` helpers.
+    pub fn into_tool_message(&self, tool_call_id: Option<String>) -> TextMessage {
+        let content = if self.success {
+            self.result.clone()
+        } else {
+            let err = error_context()
+                .with_tool(self.tool_name.clone())
+                .execution_failed(
+                    self.error
+                        .clone()
+                        .unwrap_or_else(|| "Unknown error".to_string()),
+                );
+            tool_error_json("execution_failed", err.to_string())
+        };
+        tool_message(&content, tool_call_id)
+    }
+}
+
+/// `{"error": {"type": kind, "message": message}}` tool-message content.
+fn tool_error_json(kind: &str, message: impl Into<String>) -> serde_json::Value {
+    serde_json::json!({ "error": { "type": kind, "message": message.into() } })
+}
+
+/// Tool message carrying `content`, tied to `tool_call_id` when known.
+fn tool_message(content: &serde_json::Value, tool_call_id: Option<String>) -> TextMessage {
+    let s = serde_json::to_string(content).unwrap_or_else(|_| "{}".to_string());
+    match tool_call_id {
+        Some(id) => TextMessage::tool_with_id(s, id),
+        None => TextMessage::tool(s),
+    }
 }
 
+/// Rewrites a successful tool output, given the tool name and the raw output.
+pub type OutputTransform = Arc<dyn Fn(&str, serde_json::Value) -> serde_json::Value + Send + Sync>;
+
 /// Enhanced tool executor with built-in registry and fluent API
 #[derive(Clone)]
 pub struct ToolExecutor {
     tools: Arc<DashMap<String, Arc<dyn DynTool>>>,
     config: ExecutionConfig,
     cache: ToolCallCache,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    output_transform: Option<OutputTransform>,
+    /// Recent successful call latencies per tool, for the adaptive timeout
+    latencies: Arc<DashMap<String, VecDeque<Duration>>>,
 }
 
 impl std::fmt::Debug for ToolExecutor {
@@ -146,6 +310,8 @@ impl std::fmt::Debug for ToolExecutor {
             .field("tool_count", &tool_count)
             .field("config", &self.config)
             .field("cache_enabled", &cache_enabled)
+            .field("metrics_sink", &self.metrics.is_some())
+            .field("output_transform", &self.output_transform.is_some())
             .finish()
     }
 }
@@ -163,6 +329,9 @@ impl ToolExecutor {
             tools: Arc::new(DashMap::new()),
             config: ExecutionConfig::default(),
             cache: ToolCallCache::new(),
+            metrics: None,
+            output_transform: None,
+            latencies: Arc::new(DashMap::new()),
         }
     }
 
@@ -171,6 +340,47 @@ impl ToolExecutor {
         ExecutorBuilder::new()
     }
 
+    /// Report every execution result to `sink`
+    pub fn with_metrics_sink(mut self, sink: Arc<dyn MetricsSink>) -> Self {
+        self.metrics = Some(sink);
+        self
+    }
+
+    /// Rewrite every successful tool output with `transform`, e.g. to
+    /// truncate long strings and mark them with `"_truncated": true`.
+    ///
+    /// The transform runs before the result is cached, so cached values are
+    /// already rewritten. It must be deterministic: a cache hit returns the
+    /// value it produced earlier.
+    pub fn with_output_transform(mut self, transform: OutputTransform) -> Self {
+        self.output_transform = Some(transform);
+        self
+    }
+
+    /// Resolve tool names the model got slightly wrong.
+    ///
+    /// When no tool is registered under the requested name, the closest
+    /// registered name is used instead: a case-insensitive match, a name
+    /// the request extends with a `_`/`-`/`.` suffix (`get_weather_tool`
+    /// for `get_weather`), or one within [`FUZZY_MAX_DISTANCE`] edits. The
+    /// result then carries the requested name in `metadata["resolved_from"]`.
+    /// If several names are equally close, the call fails with
+    /// [`ToolError::AmbiguousToolName`].
+    pub fn with_fuzzy_tool_matching(mut self, enabled: bool) -> Self {
+        self.config.fuzzy_tool_matching = enabled;
+        self
+    }
+
+    /// Repair malformed JSON arguments of LLM tool calls with
+    /// [`repair_json`](super::llm::repair_json) (trailing commas, single
+    /// quotes, code fences, ...) instead of passing them to the tool as
+    /// `{"_raw": "..."}`. A repaired call's result carries
+    /// `metadata["json_repaired"] = true`.
+    pub fn with_json_repair(mut self, enabled: bool) -> Self {
+        self.config.json_repair = enabled;
+        self
+    }
+
     /// Enable or disable tool call result caching
     pub fn with_cache_enabled(mut self, enabled: bool) -> Self {
         self.cache = self.cache.with_enabled(enabled);
@@ -216,6 +426,72 @@ impl ToolExecutor {
         Ok(self)
     }
 
+    /// Register several tools at once, all or nothing.
+    ///
+    /// Returns the registered names in input order. If any name is already
+    /// registered (or appears twice in `tools`), the tools added by this call
+    /// are removed again and a `RegistrationError` naming the conflict is
+    /// returned, leaving the registry as it was. Concurrent readers may
+    /// briefly see the partially added set before it is rolled back.
+    pub fn register_many(&self, tools: Vec<Box<dyn DynTool>>) -> ToolResult<Vec<String>> {
+        let mut added: Vec<String> = Vec::with_capacity(tools.len());
+        for tool in tools {
+            let name = tool.name().to_string();
+            // The entry guard locks its shard; release it before rolling back.
+            let inserted = match self.tools.entry(name.clone()) {
+                dashmap::Entry::Occupied(_) => false,
+                dashmap::Entry::Vacant(entry) => {
+                    entry.insert(Arc::from(tool));
+                    true
+                },
+            };
+            if !inserted {
+                for registered in &added {
+                    self.tools.remove(registered);
+                }
+                return Err(ToolError::RegistrationError {
+                    message: format!("Tool '{}' is already registered", name).into(),
+                });
+            }
+            added.push(name);
+        }
+        Ok(added)
+    }
+
+    /// Copy every tool of `other` into this executor, all or nothing.
+    ///
+    /// Returns the merged names, sorted. If any of `other`'s tools shares a
+    /// name with one registered here, nothing is merged and the
+    /// `RegistrationError` lists every conflicting name.
+    ///
+    /// Tools are copied with [`DynTool::clone_box`]; `other`'s config,
+    /// cache, metrics sink and output transform are not carried over, so
+    /// merged tools run with this executor's settings and cache.
+    pub fn merge_from(&self, other: &ToolExecutor) -> ToolResult<Vec<String>> {
+        let mut tools: Vec<Box<dyn DynTool>> = other
+            .tools
+            .iter()
+            .map(|entry| entry.value().clone_box())
+            .collect();
+        tools.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let conflicts: Vec<&str> = tools
+            .iter()
+            .map(|t| t.name())
+            .filter(|name| self.tools.contains_key(*name))
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(ToolError::RegistrationError {
+                message: format!(
+                    "Cannot merge: tools already registered: {}",
+                    conflicts.join(", ")
+                )
+                .into(),
+            });
+        }
+        self.register_many(tools)
+    }
+
     /// Chain-friendly: try to add a dynamic tool (ignores error)
     pub fn try_add_dyn_tool(&self, tool: Box<dyn DynTool>) -> &Self {
         let name = tool.name().to_string();
@@ -246,15 +522,139 @@ impl ToolExecutor {
         self.tools.iter().map(|entry| entry.key().clone()).collect()
     }
 
+    /// Describe all registered tools, sorted by name.
+    ///
+    /// The registry is snapshotted first, so tools added or removed
+    /// concurrently either appear in full or not at all.
+    pub fn list_tools(&self) -> Vec<ToolDescriptor> {
+        let tools: Vec<Arc<dyn DynTool>> = self
+            .tools
+            .iter()
+            .map(|entry| Arc::clone(entry.value()))
+            .collect();
+        let mut descriptors: Vec<ToolDescriptor> = tools
+            .iter()
+            .map(|tool| ToolDescriptor::from_tool(tool.as_ref()))
+            .collect();
+        descriptors.sort_by(|a, b| a.name.cmp(&b.name));
+        descriptors
+    }
+
     fn get_tool(&self, name: &str) -> Option<Arc<dyn DynTool>> {
         self.tools.get(name).map(|t| Arc::clone(t.value()))
     }
 
     /// Execute a tool with detailed result and exponential backoff
+    ///
+    /// The result is reported to the metrics sink, if one is configured.
     pub async fn execute(
         &self,
         tool_name: &str,
         input: serde_json::Value,
+    ) -> ToolResult<ExecutionResult> {
+        self.execute_with_context(tool_name, input, HashMap::new())
+            .await
+    }
+
+    /// Like [`execute`](Self::execute), merging `ctx` (e.g. a request or
+    /// user id) into the result's `metadata` before it is returned and
+    /// reported to the metrics sink. Keys set by the executor itself, such
+    /// as `cache_hit`, take precedence.
+    pub async fn execute_with_context(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+        ctx: HashMap<String, serde_json::Value>,
+    ) -> ToolResult<ExecutionResult> {
+        self.execute_traced(tool_name, input, ctx, true).await
+    }
+
+    /// Like [`execute`](Self::execute), but neither reads nor fills the
+    /// cache, for a call whose output must be fresh (e.g. the current time).
+    /// Other calls keep using the cache. The result's metadata has
+    /// `"cache_bypassed": true`.
+    pub async fn execute_no_cache(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+    ) -> ToolResult<ExecutionResult> {
+        self.execute_traced(tool_name, input, HashMap::new(), false)
+            .await
+    }
+
+    async fn execute_traced(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+        ctx: HashMap<String, serde_json::Value>,
+        use_cache: bool,
+    ) -> ToolResult<ExecutionResult> {
+        let span = tool_span!(
+            "tool.execute",
+            tool_name,
+            attempt = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+            cache_hit = tracing::field::Empty,
+        );
+        let result = self
+            .execute_resolved(tool_name, input, use_cache)
+            .instrument(span.clone())
+            .await
+            .map(|mut result| {
+                if !use_cache {
+                    result
+                        .metadata
+                        .insert("cache_bypassed".to_string(), serde_json::Value::Bool(true));
+                }
+                for (key, value) in ctx {
+                    result.metadata.entry(key).or_insert(value);
+                }
+                result
+            });
+        if let Ok(result) = &result {
+            span.record("attempt", result.retries + 1);
+            span.record("duration_ms", result.duration.as_millis() as u64);
+            span.record(
+                "cache_hit",
+                result.metadata.get("cache_hit") == Some(&serde_json::Value::Bool(true)),
+            );
+        }
+        if let (Some(sink), Ok(result)) = (&self.metrics, &result) {
+            sink.record(result);
+        }
+        result
+    }
+
+    /// Run `tool_name`, or the registered name fuzzy matching resolves it
+    /// to.
+    async fn execute_resolved(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+        use_cache: bool,
+    ) -> ToolResult<ExecutionResult> {
+        if !self.config.fuzzy_tool_matching || self.tools.contains_key(tool_name) {
+            return self.execute_with_retries(tool_name, input, use_cache).await;
+        }
+        let names: Vec<String> = self.tools.iter().map(|e| e.key().clone()).collect();
+        match closest_tool_names(tool_name, &names).as_slice() {
+            [] => self.execute_with_retries(tool_name, input, use_cache).await,
+            [resolved] => Ok(self
+                .execute_with_retries(resolved, input, use_cache)
+                .await?
+                .with_metadata("resolved_from", serde_json::json!(tool_name))),
+            candidates => Err(ToolError::AmbiguousToolName {
+                name: tool_name.to_string().into(),
+                candidates: candidates.to_vec(),
+            }),
+        }
+    }
+
+    async fn execute_with_retries(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+        use_cache: bool,
     ) -> ToolResult<ExecutionResult> {
         let start_time = Instant::now();
         let mut retries = 0;
@@ -262,7 +662,7 @@ impl ToolExecutor {
 
         // Check cache first
         let cache_key = CacheKey::new(tool_name.to_string(), input.clone());
-        if let Some(cached_result) = self.cache.get(&cache_key) {
+        if use_cache && let Some(cached_result) = self.cache.get(&cache_key) {
             let duration = start_time.elapsed();
             return Ok(ExecutionResult::success(
                 tool_name.to_string(),
@@ -273,46 +673,69 @@ impl ToolExecutor {
             .with_metadata("cache_hit", serde_json::Value::Bool(true)));
         }
 
+        let attempt_timeout = self.effective_timeout(tool_name);
+        let with_timeout =
+            |result: ExecutionResult| match (&self.config.adaptive_timeout, attempt_timeout) {
+                (Some(_), Some(t)) => {
+                    result.with_metadata("timeout_ms", serde_json::json!(t.as_millis() as u64))
+                },
+                _ => result,
+            };
+
         loop {
-            match self.execute_once(tool_name, &input).await {
+            match self
+                .execute_once(tool_name, &input, retries + 1, attempt_timeout)
+                .await
+            {
                 Ok(result) => {
+                    let result = match &self.output_transform {
+                        Some(transform) => transform(tool_name, result),
+                        None => result,
+                    };
                     let duration = start_time.elapsed();
                     // Cache the successful result
-                    self.cache.insert(cache_key, result.clone(), None);
-
-                    return Ok(ExecutionResult::success(
-                        tool_name.to_string(),
-                        result,
-                        duration,
-                        retries,
-                    )
-                    .with_metadata("cache_hit", serde_json::Value::Bool(false)));
+                    if use_cache {
+                        self.cache.insert(cache_key, result.clone(), None);
+                    }
+
+                    return Ok(with_timeout(
+                        ExecutionResult::success(tool_name.to_string(), result, duration, retries)
+                            .with_metadata("cache_hit", serde_json::Value::Bool(false)),
+                    ));
                 },
                 Err(error) => {
                     // Only retry on retryable errors (timeout, transient failures)
                     if !error.is_retryable() {
                         let duration = start_time.elapsed();
-                        return Ok(ExecutionResult::failure(
+                        return Ok(with_timeout(ExecutionResult::failure(
                             tool_name.to_string(),
                             error.to_string(),
                             duration,
                             retries,
-                        ));
+                        )));
                     }
 
                     if retries >= retry_config.max_retries {
                         let duration = start_time.elapsed();
-                        return Ok(ExecutionResult::failure(
+                        return Ok(with_timeout(ExecutionResult::failure(
                             tool_name.to_string(),
                             error.to_string(),
                             duration,
                             retries,
-                        ));
+                        )));
                     }
 
                     retries += 1;
 
                     if self.config.enable_logging {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(
+                            tool_name,
+                            attempt = retries,
+                            error = %error,
+                            "tool execution failed, retrying"
+                        );
+                        #[cfg(not(feature = "tracing"))]
                         eprintln!("Tool execution failed (attempt {}): {}", retries, error);
                     }
 
@@ -330,13 +753,99 @@ impl ToolExecutor {
         tool_name: &str,
         input: serde_json::Value,
     ) -> ToolResult<serde_json::Value> {
-        let result = self.execute(tool_name, input).await?;
-        if result.success {
-            Ok(result.result)
+        result_value(tool_name, self.execute(tool_name, input).await)
+    }
+
+    /// Execute a tool, aborting as soon as `token` is cancelled.
+    ///
+    /// Returns [`ToolError::Cancelled`] if the token fires before the tool
+    /// (including any retries) completes; the in-flight handler future is
+    /// dropped.
+    pub async fn execute_with_cancel(
+        &self,
+        tool_name: &str,
+        input: serde_json::Value,
+        token: CancellationToken,
+    ) -> ToolResult<ExecutionResult> {
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(error_context().with_tool(tool_name).cancelled()),
+            result = self.execute(tool_name, input) => result,
+        }
+    }
+
+    /// Validate a call against the tool's input schema without executing it.
+    ///
+    /// Checks that `tool_name` is registered and that `input` satisfies its
+    /// `input_schema()`. Every schema violation is reported (with its JSON
+    /// pointer), not just the first one. The handler is never invoked.
+    pub fn validate_call(&self, tool_name: &str, input: &serde_json::Value) -> ToolResult<()> {
+        let tool = self
+            .get_tool(tool_name)
+            .ok_or_else(|| error_context().with_tool(tool_name).tool_not_found())?;
+
+        let validator = compile_schema_cached(&tool.input_schema())?;
+        let errors: Vec<String> = validator
+            .iter_errors(input)
+            .map(|e| {
+                let path = e.instance_path().to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{}: {}", path, e)
+                }
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
         } else {
             Err(error_context()
                 .with_tool(tool_name)
-                .execution_failed(result.error.unwrap_or_else(|| "Unknown error".to_string())))
+                .invalid_parameters(format!("Input validation failed: {}", errors.join("; "))))
+        }
+    }
+
+    /// Pre-flight a batch of LLM tool_calls without executing any of them.
+    ///
+    /// Arguments are parsed the same way as in
+    /// [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered), except
+    /// that malformed JSON is reported as an error instead of being passed
+    /// through. Results are returned in input order.
+    pub fn validate_tool_calls(&self, calls: &[ToolCallMessage]) -> Vec<ToolResult<()>> {
+        calls
+            .iter()
+            .map(|tc| {
+                let func = tc.function().ok_or_else(|| {
+                    error_context().invalid_parameters("tool_call.function is missing")
+                })?;
+                let name = func.name().unwrap_or("");
+                let args_str = func.arguments().unwrap_or("{}");
+                let (args, _) = self.parse_call_arguments(args_str).map_err(|e| {
+                    error_context()
+                        .with_tool(name)
+                        .invalid_parameters(format!("Arguments are not valid JSON: {}", e))
+                })?;
+                self.validate_call(name, &args)
+            })
+            .collect()
+    }
+
+    /// Parse tool-call arguments, falling back to
+    /// [`repair_json`](super::llm::repair_json) when JSON repair is enabled.
+    /// The flag is `true` when the arguments had to be repaired.
+    fn parse_call_arguments(
+        &self,
+        args: &str,
+    ) -> Result<(serde_json::Value, bool), serde_json::Error> {
+        match serde_json::from_str(args) {
+            Ok(value) => Ok((value, false)),
+            Err(e) => match self.config.json_repair {
+                true => super::llm::repair_json(args)
+                    .map(|value| (value, true))
+                    .ok_or(e),
+                false => Err(e),
+            },
         }
     }
 
@@ -401,52 +910,88 @@ impl ToolExecutor {
                 ))
             })?;
 
-            // Extract name/description/parameters from spec
-            let (name, description, parameters) =
-                crate::toolkits::core::parse_function_spec_details(&spec).map_err(|e| {
-                    error_context().invalid_parameters(format!(
-                        "Failed to parse spec {}: {}",
-                        path.display(),
-                        e
-                    ))
-                })?;
-
-            let handler = match handlers.get(&name) {
-                Some(h) => h.clone(),
-                None => {
-                    if strict {
-                        return Err(error_context().invalid_parameters(format!(
-                            "No handler registered for function '{}' (file {})",
-                            name,
-                            path.display()
-                        )));
-                    } else {
-                        // skip silently
-                        continue;
-                    }
-                },
-            };
-
-            // Build FunctionTool via existing builder path (will auto-complete schema
-            // defaults)
-            let mut builder =
-                crate::toolkits::core::FunctionTool::builder(name.clone(), description);
-            if let Some(p) = parameters {
-                builder = builder.schema(p);
+            let origin = format!("file {}", path.display());
+            if let Some(name) = self.register_function_spec(&spec, &origin, handlers, strict)? {
+                added.push(name);
             }
-            let tool = builder
-                .handler(move |args| {
-                    let h = handler.clone();
-                    h(args)
-                })
-                .build()?;
+        }
+        Ok(added)
+    }
 
-            self.add_dyn_tool(Box::new(tool))?;
-            added.push(name);
+    /// Register function specs held in memory (e.g. embedded strings or
+    /// rows fetched from a database) with handlers.
+    ///
+    /// Accepts the same spec shapes, handler map and `strict` semantics as
+    /// [`add_functions_from_dir_with_registry`](Self::add_functions_from_dir_with_registry),
+    /// without touching the filesystem. Specs are registered in order.
+    ///
+    /// Returns the list of function names successfully registered.
+    pub fn add_functions_from_specs_with_registry(
+        &self,
+        specs: &[serde_json::Value],
+        handlers: &std::collections::HashMap<String, ToolHandler>,
+        strict: bool,
+    ) -> ToolResult<Vec<String>> {
+        let mut added = Vec::new();
+        for (index, spec) in specs.iter().enumerate() {
+            let origin = format!("spec #{}", index);
+            if let Some(name) = self.register_function_spec(spec, &origin, handlers, strict)? {
+                added.push(name);
+            }
         }
         Ok(added)
     }
 
+    /// Build and register a FunctionTool from a single spec.
+    ///
+    /// Returns `Ok(None)` when the spec has no handler and `strict` is off.
+    /// `origin` identifies the spec in error messages.
+    fn register_function_spec(
+        &self,
+        spec: &serde_json::Value,
+        origin: &str,
+        handlers: &std::collections::HashMap<String, ToolHandler>,
+        strict: bool,
+    ) -> ToolResult<Option<String>> {
+        // Extract name/description/parameters from spec
+        let (name, description, parameters) =
+            crate::toolkits::core::parse_function_spec_details(spec).map_err(|e| {
+                error_context()
+                    .invalid_parameters(format!("Failed to parse spec ({}): {}", origin, e))
+            })?;
+
+        let handler = match handlers.get(&name) {
+            Some(h) => h.clone(),
+            None => {
+                if strict {
+                    return Err(error_context().invalid_parameters(format!(
+                        "No handler registered for function '{}' ({})",
+                        name, origin
+                    )));
+                } else {
+                    // skip silently
+                    return Ok(None);
+                }
+            },
+        };
+
+        // Build FunctionTool via existing builder path (will auto-complete schema
+        // defaults)
+        let mut builder = crate::toolkits::core::FunctionTool::builder(name.clone(), description);
+        if let Some(p) = parameters {
+            builder = builder.schema(p);
+        }
+        let tool = builder
+            .handler(move |args| {
+                let h = handler.clone();
+                h(args)
+            })
+            .build()?;
+
+        self.add_dyn_tool(Box::new(tool))?;
+        Ok(Some(name))
+    }
+
     /// Execute LLM tool_calls in parallel and return `TextMessage::tool`
     /// messages.
     ///
@@ -462,52 +1007,107 @@ impl ToolExecutor {
     /// Returns:
     /// - `Vec<TextMessage>` ready to be appended to ChatCompletion as tool
     ///   messages.
-    async fn execute_single_tool_call(&self, tc: &ToolCallMessage) -> TextMessage {
+    async fn execute_single_tool_call(
+        &self,
+        tc: &ToolCallMessage,
+        cancel: Option<&CancellationToken>,
+        mut ctx: HashMap<String, serde_json::Value>,
+    ) -> TextMessage {
         let id_opt = tc.id().map(|s| s.to_string());
         let func_opt = tc.function();
 
         if let Some(func) = func_opt {
             let name = func.name().unwrap_or("").to_string();
             let args_str = func.arguments().unwrap_or("{}");
-            let args_json: serde_json::Value = serde_json::from_str(args_str)
-                .unwrap_or_else(|_| serde_json::json!({ "_raw": args_str }));
-
-            let content_json = match self.execute_simple(&name, args_json).await {
-                Ok(v) => v,
-                Err(err) => serde_json::json!({
-                    "error": { "type": "execution_failed", "message": err.to_string() }
-                }),
+            let args_json = match self.parse_call_arguments(args_str) {
+                Ok((args, repaired)) => {
+                    if repaired {
+                        ctx.insert("json_repaired".to_string(), serde_json::Value::Bool(true));
+                    }
+                    args
+                },
+                Err(_) => serde_json::json!({ "_raw": args_str }),
             };
 
-            let s = serde_json::to_string(&content_json).unwrap_or_else(|_| "{}".to_string());
-
-            if let Some(id) = id_opt {
-                TextMessage::tool_with_id(s, id)
-            } else {
-                TextMessage::tool(s)
+            let run = self.execute_with_context(&name, args_json, ctx);
+            let outcome = match cancel {
+                Some(token) => tokio::select! {
+                    biased;
+                    _ = token.cancelled() => Err(error_context().with_tool(name.as_str()).cancelled()),
+                    result = run => result,
+                },
+                None => run.await,
+            };
+            match outcome {
+                Ok(result) => result.into_tool_message(id_opt),
+                Err(err @ ToolError::Cancelled { .. }) => {
+                    tool_message(&tool_error_json("cancelled", err.to_string()), id_opt)
+                },
+                Err(err) => tool_message(
+                    &tool_error_json("execution_failed", err.to_string()),
+                    id_opt,
+                ),
             }
         } else {
-            let s = serde_json::json!({
-                "error": { "type": "missing_function", "message": "tool_call.function is missing" }
-            })
-            .to_string();
-
-            if let Some(id) = id_opt {
-                TextMessage::tool_with_id(s, id)
-            } else {
-                TextMessage::tool(s)
-            }
+            tool_message(
+                &tool_error_json("missing_function", "tool_call.function is missing"),
+                id_opt,
+            )
         }
     }
 
     pub async fn execute_tool_calls_parallel(&self, calls: &[ToolCallMessage]) -> Vec<TextMessage> {
+        self.run_tool_calls_parallel(calls, None, &[]).await
+    }
+
+    /// Like [`execute_tool_calls_parallel`](Self::execute_tool_calls_parallel),
+    /// merging `contexts[i]` into the metadata of call `i`'s result as
+    /// [`execute_with_context`](Self::execute_with_context) does. Calls
+    /// without an entry, or with `None`, get no extra metadata.
+    pub async fn execute_tool_calls_parallel_with_context(
+        &self,
+        calls: &[ToolCallMessage],
+        contexts: &[Option<HashMap<String, serde_json::Value>>],
+    ) -> Vec<TextMessage> {
+        self.run_tool_calls_parallel(calls, None, contexts).await
+    }
+
+    /// Like [`execute_tool_calls_parallel`](Self::execute_tool_calls_parallel),
+    /// but all outstanding calls are aborted when `token` is cancelled.
+    ///
+    /// Cancelled calls still yield a tool message whose content is
+    /// `{"error": {"type": "cancelled", ...}}`.
+    pub async fn execute_tool_calls_parallel_with_cancel(
+        &self,
+        calls: &[ToolCallMessage],
+        token: CancellationToken,
+    ) -> Vec<TextMessage> {
+        self.run_tool_calls_parallel(calls, Some(token), &[]).await
+    }
+
+    async fn run_tool_calls_parallel(
+        &self,
+        calls: &[ToolCallMessage],
+        cancel: Option<CancellationToken>,
+        contexts: &[Option<HashMap<String, serde_json::Value>>],
+    ) -> Vec<TextMessage> {
+        let span = tool_span!("tool.calls", mode = "parallel", count = calls.len());
         let mut set = JoinSet::new();
 
         // Clone the calls to avoid borrowing issues
         let calls_vec = calls.to_vec();
-        for tc in calls_vec {
+        for (i, tc) in calls_vec.into_iter().enumerate() {
             let this = self.clone();
-            set.spawn(async move { this.execute_single_tool_call(&tc).await });
+            let cancel = cancel.clone();
+            let ctx = call_context(contexts, i);
+            // Spawned tasks do not inherit the caller's span; attach it.
+            set.spawn(
+                async move {
+                    this.execute_single_tool_call(&tc, cancel.as_ref(), ctx)
+                        .await
+                }
+                .instrument(span.clone()),
+            );
         }
 
         let mut messages = Vec::with_capacity(calls.len());
@@ -538,28 +1138,258 @@ impl ToolExecutor {
     /// - Vec<TextMessage> in the same order as input calls, ready for
     ///   ChatCompletion
     pub async fn execute_tool_calls_ordered(&self, calls: &[ToolCallMessage]) -> Vec<TextMessage> {
+        self.run_tool_calls_ordered(calls, None, None, &[]).await
+    }
+
+    /// Like [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered),
+    /// pairing each message with the id of the call that produced it, so
+    /// results can be matched to calls without reading `tool_call_id` back
+    /// out of the messages. Calls without an id are paired with `""`.
+    pub async fn execute_tool_calls_ordered_with_ids(
+        &self,
+        calls: &[ToolCallMessage],
+    ) -> Vec<(String, TextMessage)> {
+        let messages = self.run_tool_calls_ordered(calls, None, None, &[]).await;
+        calls
+            .iter()
+            .map(|tc| tc.id().unwrap_or_default().to_string())
+            .zip(messages)
+            .collect()
+    }
+
+    /// Like [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered),
+    /// merging `contexts[i]` into the metadata of call `i`'s result as
+    /// [`execute_with_context`](Self::execute_with_context) does. Calls
+    /// without an entry, or with `None`, get no extra metadata.
+    pub async fn execute_tool_calls_ordered_with_context(
+        &self,
+        calls: &[ToolCallMessage],
+        contexts: &[Option<HashMap<String, serde_json::Value>>],
+    ) -> Vec<TextMessage> {
+        self.run_tool_calls_ordered(calls, None, None, contexts)
+            .await
+    }
+
+    /// Like [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered),
+    /// but all outstanding calls are aborted when `token` is cancelled.
+    ///
+    /// One message is still returned per input call, in order; cancelled
+    /// calls carry `{"error": {"type": "cancelled", ...}}` as a placeholder so
+    /// every tool_call id gets an answer.
+    pub async fn execute_tool_calls_ordered_with_cancel(
+        &self,
+        calls: &[ToolCallMessage],
+        token: CancellationToken,
+    ) -> Vec<TextMessage> {
+        self.run_tool_calls_ordered(calls, Some(token), None, &[])
+            .await
+    }
+
+    /// Like [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered),
+    /// but at most `max_concurrent` tools run at a time (a limit of 0 is
+    /// treated as 1). Results keep the order of `calls`.
+    pub async fn execute_tool_calls_ordered_limited(
+        &self,
+        calls: &[ToolCallMessage],
+        max_concurrent: usize,
+    ) -> Vec<TextMessage> {
+        self.run_tool_calls_ordered(calls, None, Some(max_concurrent), &[])
+            .await
+    }
+
+    async fn run_tool_calls_ordered(
+        &self,
+        calls: &[ToolCallMessage],
+        cancel: Option<CancellationToken>,
+        max_concurrent: Option<usize>,
+        contexts: &[Option<HashMap<String, serde_json::Value>>],
+    ) -> Vec<TextMessage> {
         use futures::future::join_all;
 
+        let limit = max_concurrent.map(|n| Arc::new(tokio::sync::Semaphore::new(n.max(1))));
         let calls_vec = calls.to_vec();
         let futures: Vec<_> = calls_vec
             .into_iter()
-            .map(|tc| {
+            .enumerate()
+            .map(|(i, tc)| {
                 let this = self.clone();
-                async move { this.execute_single_tool_call(&tc).await }
+                let cancel = cancel.clone();
+                let limit = limit.clone();
+                let ctx = call_context(contexts, i);
+                async move {
+                    // The semaphore is never closed, so acquiring cannot fail.
+                    let _permit = match &limit {
+                        Some(semaphore) => semaphore.acquire().await.ok(),
+                        None => None,
+                    };
+                    this.execute_single_tool_call(&tc, cancel.as_ref(), ctx)
+                        .await
+                }
             })
             .collect();
 
-        join_all(futures).await
+        join_all(futures)
+            .instrument(tool_span!(
+                "tool.calls",
+                mode = "ordered",
+                count = calls.len()
+            ))
+            .await
     }
 
-    /// Export a single registered tool as Tools::Function (for LLM function
-    /// calling)
-    pub fn export_tool_as_function(&self, name: &str) -> Option<Tools> {
-        let tool = self.tools.get(name)?;
-        let meta = tool.metadata();
-        let schema = tool.input_schema();
-        let func = Function::new(meta.name.clone(), meta.description.clone(), schema);
-        Some(Tools::Function { function: func })
+    /// Run a chat to completion, executing the tool calls it requests.
+    ///
+    /// Each round sends `chat`; when the first choice asks for tool calls, the
+    /// assistant message and the results of
+    /// [`execute_tool_calls_ordered`](Self::execute_tool_calls_ordered) are
+    /// appended and the request is sent again. At most `max_rounds` rounds of
+    /// tool calls are executed, so at most `max_rounds + 1` requests are sent.
+    ///
+    /// Returns the first response without tool calls. If the model still asks
+    /// for tools once `max_rounds` is used up, fails with
+    /// [`ZaiError::ToolRoundsExhausted`] carrying that response.
+    pub async fn drive_chat<N>(
+        &self,
+        chat: ChatCompletion<N, TextMessage, StreamOff>,
+        max_rounds: usize,
+    ) -> ZaiResult<ChatCompletionResponse>
+    where
+        N: ModelName + Chat + Serialize,
+        (N, TextMessage): Bounded,
+        ChatBody<N, TextMessage>: Serialize,
+    {
+        self.run_chat_loop(chat, max_rounds, None, None).await
+    }
+
+    /// Like [`drive_chat`](Self::drive_chat), adding the usage of every
+    /// response received to `usage`, including responses before an error.
+    /// Pass the same accumulator to several calls to total a whole session.
+    pub async fn drive_chat_with_usage<N>(
+        &self,
+        chat: ChatCompletion<N, TextMessage, StreamOff>,
+        max_rounds: usize,
+        usage: &mut UsageAccumulator,
+    ) -> ZaiResult<ChatCompletionResponse>
+    where
+        N: ModelName + Chat + Serialize,
+        (N, TextMessage): Bounded,
+        ChatBody<N, TextMessage>: Serialize,
+    {
+        self.run_chat_loop(chat, max_rounds, None, Some(usage))
+            .await
+    }
+
+    /// Like [`drive_chat`](Self::drive_chat), but gives up once `deadline`
+    /// passes.
+    ///
+    /// The remaining time is checked before each request and each batch of
+    /// tool calls. Requests are cut off at the deadline, and each tool
+    /// attempt's timeout is shortened to the remaining time. Running out of
+    /// time fails with [`ZaiError::DeadlineExceeded`], carrying the 1-based
+    /// round in progress and the last response received, if any.
+    pub async fn drive_chat_with_deadline<N>(
+        &self,
+        chat: ChatCompletion<N, TextMessage, StreamOff>,
+        max_rounds: usize,
+        deadline: Instant,
+    ) -> ZaiResult<ChatCompletionResponse>
+    where
+        N: ModelName + Chat + Serialize,
+        (N, TextMessage): Bounded,
+        ChatBody<N, TextMessage>: Serialize,
+    {
+        self.run_chat_loop(chat, max_rounds, Some(deadline), None)
+            .await
+    }
+
+    async fn run_chat_loop<N>(
+        &self,
+        mut chat: ChatCompletion<N, TextMessage, StreamOff>,
+        max_rounds: usize,
+        deadline: Option<Instant>,
+        mut usage: Option<&mut UsageAccumulator>,
+    ) -> ZaiResult<ChatCompletionResponse>
+    where
+        N: ModelName + Chat + Serialize,
+        (N, TextMessage): Bounded,
+        ChatBody<N, TextMessage>: Serialize,
+    {
+        let mut rounds = 0;
+        let mut last: Option<ChatCompletionResponse> = None;
+        let exceeded =
+            |round: usize, response: Option<ChatCompletionResponse>| ZaiError::DeadlineExceeded {
+                round,
+                response: response.map(Box::new),
+            };
+        loop {
+            let response = match remaining_until(deadline) {
+                None => chat.send().await?,
+                Some(Some(left)) => match timeout(left, chat.send()).await {
+                    Ok(response) => response?,
+                    Err(_) => return Err(exceeded(rounds + 1, last)),
+                },
+                Some(None) => return Err(exceeded(rounds + 1, last)),
+            };
+            if let Some(usage) = usage.as_deref_mut() {
+                usage.add_response(&response);
+            }
+            let Some(message) = response
+                .choices
+                .as_ref()
+                .and_then(|choices| choices.first())
+                .map(|choice| &choice.message)
+            else {
+                return Ok(response);
+            };
+            let calls = match message.tool_calls.as_deref() {
+                Some(calls) if !calls.is_empty() => calls,
+                _ => return Ok(response),
+            };
+            if rounds >= max_rounds {
+                return Err(ZaiError::ToolRoundsExhausted {
+                    rounds,
+                    response: Box::new(response),
+                });
+            }
+            rounds += 1;
+
+            let results = match remaining_until(deadline) {
+                None => self.execute_tool_calls_ordered(calls).await,
+                Some(Some(left)) => {
+                    let mut budgeted = self.clone();
+                    budgeted.config.timeout =
+                        Some(budgeted.config.timeout.map_or(left, |t| t.min(left)));
+                    if let Some(adaptive) = &mut budgeted.config.adaptive_timeout {
+                        adaptive.max = adaptive.max.min(left);
+                        adaptive.min = adaptive.min.min(left);
+                    }
+                    match timeout(left, budgeted.execute_tool_calls_ordered(calls)).await {
+                        Ok(results) => results,
+                        Err(_) => return Err(exceeded(rounds, Some(response))),
+                    }
+                },
+                Some(None) => return Err(exceeded(rounds, Some(response))),
+            };
+            // Tool attempts cut short by the budget end the round here.
+            if matches!(remaining_until(deadline), Some(None)) {
+                return Err(exceeded(rounds, Some(response)));
+            }
+            chat = chat.add_messages(assistant_tool_message(message));
+            for result in results {
+                chat = chat.add_messages(result);
+            }
+            last = Some(response);
+        }
+    }
+
+    /// Export a single registered tool as Tools::Function (for LLM function
+    /// calling)
+    pub fn export_tool_as_function(&self, name: &str) -> Option<Tools> {
+        let tool = self.tools.get(name)?;
+        let meta = tool.metadata();
+        let schema = tool.input_schema();
+        let func = Function::new(meta.name.clone(), meta.description.clone(), schema);
+        Some(Tools::Function { function: func })
     }
 
     /// Export all registered tools as a Vec<Tools::Function>
@@ -593,17 +1423,107 @@ impl ToolExecutor {
             .collect()
     }
 
+    /// Export all registered tools in the OpenAI `tools` format, sorted by
+    /// name.
+    ///
+    /// Each entry has exactly this shape:
+    ///
+    /// ```json
+    /// {"type": "function", "function": {"name": "...", "description": "...", "parameters": {...}}}
+    /// ```
+    ///
+    /// Unlike serializing [`export_all_tools_as_functions`](Self::export_all_tools_as_functions)
+    /// with Zhipu's [`Tools`] type, `parameters` is always present (an empty
+    /// object schema when the tool has none) and no other keys are emitted,
+    /// so the array loads directly into OpenAI-compatible frameworks.
+    pub fn export_openai_tools_json(&self) -> serde_json::Value {
+        let mut functions: Vec<Function> = self
+            .export_all_tools_as_functions()
+            .into_iter()
+            .filter_map(|tool| match tool {
+                Tools::Function { function } => Some(function),
+                _ => None,
+            })
+            .collect();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let tools = functions
+            .into_iter()
+            .map(|function| {
+                let parameters = function
+                    .parameters
+                    .unwrap_or_else(|| serde_json::json!({"type": "object", "properties": {}}));
+                serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": function.name,
+                        "description": function.description,
+                        "parameters": parameters,
+                    }
+                })
+            })
+            .collect();
+        serde_json::Value::Array(tools)
+    }
+
+    /// Write [`export_openai_tools_json`](Self::export_openai_tools_json) to
+    /// `path` as pretty-printed JSON.
+    pub fn write_openai_tools_json(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.export_openai_tools_json())?;
+        std::fs::write(path, json)
+    }
+
     async fn execute_once(
         &self,
         tool_name: &str,
         input: &serde_json::Value,
+        attempt: u32,
+        attempt_timeout: Option<Duration>,
+    ) -> ToolResult<serde_json::Value> {
+        let span = tool_span!(
+            "tool.attempt",
+            tool_name,
+            attempt = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        );
+        span.record("attempt", attempt);
+        let start = Instant::now();
+        let result = self
+            .execute_once_inner(tool_name, input, attempt_timeout)
+            .instrument(span.clone())
+            .await;
+        let elapsed = start.elapsed();
+        span.record("duration_ms", elapsed.as_millis() as u64);
+        if result.is_ok() {
+            self.record_latency(tool_name, elapsed);
+        }
+        result
+    }
+
+    async fn execute_once_inner(
+        &self,
+        tool_name: &str,
+        input: &serde_json::Value,
+        attempt_timeout: Option<Duration>,
     ) -> ToolResult<serde_json::Value> {
         let tool = self
             .get_tool(tool_name)
             .ok_or_else(|| error_context().with_tool(tool_name).tool_not_found())?;
-        let execution_future = tool.execute_json(input.clone());
+        // A panicking handler fails this call only, not the whole batch.
+        let execution_future = AssertUnwindSafe(tool.execute_json(input.clone()))
+            .catch_unwind()
+            .map(|outcome| {
+                outcome.unwrap_or_else(|panic| {
+                    Err(error_context()
+                        .with_tool(tool_name)
+                        .execution_failed(format!("tool panicked: {}", panic_message(&*panic))))
+                })
+            });
 
-        match self.config.timeout {
+        match attempt_timeout {
             Some(timeout_duration) => match timeout(timeout_duration, execution_future).await {
                 Ok(result) => result,
                 Err(_) => Err(error_context()
@@ -614,16 +1534,164 @@ impl ToolExecutor {
         }
     }
 
+    /// Timeout applied to the next call of `tool_name`: the adaptive
+    /// timeout once enough latencies are known, the static one otherwise
+    pub fn effective_timeout(&self, tool_name: &str) -> Option<Duration> {
+        let adaptive = self.config.adaptive_timeout.as_ref().and_then(|adaptive| {
+            let latencies = self.latencies.get(tool_name)?;
+            adaptive.compute(&latencies)
+        });
+        adaptive.or(self.config.timeout)
+    }
+
+    fn record_latency(&self, tool_name: &str, latency: Duration) {
+        let Some(adaptive) = &self.config.adaptive_timeout else {
+            return;
+        };
+        let mut latencies = self.latencies.entry(tool_name.to_string()).or_default();
+        if latencies.len() >= adaptive.window.max(1) {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+
     /// Get the config
     pub fn config(&self) -> &ExecutionConfig {
         &self.config
     }
 }
 
+/// Context for call `index`, empty when none was given.
+fn call_context(
+    contexts: &[Option<HashMap<String, serde_json::Value>>],
+    index: usize,
+) -> HashMap<String, serde_json::Value> {
+    contexts.get(index).cloned().flatten().unwrap_or_default()
+}
+
+/// Largest edit distance at which fuzzy tool matching accepts a name.
+pub const FUZZY_MAX_DISTANCE: usize = 2;
+
+/// Registered names closest to `requested` (ties sorted), or none within
+/// range. Names are compared case-insensitively; a name that `requested`
+/// extends with a separator-led suffix counts as distance 1.
+fn closest_tool_names(requested: &str, names: &[String]) -> Vec<String> {
+    let requested = requested.to_lowercase();
+    let mut best: Option<usize> = None;
+    let mut matches = Vec::new();
+    for name in names {
+        let lower = name.to_lowercase();
+        let extends = requested
+            .strip_prefix(&lower)
+            .is_some_and(|rest| rest.starts_with(['_', '-', '.']));
+        let distance = if lower == requested {
+            0
+        } else if extends {
+            1
+        } else {
+            edit_distance(&requested, &lower)
+        };
+        if distance > FUZZY_MAX_DISTANCE || best.is_some_and(|b| distance > b) {
+            continue;
+        }
+        if best != Some(distance) {
+            matches.clear();
+            best = Some(distance);
+        }
+        matches.push(name.clone());
+    }
+    matches.sort();
+    matches
+}
+
+/// Levenshtein distance between two strings, by character.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Text of a caught panic payload.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Time left until `deadline`: `None` without a deadline, `Some(None)` once
+/// it has passed.
+fn remaining_until(deadline: Option<Instant>) -> Option<Option<Duration>> {
+    deadline.map(|d| {
+        d.checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+    })
+}
+
+/// Echo an assistant message with tool calls back into the conversation.
+///
+/// Only function calls are kept; they are the calls this executor answers.
+fn assistant_tool_message(message: &Message) -> TextMessage {
+    let content = message
+        .content
+        .as_ref()
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(str::to_string);
+    let calls = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .filter_map(|tc| {
+            let func = tc.function.as_ref()?;
+            Some(ToolCall::new_function(
+                tc.id.clone().unwrap_or_default(),
+                FunctionParams::new(
+                    func.name.clone().unwrap_or_default(),
+                    func.arguments.clone().unwrap_or_else(|| "{}".to_string()),
+                ),
+            ))
+        })
+        .collect();
+    TextMessage::assistant_with_tools(content, calls)
+}
+
+/// Flatten an [`ExecutionResult`] into its value, turning a failed execution
+/// into [`ToolError::ExecutionFailed`].
+fn result_value(
+    tool_name: &str,
+    result: ToolResult<ExecutionResult>,
+) -> ToolResult<serde_json::Value> {
+    let result = result?;
+    if result.success {
+        Ok(result.result)
+    } else {
+        Err(error_context()
+            .with_tool(tool_name)
+            .execution_failed(result.error.unwrap_or_else(|| "Unknown error".to_string())))
+    }
+}
+
 /// Builder for creating tool executors with fluent API
 pub struct ExecutorBuilder {
     config: ExecutionConfig,
     cache_config: Option<CacheConfig>,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    output_transform: Option<OutputTransform>,
 }
 
 #[derive(Clone)]
@@ -645,6 +1713,8 @@ impl ExecutorBuilder {
         Self {
             config: ExecutionConfig::default(),
             cache_config: None,
+            metrics: None,
+            output_transform: None,
         }
     }
 
@@ -654,6 +1724,15 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Time each tool out at `factor` times the p95 of its recent
+    /// successful call latencies, bounded by `min` and `max`; see
+    /// [`AdaptiveTimeout`]. The static timeout applies until enough calls
+    /// have been observed.
+    pub fn adaptive_timeout(mut self, factor: f64, min: Duration, max: Duration) -> Self {
+        self.config.adaptive_timeout = Some(AdaptiveTimeout::new(factor, min, max));
+        self
+    }
+
     /// Set maximum number of retries
     pub fn retries(mut self, retries: u32) -> Self {
         self.config.retry_config.max_retries = retries;
@@ -666,6 +1745,20 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Enable or disable fuzzy tool-name resolution; see
+    /// [`ToolExecutor::with_fuzzy_tool_matching`]
+    pub fn fuzzy_tool_matching(mut self, enabled: bool) -> Self {
+        self.config.fuzzy_tool_matching = enabled;
+        self
+    }
+
+    /// Enable or disable repair of malformed tool-call arguments; see
+    /// [`ToolExecutor::with_json_repair`]
+    pub fn json_repair(mut self, enabled: bool) -> Self {
+        self.config.json_repair = enabled;
+        self
+    }
+
     /// Enable tool call result caching
     pub fn enable_cache(mut self) -> Self {
         self.cache_config
@@ -712,6 +1805,19 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Report every execution result to `sink`
+    pub fn metrics_sink(mut self, sink: Arc<dyn MetricsSink>) -> Self {
+        self.metrics = Some(sink);
+        self
+    }
+
+    /// Rewrite every successful tool output; see
+    /// [`ToolExecutor::with_output_transform`]
+    pub fn output_transform(mut self, transform: OutputTransform) -> Self {
+        self.output_transform = Some(transform);
+        self
+    }
+
     /// Build the final executor
     pub fn build(self) -> ToolExecutor {
         let cache = match self.cache_config {
@@ -726,14 +1832,23 @@ impl ExecutorBuilder {
             tools: Arc::new(DashMap::new()),
             config: self.config,
             cache,
+            metrics: self.metrics,
+            output_transform: self.output_transform,
+            latencies: Arc::new(DashMap::new()),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use tokio::sync::mpsc::UnboundedReceiver;
+
     use super::*;
-    use crate::toolkits::core::FunctionTool;
+    use crate::{
+        client::test_server::{Reply, Request, received, serve_in_order},
+        model::chat_base_response::ToolFunction,
+        toolkits::core::FunctionTool,
+    };
 
     #[test]
     fn test_retry_config_default() {
@@ -905,6 +2020,71 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_register_many_is_all_or_nothing() {
+        let tool = |name: &str| -> Box<dyn DynTool> {
+            Box::new(
+                FunctionTool::builder(name, "Bulk tool")
+                    .handler(|_args| async move { Ok(serde_json::json!({})) })
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let executor = ToolExecutor::new();
+        executor.add_dyn_tool(tool("existing")).unwrap();
+
+        let err = executor
+            .register_many(vec![tool("a"), tool("b"), tool("existing"), tool("c")])
+            .unwrap_err();
+        assert!(err.to_string().contains("existing"), "{}", err);
+        assert_eq!(executor.tool_names(), vec!["existing".to_string()]);
+
+        assert!(executor.register_many(vec![tool("a"), tool("a")]).is_err());
+        assert_eq!(executor.tool_names().len(), 1);
+
+        let names = executor.register_many(vec![tool("b"), tool("a")]).unwrap();
+        assert_eq!(names, vec!["b".to_string(), "a".to_string()]);
+        assert_eq!(executor.tool_names().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_merge_from_combines_or_rejects() {
+        let tool = |name: &str| -> Box<dyn DynTool> {
+            Box::new(
+                FunctionTool::builder(name, "Plugin tool")
+                    .handler(|_args| async move { Ok(serde_json::json!({"ok": true})) })
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let main = ToolExecutor::new();
+        main.add_dyn_tool(tool("search")).unwrap();
+        let plugin = ToolExecutor::new();
+        plugin
+            .register_many(vec![tool("weather"), tool("calc")])
+            .unwrap();
+
+        let merged = main.merge_from(&plugin).unwrap();
+        assert_eq!(merged, vec!["calc".to_string(), "weather".to_string()]);
+        let mut names = main.tool_names();
+        names.sort();
+        assert_eq!(names, vec!["calc", "search", "weather"]);
+        assert_eq!(plugin.tool_names().len(), 2);
+        assert!(
+            main.execute_simple("calc", serde_json::json!({}))
+                .await
+                .is_ok()
+        );
+
+        let clashing = ToolExecutor::new();
+        clashing
+            .register_many(vec![tool("fresh"), tool("search")])
+            .unwrap();
+        let err = main.merge_from(&clashing).unwrap_err();
+        assert!(err.to_string().contains("search"), "{}", err);
+        assert!(!main.has_tool("fresh"));
+    }
+
     #[test]
     fn test_tool_executor_try_add_dyn_tool() {
         let executor = ToolExecutor::new();
@@ -1001,6 +2181,36 @@ mod tests {
         assert!(names.contains(&"tool3".to_string()));
     }
 
+    #[test]
+    fn test_list_tools_describes_all_tools() {
+        let executor = ToolExecutor::new();
+        let tool = FunctionTool::builder("weather", "Get weather")
+            .property("city", serde_json::json!({"type": "string"}))
+            .metadata(|m| m.version("2.1.0"))
+            .handler(|_args| async move { Ok(serde_json::json!({})) })
+            .build()
+            .unwrap();
+        let other = FunctionTool::builder("add", "Add numbers")
+            .handler(|_args| async move { Ok(serde_json::json!({})) })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+        executor.add_dyn_tool(Box::new(other)).unwrap();
+
+        let tools = executor.list_tools();
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["add", "weather"]);
+        assert_eq!(tools[1].version, "2.1.0");
+        assert_eq!(
+            tools[1].input_schema,
+            executor.input_schema("weather").unwrap()
+        );
+
+        let json = serde_json::to_value(&tools[1]).unwrap();
+        assert_eq!(json["description"], "Get weather");
+        assert_eq!(json["input_schema"]["properties"]["city"]["type"], "string");
+    }
+
     #[tokio::test]
     async fn test_tool_executor_execute_success() {
         let executor = ToolExecutor::new();
@@ -1027,6 +2237,403 @@ mod tests {
         assert_eq!(result.retries, 0);
     }
 
+    /// A recorded span: its name and `(field, value)` pairs.
+    #[cfg(feature = "tracing")]
+    type RecordedSpan = (String, Vec<(String, String)>);
+
+    /// Records span names and fields for the `tracing` instrumentation tests.
+    #[cfg(feature = "tracing")]
+    #[derive(Default, Clone)]
+    struct SpanRecorder {
+        spans: Arc<std::sync::Mutex<Vec<RecordedSpan>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Vec::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name().to_string(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, id: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut FieldVisitor(&mut spans[id.into_u64() as usize - 1].1));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_execute_records_tracing_spans() {
+        let recorder = SpanRecorder::default();
+        let executor = ToolExecutor::new().with_cache_enabled(true);
+        let tool = FunctionTool::builder("echo", "Echo input")
+            .handler(|args| async move { Ok(args) })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                executor
+                    .execute("echo", serde_json::json!({}))
+                    .await
+                    .unwrap();
+                executor
+                    .execute("echo", serde_json::json!({}))
+                    .await
+                    .unwrap();
+            });
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        let field = |span: &RecordedSpan, name: &str| {
+            span.1
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+        };
+        let executes: Vec<_> = spans.iter().filter(|s| s.0 == "tool.execute").collect();
+        assert_eq!(executes.len(), 2);
+        for span in &executes {
+            assert_eq!(field(span, "tool_name").as_deref(), Some("\"echo\""));
+            assert_eq!(field(span, "attempt").as_deref(), Some("1"));
+            assert!(field(span, "duration_ms").is_some());
+        }
+        assert_eq!(field(executes[0], "cache_hit").as_deref(), Some("false"));
+        assert_eq!(field(executes[1], "cache_hit").as_deref(), Some("true"));
+
+        // The cached call never reaches the handler.
+        let attempts: Vec<_> = spans.iter().filter(|s| s.0 == "tool.attempt").collect();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(field(attempts[0], "attempt").as_deref(), Some("1"));
+    }
+
+    /// Answer one chat request per body in `bodies`, in order.
+    async fn serve_chat_responses(
+        bodies: Vec<serde_json::Value>,
+    ) -> (String, UnboundedReceiver<Request>) {
+        serve_in_order(bodies.into_iter().map(|b| Reply::json(200, b)).collect()).await
+    }
+
+    fn tool_call_response(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "resp",
+            "choices": [{
+                "index": 0,
+                "finish_reason": "tool_calls",
+                "message": {
+                    "role": "assistant",
+                    "tool_calls": [{
+                        "id": id,
+                        "type": "function",
+                        "function": {"name": "add_tool", "arguments": "{\"a\": 2, \"b\": 3}"}
+                    }]
+                }
+            }]
+        })
+    }
+
+    fn add_tool_executor() -> ToolExecutor {
+        let executor = ToolExecutor::new();
+        let tool = FunctionTool::builder("add_tool", "Add two numbers")
+            .property("a", serde_json::json!({"type": "number"}))
+            .property("b", serde_json::json!({"type": "number"}))
+            .handler(|args| async move {
+                let a = args["a"].as_i64().unwrap_or(0);
+                let b = args["b"].as_i64().unwrap_or(0);
+                Ok(serde_json::json!({"result": a + b}))
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+        executor
+    }
+
+    fn chat_against(
+        base: &str,
+    ) -> ChatCompletion<crate::model::chat_models::GLM4_5_flash, TextMessage, StreamOff> {
+        ChatCompletion::new(
+            crate::model::chat_models::GLM4_5_flash {},
+            TextMessage::user("2 + 3?"),
+            "key".to_string(),
+        )
+        .with_endpoint(crate::client::Endpoint::new(format!("{}/api", base)))
+    }
+
+    #[tokio::test]
+    async fn test_drive_chat_feeds_tool_results_back() {
+        let final_answer = serde_json::json!({
+            "id": "resp",
+            "choices": [{"index": 0, "finish_reason": "stop",
+                         "message": {"role": "assistant", "content": "5"}}]
+        });
+        let (base, mut server) =
+            serve_chat_responses(vec![tool_call_response("call_1"), final_answer]).await;
+
+        let response = add_tool_executor()
+            .drive_chat(chat_against(&base), 3)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.choices.unwrap()[0].message.content,
+            Some(serde_json::json!("5"))
+        );
+
+        let requests = received(&mut server);
+        assert_eq!(requests.len(), 2);
+        let body = requests[1].json();
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(messages[1]["tool_calls"][0]["function"]["name"], "add_tool");
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], "call_1");
+        assert_eq!(messages[2]["content"], "{\"result\":5}");
+    }
+
+    #[tokio::test]
+    async fn test_drive_chat_with_usage_sums_rounds() {
+        let mut first = tool_call_response("call_1");
+        first["model"] = serde_json::json!("glm-4.5-flash");
+        first["usage"] = serde_json::json!({"prompt_tokens": 40, "completion_tokens": 10});
+        let final_answer = serde_json::json!({
+            "id": "resp",
+            "model": "glm-4.5-flash",
+            "usage": {"prompt_tokens": 60, "completion_tokens": 2, "total_tokens": 62},
+            "choices": [{"index": 0, "finish_reason": "stop",
+                         "message": {"role": "assistant", "content": "5"}}]
+        });
+        let (base, _server) = serve_chat_responses(vec![first, final_answer]).await;
+
+        let mut usage = UsageAccumulator::new();
+        add_tool_executor()
+            .drive_chat_with_usage(chat_against(&base), 3, &mut usage)
+            .await
+            .unwrap();
+        assert_eq!(usage.requests(), 2);
+        let total = usage.total();
+        assert_eq!(total.prompt_tokens, Some(100));
+        assert_eq!(total.completion_tokens, Some(12));
+        assert_eq!(total.total_tokens, Some(112));
+    }
+
+    #[tokio::test]
+    async fn test_drive_chat_stops_after_max_rounds() {
+        let (base, mut server) =
+            serve_chat_responses(vec![tool_call_response("c1"), tool_call_response("c2")]).await;
+
+        let err = add_tool_executor()
+            .drive_chat(chat_against(&base), 1)
+            .await
+            .unwrap_err();
+        match err {
+            ZaiError::ToolRoundsExhausted { rounds, response } => {
+                assert_eq!(rounds, 1);
+                let calls = response.choices.unwrap()[0].message.tool_calls.clone();
+                assert_eq!(calls.unwrap()[0].id.as_deref(), Some("c2"));
+            },
+            other => panic!("unexpected error: {other}"),
+        }
+        assert_eq!(received(&mut server).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_drive_chat_with_deadline_stops_mid_round() {
+        let (base, mut server) = serve_chat_responses(vec![tool_call_response("c1")]).await;
+        let executor = ToolExecutor::new();
+        let slow = FunctionTool::builder("add_tool", "Slow add")
+            .property("a", serde_json::json!({"type": "number"}))
+            .property("b", serde_json::json!({"type": "number"}))
+            .handler(|_args| async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(serde_json::json!({}))
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(slow)).unwrap();
+
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(300);
+        let err = executor
+            .drive_chat_with_deadline(chat_against(&base), 3, deadline)
+            .await
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        match err {
+            ZaiError::DeadlineExceeded { round, response } => {
+                assert_eq!(round, 1);
+                let calls = response.unwrap().choices.unwrap()[0]
+                    .message
+                    .tool_calls
+                    .clone();
+                assert_eq!(calls.unwrap()[0].id.as_deref(), Some("c1"));
+            },
+            other => panic!("unexpected error: {other}"),
+        }
+        assert_eq!(received(&mut server).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_drive_chat_with_past_deadline_sends_nothing() {
+        let err = add_tool_executor()
+            .drive_chat_with_deadline(chat_against("http://127.0.0.1:9"), 3, Instant::now())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ZaiError::DeadlineExceeded {
+                round: 1,
+                response: None
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_output_transform_truncates_before_caching() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = calls.clone();
+        let page = FunctionTool::builder("fetch_page", "Fetch a page")
+            .handler(move |_args| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Ok(serde_json::json!({"body": "x".repeat(10_000)}))
+                }
+            })
+            .build()
+            .unwrap();
+        let transform: OutputTransform = Arc::new(|tool, mut output| {
+            assert_eq!(tool, "fetch_page");
+            if let Some(body) = output["body"].as_str()
+                && body.len() > 100
+            {
+                output["body"] = serde_json::json!(&body[..100]);
+                output["_truncated"] = serde_json::json!(true);
+            }
+            output
+        });
+        let executor = ToolExecutor::new()
+            .with_cache_enabled(true)
+            .with_output_transform(transform);
+        executor.add_dyn_tool(Box::new(page)).unwrap();
+
+        let first = executor
+            .execute("fetch_page", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(first.result["body"].as_str().unwrap().len(), 100);
+        assert_eq!(first.result["_truncated"], true);
+
+        let cached = executor
+            .execute("fetch_page", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(cached.metadata["cache_hit"], true);
+        assert_eq!(cached.result, first.result);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_no_cache_bypasses_lookup_and_insert() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = calls.clone();
+        let clock = FunctionTool::builder("clock", "Counts its invocations")
+            .property("tz", serde_json::json!({"type": "string"}))
+            .handler(move |_args| {
+                let counter = counter.clone();
+                async move {
+                    let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                    Ok(serde_json::json!({"call": n}))
+                }
+            })
+            .build()
+            .unwrap();
+        let executor = ToolExecutor::new().with_cache_enabled(true);
+        executor.add_dyn_tool(Box::new(clock)).unwrap();
+        let input = || serde_json::json!({"tz": "UTC"});
+
+        let first = executor.execute("clock", input()).await.unwrap();
+        assert_eq!(first.result["call"], 1);
+        assert!(!first.metadata.contains_key("cache_bypassed"));
+
+        // Not served from the cache, and not written to it either.
+        let fresh = executor.execute_no_cache("clock", input()).await.unwrap();
+        assert_eq!(fresh.result["call"], 2);
+        assert_eq!(fresh.metadata["cache_bypassed"], true);
+        assert_eq!(fresh.metadata["cache_hit"], false);
+
+        let cached = executor.execute("clock", input()).await.unwrap();
+        assert_eq!(cached.metadata["cache_hit"], true);
+        assert_eq!(cached.result["call"], 1);
+
+        // A fresh input is not cached by a bypassing call.
+        let other = || serde_json::json!({"tz": "CET"});
+        executor.execute_no_cache("clock", other()).await.unwrap();
+        let miss = executor.execute("clock", other()).await.unwrap();
+        assert_eq!(miss.metadata["cache_hit"], false);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_execution_result_into_tool_message() {
+        let ok = ExecutionResult::success(
+            "add_tool".to_string(),
+            serde_json::json!({"result": 5}),
+            Duration::ZERO,
+            0,
+        );
+        let msg = serde_json::to_value(ok.into_tool_message(Some("call_1".to_string()))).unwrap();
+        assert_eq!(msg["role"], "tool");
+        assert_eq!(msg["tool_call_id"], "call_1");
+        assert_eq!(msg["content"], "{\"result\":5}");
+
+        let failed = ExecutionResult::failure(
+            "add_tool".to_string(),
+            "division by zero".to_string(),
+            Duration::ZERO,
+            2,
+        );
+        let msg = serde_json::to_value(failed.into_tool_message(None)).unwrap();
+        assert!(msg.get("tool_call_id").is_none());
+        let content: serde_json::Value =
+            serde_json::from_str(msg["content"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            content,
+            serde_json::json!({"error": {
+                "type": "execution_failed",
+                "message": "Tool 'add_tool' execution failed: division by zero"
+            }})
+        );
+    }
+
     #[tokio::test]
     async fn test_tool_executor_execute_failure() {
         let executor = ToolExecutor::new();
@@ -1172,6 +2779,75 @@ mod tests {
         assert_eq!(builder.config.timeout, Some(Duration::from_secs(60)));
     }
 
+    #[test]
+    fn test_adaptive_timeout_compute() {
+        let adaptive = AdaptiveTimeout::new(2.0, Duration::from_millis(50), Duration::from_secs(1));
+        let mut latencies: VecDeque<Duration> =
+            (1..=4).map(|ms| Duration::from_millis(ms * 10)).collect();
+        assert_eq!(adaptive.compute(&latencies), None);
+
+        latencies.push_back(Duration::from_millis(100));
+        assert_eq!(
+            adaptive.compute(&latencies),
+            Some(Duration::from_millis(200))
+        );
+        // Clamped to the bounds.
+        latencies
+            .iter_mut()
+            .for_each(|l| *l = Duration::from_millis(1));
+        assert_eq!(
+            adaptive.compute(&latencies),
+            Some(Duration::from_millis(50))
+        );
+        latencies
+            .iter_mut()
+            .for_each(|l| *l = Duration::from_secs(5));
+        assert_eq!(adaptive.compute(&latencies), Some(Duration::from_secs(1)));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_timeout_converges_below_static() {
+        let executor = ExecutorBuilder::new()
+            .adaptive_timeout(3.0, Duration::from_millis(20), Duration::from_secs(60))
+            .disable_cache()
+            .build();
+        let tool = FunctionTool::builder("fast", "Fast tool")
+            .handler(|_| async {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                Ok(serde_json::json!({}))
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+
+        let static_ms = 30_000;
+        let first = executor
+            .execute("fast", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(first.metadata["timeout_ms"], static_ms);
+        for _ in 0..5 {
+            executor
+                .execute("fast", serde_json::json!({}))
+                .await
+                .unwrap();
+        }
+
+        let adaptive = executor.effective_timeout("fast").unwrap();
+        assert!(adaptive < Duration::from_secs(1), "{:?}", adaptive);
+        assert!(adaptive >= Duration::from_millis(20));
+        let next = executor
+            .execute("fast", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(next.metadata["timeout_ms"].as_u64().unwrap() < static_ms);
+        // Tools without samples keep the static timeout.
+        assert_eq!(
+            executor.effective_timeout("other"),
+            Some(Duration::from_secs(30))
+        );
+    }
+
     #[test]
     fn test_executor_builder_retries() {
         let builder = ExecutorBuilder::new().retries(5);
@@ -1275,6 +2951,47 @@ mod tests {
         assert!(names.contains(&"tool2".to_string()));
     }
 
+    #[test]
+    fn test_export_openai_tools_json_shape() {
+        let executor = ToolExecutor::new();
+        for (name, description) in [("zeta", "Last tool"), ("alpha", "First tool")] {
+            let tool = FunctionTool::builder(name, description)
+                .property("q", serde_json::json!({"type": "string"}))
+                .required("q")
+                .handler(|_args| async move { Ok(serde_json::json!({})) })
+                .build()
+                .unwrap();
+            executor.add_dyn_tool(Box::new(tool)).unwrap();
+        }
+
+        let exported = executor.export_openai_tools_json();
+        let tools = exported.as_array().unwrap();
+        assert_eq!(tools.len(), 2);
+        let first = &tools[0];
+        assert_eq!(first["type"], "function");
+        assert_eq!(first.as_object().unwrap().len(), 2);
+        let function = first["function"].as_object().unwrap();
+        let mut keys: Vec<_> = function.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["description", "name", "parameters"]);
+        assert_eq!(function["name"], "alpha");
+        assert_eq!(function["description"], "First tool");
+        assert_eq!(function["parameters"]["type"], "object");
+        assert_eq!(
+            function["parameters"]["properties"]["q"],
+            serde_json::json!({"type": "string"})
+        );
+        assert_eq!(function["parameters"]["required"], serde_json::json!(["q"]));
+        assert_eq!(tools[1]["function"]["name"], "zeta");
+
+        let path = std::env::temp_dir().join(format!("tools-{}.json", uuid::Uuid::new_v4()));
+        executor.write_openai_tools_json(&path).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, exported);
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_export_tools_filtered() {
         let executor = ToolExecutor::new();
@@ -1371,10 +3088,32 @@ mod tests {
         assert_eq!(attempt_counter.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 
+    /// A function tool call as the model sends it
+    fn tool_call<'a>(id: impl Into<Option<&'a str>>, name: &str, args: &str) -> ToolCallMessage {
+        ToolCallMessage {
+            index: None,
+            id: id.into().map(str::to_string),
+            type_: Some("function".to_string()),
+            function: Some(ToolFunction {
+                name: Some(name.to_string()),
+                arguments: Some(args.to_string()),
+            }),
+            mcp: None,
+        }
+    }
+
+    /// Metrics sink keeping every recorded result
+    #[derive(Default)]
+    struct Recorded(std::sync::Mutex<Vec<ExecutionResult>>);
+
+    impl MetricsSink for Recorded {
+        fn record(&self, result: &ExecutionResult) {
+            self.0.lock().unwrap().push(result.clone());
+        }
+    }
+
     #[tokio::test]
     async fn test_execute_tool_calls_ordered_preserves_order() {
-        use crate::model::chat_base_response::{ToolCallMessage, ToolFunction};
-
         let executor = ToolExecutor::new();
 
         // Register two tools that return different results
@@ -1400,24 +3139,8 @@ mod tests {
         executor.add_dyn_tool(Box::new(tool2)).unwrap();
 
         let calls = vec![
-            ToolCallMessage {
-                id: Some("call_1".to_string()),
-                type_: Some("function".to_string()),
-                function: Some(ToolFunction {
-                    name: Some("tool_a".to_string()),
-                    arguments: Some(r#"{"n": 1}"#.to_string()),
-                }),
-                mcp: None,
-            },
-            ToolCallMessage {
-                id: Some("call_2".to_string()),
-                type_: Some("function".to_string()),
-                function: Some(ToolFunction {
-                    name: Some("tool_b".to_string()),
-                    arguments: Some(r#"{"n": 2}"#.to_string()),
-                }),
-                mcp: None,
-            },
+            tool_call("call_1", "tool_a", r#"{"n": 1}"#),
+            tool_call("call_2", "tool_b", r#"{"n": 2}"#),
         ];
 
         let results = executor.execute_tool_calls_ordered(&calls).await;
@@ -1443,10 +3166,131 @@ mod tests {
         assert!(parsed2["n"].as_i64() == Some(2));
     }
 
+    fn named_tool(name: &str) -> Box<dyn DynTool> {
+        let reply = name.to_string();
+        Box::new(
+            FunctionTool::builder(name, "Reports its name")
+                .handler(move |_args| {
+                    let reply = reply.clone();
+                    async move { Ok(serde_json::json!(reply)) }
+                })
+                .build()
+                .unwrap(),
+        )
+    }
+
     #[tokio::test]
-    async fn test_execute_tool_calls_parallel_returns_all() {
-        use crate::model::chat_base_response::{ToolCallMessage, ToolFunction};
+    async fn test_fuzzy_tool_matching_resolves_near_miss() {
+        let executor = ToolExecutor::new();
+        executor.add_dyn_tool(named_tool("get_weather")).unwrap();
+        executor.add_dyn_tool(named_tool("send_email")).unwrap();
+
+        let miss = executor
+            .execute("get_weather_tool", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(!miss.success, "fuzzy matching is opt-in");
+
+        let executor = executor.with_fuzzy_tool_matching(true);
+        for requested in ["get_weather_tool", "Get_Weather", "get_wether"] {
+            let result = executor
+                .execute(requested, serde_json::json!({}))
+                .await
+                .unwrap();
+            assert!(result.success, "{}: {:?}", requested, result.error);
+            assert_eq!(result.tool_name, "get_weather");
+            assert_eq!(result.result, serde_json::json!("get_weather"));
+            assert_eq!(
+                result.metadata["resolved_from"],
+                serde_json::json!(requested)
+            );
+        }
+
+        let exact = executor
+            .execute("send_email", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(!exact.metadata.contains_key("resolved_from"));
+
+        let far = executor
+            .execute("lookup_stock", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(far.error.unwrap().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_tool_matching_reports_ambiguity() {
+        let executor = ToolExecutor::new().with_fuzzy_tool_matching(true);
+        executor.add_dyn_tool(named_tool("get_data")).unwrap();
+        executor.add_dyn_tool(named_tool("set_data")).unwrap();
+
+        let error = executor
+            .execute("xet_data", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, ToolError::AmbiguousToolName { name, candidates }
+                if name == "xet_data" && candidates == &["get_data", "set_data"]),
+            "{:?}",
+            error
+        );
+        assert!(
+            error.to_string().contains("get_data, set_data"),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_panicking_tool_fails_only_its_call() {
+        let executor = ToolExecutor::new();
+        let panicking = FunctionTool::builder("boom", "Always panics")
+            .handler(|_args| async move {
+                if true {
+                    panic!("kaboom");
+                }
+                Ok(serde_json::json!({}))
+            })
+            .build()
+            .unwrap();
+        let echo = FunctionTool::builder("echo", "Echo input")
+            .handler(|args| async move { Ok(args) })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(panicking)).unwrap();
+        executor.add_dyn_tool(Box::new(echo)).unwrap();
+
+        let result = executor
+            .execute("boom", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("tool panicked: kaboom"), "{}", error);
+
+        let call = |id: &str, name: &str| tool_call(id, name, "{}");
+        let calls = vec![call("c1", "boom"), call("c2", "echo")];
+        let contents: Vec<serde_json::Value> = executor
+            .execute_tool_calls_parallel(&calls)
+            .await
+            .into_iter()
+            .map(|m| match m {
+                TextMessage::Tool { content, .. } => serde_json::from_str(&content).unwrap(),
+                _ => panic!("Expected Tool message"),
+            })
+            .collect();
+        assert_eq!(contents.len(), 2);
+        assert!(contents.contains(&serde_json::json!({})), "{:?}", contents);
+        assert!(contents.iter().any(|c| {
+            c["error"]["message"]
+                .as_str()
+                .is_some_and(|m| m.contains("tool panicked: kaboom"))
+        }));
+    }
 
+    #[tokio::test]
+    async fn test_execute_tool_calls_parallel_returns_all() {
         let executor = ToolExecutor::new();
 
         let tool1 = FunctionTool::builder("parallel_a", "First parallel tool")
@@ -1471,27 +3315,447 @@ mod tests {
         executor.add_dyn_tool(Box::new(tool2)).unwrap();
 
         let calls = vec![
+            tool_call("call_1", "parallel_a", r#"{"n": 1}"#),
+            tool_call("call_2", "parallel_b", r#"{"n": 2}"#),
+        ];
+
+        let results = executor.execute_tool_calls_parallel(&calls).await;
+        assert_eq!(results.len(), 2);
+    }
+
+    fn validated_tool(called: Arc<std::sync::atomic::AtomicBool>) -> FunctionTool {
+        FunctionTool::builder("add", "Add two numbers")
+            .property("a", serde_json::json!({"type": "number"}))
+            .property("b", serde_json::json!({"type": "number"}))
+            .required("a")
+            .required("b")
+            .handler(move |_args| {
+                let called = called.clone();
+                async move {
+                    called.store(true, std::sync::atomic::Ordering::SeqCst);
+                    Ok(serde_json::json!({}))
+                }
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_call() {
+        let called = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let executor = ToolExecutor::new();
+        executor
+            .add_dyn_tool(Box::new(validated_tool(called.clone())))
+            .unwrap();
+
+        assert!(
+            executor
+                .validate_call("add", &serde_json::json!({"a": 1, "b": 2}))
+                .is_ok()
+        );
+
+        let err = executor
+            .validate_call("add", &serde_json::json!({"a": "x"}))
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidParameters { .. }));
+        let msg = err.to_string();
+        assert!(msg.contains("/a"));
+        assert!(msg.contains("\"b\""));
+
+        assert!(matches!(
+            executor.validate_call("missing", &serde_json::json!({})),
+            Err(ToolError::ToolNotFound { .. })
+        ));
+        assert!(!called.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_validate_tool_calls() {
+        let called = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let executor = ToolExecutor::new();
+        executor
+            .add_dyn_tool(Box::new(validated_tool(called.clone())))
+            .unwrap();
+
+        let call = |name: &str, args: &str| tool_call(None, name, args);
+        let calls = vec![
+            call("add", r#"{"a": 1, "b": 2}"#),
+            call("add", r#"{"a": 1}"#),
+            call("add", "not json"),
+            call("unknown", "{}"),
             ToolCallMessage {
-                id: Some("call_1".to_string()),
-                type_: Some("function".to_string()),
-                function: Some(ToolFunction {
-                    name: Some("parallel_a".to_string()),
-                    arguments: Some(r#"{"n": 1}"#.to_string()),
-                }),
-                mcp: None,
-            },
-            ToolCallMessage {
-                id: Some("call_2".to_string()),
-                type_: Some("function".to_string()),
-                function: Some(ToolFunction {
-                    name: Some("parallel_b".to_string()),
-                    arguments: Some(r#"{"n": 2}"#.to_string()),
-                }),
+                index: None,
+                id: None,
+                type_: None,
+                function: None,
                 mcp: None,
             },
         ];
 
-        let results = executor.execute_tool_calls_parallel(&calls).await;
-        assert_eq!(results.len(), 2);
+        let results = executor.validate_tool_calls(&calls);
+        assert_eq!(results.len(), 5);
+        assert!(results[0].is_ok());
+        assert!(results[1..].iter().all(Result::is_err));
+        assert!(!called.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_json_repair_of_tool_call_arguments() {
+        let recorded = Arc::new(Recorded::default());
+        let tool = FunctionTool::builder("echo", "Echo")
+            .property("city", serde_json::json!({"type": "string"}))
+            .handler(|args| async move { Ok(args) })
+            .build()
+            .unwrap();
+        let executor = ToolExecutor::builder()
+            .metrics_sink(recorded.clone())
+            .build();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+        let call = |args: &str| tool_call("call_1", "echo", args);
+        let content = |message: &TextMessage| match message {
+            TextMessage::Tool { content, .. } => content.clone(),
+            other => panic!("expected a tool message, got {:?}", other),
+        };
+        let malformed = call("{city: 'Paris',}");
+
+        // Off by default: the arguments reach the tool as `_raw`.
+        let off = executor
+            .execute_tool_calls_ordered(std::slice::from_ref(&malformed))
+            .await;
+        assert!(content(&off[0]).contains("_raw"));
+        assert!(executor.validate_tool_calls(std::slice::from_ref(&malformed))[0].is_err());
+
+        let executor = executor.with_json_repair(true);
+        let on = executor
+            .execute_tool_calls_ordered(&[malformed.clone(), call("{\"city\": \"Rome\"}")])
+            .await;
+        assert_eq!(content(&on[0]), r#"{"city":"Paris"}"#);
+        assert!(executor.validate_tool_calls(&[malformed])[0].is_ok());
+
+        {
+            let results = recorded.0.lock().unwrap();
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[1].metadata["json_repaired"], true);
+            assert!(!results[2].metadata.contains_key("json_repaired"));
+        }
+
+        // Unrepairable input still falls back to `_raw`.
+        let garbled = executor
+            .execute_tool_calls_ordered(&[call("the city is Paris")])
+            .await;
+        assert!(content(&garbled[0]).contains("_raw"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_context_passes_metadata_through() {
+        let recorded = Arc::new(Recorded::default());
+        let executor = ToolExecutor::builder()
+            .retries(0)
+            .metrics_sink(recorded.clone())
+            .build();
+        let tool = FunctionTool::builder("check", "Fails on demand")
+            .property("fail", serde_json::json!({"type": "boolean"}))
+            .property("id", serde_json::json!({"type": "string"}))
+            .handler(|args| async move {
+                if args["fail"] == true {
+                    Err(error_context().execution_failed("requested failure"))
+                } else {
+                    Ok(args)
+                }
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+        let ctx = |request_id: &str| {
+            HashMap::from([
+                ("request_id".to_string(), serde_json::json!(request_id)),
+                ("cache_hit".to_string(), serde_json::json!("overridden?")),
+            ])
+        };
+
+        let ok = executor
+            .execute_with_context("check", serde_json::json!({"fail": false}), ctx("r-1"))
+            .await
+            .unwrap();
+        assert!(ok.success);
+        assert_eq!(ok.metadata["request_id"], "r-1");
+        assert_eq!(ok.metadata["cache_hit"], false, "executor keys win");
+
+        let failed = executor
+            .execute_with_context("check", serde_json::json!({"fail": true}), ctx("r-2"))
+            .await
+            .unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.metadata["request_id"], "r-2");
+
+        let call = |id: &str| {
+            let args = format!(r#"{{"fail": false, "id": "{}"}}"#, id);
+            tool_call(id, "check", &args)
+        };
+        executor
+            .execute_tool_calls_ordered_with_context(&[call("a"), call("b")], &[Some(ctx("r-3"))])
+            .await;
+        executor
+            .execute_tool_calls_parallel_with_context(&[call("c")], &[Some(ctx("r-4"))])
+            .await;
+
+        let results = recorded.0.lock().unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].metadata["request_id"], "r-1");
+        assert_eq!(results[1].metadata["request_id"], "r-2");
+        let by_id = |id: &str| {
+            results[2..]
+                .iter()
+                .find(|r| r.result["id"] == id)
+                .unwrap()
+                .metadata
+                .get("request_id")
+                .cloned()
+        };
+        assert_eq!(by_id("a"), Some(serde_json::json!("r-3")));
+        assert_eq!(by_id("b"), None);
+        assert_eq!(by_id("c"), Some(serde_json::json!("r-4")));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_sink_records_every_execute() {
+        use crate::toolkits::metrics::InMemoryMetrics;
+
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let executor = ToolExecutor::builder()
+            .retries(0)
+            .metrics_sink(metrics.clone())
+            .build();
+        let tool = FunctionTool::builder("echo", "Echo")
+            .property("fail", serde_json::json!({"type": "boolean"}))
+            .handler(|args| async move {
+                if args["fail"] == true {
+                    Err(error_context().execution_failed("requested failure"))
+                } else {
+                    Ok(args)
+                }
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+
+        executor
+            .execute("echo", serde_json::json!({"fail": false}))
+            .await
+            .unwrap();
+        executor
+            .execute("echo", serde_json::json!({"fail": true}))
+            .await
+            .unwrap();
+
+        let stats = &metrics.snapshot()["echo"];
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.successes, 1);
+        assert!((stats.success_rate - 0.5).abs() < f64::EPSILON);
+    }
+
+    fn slow_tool(name: &str) -> FunctionTool {
+        FunctionTool::builder(name, "Sleeps for a long time")
+            .handler(|_args| async move {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(serde_json::json!({"done": true}))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_cancel() {
+        let executor = ToolExecutor::new();
+        executor.add_dyn_tool(Box::new(slow_tool("slow"))).unwrap();
+
+        let token = CancellationToken::new();
+        let trigger = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let err = executor
+            .execute_with_cancel("slow", serde_json::json!({}), token)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Cancelled { .. }));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_calls_ordered_with_ids() {
+        let executor = ToolExecutor::new();
+        // Finishes last, so order cannot come from completion order.
+        let slow = FunctionTool::builder("slow", "Sleeps briefly")
+            .handler(|_args| async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(serde_json::json!({"done": true}))
+            })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(slow)).unwrap();
+        let echo = FunctionTool::builder("echo", "Echoes its arguments")
+            .property("n", serde_json::json!({"type": "number"}))
+            .handler(|args| async move { Ok(args) })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(echo)).unwrap();
+
+        let calls = vec![
+            tool_call("c1", "slow", "{}"),
+            tool_call("c2", "missing_tool", "{}"),
+            tool_call(None, "echo", r#"{"n": 3}"#),
+            tool_call("c4", "echo", r#"{"n": 4}"#),
+        ];
+
+        let results = executor.execute_tool_calls_ordered_with_ids(&calls).await;
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["c1", "c2", "", "c4"]);
+        for (id, message) in &results {
+            let TextMessage::Tool {
+                content,
+                tool_call_id,
+            } = message
+            else {
+                panic!("unexpected message: {:?}", message);
+            };
+            assert_eq!(tool_call_id.as_deref().unwrap_or_default(), id);
+            match id.as_str() {
+                "c2" => assert!(content.contains("execution_failed"), "{}", content),
+                "" => assert!(content.contains("\"n\":3"), "{}", content),
+                "c4" => assert!(content.contains("\"n\":4"), "{}", content),
+                _ => assert!(content.contains("\"done\":true"), "{}", content),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_calls_ordered_with_cancel() {
+        let executor = ToolExecutor::new();
+        executor.add_dyn_tool(Box::new(slow_tool("slow"))).unwrap();
+        let fast = FunctionTool::builder("fast", "Returns immediately")
+            .handler(|_args| async move { Ok(serde_json::json!({"ok": true})) })
+            .build()
+            .unwrap();
+        executor.add_dyn_tool(Box::new(fast)).unwrap();
+
+        let call = |id: &str, name: &str| tool_call(id, name, "{}");
+        let calls = vec![call("c1", "slow"), call("c2", "fast"), call("c3", "slow")];
+
+        let token = CancellationToken::new();
+        let trigger = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            trigger.cancel();
+        });
+
+        let messages = executor
+            .execute_tool_calls_ordered_with_cancel(&calls, token)
+            .await;
+        assert_eq!(messages.len(), 3);
+        let contents: Vec<(String, String)> = messages
+            .into_iter()
+            .map(|m| match m {
+                TextMessage::Tool {
+                    content,
+                    tool_call_id,
+                } => (tool_call_id.unwrap(), content),
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect();
+        assert_eq!(contents[0].0, "c1");
+        assert!(contents[0].1.contains("\"cancelled\""));
+        assert_eq!(contents[1].0, "c2");
+        assert!(contents[1].1.contains("\"ok\":true"));
+        assert_eq!(contents[2].0, "c3");
+        assert!(contents[2].1.contains("\"cancelled\""));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_calls_ordered_limited_caps_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (r, p) = (running.clone(), peak.clone());
+        let tool = FunctionTool::builder("tracked", "Tracks concurrent runs")
+            .property("n", serde_json::json!({"type": "integer"}))
+            .handler(move |args| {
+                let (running, peak) = (r.clone(), p.clone());
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(serde_json::json!({"n": args["n"]}))
+                }
+            })
+            .build()
+            .unwrap();
+        let executor = ToolExecutor::new();
+        executor.add_dyn_tool(Box::new(tool)).unwrap();
+
+        let calls: Vec<ToolCallMessage> = (0..6)
+            .map(|n| {
+                tool_call(
+                    format!("c{}", n).as_str(),
+                    "tracked",
+                    &format!("{{\"n\": {}}}", n),
+                )
+            })
+            .collect();
+
+        let messages = executor.execute_tool_calls_ordered_limited(&calls, 2).await;
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let ids: Vec<_> = messages
+            .iter()
+            .map(|m| match m {
+                TextMessage::Tool { tool_call_id, .. } => tool_call_id.clone().unwrap(),
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, vec!["c0", "c1", "c2", "c3", "c4", "c5"]);
+    }
+
+    #[tokio::test]
+    async fn test_add_functions_from_specs_with_registry() {
+        let mut handlers: std::collections::HashMap<String, ToolHandler> =
+            std::collections::HashMap::new();
+        handlers.insert(
+            "get_weather".to_string(),
+            Arc::new(|args| Box::pin(async move { Ok(serde_json::json!({"city": args["city"]})) })),
+        );
+
+        let specs = vec![
+            serde_json::json!({
+                "name": "get_weather",
+                "description": "Get weather",
+                "parameters": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }),
+            serde_json::json!({
+                "type": "function",
+                "function": {"name": "no_handler", "description": "Skipped"}
+            }),
+        ];
+
+        let executor = ToolExecutor::new();
+        let added = executor
+            .add_functions_from_specs_with_registry(&specs, &handlers, false)
+            .unwrap();
+        assert_eq!(added, vec!["get_weather".to_string()]);
+        assert!(!executor.has_tool("no_handler"));
+        let out = executor
+            .execute_simple("get_weather", serde_json::json!({"city": "Paris"}))
+            .await
+            .unwrap();
+        assert_eq!(out["city"], "Paris");
+
+        let err = ToolExecutor::new()
+            .add_functions_from_specs_with_registry(&specs, &handlers, true)
+            .unwrap_err();
+        assert!(err.to_string().contains("spec #1"));
     }
 }
@@ -6,6 +6,7 @@
 
 use std::borrow::Cow;
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 /// A parsed tool call request from an LLM response with zero-copy optimization.
@@ -22,6 +23,34 @@ pub struct LlmToolCall<'a> {
     pub arguments: Value,
 }
 
+impl LlmToolCall<'_> {
+    /// Arguments as JSON, unwrapping one level of double encoding.
+    ///
+    /// Some models encode `arguments` twice, so that after the usual parse
+    /// it is still a string such as `{"city": "Paris"}`; such a string is
+    /// parsed once more. Strings that are not JSON are returned unchanged.
+    pub fn parse_arguments_value(&self) -> Value {
+        match &self.arguments {
+            Value::String(s) => serde_json::from_str(s).unwrap_or_else(|_| self.arguments.clone()),
+            other => other.clone(),
+        }
+    }
+
+    /// Deserialize the arguments into `T`, accepting double-encoded JSON as
+    /// [`parse_arguments_value`](Self::parse_arguments_value) does.
+    ///
+    /// Malformed arguments fail with the JSON syntax error (line and
+    /// column) rather than a type mismatch.
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        match self.parse_arguments_value() {
+            // Still a string: either `T` wants one, or it was not valid JSON.
+            Value::String(s) => serde_json::from_value(Value::String(s.clone()))
+                .or_else(|_| serde_json::from_str(&s)),
+            value => serde_json::from_value(value),
+        }
+    }
+}
+
 /// Normalize JSON arguments for better consistency
 pub fn normalize_arguments(args: &Value) -> Value {
     match args {
@@ -42,6 +71,99 @@ pub fn normalize_arguments(args: &Value) -> Value {
     }
 }
 
+/// Parse JSON produced by a model, repairing common mistakes if needed.
+///
+/// Valid JSON is parsed as-is. Otherwise these fixes are applied before
+/// parsing again:
+/// - a surrounding markdown code fence (```` ```json ... ``` ````) is removed
+/// - prose before the first `{`/`[` and after the last `}`/`]` is dropped
+/// - single-quoted strings become double-quoted
+/// - unquoted object keys are quoted
+/// - trailing commas before `}` or `]` are removed
+///
+/// Returns `None` if the input still does not parse.
+pub fn repair_json(s: &str) -> Option<Value> {
+    if let Ok(value) = serde_json::from_str(s) {
+        return Some(value);
+    }
+    let fenced = strip_code_fence(s);
+    let start = fenced.find(['{', '['])?;
+    let end = fenced.rfind(['}', ']'])?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&normalize_json_syntax(&fenced[start..=end])).ok()
+}
+
+/// Contents of the first markdown code fence, or the input if it has none.
+fn strip_code_fence(s: &str) -> &str {
+    let Some(open) = s.find("```") else {
+        return s;
+    };
+    // Skip the info string, e.g. `json`.
+    let body = s[open + 3..].trim_start_matches(|c: char| c.is_ascii_alphanumeric());
+    match body.find("```") {
+        Some(close) => &body[..close],
+        None => body,
+    }
+}
+
+/// Rewrite JavaScript-style object syntax into JSON: single quotes, bare
+/// keys and trailing commas. Text inside strings is left alone.
+fn normalize_json_syntax(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let next_significant = |from: usize| chars[from..].iter().find(|c| !c.is_whitespace());
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            quote @ ('"' | '\'') => {
+                out.push('"');
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    match chars[i] {
+                        '\\' if i + 1 < chars.len() => {
+                            // `\'` only needs escaping inside single quotes.
+                            if chars[i + 1] != '\'' {
+                                out.push('\\');
+                            }
+                            out.push(chars[i + 1]);
+                            i += 1;
+                        },
+                        '"' => out.push_str("\\\""),
+                        c => out.push(c),
+                    }
+                    i += 1;
+                }
+                out.push('"');
+                i += 1;
+            },
+            ',' if matches!(next_significant(i + 1), Some('}' | ']')) => i += 1,
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '-'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if next_significant(i) == Some(&':') {
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                } else {
+                    out.push_str(&word);
+                }
+            },
+            c => {
+                out.push(c);
+                i += 1;
+            },
+        }
+    }
+    out
+}
+
 /// Parse tool calls with better error recovery and zero-copy optimization
 pub fn parse_tool_calls_robust(response: &Value) -> Vec<LlmToolCall<'_>> {
     let mut results = Vec::new();
@@ -210,6 +332,72 @@ mod tests {
 
     use super::*;
 
+    fn call_with_arguments(arguments: Value) -> Value {
+        json!({"tool_calls": [{"id": "c1", "type": "function",
+            "function": {"name": "weather", "arguments": arguments}}]})
+    }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct WeatherArgs {
+        city: String,
+    }
+
+    #[test]
+    fn test_repair_json() {
+        let expected = json!({"city": "Paris", "days": [1, 2]});
+        for input in [
+            r#"{"city": "Paris", "days": [1, 2]}"#,
+            r#"{"city": "Paris", "days": [1, 2,],}"#,
+            "```json\n{\"city\": \"Paris\", \"days\": [1, 2]}\n```",
+            r#"Sure! Here are the arguments: {"city": "Paris", "days": [1, 2]} Hope it helps."#,
+            r#"{'city': 'Paris', 'days': [1, 2]}"#,
+            r#"{city: "Paris", days: [1, 2]}"#,
+        ] {
+            assert_eq!(repair_json(input), Some(expected.clone()), "{}", input);
+        }
+
+        // String contents are left alone.
+        assert_eq!(
+            repair_json(r#"{'note': 'say "hi", ok: it\'s fine,}'}"#),
+            Some(json!({"note": "say \"hi\", ok: it's fine,}"}))
+        );
+        for input in ["not json at all", "{\"city\": }", "} backwards {"] {
+            assert_eq!(repair_json(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_arguments_plain_object() {
+        for arguments in [json!(r#"{"city": "Paris"}"#), json!({"city": "Paris"})] {
+            let message = call_with_arguments(arguments);
+            let call = &parse_tool_calls_from_message(&message)[0];
+            assert_eq!(call.parse_arguments_value(), json!({"city": "Paris"}));
+            let args: WeatherArgs = call.parse_arguments().unwrap();
+            assert_eq!(args.city, "Paris");
+        }
+    }
+
+    #[test]
+    fn test_parse_arguments_double_encoded() {
+        let double = serde_json::to_string(r#"{"city": "Paris"}"#).unwrap();
+        let message = call_with_arguments(json!(double));
+        let call = &parse_tool_calls_from_message(&message)[0];
+        assert_eq!(call.arguments, json!(r#"{"city": "Paris"}"#));
+        assert_eq!(call.parse_arguments_value(), json!({"city": "Paris"}));
+        let args: WeatherArgs = call.parse_arguments().unwrap();
+        assert_eq!(args.city, "Paris");
+    }
+
+    #[test]
+    fn test_parse_arguments_malformed() {
+        let message = call_with_arguments(json!(r#"{"city": "Paris""#));
+        let call = &parse_tool_calls_from_message(&message)[0];
+        assert_eq!(call.parse_arguments_value(), json!(r#"{"city": "Paris""#));
+        let err = call.parse_arguments::<WeatherArgs>().unwrap_err();
+        assert!(err.is_eof(), "{}", err);
+        assert!(err.to_string().contains("line 1"), "{}", err);
+    }
+
     #[test]
     fn test_normalize_arguments_string() {
         let args = json!(r#"{"city": "Shenzhen"}"#);
@@ -0,0 +1,228 @@
+//! Aggregated tool execution metrics
+//!
+//! A [`MetricsSink`] attached to a [`ToolExecutor`](super::executor::ToolExecutor)
+//! receives every [`ExecutionResult`] produced by `execute`, which makes it the
+//! single place to forward tool performance to logs, Prometheus and the like.
+//! [`InMemoryMetrics`] is a ready-made sink that keeps per-tool counters and
+//! latency percentiles.
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use zai_rs::toolkits::prelude::*;
+//!
+//! let metrics = Arc::new(InMemoryMetrics::new());
+//! let executor = ToolExecutor::builder().metrics_sink(metrics.clone()).build();
+//! // ... execute tools ...
+//! for (tool, stats) in metrics.snapshot() {
+//!     println!("{tool}: {} calls, p95 {:?}", stats.count, stats.p95);
+//! }
+//! ```
+
+use std::{collections::HashMap, collections::VecDeque, sync::Mutex, time::Duration};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use super::executor::ExecutionResult;
+
+/// Receiver for execution results
+pub trait MetricsSink: Send + Sync {
+    /// Called once per `ToolExecutor::execute`, after retries are exhausted
+    fn record(&self, result: &ExecutionResult);
+}
+
+/// Aggregated statistics for a single tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolStats {
+    /// Total number of recorded executions
+    pub count: u64,
+    /// Number of successful executions
+    pub successes: u64,
+    /// Number of failed executions
+    pub failures: u64,
+    /// Total retries across all executions
+    pub retries: u64,
+    /// Fraction of successful executions (0.0 - 1.0)
+    pub success_rate: f64,
+    /// Median latency over the retained samples
+    pub p50: Duration,
+    /// 95th percentile latency over the retained samples
+    pub p95: Duration,
+}
+
+/// Default number of latency samples kept per tool
+pub const DEFAULT_MAX_SAMPLES: usize = 1024;
+
+#[derive(Default)]
+struct ToolRecord {
+    count: u64,
+    successes: u64,
+    retries: u64,
+    durations: VecDeque<Duration>,
+}
+
+/// In-memory [`MetricsSink`] tracking count, success rate and latency
+/// percentiles per tool name.
+///
+/// Counters cover every recorded call; percentiles are computed over the
+/// most recent `max_samples` durations so memory stays bounded.
+pub struct InMemoryMetrics {
+    records: DashMap<String, Mutex<ToolRecord>>,
+    max_samples: usize,
+}
+
+impl std::fmt::Debug for InMemoryMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryMetrics")
+            .field("tools", &self.records.len())
+            .field("max_samples", &self.max_samples)
+            .finish()
+    }
+}
+
+impl Default for InMemoryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        Self::with_max_samples(DEFAULT_MAX_SAMPLES)
+    }
+
+    /// Keep at most `max_samples` latency samples per tool
+    pub fn with_max_samples(max_samples: usize) -> Self {
+        Self {
+            records: DashMap::new(),
+            max_samples: max_samples.max(1),
+        }
+    }
+
+    /// Current statistics keyed by tool name
+    pub fn snapshot(&self) -> HashMap<String, ToolStats> {
+        self.records
+            .iter()
+            .map(|entry| {
+                let record = entry.value().lock().unwrap_or_else(|e| e.into_inner());
+                (entry.key().clone(), stats_of(&record))
+            })
+            .collect()
+    }
+
+    /// Drop all recorded data
+    pub fn reset(&self) {
+        self.records.clear();
+    }
+}
+
+impl MetricsSink for InMemoryMetrics {
+    fn record(&self, result: &ExecutionResult) {
+        let entry = self.records.entry(result.tool_name.clone()).or_default();
+        let mut record = entry.lock().unwrap_or_else(|e| e.into_inner());
+        record.count += 1;
+        if result.success {
+            record.successes += 1;
+        }
+        record.retries += u64::from(result.retries);
+        if record.durations.len() == self.max_samples {
+            record.durations.pop_front();
+        }
+        record.durations.push_back(result.duration);
+    }
+}
+
+fn stats_of(record: &ToolRecord) -> ToolStats {
+    let mut sorted: Vec<Duration> = record.durations.iter().copied().collect();
+    sorted.sort_unstable();
+    ToolStats {
+        count: record.count,
+        successes: record.successes,
+        failures: record.count - record.successes,
+        retries: record.retries,
+        success_rate: if record.count == 0 {
+            0.0
+        } else {
+            record.successes as f64 / record.count as f64
+        },
+        p50: percentile(&sorted, 50),
+        p95: percentile(&sorted, 95),
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice
+pub(crate) fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(tool: &str, ms: u64, success: bool) -> ExecutionResult {
+        if success {
+            ExecutionResult::success(
+                tool.to_string(),
+                serde_json::json!({}),
+                Duration::from_millis(ms),
+                0,
+            )
+        } else {
+            ExecutionResult::failure(
+                tool.to_string(),
+                "boom".to_string(),
+                Duration::from_millis(ms),
+                2,
+            )
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 95), Duration::from_millis(95));
+        assert_eq!(percentile(&[], 95), Duration::ZERO);
+        assert_eq!(
+            percentile(&[Duration::from_millis(7)], 50),
+            Duration::from_millis(7)
+        );
+    }
+
+    #[test]
+    fn test_in_memory_metrics_snapshot() {
+        let metrics = InMemoryMetrics::new();
+        for ms in 1..=20 {
+            metrics.record(&result("a", ms, ms != 20));
+        }
+        metrics.record(&result("b", 5, true));
+
+        let snapshot = metrics.snapshot();
+        let a = &snapshot["a"];
+        assert_eq!(a.count, 20);
+        assert_eq!(a.failures, 1);
+        assert_eq!(a.retries, 2);
+        assert!((a.success_rate - 0.95).abs() < f64::EPSILON);
+        assert_eq!(a.p50, Duration::from_millis(10));
+        assert_eq!(a.p95, Duration::from_millis(19));
+        assert_eq!(snapshot["b"].count, 1);
+    }
+
+    #[test]
+    fn test_in_memory_metrics_bounded_samples() {
+        let metrics = InMemoryMetrics::with_max_samples(2);
+        for ms in [100, 1, 2] {
+            metrics.record(&result("a", ms, true));
+        }
+        let stats = &metrics.snapshot()["a"];
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.p95, Duration::from_millis(2));
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+}
@@ -12,10 +12,15 @@
 //!   logic
 //! - [`llm`] — LLM-specific parsing utilities (tool-call extraction)
 //! - [`cache`] — In-memory tool-call cache with statistics
+//! - [`metrics`] — Pluggable execution metrics sinks
 //!
 //! # Feature-gated
 //!
 //! - `rmcp-kits` — RMCP protocol bridge for MCP tool calling
+//! - `builtin-tools` — Ready-made tools in [`builtin`] (HTTP endpoint and
+//!   knowledge-base search tools)
+//! - `monitoring` — Prometheus exporter for execution metrics in
+//!   [`prometheus`]
 //!
 //! # Quick Start
 //!
@@ -39,11 +44,20 @@ pub mod core;
 pub mod error;
 pub mod executor;
 pub mod llm;
+pub mod metrics;
+
+// Built-in tools (feature-gated)
+#[cfg(feature = "builtin-tools")]
+pub mod builtin;
 
 // RMCP bridge (feature-gated)
 #[cfg(feature = "rmcp-kits")]
 pub mod rmcp_kits;
 
+// Prometheus exporter (feature-gated)
+#[cfg(feature = "monitoring")]
+pub mod prometheus;
+
 /// Prelude module for convenient imports
 ///
 /// This module re-exports commonly used types and traits from the toolkits
@@ -63,22 +77,28 @@ pub mod prelude {
 
     // Caching
     pub use crate::toolkits::cache::{CacheEntry, CacheKey, CacheStats, ToolCallCache};
-    pub use crate::toolkits::core::{DynTool, FunctionTool, ToolMetadata, conversions};
+    pub use crate::toolkits::core::{
+        DynTool, FunctionTool, ToolDescriptor, ToolMetadata, conversions,
+    };
     // Error handling
-    pub use crate::toolkits::error::{ToolError, ToolResult, error_context};
+    pub use crate::toolkits::error::{ToolError, ToolErrorKind, ToolResult, error_context};
     // Execution (executor now owns registration APIs)
     pub use crate::toolkits::executor::{
-        ExecutionConfig, ExecutionResult, ExecutorBuilder, ToolExecutor,
+        AdaptiveTimeout, ExecutionConfig, ExecutionResult, ExecutorBuilder, OutputTransform,
+        ToolExecutor,
     };
     // LLM parsing helpers
     pub use crate::toolkits::llm::{
         LlmToolCall, parse_first_tool_call, parse_tool_calls, parse_tool_calls_from_message,
+        repair_json,
     };
+    // Metrics
+    pub use crate::toolkits::metrics::{InMemoryMetrics, MetricsSink, ToolStats};
     // RMCP bridge exports when enabled
     #[cfg(feature = "rmcp-kits")]
     pub use crate::toolkits::rmcp_kits::{
-        McpToolCaller, call_mcp_tool, call_mcp_tools_collect, call_tool_result_to_json,
-        mcp_tool_to_function, mcp_tools_to_functions,
+        McpDynTool, McpToolCaller, call_mcp_tool, call_mcp_tools_collect, call_tool_result_to_json,
+        mcp_tool_as_dyn_tool, mcp_tool_to_function, mcp_tools_as_dyn_tools, mcp_tools_to_functions,
     };
 }
 
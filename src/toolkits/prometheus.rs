@@ -0,0 +1,222 @@
+//! Prometheus exporter for tool execution metrics (feature `monitoring`)
+//!
+//! [`PrometheusExporter`] is a [`MetricsSink`] that renders what it records in
+//! the Prometheus text exposition format, ready to be served from a
+//! `/metrics` endpoint. Every series carries a `tool_name` and a `success`
+//! label, so error rates are a ratio of the `success="false"` series:
+//!
+//! ```text
+//! # HELP zai_tool_executions_total Tool executions, after retries.
+//! # TYPE zai_tool_executions_total counter
+//! zai_tool_executions_total{tool_name="get_weather",success="true"} 12
+//! ```
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use zai_rs::toolkits::{prelude::*, prometheus::PrometheusExporter};
+//!
+//! let exporter = Arc::new(PrometheusExporter::new());
+//! let executor = ToolExecutor::builder().metrics_sink(exporter.clone()).build();
+//! // ... execute tools, then from the metrics handler:
+//! let body = exporter.render();
+//! ```
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use parking_lot::Mutex;
+
+use super::{executor::ExecutionResult, metrics::MetricsSink};
+
+/// Latency bucket bounds in seconds, the Prometheus client defaults
+pub const DEFAULT_DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Default)]
+struct Series {
+    count: u64,
+    retries: u64,
+    duration_sum: f64,
+    /// Non-cumulative count per bucket bound
+    buckets: Vec<u64>,
+}
+
+/// Metrics sink rendering the Prometheus text format
+#[derive(Debug)]
+pub struct PrometheusExporter {
+    bounds: Vec<f64>,
+    series: Mutex<BTreeMap<(String, bool), Series>>,
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrometheusExporter {
+    /// Exporter with [`DEFAULT_DURATION_BUCKETS`]
+    pub fn new() -> Self {
+        Self::with_buckets(DEFAULT_DURATION_BUCKETS.to_vec())
+    }
+
+    /// Exporter with custom latency bucket bounds in seconds; the `+Inf`
+    /// bucket is always added
+    pub fn with_buckets(mut bounds: Vec<f64>) -> Self {
+        bounds.retain(|b| b.is_finite());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        Self {
+            bounds,
+            series: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Drop all recorded series
+    pub fn reset(&self) {
+        self.series.lock().clear();
+    }
+
+    /// Recorded metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let series = self.series.lock();
+        let mut out = String::new();
+
+        family(
+            &mut out,
+            "zai_tool_executions_total",
+            "Tool executions, after retries.",
+            "counter",
+        );
+        for ((tool, success), s) in series.iter() {
+            let _ = writeln!(
+                out,
+                "zai_tool_executions_total{{{}}} {}",
+                labels(tool, *success),
+                s.count
+            );
+        }
+
+        family(
+            &mut out,
+            "zai_tool_retries_total",
+            "Retries spent on tool executions.",
+            "counter",
+        );
+        for ((tool, success), s) in series.iter() {
+            let _ = writeln!(
+                out,
+                "zai_tool_retries_total{{{}}} {}",
+                labels(tool, *success),
+                s.retries
+            );
+        }
+
+        let name = "zai_tool_execution_duration_seconds";
+        family(
+            &mut out,
+            name,
+            "Tool execution latency in seconds, including retries.",
+            "histogram",
+        );
+        for ((tool, success), s) in series.iter() {
+            let labels = labels(tool, *success);
+            let mut cumulative = 0;
+            for (bound, count) in self.bounds.iter().zip(&s.buckets) {
+                cumulative += count;
+                let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+            }
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", s.count);
+            let _ = writeln!(out, "{name}_sum{{{labels}}} {}", s.duration_sum);
+            let _ = writeln!(out, "{name}_count{{{labels}}} {}", s.count);
+        }
+        out
+    }
+}
+
+impl MetricsSink for PrometheusExporter {
+    fn record(&self, result: &ExecutionResult) {
+        let seconds = result.duration.as_secs_f64();
+        let mut series = self.series.lock();
+        let s = series
+            .entry((result.tool_name.clone(), result.success))
+            .or_insert_with(|| Series {
+                buckets: vec![0; self.bounds.len()],
+                ..Series::default()
+            });
+        s.count += 1;
+        s.retries += u64::from(result.retries);
+        s.duration_sum += seconds;
+        if let Some(i) = self.bounds.iter().position(|b| seconds <= *b) {
+            s.buckets[i] += 1;
+        }
+    }
+}
+
+fn family(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn labels(tool: &str, success: bool) -> String {
+    format!("tool_name=\"{}\",success=\"{}\"", escape(tool), success)
+}
+
+/// Escape a label value: backslash, double quote and newline
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_render_families_and_labels() {
+        let exporter = PrometheusExporter::with_buckets(vec![1.0, 0.1]);
+        exporter.record(&ExecutionResult::success(
+            "get_weather".to_string(),
+            serde_json::json!({}),
+            Duration::from_millis(50),
+            0,
+        ));
+        exporter.record(&ExecutionResult::success(
+            "get_weather".to_string(),
+            serde_json::json!({}),
+            Duration::from_millis(500),
+            1,
+        ));
+        exporter.record(&ExecutionResult::failure(
+            "say \"hi\"".to_string(),
+            "boom".to_string(),
+            Duration::from_secs(3),
+            2,
+        ));
+
+        let text = exporter.render();
+        for line in [
+            "# TYPE zai_tool_executions_total counter",
+            "# TYPE zai_tool_retries_total counter",
+            "# TYPE zai_tool_execution_duration_seconds histogram",
+            "zai_tool_executions_total{tool_name=\"get_weather\",success=\"true\"} 2",
+            "zai_tool_executions_total{tool_name=\"say \\\"hi\\\"\",success=\"false\"} 1",
+            "zai_tool_retries_total{tool_name=\"get_weather\",success=\"true\"} 1",
+            "zai_tool_execution_duration_seconds_bucket{tool_name=\"get_weather\",success=\"true\",le=\"0.1\"} 1",
+            "zai_tool_execution_duration_seconds_bucket{tool_name=\"get_weather\",success=\"true\",le=\"1\"} 2",
+            "zai_tool_execution_duration_seconds_bucket{tool_name=\"say \\\"hi\\\"\",success=\"false\",le=\"1\"} 0",
+            "zai_tool_execution_duration_seconds_bucket{tool_name=\"say \\\"hi\\\"\",success=\"false\",le=\"+Inf\"} 1",
+            "zai_tool_execution_duration_seconds_sum{tool_name=\"get_weather\",success=\"true\"} 0.55",
+            "zai_tool_execution_duration_seconds_count{tool_name=\"get_weather\",success=\"true\"} 2",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}\n{text}");
+        }
+
+        exporter.reset();
+        assert!(!exporter.render().contains("get_weather"));
+    }
+}
@@ -5,6 +5,7 @@
 //! - Conversion from `rmcp::model::Tool` to zai-rs function-call tool defs
 //! - Simple helpers to call RMCP tools and normalize results
 //! - A small caller struct to encapsulate `ServerSink` usage
+//! - [`DynTool`] wrappers that run MCP tools, for a [`ToolExecutor`]
 //!
 //! All APIs are feature-gated behind `rmcp-kits`.
 //!
@@ -34,9 +35,25 @@
 //! ]).await?;
 //! # Ok(()) }
 //! ```
+//!
+//! Example: register every MCP tool with a [`ToolExecutor`]
+//! ```rust,ignore
+//! use zai_rs::toolkits::{prelude::*, rmcp_kits::mcp_tools_as_dyn_tools};
+//! # async fn run(server: rmcp::service::ServerSink) -> anyhow::Result<()> {
+//! let tools = server.list_all_tools().await?;
+//! let executor = ToolExecutor::new();
+//! executor.register_many(mcp_tools_as_dyn_tools(&tools, &server))?;
+//! // Tool definitions for the chat request, and execution of the model's
+//! // tool calls, now go through the executor.
+//! let results = executor.execute_tool_calls_parallel(&tool_calls).await;
+//! # Ok(()) }
+//! ```
+//!
+//! [`ToolExecutor`]: crate::toolkits::executor::ToolExecutor
 
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
+use async_trait::async_trait;
 use rmcp::{
     model::{CallToolRequestParams, CallToolResult, Tool},
     service::ServerSink,
@@ -45,7 +62,13 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::Validate;
 
-use crate::model::{Function, Tools};
+use crate::{
+    model::{Function, Tools},
+    toolkits::{
+        core::{DynTool, ToolMetadata},
+        error::{ToolResult, error_context},
+    },
+};
 
 /// Convert a single RMCP tool to a zai-rs function-call definition.
 ///
@@ -199,6 +222,100 @@ impl McpToolCaller {
     }
 }
 
+/// A [`DynTool`] that runs an MCP tool on the server; see
+/// [`mcp_tool_as_dyn_tool`].
+#[derive(Clone)]
+pub struct McpDynTool {
+    metadata: ToolMetadata,
+    input_schema: Value,
+    server: ServerSink,
+}
+
+#[async_trait]
+impl DynTool for McpDynTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    /// Forward the call with `call_tool`. Arguments are checked by the
+    /// server against its schema; a result flagged `is_error` is an
+    /// execution failure carrying the result JSON.
+    async fn execute_json(&self, input: Value) -> ToolResult<Value> {
+        let arguments = match input {
+            Value::Object(map) => Some(map),
+            Value::Null => None,
+            _ => {
+                return Err(error_context()
+                    .with_tool(self.name())
+                    .invalid_parameters("arguments must be a JSON object"));
+            },
+        };
+        let failed = |message: String| {
+            error_context()
+                .with_tool(self.name())
+                .execution_failed(message)
+        };
+
+        let mut request = CallToolRequestParams::new(self.name().to_string());
+        if let Some(arguments) = arguments {
+            request = request.with_arguments(arguments);
+        }
+        let res = self
+            .server
+            .call_tool(request)
+            .await
+            .map_err(|e| failed(format!("RMCP service error: {}", e)))?;
+        let value = call_tool_result_to_json(&res);
+        if res.is_error == Some(true) {
+            return Err(failed(value.to_string()));
+        }
+        Ok(value)
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTool> {
+        Box::new(self.clone())
+    }
+}
+
+/// Wrap an RMCP tool as a [`DynTool`] that executes on `server`.
+///
+/// The MCP name is kept as is (MCP names may contain `-`, which
+/// [`ToolMetadata::new`] rejects) and the tool is tagged `mcp`.
+pub fn mcp_tool_as_dyn_tool(tool: &Tool, server: ServerSink) -> Box<dyn DynTool> {
+    let metadata = ToolMetadata {
+        name: Cow::Owned(tool.name.to_string()),
+        description: Cow::Owned(
+            tool.description
+                .as_deref()
+                .unwrap_or("Remote MCP tool")
+                .to_string(),
+        ),
+        version: Cow::Borrowed("1.0.0"),
+        author: None,
+        tags: vec![Cow::Borrowed("mcp")],
+        enabled: true,
+        metadata: HashMap::new(),
+    };
+    Box::new(McpDynTool {
+        metadata,
+        input_schema: tool.schema_as_json_value(),
+        server,
+    })
+}
+
+/// Wrap RMCP tools as [`DynTool`]s, e.g. for
+/// [`ToolExecutor::register_many`](crate::toolkits::executor::ToolExecutor::register_many).
+pub fn mcp_tools_as_dyn_tools(tools: &[Tool], server: &ServerSink) -> Vec<Box<dyn DynTool>> {
+    tools
+        .iter()
+        .map(|tool| mcp_tool_as_dyn_tool(tool, server.clone()))
+        .collect()
+}
+
 /// Execute tool calls requested by the first choice in a ChatCompletionResponse
 /// and build tool messages ready to append to the chat.
 ///
@@ -223,6 +340,7 @@ pub async fn execute_tool_calls_as_messages(
         .and_then(|c| c.message().tool_calls());
 
     let Some(calls) = calls else { return Ok(out) };
+    #[cfg(feature = "tracing")]
     tracing::info!("AI requested tool calls: {}", calls.len());
 
     for tc in calls {
@@ -230,6 +348,7 @@ pub async fn execute_tool_calls_as_messages(
         let id = match tc.id() {
             Some(id) => id.to_string(),
             None => {
+                #[cfg(feature = "tracing")]
                 tracing::warn!("Tool call without id, skipping");
                 continue;
             },
@@ -239,6 +358,7 @@ pub async fn execute_tool_calls_as_messages(
         let func = match tc.function() {
             Some(f) => f,
             None => {
+                #[cfg(feature = "tracing")]
                 tracing::warn!("Tool call missing function payload, skipping");
                 continue;
             },
@@ -248,6 +368,7 @@ pub async fn execute_tool_calls_as_messages(
         let name = match func.name() {
             Some(n) => n.to_string(),
             None => {
+                #[cfg(feature = "tracing")]
                 tracing::warn!("Tool call missing function name, skipping");
                 continue;
             },
@@ -258,10 +379,13 @@ pub async fn execute_tool_calls_as_messages(
             Some(arg_str) => match serde_json::from_str::<serde_json::Value>(arg_str) {
                 Ok(serde_json::Value::Object(map)) => Some(serde_json::Value::Object(map)),
                 Ok(_) => {
+                    #[cfg(feature = "tracing")]
                     tracing::warn!("Function arguments are not an object; passing None");
                     None
                 },
+                #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
                 Err(e) => {
+                    #[cfg(feature = "tracing")]
                     tracing::warn!("Failed to parse function arguments JSON: {}", e);
                     None
                 },
@@ -311,6 +435,7 @@ where
 
     let first_resp = chat.send().await?;
 
+    #[cfg(feature = "tracing")]
     tracing::info!("AI response: {:#?}", first_resp);
 
     let tool_msgs: Vec<crate::model::chat_message_types::TextMessage> =
@@ -340,6 +465,9 @@ where
 /// - If content is an array, return the first item of type "text"'s `text`
 ///   field
 /// - Otherwise return None
+///
+/// Use [`ChatCompletionResponse::final_text`](crate::model::chat_base_response::ChatCompletionResponse::final_text)
+/// to concatenate all parts and stringify other JSON instead.
 #[cfg(feature = "rmcp-kits")]
 pub fn extract_final_text(
     resp: &crate::model::chat_base_response::ChatCompletionResponse,
@@ -77,6 +77,23 @@ pub async fn start_mock_server(config: MockServerConfig) -> Result<(), Box<dyn s
     let listener = TcpListener::bind(addr).await?;
     println!("Mock server running on http://127.0.0.1:9876");
 
+    Ok(serve(listener, state).await?)
+}
+
+/// Start the mock server on a free port in the background.
+///
+/// Returns `config` with `base_url` pointing at the bound address; the
+/// server accepts connections as soon as this returns.
+#[allow(dead_code)]
+pub async fn spawn_mock_server(mut config: MockServerConfig) -> std::io::Result<MockServerConfig> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    config.base_url = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(serve(listener, MockServerState::new(config.clone())));
+    Ok(config)
+}
+
+/// Accept connections on `listener` until it fails
+async fn serve(listener: TcpListener, state: MockServerState) -> std::io::Result<()> {
     loop {
         let (stream, _) = listener.accept().await?;
         let io = TokioIo::new(stream);
@@ -9,7 +9,11 @@ use serde_json::json;
 use tokio::time::sleep;
 
 mod common;
-use common::mock_server::{MockServerClient, MockServerConfig};
+use common::mock_server::{MockServerClient, MockServerConfig, spawn_mock_server};
+use zai_rs::{
+    client::Endpoint,
+    model::{chat::ChatCompletion, chat_message_types::TextMessage, chat_models::GLM4_5_flash},
+};
 
 /// Integration test for chat completion
 #[tokio::test]
@@ -25,6 +29,27 @@ async fn test_chat_completion_integration() {
     );
 }
 
+/// Chat completion sent to the mock server through a custom endpoint
+#[tokio::test]
+async fn test_chat_completion_custom_endpoint() {
+    let config = spawn_mock_server(MockServerConfig::default())
+        .await
+        .expect("bind mock server");
+    let endpoint = Endpoint::new(format!("{}/api", config.base_url));
+
+    let request = ChatCompletion::new(GLM4_5_flash {}, TextMessage::user("hello"), config.api_key)
+        .with_endpoint(endpoint);
+    let response = request.send().await.expect("mock chat response");
+
+    assert_eq!(response.id(), Some("chatcmpl-1234567890"));
+    assert_eq!(
+        response.choices().unwrap()[0].message().content(),
+        Some(&json!(
+            "This is a mock response from the integration test server."
+        ))
+    );
+}
+
 /// Integration test for error handling
 #[tokio::test]
 async fn test_error_handling_integration() {